@@ -0,0 +1,371 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+use twilight_model::{
+    channel::Channel,
+    gateway::event::Event,
+    guild::{Emoji, Guild, Role},
+    id::{
+        marker::{ChannelMarker, EmojiMarker, GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::pool::lock;
+
+/// A name matching an autocomplete query, returned by
+/// [`crate::Cache::suggest_roles`], [`crate::Cache::suggest_channels`] and
+/// [`crate::Cache::suggest_emojis`]
+///
+/// These have everything needed for an autocomplete choice, so responding to
+/// the interaction doesn't require reading from the backend
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Suggestion<T> {
+    /// The ID of the role, channel or emoji
+    pub id: Id<T>,
+    /// The name of the role, channel or emoji
+    pub name: String,
+}
+
+/// A process-local index of the names of the roles, channels and emojis of
+/// each guild, used to suggest them to autocomplete interactions without
+/// querying the backend
+///
+/// It's kept up to date by [`crate::Cache::update`], so it only has the
+/// entities of the guilds received after it was created, names updated with
+/// [`crate::Backend`] methods directly aren't indexed
+///
+/// To use it, keep it in your cache and override
+/// [`crate::Cache::autocomplete_index`] to return it
+///
+/// # Matching
+///
+/// Queries match names case-insensitively, names that start with the query
+/// are suggested first, then names with a word that starts with it, for
+/// example `mod` matches `Moderator` then `Server Mods`, then names that
+/// have the query's characters in order, for example `gnrl` matches
+/// `general`
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::autocomplete::AutocompleteIndex;
+///
+/// let index = AutocompleteIndex::new();
+/// let roles = cache.suggest_roles(guild_id, "mod", 25);
+/// ```
+#[derive(Debug, Default)]
+pub struct AutocompleteIndex {
+    /// The indexed names of each guild
+    guilds: Mutex<HashMap<Id<GuildMarker>, GuildNames>>,
+}
+
+impl AutocompleteIndex {
+    /// Create an empty index
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the number of guilds in the index
+    #[must_use]
+    pub fn len(&self) -> usize {
+        lock(&self.guilds).len()
+    }
+
+    /// Return whether the index is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        lock(&self.guilds).is_empty()
+    }
+
+    /// Remove every guild from the index
+    pub fn clear(&self) {
+        lock(&self.guilds).clear();
+    }
+
+    /// Return the roles of the guild whose names match the query
+    pub(crate) fn roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<RoleMarker>> {
+        lock(&self.guilds)
+            .get(&guild_id)
+            .map(|guild| guild.roles.suggest(query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Return the channels and threads of the guild whose names match the
+    /// query
+    pub(crate) fn channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<ChannelMarker>> {
+        lock(&self.guilds)
+            .get(&guild_id)
+            .map(|guild| guild.channels.suggest(query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Return the emojis of the guild whose names match the query
+    pub(crate) fn emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<EmojiMarker>> {
+        lock(&self.guilds)
+            .get(&guild_id)
+            .map(|guild| guild.emojis.suggest(query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Replace the indexed names of the guild with the names of its roles,
+    /// channels, threads and emojis
+    ///
+    /// The roles are passed separately since [`crate::Cache::update_owned`]
+    /// takes them out of the guild
+    pub(crate) fn insert_guild(&self, guild: &Guild, roles: &[Role]) {
+        let mut names = GuildNames::default();
+        for role in roles {
+            names.roles.insert(role.id, &role.name);
+        }
+        for channel in guild.channels.iter().chain(&guild.threads) {
+            if let Some(name) = &channel.name {
+                names.channels.insert(channel.id, name);
+            }
+        }
+        for emoji in &guild.emojis {
+            names.emojis.insert(emoji.id, &emoji.name);
+        }
+        lock(&self.guilds).insert(guild.id, names);
+    }
+
+    /// Add or replace the name of the role
+    pub(crate) fn insert_role(&self, guild_id: Id<GuildMarker>, role: &Role) {
+        lock(&self.guilds)
+            .entry(guild_id)
+            .or_default()
+            .roles
+            .insert(role.id, &role.name);
+    }
+
+    /// Update the index with the names the event adds, changes or removes
+    pub(crate) fn update(&self, event: &Event) {
+        match event {
+            Event::ChannelCreate(channel) => self.insert_channel(channel),
+            Event::ChannelUpdate(channel) => self.insert_channel(channel),
+            Event::ChannelDelete(channel) => self.remove_channel(channel.guild_id, channel.id),
+            Event::ThreadCreate(thread) => self.insert_channel(thread),
+            Event::ThreadUpdate(thread) => self.insert_channel(thread),
+            Event::ThreadDelete(thread) => self.remove_channel(Some(thread.guild_id), thread.id),
+            Event::ThreadListSync(sync) => {
+                for thread in &sync.threads {
+                    self.insert_channel(thread);
+                }
+            }
+            Event::GuildCreate(guild) => self.insert_guild(&guild.0, &guild.roles),
+            Event::GuildDelete(guild) => {
+                if !guild.unavailable {
+                    lock(&self.guilds).remove(&guild.id);
+                }
+            }
+            Event::GuildEmojisUpdate(emojis) => {
+                self.replace_emojis(emojis.guild_id, &emojis.emojis);
+            }
+            Event::RoleCreate(role) => self.insert_role(role.guild_id, &role.role),
+            Event::RoleUpdate(role) => self.insert_role(role.guild_id, &role.role),
+            Event::RoleDelete(role) => {
+                if let Some(guild) = lock(&self.guilds).get_mut(&role.guild_id) {
+                    guild.roles.remove(role.role_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Add or replace the name of the channel or thread, if it's in a guild
+    /// and has a name
+    fn insert_channel(&self, channel: &Channel) {
+        if let (Some(guild_id), Some(name)) = (channel.guild_id, &channel.name) {
+            lock(&self.guilds)
+                .entry(guild_id)
+                .or_default()
+                .channels
+                .insert(channel.id, name);
+        }
+    }
+
+    /// Remove the name of the channel or thread
+    fn remove_channel(&self, guild_id: Option<Id<GuildMarker>>, channel_id: Id<ChannelMarker>) {
+        let mut guilds = lock(&self.guilds);
+        if let Some(guild) = guild_id.and_then(|id| guilds.get_mut(&id)) {
+            guild.channels.remove(channel_id);
+        }
+    }
+
+    /// Replace the names of the guild's emojis
+    fn replace_emojis(&self, guild_id: Id<GuildMarker>, emojis: &[Emoji]) {
+        let mut names = NameIndex::default();
+        for emoji in emojis {
+            names.insert(emoji.id, &emoji.name);
+        }
+        lock(&self.guilds).entry(guild_id).or_default().emojis = names;
+    }
+}
+
+/// The indexed names of a guild's entities
+#[derive(Debug, Default)]
+struct GuildNames {
+    /// The names of the roles
+    roles: NameIndex<RoleMarker>,
+    /// The names of the channels and threads
+    channels: NameIndex<ChannelMarker>,
+    /// The names of the emojis
+    emojis: NameIndex<EmojiMarker>,
+}
+
+/// The names of entities of a kind, indexed by the lowercase words they start
+/// with
+#[derive(Debug)]
+struct NameIndex<T> {
+    /// The names of the entities
+    names: HashMap<Id<T>, String>,
+    /// The lowercase names of the entities, starting from each of their
+    /// words, ordered so that the keys starting with a query are adjacent
+    keys: BTreeSet<(String, Id<T>)>,
+}
+
+impl<T> Default for NameIndex<T> {
+    fn default() -> Self {
+        Self {
+            names: HashMap::new(),
+            keys: BTreeSet::new(),
+        }
+    }
+}
+
+impl<T> NameIndex<T> {
+    /// Add or replace the name of the entity
+    fn insert(&mut self, id: Id<T>, name: &str) {
+        self.remove(id);
+        for key in keys(name) {
+            self.keys.insert((key, id));
+        }
+        self.names.insert(id, name.to_owned());
+    }
+
+    /// Remove the name of the entity
+    fn remove(&mut self, id: Id<T>) {
+        if let Some(name) = self.names.remove(&id) {
+            for key in keys(&name) {
+                self.keys.remove(&(key, id));
+            }
+        }
+    }
+
+    /// Return `limit` names matching the query, the best matches first
+    ///
+    /// A limit of 0 means to return all matching names
+    fn suggest(&self, query: &str, limit: u16) -> Vec<Suggestion<T>> {
+        let lowercase_query = query.to_lowercase();
+        let max_suggestions = if limit == 0 {
+            usize::MAX
+        } else {
+            usize::from(limit)
+        };
+
+        let mut ranks = HashMap::new();
+        for (key, id) in self
+            .keys
+            .range((lowercase_query.clone(), Id::new(1))..)
+            .take_while(|(key, _)| key.starts_with(&lowercase_query))
+        {
+            let rank = if self
+                .names
+                .get(id)
+                .map_or(false, |name| name.to_lowercase() == *key)
+            {
+                MatchRank::Name
+            } else {
+                MatchRank::Word
+            };
+            let best_rank = ranks.entry(*id).or_insert(rank);
+            *best_rank = (*best_rank).min(rank);
+        }
+        if ranks.len() < max_suggestions {
+            for (id, name) in &self.names {
+                if !ranks.contains_key(id) && is_subsequence(&lowercase_query, &name.to_lowercase())
+                {
+                    ranks.insert(*id, MatchRank::Subsequence);
+                }
+            }
+        }
+
+        let mut suggestions = ranks
+            .into_iter()
+            .filter_map(|(id, rank)| {
+                self.names
+                    .get(&id)
+                    .map(|name| (rank, name.to_lowercase(), id, name))
+            })
+            .collect::<Vec<_>>();
+        suggestions.sort_unstable_by(|(rank_a, name_a, id_a, _), (rank_b, name_b, id_b, _)| {
+            (rank_a, name_a, id_a).cmp(&(rank_b, name_b, id_b))
+        });
+        suggestions
+            .into_iter()
+            .take(max_suggestions)
+            .map(|(_, _, id, name)| Suggestion {
+                id,
+                name: name.clone(),
+            })
+            .collect()
+    }
+}
+
+/// How well a name matches a query, ordered from the best to the worst
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchRank {
+    /// The name starts with the query
+    Name,
+    /// A word in the name starts with the query
+    Word,
+    /// The name has the query's characters in order
+    Subsequence,
+}
+
+/// Return the keys a name is indexed by, its lowercase form starting from each
+/// of its words
+///
+/// Words are separated by characters that aren't alphanumeric, so channel
+/// names like `off-topic` are split too
+fn keys(name: &str) -> Vec<String> {
+    let lowercase_name = name.to_lowercase();
+    let mut keys = vec![lowercase_name.clone()];
+    let mut previous_is_separator = false;
+    for (index, character) in lowercase_name.char_indices() {
+        let is_separator = !character.is_alphanumeric();
+        if previous_is_separator && !is_separator {
+            if let Some(key) = lowercase_name.get(index..) {
+                keys.push(key.to_owned());
+            }
+        }
+        previous_is_separator = is_separator;
+    }
+    keys
+}
+
+/// Return whether the characters of the query are in the name in order
+fn is_subsequence(query: &str, name: &str) -> bool {
+    let mut name_chars = name.chars();
+    query
+        .chars()
+        .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+}
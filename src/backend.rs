@@ -1,28 +1,40 @@
 #![allow(clippy::std_instead_of_core)]
 
-use core::fmt::Display;
-use std::error::Error;
+use core::{fmt::Display, ops::BitOr};
+use std::{collections::HashMap, error::Error};
 
 use async_trait::async_trait;
 use twilight_model::{
-    channel::StageInstance,
+    channel::{permission_overwrite::PermissionOverwriteType, StageInstance},
+    guild::GuildFeature,
     id::{
         marker::{
             ChannelMarker, EmojiMarker, GenericMarker, GuildMarker, MessageMarker, RoleMarker,
-            StageMarker, UserMarker,
+            StageMarker, StickerMarker, UserMarker,
         },
         Id,
     },
+    oauth::PartialApplication,
     user::CurrentUser,
+    util::Timestamp,
 };
 
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
 use crate::{
     cache,
+    config::MessageKindFilter,
+    counts::EntityCount,
     model::{
-        CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField,
-        CachedEmoji, CachedGuild, CachedMember, CachedMessage, CachedPermissionOverwrite,
-        CachedPresence, CachedReaction, CachedRole, CachedSticker,
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker, GuildPatch, MemberPatch,
     },
+    schema::CURRENT_SCHEMA_VERSION,
 };
 
 impl<E: Display + Send> From<E> for cache::Error<E> {
@@ -31,6 +43,85 @@ impl<E: Display + Send> From<E> for cache::Error<E> {
     }
 }
 
+/// The features a backend supports, returned by [`Backend::capabilities`]
+///
+/// Combine them with `|`, for example
+/// `Capabilities::TRANSACTIONS | Capabilities::STREAMING`
+///
+/// Only [`Capabilities::CASCADE`] changes how [`super::Cache`] updates the
+/// backend, the other capabilities are only advertised to the code using the
+/// backend, so a backend should only return the ones it actually supports,
+/// [`super::tiered::TieredCache`] returns the ones both of its tiers support
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// The bits of the capabilities
+    bits: u8,
+}
+
+impl Capabilities {
+    /// The backend can run multiple writes atomically, so that the code using
+    /// the backend can group its own writes
+    ///
+    /// Not checked by [`super::Cache`]
+    pub const TRANSACTIONS: Self = Self { bits: 0b001 };
+    /// The backend can return query results as a stream instead of collecting
+    /// them first, so that the code using the backend can read large results
+    /// without loading them into memory
+    ///
+    /// Not checked by [`super::Cache`], whose getters collect their results
+    pub const STREAMING: Self = Self { bits: 0b010 };
+    /// The backend can search text, such as message content, with an index,
+    /// so that the code using the backend can run text searches without
+    /// scanning every row
+    ///
+    /// Not checked by [`super::Cache`]
+    pub const FULL_TEXT_SEARCH: Self = Self { bits: 0b100 };
+    /// The backend removes the data that belongs to an entity when the entity
+    /// is removed, such as with `ON DELETE CASCADE`, refer to
+    /// [`Backend::supports_cascade`] for what's expected to be removed
+    ///
+    /// Checked by [`Backend::supports_cascade`] by default, when it's
+    /// supported, [`super::Cache`] skips the calls that remove the entity's
+    /// data separately
+    pub const CASCADE: Self = Self { bits: 0b1000 };
+
+    /// Return capabilities with none of the features
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self { bits: 0 }
+    }
+
+    /// Return the bits of the capabilities
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.bits
+    }
+
+    /// Return whether all of the given capabilities are supported
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+
+    /// Return the capabilities supported by both
+    #[must_use]
+    pub const fn intersection(self, other: Self) -> Self {
+        Self {
+            bits: self.bits & other.bits,
+        }
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
 /// Provides methods to add, replace or delete data in the cache
 ///
 /// This is for adding support for a backend, users of the cache itself only
@@ -41,6 +132,12 @@ impl<E: Display + Send> From<E> for cache::Error<E> {
 /// All of the data in the cache should be cleared every time the bot restarts
 /// so that the cache can be rebuilt without the now-invalid data
 ///
+/// If the backend persists the schema itself, for example the tables, it
+/// should also persist [`CURRENT_SCHEMA_VERSION`] when creating it, and
+/// implement [`Backend::schema_version`] and [`Backend::migrate`], so that
+/// changes to the models in newer versions of this crate are detected at
+/// startup with [`super::Cache::migrate_schema`]
+///
 /// # Uniqueness
 ///
 /// Unless documented otherwise, only the main `id` field is unique, if there's
@@ -58,8 +155,9 @@ impl<E: Display + Send> From<E> for cache::Error<E> {
 /// It's also advisable to implement your backend library's traits to
 /// (de)serialize Discord models for the backend to streamline your codebase
 ///
-/// Creating indexes for every ID field/column (for example, both `user_id` and
-/// `guild_id` in `users`) will be a huge performance improvement
+/// The indexes the queries of these methods need are returned by
+/// [`crate::schema::recommended_indexes`], creating them will be a huge
+/// performance improvement
 ///
 /// # Example
 ///
@@ -108,9 +206,234 @@ pub trait Backend {
     /// The error type the backend returns, for example `sqlx::Error`
     type Error: Error + Send + Sync + 'static;
 
+    /// Get the schema version the data in the backend was written with, or
+    /// `None` if the backend has no data yet
+    ///
+    /// Returns [`CURRENT_SCHEMA_VERSION`] by default, which is correct for
+    /// backends that don't persist their schema
+    ///
+    /// This method is used internally in [`super::Cache::migrate_schema`]
+    async fn schema_version(&self) -> Result<Option<u32>, Self::Error> {
+        Ok(Some(CURRENT_SCHEMA_VERSION))
+    }
+
+    /// Migrate the data in the backend from the schema version `from` to `to`,
+    /// then persist `to` as the schema version
+    ///
+    /// The changes of each version are described in [`crate::schema::MIGRATIONS`],
+    /// since the data in the cache is cleared on restarts anyway, dropping
+    /// and recreating the schema is usually enough
+    ///
+    /// Does nothing by default, which is correct for backends that don't
+    /// persist their schema
+    ///
+    /// This method is used internally in [`super::Cache::migrate_schema`]
+    async fn migrate(&self, _from: u32, _to: u32) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Prepare the backend for the queries the cache runs, for example by
+    /// creating prepared statements, indexes or materialized views
+    ///
+    /// The queries are the ones described by the methods of this trait, the
+    /// getters and helpers of [`super::Cache`] only run them, the indexes they
+    /// need are returned by [`crate::schema::recommended_indexes`]
+    ///
+    /// Does nothing by default, which is correct for backends that don't plan
+    /// their queries
+    ///
+    /// This method is used internally in [`super::Cache::init`]
+    async fn prepare(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Check whether the backend is reachable and working, for example to
+    /// report it in a health endpoint
+    ///
+    /// Does nothing by default, which is correct for in-memory backends
+    ///
+    /// This should be something like `SELECT 1`
+    async fn health_check(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Get the features the backend supports, so that wrappers and
+    /// [`super::Cache`] methods can adapt to the backend
+    ///
+    /// Returns [`Capabilities::empty`] by default
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::empty()
+    }
+
+    /// Return whether the backend removes the data that belongs to an entity
+    /// when the entity is removed, so that [`super::Cache`] doesn't remove it
+    /// with separate calls
+    ///
+    /// When it's `true`, the backend is expected to remove:
+    ///
+    /// - The embeds, embed fields, attachments, attachment posts, reactions,
+    ///   stickers and revisions of a message in [`Backend::delete_message`]
+    ///
+    /// - The channels, permission overwrites, emojis, stickers, members,
+    ///   presences, role assignments, roles and stage instances of a guild in
+    ///   [`Backend::delete_guild`]
+    ///
+    /// - The permission overwrites and private channel recipients of a channel
+    ///   in [`Backend::delete_channel`]
+    ///
+    /// - The role assignments of a role in [`Backend::delete_role`] and of a
+    ///   member in [`Backend::delete_member`]
+    ///
+    /// Returns whether [`Backend::capabilities`] contains
+    /// [`Capabilities::CASCADE`] by default
+    fn supports_cascade(&self) -> bool {
+        self.capabilities().contains(Capabilities::CASCADE)
+    }
+
     /// Set or replace the current user information of the bot
     async fn set_current_user(&self, current_user: CurrentUser) -> Result<(), Self::Error>;
 
+    /// Set or replace the current application information of the bot
+    async fn set_current_application(
+        &self,
+        application: PartialApplication,
+    ) -> Result<(), Self::Error>;
+
+    /// Add or replace a shard's session in the cache
+    ///
+    /// The session's shard ID is unique
+    async fn upsert_session(&self, session: CachedSession) -> Result<(), Self::Error>;
+
+    /// Add or replace a shard's state in the cache
+    ///
+    /// The state's shard ID is unique
+    ///
+    /// This method is used internally in [`super::Cache::update_sharded`]
+    async fn set_shard_state(&self, state: CachedShardState) -> Result<(), Self::Error>;
+
+    /// Add or replace an interaction in the cache
+    ///
+    /// This method is used internally in [`super::Cache::update`] if
+    /// [`crate::config::CacheConfig::interactions`] is enabled
+    async fn upsert_interaction(&self, interaction: CachedInteraction) -> Result<(), Self::Error>;
+
+    /// Remove the interactions whose tokens expired before the given timestamp
+    /// from the cache
+    ///
+    /// This method is used internally in [`super::Cache::sweep`] if
+    /// [`crate::config::CacheConfig::interactions`] is enabled
+    ///
+    /// This should be something like `DELETE FROM interactions WHERE
+    /// token_expires_at < ?`
+    async fn delete_expired_interactions(&self, now: Timestamp) -> Result<(), Self::Error>;
+
+    /// Record the event with the given key as applied at the given time,
+    /// returning whether it was already recorded
+    ///
+    /// This method is used internally in [`super::Cache::update`] if
+    /// [`crate::config::CacheConfig::event_dedup`] is enabled
+    ///
+    /// This should be something like `INSERT OR IGNORE INTO seen_events (key,
+    /// seen_at) VALUES (?, ?)`, returning `true` if no rows were inserted
+    async fn seen_event(&self, key: String, now: Timestamp) -> Result<bool, Self::Error>;
+
+    /// Remove the key of the event recorded with [`Self::seen_event`], so that
+    /// the event is applied if it's received again
+    ///
+    /// This method is used internally in [`super::Cache::update`] if
+    /// [`crate::config::CacheConfig::event_dedup`] is enabled and the event
+    /// couldn't be applied
+    ///
+    /// This should be something like `DELETE FROM seen_events WHERE key = ?`
+    async fn forget_event(&self, key: String) -> Result<(), Self::Error>;
+
+    /// Remove the keys of the events applied before the given timestamp
+    ///
+    /// This method is used internally in [`super::Cache::sweep`] if
+    /// [`crate::config::CacheConfig::event_dedup`] is enabled
+    ///
+    /// This should be something like `DELETE FROM seen_events WHERE seen_at <
+    /// ?`
+    async fn delete_seen_events_before(&self, timestamp: Timestamp) -> Result<(), Self::Error>;
+
+    /// Record the sequence number of the latest update of the entity with the
+    /// given key, received by the given shard, if it's higher than the
+    /// recorded one, returning whether it was recorded
+    ///
+    /// This method is used internally in [`super::Cache::update_sharded`] if
+    /// [`crate::config::CacheConfig::ordered_updates`] is enabled, the update
+    /// is skipped if this returns `false`
+    ///
+    /// This should be something like `INSERT INTO update_sequences (key,
+    /// shard_id, sequence) VALUES (?, ?, ?) ON CONFLICT (key) DO UPDATE SET
+    /// shard_id = excluded.shard_id, sequence = excluded.sequence WHERE
+    /// excluded.sequence > sequence`, returning `true` if a row was changed
+    async fn advance_update_sequence(
+        &self,
+        key: String,
+        shard_id: u64,
+        sequence: u64,
+    ) -> Result<bool, Self::Error>;
+
+    /// Remove the sequence numbers recorded for the given shard
+    ///
+    /// This method is used internally in [`super::Cache::update_sharded`] when
+    /// the shard receives a ready event, since sequence numbers restart in a
+    /// new session
+    ///
+    /// This should be something like `DELETE FROM update_sequences WHERE
+    /// shard_id = ?`
+    async fn delete_shard_update_sequences(&self, shard_id: u64) -> Result<(), Self::Error>;
+
+    /// Add or replace the value of an extension's key
+    ///
+    /// Extensions are layers wrapping the cache that need to persist their own
+    /// state, such as memoized results or the state of shards, they store it
+    /// here instead of requiring backends to define tables for them, the
+    /// namespace is the extension's name so that their keys don't collide
+    ///
+    /// The namespace and key are unique together
+    ///
+    /// This should be something like `INSERT OR REPLACE INTO extensions
+    /// (namespace, key, value) VALUES (?, ?, ?)`
+    async fn set_extension(
+        &self,
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error>;
+
+    /// Return the value of an extension's key, `None` if it isn't set
+    ///
+    /// This should be something like `SELECT value FROM extensions WHERE
+    /// namespace = ? AND key = ?`
+    async fn get_extension(
+        &self,
+        namespace: String,
+        key: String,
+    ) -> Result<Option<Vec<u8>>, Self::Error>;
+
+    /// Remove the value of an extension's key
+    ///
+    /// This should be something like `DELETE FROM extensions WHERE namespace =
+    /// ? AND key = ?`
+    async fn delete_extension(&self, namespace: String, key: String) -> Result<(), Self::Error>;
+
+    /// Set the time of the latest message in a channel, keeping the later one
+    /// if a later time is already set
+    ///
+    /// This method is used internally in [`super::Cache::update`] if
+    /// [`crate::config::CacheConfig::channel_activity`] is enabled
+    ///
+    /// This should be something like `INSERT INTO channel_activity
+    /// (channel_id, last_activity) VALUES (?, ?) ON CONFLICT (channel_id) DO
+    /// UPDATE SET last_activity = MAX(last_activity, excluded.last_activity)`
+    async fn set_channel_last_activity(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error>;
+
     /// Add or replace a channel in the cache
     async fn upsert_channel(&self, channel: CachedChannel) -> Result<(), Self::Error>;
 
@@ -139,18 +462,253 @@ pub trait Backend {
         channel_id: Id<ChannelMarker>,
     ) -> Result<(), Self::Error>;
 
+    /// Get the permission overwrite of a channel for the given member or role
+    ///
+    /// This method is used internally in [`super::Cache::member_overwrites`]
+    /// and [`super::Cache::role_overwrites`]
+    ///
+    /// This should be something like `SELECT * FROM permission_overwrites
+    /// WHERE channel_id = ? AND id = ? AND kind = ?`
+    async fn select_overwrites_by_target(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        target_id: Id<GenericMarker>,
+        kind: PermissionOverwriteType,
+    ) -> Result<Option<CachedPermissionOverwrite>, Self::Error>;
+
+    /// Add a DM channel's recipient to the cache
+    ///
+    /// Only the combination of channel ID and user ID is unique, they're not
+    /// unique on their own
+    async fn upsert_private_channel(
+        &self,
+        private_channel: CachedPrivateChannel,
+    ) -> Result<(), Self::Error>;
+
+    /// Remove a DM channel's recipients from the cache
+    ///
+    /// This should be something like `DELETE FROM private_channels WHERE
+    /// channel_id = ?`
+    async fn delete_private_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error>;
+
     /// Add or replace a message in the cache
     async fn upsert_message(&self, message: CachedMessage) -> Result<(), Self::Error>;
 
+    /// Add or replace the given messages in the cache
+    ///
+    /// Calls [`Backend::upsert_message`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_messages(&self, messages: Vec<CachedMessage>) -> Result<(), Self::Error> {
+        for message in messages {
+            self.upsert_message(message).await?;
+        }
+
+        Ok(())
+    }
+
     /// Remove a message from the cache
     async fn delete_message(&self, message_id: Id<MessageMarker>) -> Result<(), Self::Error>;
 
+    /// Remove the messages with the given IDs from the cache
+    ///
+    /// Calls [`Backend::delete_message`] for each message by default,
+    /// backends that can remove them in one query should override it
+    ///
+    /// This method is used internally when messages are bulk deleted
+    ///
+    /// This should be something like `DELETE FROM messages WHERE id IN (?, ?)`
+    async fn delete_messages(&self, message_ids: &[Id<MessageMarker>]) -> Result<(), Self::Error> {
+        for &message_id in message_ids {
+            self.delete_message(message_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the embeds, embed fields, attachments, attachment posts,
+    /// reactions, stickers and revisions of the messages with the given IDs
+    /// from the cache
+    ///
+    /// Calls the methods to remove each of them for each message by default,
+    /// backends that can remove them in a few queries should override it
+    ///
+    /// This method is used internally when messages are bulk deleted
+    ///
+    /// This should be something like `DELETE FROM attachments WHERE message_id
+    /// IN (?, ?)` for each of them
+    async fn delete_messages_data(
+        &self,
+        message_ids: &[Id<MessageMarker>],
+    ) -> Result<(), Self::Error> {
+        for &message_id in message_ids {
+            let embed_ids: Vec<_> = self
+                .select_message_embeds(message_id)
+                .await?
+                .iter()
+                .map(|embed| embed.id)
+                .collect();
+            self.delete_embed_fields_bulk(&embed_ids).await?;
+            self.delete_embeds_bulk(&embed_ids).await?;
+            #[cfg(feature = "embeds-json")]
+            self.delete_message_embeds_json(message_id).await?;
+            self.delete_message_attachments(message_id).await?;
+            self.delete_message_reactions(message_id).await?;
+            self.delete_message_stickers(message_id).await?;
+            self.delete_message_revisions(message_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove the messages created before the given timestamp from the cache
+    ///
+    /// Their embeds, attachments, reactions and stickers are removed
+    /// afterwards with [`Backend::delete_orphaned_message_data`]
+    ///
+    /// This method is used internally in [`super::Cache::sweep`]
+    ///
+    /// This should be something like `DELETE FROM messages WHERE timestamp <
+    /// ?`
+    async fn delete_messages_before(&self, timestamp: Timestamp) -> Result<(), Self::Error>;
+
+    /// Remove the messages in the guild created before the given timestamp
+    /// from the cache
+    ///
+    /// Their embeds, attachments, reactions and stickers are removed
+    /// afterwards with [`Backend::delete_orphaned_message_data`]
+    ///
+    /// This method is used internally in [`super::Cache::sweep`] for the
+    /// guilds with a retention set with
+    /// [`crate::config::CacheConfig::guild_message_retention`]
+    ///
+    /// This should be something like `DELETE FROM messages WHERE guild_id = ?
+    /// AND timestamp < ?`
+    async fn delete_guild_messages_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error>;
+
+    /// Get the IDs of the users that sent a cached message in the guild since
+    /// the given timestamp
+    ///
+    /// This method is used internally in [`super::Cache::inactive_members`]
+    ///
+    /// This should be something like `SELECT DISTINCT author FROM messages
+    /// WHERE guild_id = ? AND timestamp >= ?`
+    async fn select_guild_message_authors(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error>;
+
+    /// Get the most recent cached message the user sent in the channel
+    ///
+    /// This method is used internally in [`super::Cache::can_send_now`]
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE channel_id
+    /// = ? AND author = ? ORDER BY timestamp DESC LIMIT 1`
+    async fn select_user_last_message(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedMessage>, Self::Error>;
+
+    /// Get a channel's most recent `limit` messages sent after `after` and
+    /// before `before`, excluding both, `None` meaning no bound
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This method is used internally in
+    /// [`super::Cache::channel_messages_range`]
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE channel_id
+    /// = ? AND timestamp > ? AND timestamp < ? ORDER BY timestamp DESC LIMIT
+    /// ?`
+    async fn select_channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error>;
+
+    /// Get a channel's most recent `limit` messages that pass the filter
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This method is used internally in
+    /// [`super::Cache::channel_messages_filtered`]
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE channel_id
+    /// = ? AND author_bot = false ORDER BY timestamp DESC LIMIT ?`, with the
+    /// conditions depending on what the filter excludes
+    async fn select_filtered_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error>;
+
+    /// Remove the embeds, embed fields, attachments, attachment posts,
+    /// reactions, stickers and message revisions whose messages or embeds
+    /// aren't in the cache, and the attachment blobs that aren't posted in any
+    /// message
+    ///
+    /// Stickers that aren't in a message shouldn't be removed
+    ///
+    /// This method is used internally in [`super::Cache::sweep`]
+    ///
+    /// This should be something like `DELETE FROM attachments WHERE message_id
+    /// NOT IN (SELECT id FROM messages)` for each of them
+    async fn delete_orphaned_message_data(&self) -> Result<(), Self::Error>;
+
+    /// Add a message revision to the cache
+    async fn insert_message_revision(
+        &self,
+        revision: CachedMessageRevision,
+    ) -> Result<(), Self::Error>;
+
+    /// Remove a message's revisions from the cache
+    ///
+    /// This should be something like `DELETE FROM message_revisions WHERE
+    /// message_id = ?`
+    async fn delete_message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error>;
+
     /// Add an embed to the cache
     async fn upsert_embed(&self, embed: CachedEmbed) -> Result<(), Self::Error>;
 
     /// Remove an embed from the cache
     async fn delete_embed(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error>;
 
+    /// Remove the embeds with the given IDs from the cache
+    ///
+    /// Calls [`Backend::delete_embed`] for each embed by default, backends
+    /// that can remove them in one query should override it
+    ///
+    /// This method is used internally when removing messages
+    ///
+    /// This should be something like `DELETE FROM embeds WHERE id IN (?, ?)`
+    async fn delete_embeds_bulk(&self, embed_ids: &[Id<GenericMarker>]) -> Result<(), Self::Error> {
+        for &embed_id in embed_ids {
+            self.delete_embed(embed_id).await?;
+        }
+
+        Ok(())
+    }
+
     /// Add an embed field to the cache
     ///
     /// None of the fields in this type is unique
@@ -162,6 +720,26 @@ pub trait Backend {
     /// ?`
     async fn delete_embed_fields(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error>;
 
+    /// Remove the fields of the embeds with the given IDs from the cache
+    ///
+    /// Calls [`Backend::delete_embed_fields`] for each embed by default,
+    /// backends that can remove them in one query should override it
+    ///
+    /// This method is used internally when removing messages
+    ///
+    /// This should be something like `DELETE FROM embed_fields WHERE embed_id
+    /// IN (?, ?)`
+    async fn delete_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<(), Self::Error> {
+        for &embed_id in embed_ids {
+            self.delete_embed_fields(embed_id).await?;
+        }
+
+        Ok(())
+    }
+
     /// Get embeds of a message by its ID
     ///
     /// This method is used internally in [`super::Cache::embeds`]
@@ -172,19 +750,95 @@ pub trait Backend {
 
     /// Get fields of an embed by its ID
     ///
-    /// This method is used internally in [`super::Cache::embeds`]
+    /// This method is used internally in the default implementation of
+    /// [`Backend::select_embed_fields_bulk`]
     async fn select_embed_fields(
         &self,
         embed_id: Id<GenericMarker>,
     ) -> Result<Vec<CachedEmbedField>, Self::Error>;
 
+    /// Get the fields of the embeds with the given IDs, in the order they
+    /// were added
+    ///
+    /// Calls [`Backend::select_embed_fields`] for each embed by default,
+    /// backends that can get them in one query should override it
+    ///
+    /// This method is used internally in [`super::Cache::embeds`]
+    ///
+    /// This should be something like `SELECT * FROM embed_fields WHERE
+    /// embed_id IN (?, ?)`
+    async fn select_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        let mut fields = vec![];
+        for &embed_id in embed_ids {
+            fields.extend(self.select_embed_fields(embed_id).await?);
+        }
+
+        Ok(fields)
+    }
+
+    /// Get the IDs of the embeds that aren't in the cache but have fields in
+    /// the cache
+    ///
+    /// This method is used internally in [`super::Cache::verify_integrity`]
+    ///
+    /// This should be something like `SELECT DISTINCT embed_id FROM
+    /// embed_fields WHERE embed_id NOT IN (SELECT id FROM embeds)`
+    async fn select_orphaned_embed_fields(&self) -> Result<Vec<Id<GenericMarker>>, Self::Error>;
+
+    /// Get the IDs of the messages that aren't in the cache but have embeds in
+    /// the cache
+    ///
+    /// This method is used internally in [`super::Cache::verify_integrity`]
+    ///
+    /// This should be something like `SELECT DISTINCT message_id FROM embeds
+    /// WHERE message_id NOT IN (SELECT id FROM messages)`
+    async fn select_orphaned_embeds(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error>;
+
+    /// Get the IDs of the messages that aren't in the cache but have
+    /// attachments in the cache
+    ///
+    /// This method is used internally in [`super::Cache::verify_integrity`]
+    ///
+    /// This should be something like `SELECT DISTINCT message_id FROM
+    /// attachments WHERE message_id NOT IN (SELECT id FROM messages)`
+    async fn select_orphaned_attachments(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error>;
+
+    /// Get the IDs of the messages that aren't in the cache but have reactions
+    /// in the cache
+    ///
+    /// This method is used internally in [`super::Cache::verify_integrity`]
+    ///
+    /// This should be something like `SELECT DISTINCT message_id FROM
+    /// reactions WHERE message_id NOT IN (SELECT id FROM messages)`
+    async fn select_orphaned_reactions(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error>;
+
     /// Add an attachment to the cache
     async fn upsert_attachment(&self, attachment: CachedAttachment) -> Result<(), Self::Error>;
 
-    /// Remove a message's attachments from the cache
+    /// Add the file of an attachment to the cache, replacing the one with the
+    /// same URL hash
+    ///
+    /// This method is used internally when
+    /// [`crate::config::CacheConfig::attachment_dedup`] is enabled
+    async fn upsert_attachment_blob(&self, blob: CachedAttachmentBlob) -> Result<(), Self::Error>;
+
+    /// Add the post of an attachment in a message to the cache
+    ///
+    /// This method is used internally when
+    /// [`crate::config::CacheConfig::attachment_dedup`] is enabled
+    async fn upsert_attachment_post(&self, post: CachedAttachmentPost) -> Result<(), Self::Error>;
+
+    /// Remove a message's attachments and attachment posts from the cache
+    ///
+    /// The attachment blobs are removed afterwards with
+    /// [`Backend::delete_orphaned_message_data`], since they might be posted
+    /// in other messages
     ///
     /// This should be something like `DELETE FROM attachments WHERE message_id
-    /// = ?`
+    /// = ?`, and the same for `attachment_posts`
     async fn delete_message_attachments(
         &self,
         message_id: Id<MessageMarker>,
@@ -196,7 +850,24 @@ pub trait Backend {
     /// not unique on their own
     async fn upsert_reaction(&self, reaction: CachedReaction) -> Result<(), Self::Error>;
 
+    /// Add the given reactions to the cache
+    ///
+    /// Calls [`Backend::upsert_reaction`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_reactions(&self, reactions: Vec<CachedReaction>) -> Result<(), Self::Error> {
+        for reaction in reactions {
+            self.upsert_reaction(reaction).await?;
+        }
+
+        Ok(())
+    }
+
     /// Remove a reaction from the cache
+    ///
+    /// `emoji` is the reaction's [`CachedReaction::emoji`], returned by
+    /// [`crate::model::CachedReactionEmoji::key`]
     async fn delete_reaction(
         &self,
         message_id: Id<MessageMarker>,
@@ -206,6 +877,10 @@ pub trait Backend {
 
     /// Remove a message's reactions of the given emoji from the cache
     ///
+    /// `emoji` is the reactions' [`CachedReaction::emoji`], returned by
+    /// [`crate::model::CachedReactionEmoji::key`], so custom emojis are
+    /// matched by their IDs even if they were renamed
+    ///
     /// This should be something like `DELETE FROM reactions WHERE message_id =
     /// ? AND emoji = ?`
     async fn delete_message_reactions_by_emoji(
@@ -229,6 +904,35 @@ pub trait Backend {
     /// unique on their own
     async fn upsert_member(&self, member: CachedMember) -> Result<(), Self::Error>;
 
+    /// Add or replace the given members in the cache
+    ///
+    /// Calls [`Backend::upsert_member`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_members(&self, members: Vec<CachedMember>) -> Result<(), Self::Error> {
+        for member in members {
+            self.upsert_member(member).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the fields of a cached member that are `Some` in the patch,
+    /// doing nothing if the member isn't cached
+    ///
+    /// This method is used internally in [`super::Cache::update`], only with
+    /// patches that change at least one field
+    ///
+    /// This should be something like `UPDATE members SET nick = ?, ... WHERE
+    /// guild_id = ? AND id = ?`, setting only the changed columns
+    async fn patch_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        patch: MemberPatch,
+    ) -> Result<(), Self::Error>;
+
     /// Remove a member from the cache
     async fn delete_member(
         &self,
@@ -241,12 +945,58 @@ pub trait Backend {
     /// This should be something like `DELETE FROM members WHERE guild_id = ?`
     async fn delete_guild_members(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error>;
 
+    /// Get the IDs of the guilds that both users are cached as members of
+    ///
+    /// This method is used internally in [`super::Cache::mutual_guilds`]
+    ///
+    /// This should be something like `SELECT a.guild_id FROM members AS a JOIN
+    /// members AS b ON a.guild_id = b.guild_id WHERE a.id = ? AND b.id = ?`
+    async fn select_mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Self::Error>;
+
+    /// Get `limit` members of the guild whose nickname or username starts
+    /// with the prefix, ignoring case
+    ///
+    /// A limit of 0 means to return all matching members
+    ///
+    /// The members are ordered by their nickname, or their username if they
+    /// don't have one
+    ///
+    /// This method is used internally in [`super::Cache::search_members`]
+    ///
+    /// This should be something like `SELECT * FROM members WHERE guild_id =
+    /// ? AND (nick LIKE ? OR name LIKE ?) ORDER BY COALESCE(nick, name) LIMIT
+    /// ?`, with `%` appended to the prefix and the wildcards in it escaped
+    async fn select_members_by_prefix(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Self::Error>;
+
     /// Add or replace a presence in the cache
     ///
     /// Only the combination of guild ID and user ID is unique, they're not
     /// unique on their own
     async fn upsert_presence(&self, presence: CachedPresence) -> Result<(), Self::Error>;
 
+    /// Add or replace the given presences in the cache
+    ///
+    /// Calls [`Backend::upsert_presence`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_presences(&self, presences: Vec<CachedPresence>) -> Result<(), Self::Error> {
+        for presence in presences {
+            self.upsert_presence(presence).await?;
+        }
+
+        Ok(())
+    }
+
     /// Remove a presence from the cache
     async fn delete_presence(
         &self,
@@ -259,11 +1009,80 @@ pub trait Backend {
     /// This should be something like `DELETE FROM presences WHERE guild_id = ?`
     async fn delete_guild_presences(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error>;
 
+    /// Remove the presences and activities of the users whose members aren't
+    /// in the cache
+    ///
+    /// This method is used internally in [`super::Cache::sweep`]
+    ///
+    /// This should be something like `DELETE FROM presences WHERE NOT EXISTS
+    /// (SELECT 1 FROM members WHERE members.guild_id = presences.guild_id AND
+    /// members.id = presences.user_id)` and the same for activities
+    async fn delete_memberless_presences(&self) -> Result<(), Self::Error>;
+
+    /// Set the time the entity of the given kind and ID expires at
+    ///
+    /// `guild_id` is passed for members and presences, since their IDs are only
+    /// unique in a guild, and `None` for the other kinds
+    ///
+    /// This method is used internally in [`super::Cache::update`] for the
+    /// kinds that have a TTL set with [`crate::config::CacheConfig::ttl`]
+    ///
+    /// Backends with native expiry such as Redis should set the TTL of the
+    /// entity's key, other backends should store the timestamp so that the
+    /// entity is removed in [`Backend::delete_expired`], this should be
+    /// something like `UPDATE presences SET expires_at = ? WHERE guild_id = ?
+    /// AND user_id = ?`
+    async fn set_expiry(
+        &self,
+        kind: cache::EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+        expires_at: Timestamp,
+    ) -> Result<(), Self::Error>;
+
+    /// Remove the entities that expired before the given timestamp from the
+    /// cache
+    ///
+    /// Backends that implement [`Backend::set_expiry`] with native expiry can
+    /// make this a no-op
+    ///
+    /// This method is used internally in [`super::Cache::sweep`] when any TTL
+    /// is set
+    ///
+    /// This should be something like `DELETE FROM presences WHERE expires_at <
+    /// ?` for every table with an `expires_at` column
+    async fn delete_expired(&self, now: Timestamp) -> Result<(), Self::Error>;
+
+    /// Get the number of cached entities of every kind, and the storage they
+    /// take up if the backend can tell
+    ///
+    /// Kinds the backend doesn't count can be left out of the map
+    ///
+    /// This method is used internally in [`super::Cache::stats`]
+    ///
+    /// This should be something like `SELECT COUNT(*) FROM channels` for
+    /// every kind
+    async fn entity_counts(&self) -> Result<HashMap<cache::EntityKind, EntityCount>, Self::Error>;
+
     /// Add an activity to the cache
     ///
     /// None of the fields in this type is unique
     async fn upsert_activity(&self, activity: CachedActivity) -> Result<(), Self::Error>;
 
+    /// Add the given activities to the cache
+    ///
+    /// Calls [`Backend::upsert_activity`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_activities(&self, activities: Vec<CachedActivity>) -> Result<(), Self::Error> {
+        for activity in activities {
+            self.upsert_activity(activity).await?;
+        }
+
+        Ok(())
+    }
+
     /// Remove a user's activities from the cache
     ///
     /// This should be something like `DELETE FROM activities WHERE guild_id = ?
@@ -277,42 +1096,175 @@ pub trait Backend {
     /// Add or replace a guild in the cache
     async fn upsert_guild(&self, guild: CachedGuild) -> Result<(), Self::Error>;
 
+    /// Update the fields of a cached guild that are `Some` in the patch, doing
+    /// nothing if the guild isn't cached
+    ///
+    /// This method is used internally in [`super::Cache::update`], only with
+    /// patches that change at least one field
+    ///
+    /// This should be something like `UPDATE guilds SET name = ?, ... WHERE id
+    /// = ?`, setting only the changed columns
+    async fn patch_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+        patch: GuildPatch,
+    ) -> Result<(), Self::Error>;
+
+    /// Get the cached guilds that have the given feature
+    ///
+    /// This method is used internally in [`super::Cache::guilds_with_feature`]
+    ///
+    /// This should be something like `SELECT * FROM guilds WHERE features LIKE
+    /// '%' || ? || '%'`
+    async fn select_guilds_by_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Self::Error>;
+
     /// Remove a channel from the cache
     async fn delete_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error>;
 
-    /// Add a role to the cache
+    /// Remove a guild along with its channels, emojis, stickers, members,
+    /// presences, role assignments, roles and stage instances from the cache
     ///
-    /// The role ID is unique only if the role's user ID is `None`
-    async fn insert_role(&self, role: CachedRole) -> Result<(), Self::Error>;
+    /// The permission overwrites of the guild's channels are removed before
+    /// this is called, since they're removed by their channel IDs
+    ///
+    /// Calls the methods to remove each of them and [`Backend::delete_guild`]
+    /// by default, backends that can remove them in one statement or
+    /// transaction should override it
+    ///
+    /// This method is used internally when a guild is removed from the cache,
+    /// such as when the bot is removed from it or its cached contents are
+    /// stale, if [`Backend::supports_cascade`] is `false`
+    async fn delete_guild_cascade(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.delete_guild_channels(guild_id).await?;
+        self.delete_guild_emojis(guild_id).await?;
+        self.delete_guild_stickers(guild_id).await?;
+        self.delete_guild_members(guild_id).await?;
+        self.delete_guild_presences(guild_id).await?;
+        self.delete_guild_role_assignments(guild_id).await?;
+        self.delete_guild_roles(guild_id).await?;
+        self.delete_guild_stage_instances(guild_id).await?;
+        self.delete_guild(guild_id).await
+    }
 
-    /// Update roles in the cache
+    /// Remove the channels, permission overwrites, members, presences,
+    /// activities, roles, role assignments, emojis, stickers and stage
+    /// instances whose guilds aren't in the cache
     ///
-    /// A separate method is necessary to update roles of users
+    /// Channels and stickers without a guild ID shouldn't be removed
     ///
-    /// When updating roles, make sure not to update the user ID field
+    /// This method is used internally in [`super::Cache::sweep`]
     ///
-    /// This should be something like `UPDATE roles SET (...) = (...) WHERE id =
-    /// ?`
-    async fn update_roles(&self, role: CachedRole) -> Result<(), Self::Error>;
+    /// This should be something like `DELETE FROM channels WHERE guild_id NOT
+    /// IN (SELECT id FROM guilds)` for each of them
+    async fn delete_guild_remnants(&self) -> Result<(), Self::Error>;
+
+    /// Add or replace a role in the cache
+    async fn upsert_role(&self, role: CachedRole) -> Result<(), Self::Error>;
 
     /// Remove a role from the cache
     async fn delete_role(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error>;
 
+    /// Get the everyone role of a guild
+    ///
+    /// This method is used internally in [`super::Cache::everyone_role`]
+    ///
+    /// This should be something like `SELECT * FROM roles WHERE guild_id = ?
+    /// AND id = ?`, the everyone role's ID is the guild's ID
+    async fn select_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Self::Error>;
+
+    /// Get the IDs of the guilds that aren't in the cache but have roles in
+    /// the cache
+    ///
+    /// This method is used internally in [`super::Cache::verify_integrity`]
+    ///
+    /// This should be something like `SELECT DISTINCT guild_id FROM roles
+    /// WHERE guild_id NOT IN (SELECT id FROM guilds)`
+    async fn select_orphaned_roles(&self) -> Result<Vec<Id<GuildMarker>>, Self::Error>;
+
     /// Remove a guild's roles from the cache
     ///
     /// This should be something like `DELETE FROM roles WHERE guild_id = ?`
     async fn delete_guild_roles(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error>;
 
-    /// Remove a member's roles from the cache
+    /// Add a role assignment to the cache
     ///
-    /// This should be something like `DELETE FROM roles WHERE guild_id = ? AND
-    /// user_id = ?`
-    async fn delete_member_roles(
+    /// The combination of the guild ID, user ID and role ID is unique
+    async fn upsert_role_assignment(
+        &self,
+        assignment: CachedRoleAssignment,
+    ) -> Result<(), Self::Error>;
+
+    /// Add the given role assignments to the cache
+    ///
+    /// Calls [`Backend::upsert_role_assignment`] for each of them by default, backends
+    /// that can add them in one query or transaction should override it
+    ///
+    /// This method is used internally in [`crate::batching::BatchingCache`]
+    async fn upsert_role_assignments(
+        &self,
+        assignments: Vec<CachedRoleAssignment>,
+    ) -> Result<(), Self::Error> {
+        for assignment in assignments {
+            self.upsert_role_assignment(assignment).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove a role's assignments from the cache
+    ///
+    /// This should be something like `DELETE FROM role_assignments WHERE
+    /// role_id = ?`
+    async fn delete_role_assignments(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error>;
+
+    /// Remove a guild's role assignments from the cache
+    ///
+    /// This should be something like `DELETE FROM role_assignments WHERE
+    /// guild_id = ?`
+    async fn delete_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error>;
+
+    /// Remove a member's role assignments from the cache
+    ///
+    /// This should be something like `DELETE FROM role_assignments WHERE
+    /// guild_id = ? AND user_id = ?`
+    async fn delete_member_role_assignments(
         &self,
         guild_id: Id<GuildMarker>,
         user_id: Id<UserMarker>,
     ) -> Result<(), Self::Error>;
 
+    /// Get the IDs of the users that have the given role
+    ///
+    /// This method is used internally in [`super::Cache::role_members`]
+    ///
+    /// This should be something like `SELECT user_id FROM role_assignments
+    /// WHERE role_id = ?`
+    async fn select_role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error>;
+
+    /// Get the role assignments of a guild
+    ///
+    /// This method is used internally in
+    /// [`super::Cache::members_with_permission`]
+    ///
+    /// This should be something like `SELECT * FROM role_assignments WHERE
+    /// guild_id = ?`
+    async fn select_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRoleAssignment>, Self::Error>;
+
     /// Add or replace an emoji in the cache
     async fn upsert_emoji(&self, emoji: CachedEmoji) -> Result<(), Self::Error>;
 
@@ -340,6 +1292,12 @@ pub trait Backend {
         message_id: Id<MessageMarker>,
     ) -> Result<(), Self::Error>;
 
+    /// Remove a guild sticker from the cache
+    ///
+    /// This should be something like `DELETE FROM stickers WHERE id = ? AND
+    /// message_id IS NULL`
+    async fn delete_sticker(&self, sticker_id: Id<StickerMarker>) -> Result<(), Self::Error>;
+
     /// Remove a guild's stickers from the cache
     ///
     /// This should be something like `DELETE FROM stickers WHERE guild_id = ?
@@ -360,4 +1318,160 @@ pub trait Backend {
         &self,
         guild_id: Id<GuildMarker>,
     ) -> Result<(), Self::Error>;
+
+    /// Add one to the number of times the emoji was used in reactions in the
+    /// guild on the day
+    ///
+    /// `emoji` is either the ID or the name of the emoji, `day` is the number
+    /// of days since the Unix epoch in UTC
+    ///
+    /// This should be something like `INSERT INTO emoji_uses (guild_id, emoji,
+    /// day, uses) VALUES (?, ?, ?, 1) ON CONFLICT (guild_id, emoji, day) DO
+    /// UPDATE SET uses = uses + 1`
+    #[cfg(feature = "stats")]
+    async fn increment_emoji_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        emoji: String,
+        day: u64,
+    ) -> Result<(), Self::Error>;
+
+    /// Add one to the number of times the sticker was sent in messages in the
+    /// guild on the day
+    ///
+    /// `day` is the number of days since the Unix epoch in UTC
+    ///
+    /// This should be something like `INSERT INTO sticker_uses (guild_id,
+    /// sticker_id, day, uses) VALUES (?, ?, ?, 1) ON CONFLICT (guild_id,
+    /// sticker_id, day) DO UPDATE SET uses = uses + 1`
+    #[cfg(feature = "stats")]
+    async fn increment_sticker_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sticker_id: Id<StickerMarker>,
+        day: u64,
+    ) -> Result<(), Self::Error>;
+
+    /// Get the emojis used in reactions in the guild since the day and the
+    /// number of times they were used, the most used first
+    ///
+    /// This method is used internally in [`super::Cache::emoji_usage`]
+    ///
+    /// This should be something like `SELECT emoji, SUM(uses) FROM emoji_uses
+    /// WHERE guild_id = ? AND day >= ? GROUP BY emoji ORDER BY SUM(uses)
+    /// DESC`
+    #[cfg(feature = "stats")]
+    async fn select_emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(String, u64)>, Self::Error>;
+
+    /// Get the stickers sent in messages in the guild since the day and the
+    /// number of times they were sent, the most sent first
+    ///
+    /// This method is used internally in [`super::Cache::sticker_usage`]
+    ///
+    /// This should be something like `SELECT sticker_id, SUM(uses) FROM
+    /// sticker_uses WHERE guild_id = ? AND day >= ? GROUP BY sticker_id ORDER
+    /// BY SUM(uses) DESC`
+    #[cfg(feature = "stats")]
+    async fn select_sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Self::Error>;
+
+    /// Add an entry to the end of the journal
+    ///
+    /// This method is used internally in [`super::Cache::update`] and
+    /// [`super::Cache::update_owned`]
+    ///
+    /// This should be something like `INSERT INTO journal (id, recorded_at,
+    /// kind, data) VALUES (?, ?, ?, ?)`
+    #[cfg(feature = "journal")]
+    async fn append_journal(&self, entry: CachedJournalEntry) -> Result<(), Self::Error>;
+
+    /// Get the journal entries recorded in the given time range, including
+    /// `start` and excluding `end`, the oldest first
+    ///
+    /// This method is used internally in [`super::Cache::replay`]
+    ///
+    /// This should be something like `SELECT * FROM journal WHERE recorded_at
+    /// >= ? AND recorded_at < ? ORDER BY id`
+    #[cfg(feature = "journal")]
+    async fn select_journal(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<CachedJournalEntry>, Self::Error>;
+
+    /// Add an entry to the changelog
+    ///
+    /// This method is used internally in [`super::Cache::update`]
+    ///
+    /// This should be something like `INSERT INTO changelog (id, recorded_at,
+    /// kind, entity_id, before, after) VALUES (?, ?, ?, ?, ?, ?)`
+    #[cfg(feature = "changelog")]
+    async fn append_changelog(&self, entry: CachedChangelogEntry) -> Result<(), Self::Error>;
+
+    /// Get the most recent `limit` changelog entries of an entity, the most
+    /// recent first
+    ///
+    /// A limit of 0 means to return all entries
+    ///
+    /// This method is used internally in [`super::Cache::entity_changelog`]
+    ///
+    /// This should be something like `SELECT * FROM changelog WHERE kind = ?
+    /// AND entity_id = ? ORDER BY id DESC LIMIT ?`
+    #[cfg(feature = "changelog")]
+    async fn select_changelog(
+        &self,
+        kind: cache::EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Self::Error>;
+
+    /// Add the embeds of a message to the cache as one JSON document,
+    /// replacing the message's previous document
+    ///
+    /// The document is a list of [`crate::model::CachedEmbed`]s and their
+    /// [`crate::model::CachedEmbedField`]s, the same as
+    /// [`super::Cache::embeds`] returns
+    ///
+    /// This method is used internally when
+    /// [`crate::config::CacheConfig::embeds_json`] is enabled
+    ///
+    /// This should be something like `INSERT OR REPLACE INTO message_embeds
+    /// (message_id, embeds) VALUES (?, ?)`
+    #[cfg(feature = "embeds-json")]
+    async fn upsert_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: serde_json::Value,
+    ) -> Result<(), Self::Error>;
+
+    /// Get the embeds of a message stored as one JSON document, `None` if
+    /// they aren't stored as JSON
+    ///
+    /// This method is used internally in [`super::Cache::embeds`]
+    ///
+    /// This should be something like `SELECT embeds FROM message_embeds WHERE
+    /// message_id = ?`
+    #[cfg(feature = "embeds-json")]
+    async fn select_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<serde_json::Value>, Self::Error>;
+
+    /// Remove the embeds of a message stored as one JSON document from the
+    /// cache
+    ///
+    /// This should be something like `DELETE FROM message_embeds WHERE
+    /// message_id = ?`
+    #[cfg(feature = "embeds-json")]
+    async fn delete_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error>;
 }
@@ -0,0 +1,1361 @@
+use core::{mem, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwriteType, StageInstance},
+    guild::GuildFeature,
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GenericMarker, GuildMarker, InteractionMarker,
+            MessageMarker, RoleMarker, StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+#[cfg(feature = "autocomplete")]
+use crate::autocomplete::AutocompleteIndex;
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::{
+    backend::Capabilities,
+    cache::{EntityKind, Error},
+    config::{CacheConfig, MessageKindFilter},
+    counts::EntityCount,
+    hooks::CacheHooks,
+    model::{
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker, GuildPatch, MemberPatch,
+    },
+    pool::ArcPool,
+    queue::UpdateQueue,
+    Backend, Cache,
+};
+
+/// Options for when a [`BatchingCache`] flushes its buffered writes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BatchOptions {
+    /// The number of buffered writes that triggers a flush
+    pub(crate) max_count: usize,
+    /// How long the oldest buffered write can wait before a write triggers a
+    /// flush
+    pub(crate) max_delay: Duration,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchOptions {
+    /// Create the default options, flushing after 1000 writes or after a
+    /// second
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_count: 1000,
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Set the number of buffered writes that triggers a flush
+    ///
+    /// Setting this to `0` or `1` disables batching
+    #[must_use]
+    pub const fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count;
+        self
+    }
+
+    /// Set how long the oldest buffered write can wait before a flush
+    ///
+    /// This is only checked when a write is buffered, call
+    /// [`BatchingCache::flush`] periodically to bound the delay in quiet
+    /// periods
+    #[must_use]
+    pub const fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+}
+
+/// The writes buffered by a [`BatchingCache`]
+#[derive(Debug, Default)]
+struct Batch {
+    /// The buffered messages
+    messages: Vec<CachedMessage>,
+    /// The buffered reactions
+    reactions: Vec<CachedReaction>,
+    /// The buffered members
+    members: Vec<CachedMember>,
+    /// The buffered role assignments
+    role_assignments: Vec<CachedRoleAssignment>,
+    /// The buffered presences
+    presences: Vec<CachedPresence>,
+    /// The buffered activities
+    activities: Vec<CachedActivity>,
+    /// When the first write in this batch was buffered
+    started_at: Option<Instant>,
+}
+
+impl Batch {
+    /// Return the number of buffered writes
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    fn len(&self) -> usize {
+        self.messages.len()
+            + self.reactions.len()
+            + self.members.len()
+            + self.role_assignments.len()
+            + self.presences.len()
+            + self.activities.len()
+    }
+
+    /// Return whether the batch should be flushed with the given options
+    fn is_due(&self, options: BatchOptions) -> bool {
+        self.len() >= options.max_count
+            || self.started_at.map_or(false, |started_at| {
+                started_at.elapsed() >= options.max_delay
+            })
+    }
+}
+
+/// A cache that buffers message, reaction, member, role assignment, presence
+/// and activity upserts and writes them in batches
+///
+/// The buffer is flushed when it reaches [`BatchOptions::max_count`] writes,
+/// when a write is buffered after [`BatchOptions::max_delay`], and before the
+/// methods that read the buffered kinds, update or delete them, or delete
+/// what they belong to, so that these always see the buffered writes, other
+/// methods, such as [`Cache::role`] or [`Cache::channel`], don't flush, so
+/// that, for example, the members of a guild create event are written in one
+/// batch even though their roles are read for each of them
+///
+/// Since the delay is only checked when a write is buffered, call
+/// [`BatchingCache::flush`] periodically, and before dropping the cache,
+/// otherwise the buffered writes are lost
+///
+/// If a flush fails, the writes in it are dropped and the error is returned
+/// from the method that triggered the flush
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::batching::{BatchOptions, BatchingCache};
+///
+/// let cache = BatchingCache::new(SqliteCache::new("cache.db").await?, BatchOptions::new());
+/// cache.update(&event).await?;
+/// cache.flush().await?;
+/// ```
+#[derive(Debug)]
+pub struct BatchingCache<C> {
+    /// The wrapped cache
+    inner: C,
+    /// When the buffer is flushed
+    options: BatchOptions,
+    /// The buffered writes
+    batch: Mutex<Batch>,
+}
+
+impl<C> BatchingCache<C> {
+    /// Wrap the given cache
+    #[must_use]
+    pub fn new(inner: C, options: BatchOptions) -> Self {
+        Self {
+            inner,
+            options,
+            batch: Mutex::new(Batch::default()),
+        }
+    }
+
+    /// Return the wrapped cache
+    ///
+    /// Writes that are still buffered aren't visible through it
+    #[must_use]
+    pub const fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Return the number of buffered writes
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Lock the buffer, ignoring poisoning since the buffer is never left in
+    /// an invalid state
+    fn lock(&self) -> MutexGuard<'_, Batch> {
+        self.batch.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<C: Cache + Send + Sync> BatchingCache<C> {
+    /// Write the buffered writes to the wrapped cache
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the wrapped cache returns, in which case the rest of
+    /// the buffered writes are dropped
+    pub async fn flush(&self) -> Result<(), C::Error> {
+        let batch = mem::take(&mut *self.lock());
+
+        if !batch.messages.is_empty() {
+            self.inner.upsert_messages(batch.messages).await?;
+        }
+        if !batch.reactions.is_empty() {
+            self.inner.upsert_reactions(batch.reactions).await?;
+        }
+        if !batch.members.is_empty() {
+            self.inner.upsert_members(batch.members).await?;
+        }
+        if !batch.role_assignments.is_empty() {
+            self.inner
+                .upsert_role_assignments(batch.role_assignments)
+                .await?;
+        }
+        if !batch.presences.is_empty() {
+            self.inner.upsert_presences(batch.presences).await?;
+        }
+        if !batch.activities.is_empty() {
+            self.inner.upsert_activities(batch.activities).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Buffer a write with the given function, flushing if the batch is due
+    async fn buffer(&self, push: impl FnOnce(&mut Batch) + Send) -> Result<(), C::Error> {
+        let is_due = {
+            let mut batch = self.lock();
+            batch.started_at.get_or_insert_with(Instant::now);
+            push(&mut batch);
+            batch.is_due(self.options)
+        };
+
+        if is_due {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<C: Cache + Send + Sync> Backend for BatchingCache<C> {
+    type Error = C::Error;
+
+    async fn schema_version(&self) -> Result<Option<u32>, Self::Error> {
+        self.flush().await?;
+        self.inner.schema_version().await
+    }
+
+    async fn migrate(&self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.migrate(from, to).await
+    }
+
+    async fn prepare(&self) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.prepare().await
+    }
+
+    async fn health_check(&self) -> Result<(), Self::Error> {
+        self.inner.health_check().await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+
+    fn supports_cascade(&self) -> bool {
+        self.inner.supports_cascade()
+    }
+
+    async fn set_current_user(&self, current_user: CurrentUser) -> Result<(), Self::Error> {
+        self.inner.set_current_user(current_user).await
+    }
+
+    async fn set_current_application(
+        &self,
+        application: PartialApplication,
+    ) -> Result<(), Self::Error> {
+        self.inner.set_current_application(application).await
+    }
+
+    async fn upsert_session(&self, session: CachedSession) -> Result<(), Self::Error> {
+        self.inner.upsert_session(session).await
+    }
+
+    async fn set_shard_state(&self, state: CachedShardState) -> Result<(), Self::Error> {
+        self.inner.set_shard_state(state).await
+    }
+
+    async fn upsert_interaction(&self, interaction: CachedInteraction) -> Result<(), Self::Error> {
+        self.inner.upsert_interaction(interaction).await
+    }
+
+    async fn delete_expired_interactions(&self, now: Timestamp) -> Result<(), Self::Error> {
+        self.inner.delete_expired_interactions(now).await
+    }
+
+    async fn seen_event(&self, key: String, now: Timestamp) -> Result<bool, Self::Error> {
+        self.inner.seen_event(key, now).await
+    }
+
+    async fn forget_event(&self, key: String) -> Result<(), Self::Error> {
+        self.inner.forget_event(key).await
+    }
+
+    async fn delete_seen_events_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        self.inner.delete_seen_events_before(timestamp).await
+    }
+
+    async fn set_extension(
+        &self,
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error> {
+        self.inner.set_extension(namespace, key, value).await
+    }
+
+    async fn get_extension(
+        &self,
+        namespace: String,
+        key: String,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.inner.get_extension(namespace, key).await
+    }
+
+    async fn delete_extension(&self, namespace: String, key: String) -> Result<(), Self::Error> {
+        self.inner.delete_extension(namespace, key).await
+    }
+
+    async fn advance_update_sequence(
+        &self,
+        key: String,
+        shard_id: u64,
+        sequence: u64,
+    ) -> Result<bool, Self::Error> {
+        self.inner
+            .advance_update_sequence(key, shard_id, sequence)
+            .await
+    }
+
+    async fn delete_shard_update_sequences(&self, shard_id: u64) -> Result<(), Self::Error> {
+        self.inner.delete_shard_update_sequences(shard_id).await
+    }
+
+    async fn set_channel_last_activity(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .set_channel_last_activity(channel_id, timestamp)
+            .await
+    }
+
+    async fn upsert_channel(&self, channel: CachedChannel) -> Result<(), Self::Error> {
+        self.inner.upsert_channel(channel).await
+    }
+
+    async fn delete_channel(&self, channel_id: Id<ChannelMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_channel(channel_id).await
+    }
+
+    async fn delete_guild_channels(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_channels(guild_id).await
+    }
+
+    async fn upsert_permission_overwrite(
+        &self,
+        permission_overwrite: CachedPermissionOverwrite,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .upsert_permission_overwrite(permission_overwrite)
+            .await
+    }
+
+    async fn delete_channel_permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .delete_channel_permission_overwrites(channel_id)
+            .await
+    }
+
+    async fn select_overwrites_by_target(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        target_id: Id<GenericMarker>,
+        kind: PermissionOverwriteType,
+    ) -> Result<Option<CachedPermissionOverwrite>, Self::Error> {
+        self.inner
+            .select_overwrites_by_target(channel_id, target_id, kind)
+            .await
+    }
+
+    async fn upsert_private_channel(
+        &self,
+        private_channel: CachedPrivateChannel,
+    ) -> Result<(), Self::Error> {
+        self.inner.upsert_private_channel(private_channel).await
+    }
+
+    async fn delete_private_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_private_channel(channel_id).await
+    }
+
+    async fn upsert_message(&self, message: CachedMessage) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.messages.push(message)).await
+    }
+
+    async fn upsert_messages(&self, messages: Vec<CachedMessage>) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.messages.extend(messages)).await
+    }
+
+    async fn delete_message(&self, message_id: Id<MessageMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_message(message_id).await
+    }
+
+    async fn delete_messages(&self, message_ids: &[Id<MessageMarker>]) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_messages(message_ids).await
+    }
+
+    async fn delete_messages_data(
+        &self,
+        message_ids: &[Id<MessageMarker>],
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_messages_data(message_ids).await
+    }
+
+    async fn delete_messages_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_messages_before(timestamp).await
+    }
+
+    async fn delete_guild_messages_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner
+            .delete_guild_messages_before(guild_id, timestamp)
+            .await
+    }
+
+    async fn select_guild_message_authors(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner
+            .select_guild_message_authors(guild_id, since)
+            .await
+    }
+
+    async fn select_user_last_message(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedMessage>, Self::Error> {
+        self.flush().await?;
+        self.inner
+            .select_user_last_message(channel_id, user_id)
+            .await
+    }
+
+    async fn select_channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        self.flush().await?;
+        self.inner
+            .select_channel_messages_range(channel_id, after, before, limit)
+            .await
+    }
+
+    async fn select_filtered_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        self.flush().await?;
+        self.inner
+            .select_filtered_channel_messages(channel_id, filter, limit)
+            .await
+    }
+
+    async fn delete_orphaned_message_data(&self) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_orphaned_message_data().await
+    }
+
+    async fn insert_message_revision(
+        &self,
+        revision: CachedMessageRevision,
+    ) -> Result<(), Self::Error> {
+        self.inner.insert_message_revision(revision).await
+    }
+
+    async fn delete_message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_message_revisions(message_id).await
+    }
+
+    async fn upsert_embed(&self, embed: CachedEmbed) -> Result<(), Self::Error> {
+        self.inner.upsert_embed(embed).await
+    }
+
+    async fn delete_embed(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_embed(embed_id).await
+    }
+
+    async fn delete_embeds_bulk(&self, embed_ids: &[Id<GenericMarker>]) -> Result<(), Self::Error> {
+        self.inner.delete_embeds_bulk(embed_ids).await
+    }
+
+    async fn upsert_embed_field(&self, embed_field: CachedEmbedField) -> Result<(), Self::Error> {
+        self.inner.upsert_embed_field(embed_field).await
+    }
+
+    async fn delete_embed_fields(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_embed_fields(embed_id).await
+    }
+
+    async fn delete_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_embed_fields_bulk(embed_ids).await
+    }
+
+    async fn select_message_embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedEmbed>, Self::Error> {
+        self.inner.select_message_embeds(message_id).await
+    }
+
+    async fn select_embed_fields(
+        &self,
+        embed_id: Id<GenericMarker>,
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        self.inner.select_embed_fields(embed_id).await
+    }
+
+    async fn select_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        self.inner.select_embed_fields_bulk(embed_ids).await
+    }
+
+    async fn select_orphaned_embed_fields(&self) -> Result<Vec<Id<GenericMarker>>, Self::Error> {
+        self.inner.select_orphaned_embed_fields().await
+    }
+
+    async fn select_orphaned_embeds(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_orphaned_embeds().await
+    }
+
+    async fn select_orphaned_attachments(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_orphaned_attachments().await
+    }
+
+    async fn select_orphaned_reactions(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_orphaned_reactions().await
+    }
+
+    async fn upsert_attachment(&self, attachment: CachedAttachment) -> Result<(), Self::Error> {
+        self.inner.upsert_attachment(attachment).await
+    }
+
+    async fn upsert_attachment_blob(&self, blob: CachedAttachmentBlob) -> Result<(), Self::Error> {
+        self.inner.upsert_attachment_blob(blob).await
+    }
+
+    async fn upsert_attachment_post(&self, post: CachedAttachmentPost) -> Result<(), Self::Error> {
+        self.inner.upsert_attachment_post(post).await
+    }
+
+    async fn delete_message_attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_message_attachments(message_id).await
+    }
+
+    async fn upsert_reaction(&self, reaction: CachedReaction) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.reactions.push(reaction)).await
+    }
+
+    async fn upsert_reactions(&self, reactions: Vec<CachedReaction>) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.reactions.extend(reactions)).await
+    }
+
+    async fn delete_reaction(
+        &self,
+        message_id: Id<MessageMarker>,
+        user_id: Id<UserMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_reaction(message_id, user_id, emoji).await
+    }
+
+    async fn delete_message_reactions_by_emoji(
+        &self,
+        message_id: Id<MessageMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner
+            .delete_message_reactions_by_emoji(message_id, emoji)
+            .await
+    }
+
+    async fn delete_message_reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_message_reactions(message_id).await
+    }
+
+    async fn upsert_member(&self, member: CachedMember) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.members.push(member)).await
+    }
+
+    async fn upsert_members(&self, members: Vec<CachedMember>) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.members.extend(members)).await
+    }
+
+    async fn patch_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        patch: MemberPatch,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.patch_member(guild_id, user_id, patch).await
+    }
+
+    async fn delete_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_member(user_id, guild_id).await
+    }
+
+    async fn delete_guild_members(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_members(guild_id).await
+    }
+
+    async fn select_mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_mutual_guilds(user_a, user_b).await
+    }
+
+    async fn select_members_by_prefix(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Self::Error> {
+        self.flush().await?;
+        self.inner
+            .select_members_by_prefix(guild_id, prefix, limit)
+            .await
+    }
+
+    async fn upsert_presence(&self, presence: CachedPresence) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.presences.push(presence)).await
+    }
+
+    async fn upsert_presences(&self, presences: Vec<CachedPresence>) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.presences.extend(presences)).await
+    }
+
+    async fn delete_presence(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_presence(guild_id, user_id).await
+    }
+
+    async fn delete_guild_presences(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_presences(guild_id).await
+    }
+
+    async fn delete_memberless_presences(&self) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_memberless_presences().await
+    }
+
+    async fn set_expiry(
+        &self,
+        kind: EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+        expires_at: Timestamp,
+    ) -> Result<(), Self::Error> {
+        if matches!(
+            kind,
+            EntityKind::Message | EntityKind::Member | EntityKind::Presence
+        ) {
+            self.flush().await?;
+        }
+        self.inner
+            .set_expiry(kind, entity_id, guild_id, expires_at)
+            .await
+    }
+
+    async fn delete_expired(&self, now: Timestamp) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_expired(now).await
+    }
+
+    async fn entity_counts(&self) -> Result<HashMap<EntityKind, EntityCount>, Self::Error> {
+        self.flush().await?;
+        self.inner.entity_counts().await
+    }
+
+    async fn upsert_activity(&self, activity: CachedActivity) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.activities.push(activity)).await
+    }
+
+    async fn upsert_activities(&self, activities: Vec<CachedActivity>) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.activities.extend(activities))
+            .await
+    }
+
+    async fn delete_user_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_user_activities(guild_id, user_id).await
+    }
+
+    async fn upsert_guild(&self, guild: CachedGuild) -> Result<(), Self::Error> {
+        self.inner.upsert_guild(guild).await
+    }
+
+    async fn patch_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+        patch: GuildPatch,
+    ) -> Result<(), Self::Error> {
+        self.inner.patch_guild(guild_id, patch).await
+    }
+
+    async fn select_guilds_by_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Self::Error> {
+        self.inner.select_guilds_by_feature(feature).await
+    }
+
+    async fn delete_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild(guild_id).await
+    }
+
+    async fn delete_guild_cascade(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_cascade(guild_id).await
+    }
+
+    async fn delete_guild_remnants(&self) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_remnants().await
+    }
+
+    async fn upsert_role(&self, role: CachedRole) -> Result<(), Self::Error> {
+        self.inner.upsert_role(role).await
+    }
+
+    async fn delete_role(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_role(role_id).await
+    }
+
+    async fn select_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Self::Error> {
+        self.inner.select_everyone_role(guild_id).await
+    }
+
+    async fn select_orphaned_roles(&self) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        self.inner.select_orphaned_roles().await
+    }
+
+    async fn delete_guild_roles(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_roles(guild_id).await
+    }
+
+    async fn upsert_role_assignment(
+        &self,
+        assignment: CachedRoleAssignment,
+    ) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.role_assignments.push(assignment))
+            .await
+    }
+
+    async fn upsert_role_assignments(
+        &self,
+        assignments: Vec<CachedRoleAssignment>,
+    ) -> Result<(), Self::Error> {
+        self.buffer(|batch| batch.role_assignments.extend(assignments))
+            .await
+    }
+
+    async fn delete_role_assignments(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_role_assignments(role_id).await
+    }
+
+    async fn delete_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner.delete_guild_role_assignments(guild_id).await
+    }
+
+    async fn delete_member_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.flush().await?;
+        self.inner
+            .delete_member_role_assignments(guild_id, user_id)
+            .await
+    }
+
+    async fn select_role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_role_members(role_id).await
+    }
+
+    async fn select_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRoleAssignment>, Self::Error> {
+        self.flush().await?;
+        self.inner.select_guild_role_assignments(guild_id).await
+    }
+
+    async fn upsert_emoji(&self, emoji: CachedEmoji) -> Result<(), Self::Error> {
+        self.inner.upsert_emoji(emoji).await
+    }
+
+    async fn delete_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_emoji(emoji_id).await
+    }
+
+    async fn delete_guild_emojis(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_guild_emojis(guild_id).await
+    }
+
+    async fn upsert_sticker(&self, sticker: CachedSticker) -> Result<(), Self::Error> {
+        self.inner.upsert_sticker(sticker).await
+    }
+
+    async fn delete_message_stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_message_stickers(message_id).await
+    }
+
+    async fn delete_sticker(&self, sticker_id: Id<StickerMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_sticker(sticker_id).await
+    }
+
+    async fn delete_guild_stickers(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_guild_stickers(guild_id).await
+    }
+
+    async fn upsert_stage_instance(&self, stage: StageInstance) -> Result<(), Self::Error> {
+        self.inner.upsert_stage_instance(stage).await
+    }
+
+    async fn delete_stage_instance(&self, stage_id: Id<StageMarker>) -> Result<(), Self::Error> {
+        self.inner.delete_stage_instance(stage_id).await
+    }
+
+    async fn delete_guild_stage_instances(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_guild_stage_instances(guild_id).await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_emoji_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        emoji: String,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        self.inner.increment_emoji_use(guild_id, emoji, day).await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_sticker_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sticker_id: Id<StickerMarker>,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .increment_sticker_use(guild_id, sticker_id, day)
+            .await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(String, u64)>, Self::Error> {
+        self.inner.select_emoji_usage(guild_id, since_day).await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Self::Error> {
+        self.inner.select_sticker_usage(guild_id, since_day).await
+    }
+
+    #[cfg(feature = "journal")]
+    async fn append_journal(&self, entry: CachedJournalEntry) -> Result<(), Self::Error> {
+        self.inner.append_journal(entry).await
+    }
+
+    #[cfg(feature = "journal")]
+    async fn select_journal(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<CachedJournalEntry>, Self::Error> {
+        self.inner.select_journal(start, end).await
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn append_changelog(&self, entry: CachedChangelogEntry) -> Result<(), Self::Error> {
+        self.inner.append_changelog(entry).await
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn select_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Self::Error> {
+        self.inner.select_changelog(kind, entity_id, limit).await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn upsert_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: serde_json::Value,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .upsert_message_embeds_json(message_id, embeds)
+            .await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn select_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<serde_json::Value>, Self::Error> {
+        self.inner.select_message_embeds_json(message_id).await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn delete_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.inner.delete_message_embeds_json(message_id).await
+    }
+}
+
+#[async_trait]
+impl<C: Cache + Send + Sync> Cache for BatchingCache<C> {
+    fn config(&self) -> &CacheConfig {
+        self.inner.config()
+    }
+
+    fn hooks(&self) -> Option<&dyn CacheHooks> {
+        self.inner.hooks()
+    }
+
+    fn update_queue(&self) -> Option<&UpdateQueue> {
+        self.inner.update_queue()
+    }
+
+    fn arc_pool(&self) -> Option<&ArcPool> {
+        self.inner.arc_pool()
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn autocomplete_index(&self) -> Option<&AutocompleteIndex> {
+        self.inner.autocomplete_index()
+    }
+
+    async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>> {
+        self.inner.current_user().await
+    }
+
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>> {
+        self.inner.current_application().await
+    }
+
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>> {
+        self.inner.session_info().await
+    }
+
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>> {
+        self.inner.shard_state(shard_id).await
+    }
+
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>> {
+        self.inner.shard_states().await
+    }
+
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>> {
+        self.inner.interaction(interaction_id).await
+    }
+
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        self.inner.channel(channel_id).await
+    }
+
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>> {
+        self.inner.permission_overwrites(channel_id).await
+    }
+
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        self.inner.private_channel(user_id).await
+    }
+
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        self.inner.private_channel_users(channel_id).await
+    }
+
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.inner.guild_channels(guild_id).await
+    }
+
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.inner.inactive_channels(guild_id, since).await
+    }
+
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.inner.archived_threads(guild_id).await
+    }
+
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.inner.active_threads(channel_id).await
+    }
+
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.message(message_id).await
+    }
+
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>> {
+        self.inner.message_revisions(message_id).await
+    }
+
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>> {
+        self.inner.attachments(message_id).await
+    }
+
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.attachment_posters(file_hash).await
+    }
+
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.reactions(message_id).await
+    }
+
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        self.inner.stickers(message_id).await
+    }
+
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.channel_messages(channel_id, limit).await
+    }
+
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.messages_with_sticker(sticker_id, limit).await
+    }
+
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.channel_webhook_messages(channel_id, limit).await
+    }
+
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.messages_by_webhook(webhook_id, limit).await
+    }
+
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner
+            .messages_with_emoji_reaction(emoji, guild_id, limit)
+            .await
+    }
+
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.member(user_id, guild_id).await
+    }
+
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.member_roles(user_id, guild_id).await
+    }
+
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.presence(user_id).await
+    }
+
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.member_activities(user_id).await
+    }
+
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.guild_member_activities(guild_id, user_id).await
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        self.inner.guild_ids().await
+    }
+
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        self.inner.unavailable_guilds().await
+    }
+
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        self.flush().await?;
+        self.inner.guild_members(guild_id).await
+    }
+
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>> {
+        self.inner.guild(guild_id).await
+    }
+
+    async fn role(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedRole>, Error<Self::Error>> {
+        self.inner.role(role_id).await
+    }
+
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        self.inner.guild_roles(guild_id).await
+    }
+
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>> {
+        self.inner.emoji(emoji_id).await
+    }
+
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>> {
+        self.inner.guild_emojis(guild_id).await
+    }
+
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>> {
+        self.inner.sticker(sticker_id).await
+    }
+
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        self.inner.guild_stickers(guild_id).await
+    }
+
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>> {
+        self.inner.stage_instance(stage_id).await
+    }
+}
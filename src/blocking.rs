@@ -0,0 +1,1008 @@
+use std::sync::Arc;
+
+use tokio::runtime::Handle;
+#[cfg(feature = "http")]
+use twilight_http::Client;
+#[cfg(feature = "changelog")]
+use twilight_model::id::marker::GenericMarker;
+use twilight_model::{
+    channel::StageInstance,
+    guild::{GuildFeature, Permissions},
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker,
+            StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+#[cfg(feature = "autocomplete")]
+use crate::autocomplete::Suggestion;
+#[cfg(feature = "changelog")]
+use crate::cache::EntityKind;
+#[cfg(feature = "http")]
+use crate::drift::CacheDrift;
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+use crate::{
+    cache::Error,
+    config::MessageKindFilter,
+    counts::CacheStats,
+    integrity::IntegrityReport,
+    model::{
+        CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField,
+        CachedEmoji, CachedGuild, CachedInteraction, CachedMember, CachedMessage,
+        CachedMessageRevision, CachedPermissionOverwrite, CachedPresence, CachedReaction,
+        CachedRole, CachedSession, CachedShardState, CachedSticker, ChannelRolePermissions,
+        ReactionCount,
+    },
+    structure::GuildStructure,
+    CacheReader,
+};
+
+/// A wrapper with synchronous versions of the methods to get data from the
+/// cache, for reading from it in code that isn't async, such as render threads
+/// or FFI layers
+///
+/// The methods run the methods of [`CacheReader`] to completion on the runtime
+/// of the given handle, so they return the same errors
+///
+/// # Panics
+///
+/// The methods panic if they're called from a thread that's running an async
+/// task, since they block the thread, call them from your own threads or the
+/// ones spawned with [`tokio::task::spawn_blocking`]
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::blocking::BlockingCache;
+/// use tokio::runtime::Handle;
+///
+/// let cache = BlockingCache::new(cache, Handle::current());
+/// std::thread::spawn(move || {
+///     let guild = cache.guild(guild_id).unwrap();
+/// });
+/// ```
+#[derive(Debug)]
+pub struct BlockingCache<C> {
+    /// The wrapped cache
+    inner: C,
+    /// The handle of the runtime the methods are run on
+    handle: Handle,
+}
+
+impl<C> BlockingCache<C> {
+    /// Wrap the given cache, running its methods on the runtime of the given
+    /// handle
+    #[must_use]
+    pub const fn new(inner: C, handle: Handle) -> Self {
+        Self { inner, handle }
+    }
+
+    /// Return the wrapped cache
+    #[must_use]
+    pub const fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
+impl<C: CacheReader + Sync> BlockingCache<C> {
+    /// Get the number of cached entities of every kind
+    pub fn stats(&self) -> Result<CacheStats, Error<C::Error>> {
+        self.handle.block_on(self.inner.stats())
+    }
+
+    /// Scan the cache for referential breaks, such as embed fields without
+    /// embeds, reactions of messages that aren't cached or roles of guilds that
+    /// aren't cached, without repairing them
+    pub fn verify_integrity(&self) -> Result<IntegrityReport, Error<C::Error>> {
+        self.handle.block_on(self.inner.verify_integrity())
+    }
+
+    /// Compare the cached channels, roles, members and emojis of the guild with
+    /// the ones fetched with the HTTP client, returning the differences
+    #[cfg(feature = "http")]
+    pub fn diff_against_http(
+        &self,
+        http: &Client,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CacheDrift, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.diff_against_http(http, guild_id))
+    }
+
+    /// Get the IDs of the given users that aren't cached as members of the
+    /// guild
+    pub fn missing_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[Id<UserMarker>],
+    ) -> Result<Vec<Id<UserMarker>>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.missing_members(guild_id, user_ids))
+    }
+
+    /// Get permissions of the current user in the given channel
+    pub fn self_channel_permissions(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.self_channel_permissions(channel_id))
+    }
+
+    /// Get permissions of the current user in the given guild
+    pub fn self_guild_permissions(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.self_guild_permissions(guild_id))
+    }
+
+    /// Get the permissions of the given user and channel
+    pub fn channel_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_permissions(user_id, channel_id))
+    }
+
+    /// Get the permissions of the given user and guild
+    pub fn guild_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.guild_permissions(user_id, guild_id))
+    }
+
+    /// Get whether the given user owns the guild
+    pub fn is_owner(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<C::Error>> {
+        self.handle.block_on(self.inner.is_owner(user_id, guild_id))
+    }
+
+    /// Get whether the given user owns the guild or has a role with the
+    /// administrator permission in it
+    pub fn is_admin(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<C::Error>> {
+        self.handle.block_on(self.inner.is_admin(user_id, guild_id))
+    }
+
+    /// Get the roles in the guild that grant the given permissions, for example
+    /// to audit which roles have [`Permissions::ADMINISTRATOR`]
+    pub fn roles_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<CachedRole>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.roles_with_permission(guild_id, permissions))
+    }
+
+    /// Get the IDs of the members in the guild that have the given permissions
+    /// from their roles, for example to audit who can mention everyone
+    pub fn members_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<Id<UserMarker>>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.members_with_permission(guild_id, permissions))
+    }
+
+    /// Get the channels in the guild that the given user can view
+    pub fn visible_channels(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.visible_channels(user_id, guild_id))
+    }
+
+    /// Get the current user information of the bot
+    pub fn current_user(&self) -> Result<CurrentUser, Error<C::Error>> {
+        self.handle.block_on(self.inner.current_user())
+    }
+
+    /// Get the current application information of the bot
+    pub fn current_application(&self) -> Result<PartialApplication, Error<C::Error>> {
+        self.handle.block_on(self.inner.current_application())
+    }
+
+    /// Get the cached sessions of the shards, one for each shard that
+    /// received a ready event
+    pub fn session_info(&self) -> Result<Vec<CachedSession>, Error<C::Error>> {
+        self.handle.block_on(self.inner.session_info())
+    }
+
+    /// Get the cached state of a shard, updated by [`crate::Cache::update_sharded`]
+    pub fn shard_state(&self, shard_id: u64) -> Result<Option<CachedShardState>, Error<C::Error>> {
+        self.handle.block_on(self.inner.shard_state(shard_id))
+    }
+
+    /// Get the cached states of the shards, one for each shard that updated
+    /// the cache with [`crate::Cache::update_sharded`], for example to show them in a
+    /// dashboard
+    pub fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<C::Error>> {
+        self.handle.block_on(self.inner.shard_states())
+    }
+
+    /// Get a cached interaction by its ID, cached if
+    /// [`crate::config::CacheConfig::interactions`] is enabled
+    pub fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<C::Error>> {
+        self.handle.block_on(self.inner.interaction(interaction_id))
+    }
+
+    /// Get a cached channel or thread by its ID
+    pub fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<C::Error>> {
+        self.handle.block_on(self.inner.channel(channel_id))
+    }
+
+    /// Get a cached channel or thread by its ID, shared through the pool returned by
+    /// [`crate::Cache::arc_pool`]
+    pub fn channel_ref(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<Arc<CachedChannel>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.channel_ref(channel_id))
+    }
+
+    /// Get a cached permission overwrites of a channel by its ID
+    pub fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.permission_overwrites(channel_id))
+    }
+
+    /// Get the cached permission overwrite of a channel for the given member
+    pub fn member_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.member_overwrites(channel_id, user_id))
+    }
+
+    /// Get the cached permission overwrite of a channel for the given role
+    pub fn role_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.role_overwrites(channel_id, role_id))
+    }
+
+    /// Get whether a channel's permission overwrites are synced with its
+    /// category's, like Discord shows in the channel's settings
+    pub fn channel_synced_with_category(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<bool>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_synced_with_category(channel_id))
+    }
+
+    /// Get the effective permissions in the channel of every role with a
+    /// permission overwrite in it, for example for a command that shows a
+    /// channel's permissions
+    pub fn channel_permission_matrix(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<ChannelRolePermissions>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_permission_matrix(channel_id))
+    }
+
+    /// Get the cached DM channel with the given user
+    pub fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<C::Error>> {
+        self.handle.block_on(self.inner.private_channel(user_id))
+    }
+
+    /// Get the IDs of a cached DM channel's recipients by its ID
+    pub fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.private_channel_users(channel_id))
+    }
+
+    /// Get a guild's channels and threads by its ID
+    pub fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_channels(guild_id))
+    }
+
+    /// Get a guild's archived threads by its ID
+    pub fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle.block_on(self.inner.archived_threads(guild_id))
+    }
+
+    /// Get a guild's channels whose latest message is older than the given
+    /// timestamp, for example to archive them
+    pub fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.inactive_channels(guild_id, since))
+    }
+
+    /// Get a channel's threads that aren't archived by the channel's ID
+    pub fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle.block_on(self.inner.active_threads(channel_id))
+    }
+
+    /// Get a guild's announcement channels, for example for bots that publish
+    /// the messages in them
+    pub fn announcement_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.announcement_channels(guild_id))
+    }
+
+    /// Get a guild's channels in the order the Discord client shows them
+    pub fn guild_channels_ordered(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.guild_channels_ordered(guild_id))
+    }
+
+    /// Get the layout of a guild, its settings, roles, channels and
+    /// permission overwrites, `None` if the guild isn't cached
+    pub fn guild_structure_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildStructure>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.guild_structure_snapshot(guild_id))
+    }
+
+    /// Get the slowmode of a channel in seconds, `None` if the channel isn't
+    /// cached or doesn't have slowmode
+    pub fn channel_slowmode(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<u16>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_slowmode(channel_id))
+    }
+
+    /// Get whether the slowmode of a channel lets the user send a message now,
+    /// based on the last cached message the user sent in it
+    pub fn can_send_now(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<bool, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.can_send_now(user_id, channel_id))
+    }
+
+    /// Get a cached message by its ID
+    pub fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<C::Error>> {
+        self.handle.block_on(self.inner.message(message_id))
+    }
+
+    /// Get the message a thread was started from by the thread's ID
+    pub fn thread_starter_message(
+        &self,
+        thread_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.thread_starter_message(thread_id))
+    }
+
+    /// Get the chain of replies ending at the given message, starting with the
+    /// message itself, followed by the message it replies to, the message that
+    /// one replies to and so on, for example to give a moderation or AI bot
+    /// the context of a conversation
+    pub fn reply_chain(
+        &self,
+        message_id: Id<MessageMarker>,
+        depth: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.reply_chain(message_id, depth))
+    }
+
+    /// Get cached embeds of a message by its ID
+    pub fn embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<(CachedEmbed, Vec<CachedEmbedField>)>, Error<C::Error>> {
+        self.handle.block_on(self.inner.embeds(message_id))
+    }
+
+    /// Get the cached previous versions of a message's content by its ID, the
+    /// oldest first
+    pub fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.message_revisions(message_id))
+    }
+
+    /// Get cached attachments of a message by its ID
+    pub fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<C::Error>> {
+        self.handle.block_on(self.inner.attachments(message_id))
+    }
+
+    /// Get the cached messages an attachment file was posted in by the hash
+    /// of its name, size, content type, dimensions and duration, returned by
+    /// [`crate::model::CachedAttachmentBlob::file_hash`]
+    pub fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.attachment_posters(file_hash))
+    }
+
+    /// Get cached reactions of a message by its ID
+    pub fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<C::Error>> {
+        self.handle.block_on(self.inner.reactions(message_id))
+    }
+
+    /// Get the reactions of a message aggregated by their emojis, like the
+    /// reactions in Discord's message payloads
+    pub fn reaction_counts(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<ReactionCount>, Error<C::Error>> {
+        self.handle.block_on(self.inner.reaction_counts(message_id))
+    }
+
+    /// Get cached stickers of a message by its ID
+    pub fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<C::Error>> {
+        self.handle.block_on(self.inner.stickers(message_id))
+    }
+
+    /// Get a channel's most recent `limit` messages by its ID
+    pub fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_messages(channel_id, limit))
+    }
+
+    /// Get a channel's most recent `limit` messages sent after `after` and
+    /// before `before`, excluding both, `None` meaning no bound
+    pub fn channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle.block_on(
+            self.inner
+                .channel_messages_range(channel_id, after, before, limit),
+        )
+    }
+
+    /// Get a channel's most recent `limit` messages that pass the filter, for
+    /// example to exclude system messages and the messages of bots
+    pub fn channel_messages_filtered(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle.block_on(
+            self.inner
+                .channel_messages_filtered(channel_id, filter, limit),
+        )
+    }
+
+    /// Get a channel's most recent `limit` messages sent by users, excluding
+    /// system messages and the messages of bots and webhooks
+    pub fn channel_user_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_user_messages(channel_id, limit))
+    }
+
+    /// Get the most recent `limit` messages that have the given sticker
+    pub fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.messages_with_sticker(sticker_id, limit))
+    }
+
+    /// Get a channel's most recent `limit` messages that were sent by webhooks
+    pub fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.channel_webhook_messages(channel_id, limit))
+    }
+
+    /// Get the most recent `limit` messages that were sent by the given
+    /// webhook
+    pub fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.messages_by_webhook(webhook_id, limit))
+    }
+
+    /// Get a guild's most recent `limit` messages that have a reaction with the
+    /// given emoji
+    pub fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle.block_on(
+            self.inner
+                .messages_with_emoji_reaction(emoji, guild_id, limit),
+        )
+    }
+
+    /// Get a cached member by its guild ID and user ID
+    pub fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<C::Error>> {
+        self.handle.block_on(self.inner.member(user_id, guild_id))
+    }
+
+    /// Get cached roles of a member by their ID
+    pub fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.member_roles(user_id, guild_id))
+    }
+
+    /// Get cached presence of a member by their ID
+    pub fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<C::Error>> {
+        self.handle.block_on(self.inner.presence(user_id))
+    }
+
+    /// Get cached activities of a member by their ID
+    pub fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<C::Error>> {
+        self.handle.block_on(self.inner.member_activities(user_id))
+    }
+
+    /// Get cached activities of a member in the given guild by their ID
+    pub fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.guild_member_activities(guild_id, user_id))
+    }
+
+    /// Get the IDs of the cached guilds
+    pub fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_ids())
+    }
+
+    /// Get the IDs of the cached guilds that are unavailable
+    pub fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.unavailable_guilds())
+    }
+
+    /// Get a guild's members by its ID
+    pub fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_members(guild_id))
+    }
+
+    /// Get the IDs of the guilds that both users are cached as members of
+    pub fn mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.mutual_guilds(user_a, user_b))
+    }
+
+    /// Get up to `limit` members of the guild whose nickname or username
+    /// starts with the prefix, ignoring case
+    pub fn search_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.search_members(guild_id, prefix, limit))
+    }
+
+    /// Get up to `limit` roles of the guild whose names match the query, the
+    /// best matches first
+    #[cfg(feature = "autocomplete")]
+    pub fn suggest_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<RoleMarker>> {
+        self.inner.suggest_roles(guild_id, query, limit)
+    }
+
+    /// Get up to `limit` channels and threads of the guild whose names match
+    /// the query, the best matches first
+    #[cfg(feature = "autocomplete")]
+    pub fn suggest_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<ChannelMarker>> {
+        self.inner.suggest_channels(guild_id, query, limit)
+    }
+
+    /// Get up to `limit` emojis of the guild whose names match the query, the
+    /// best matches first
+    #[cfg(feature = "autocomplete")]
+    pub fn suggest_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<EmojiMarker>> {
+        self.inner.suggest_emojis(guild_id, query, limit)
+    }
+
+    /// Get the members of the guild that look inactive since the given time,
+    /// to preview which members pruning the guild would remove
+    pub fn inactive_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedMember>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.inactive_members(guild_id, since))
+    }
+
+    /// Get a cached guild by its ID
+    pub fn guild(&self, guild_id: Id<GuildMarker>) -> Result<Option<CachedGuild>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild(guild_id))
+    }
+
+    /// Get a cached guild by its ID, shared through the pool returned by
+    /// [`crate::Cache::arc_pool`]
+    pub fn guild_ref(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<Arc<CachedGuild>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_ref(guild_id))
+    }
+
+    /// Get the URL of a cached guild's vanity invite by its ID, `None` if the
+    /// guild isn't cached or doesn't have a vanity invite
+    pub fn guild_vanity_url(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<String>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_vanity_url(guild_id))
+    }
+
+    /// Get the cached guilds that have the given feature, for example
+    /// [`GuildFeature::Community`] or [`GuildFeature::Discoverable`]
+    pub fn guilds_with_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.guilds_with_feature(feature))
+    }
+
+    /// Get a cached role by its ID
+    pub fn role(&self, role_id: Id<RoleMarker>) -> Result<Option<CachedRole>, Error<C::Error>> {
+        self.handle.block_on(self.inner.role(role_id))
+    }
+
+    /// Get a cached role by its ID, shared through the pool returned by
+    /// [`crate::Cache::arc_pool`]
+    pub fn role_ref(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<Arc<CachedRole>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.role_ref(role_id))
+    }
+
+    /// Get the everyone role of a guild by the guild's ID
+    pub fn everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Error<C::Error>> {
+        self.handle.block_on(self.inner.everyone_role(guild_id))
+    }
+
+    /// Get a guild's roles by its ID
+    pub fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_roles(guild_id))
+    }
+
+    /// Get the IDs of the members that have the given role
+    pub fn role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<C::Error>> {
+        self.handle.block_on(self.inner.role_members(role_id))
+    }
+
+    /// Get a cached emoji by its ID
+    pub fn emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<Option<CachedEmoji>, Error<C::Error>> {
+        self.handle.block_on(self.inner.emoji(emoji_id))
+    }
+
+    /// Get a guild's emojis by its ID
+    pub fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_emojis(guild_id))
+    }
+
+    /// Get a cached sticker by its ID
+    pub fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<C::Error>> {
+        self.handle.block_on(self.inner.sticker(sticker_id))
+    }
+
+    /// Get a guild's stickers by its ID
+    pub fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<C::Error>> {
+        self.handle.block_on(self.inner.guild_stickers(guild_id))
+    }
+
+    /// Get a cached stage instance by its ID
+    pub fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<C::Error>> {
+        self.handle.block_on(self.inner.stage_instance(stage_id))
+    }
+
+    /// Get the emojis used in reactions in the guild since the given time and
+    /// the number of times they were used, the most used first
+    #[cfg(feature = "stats")]
+    pub fn emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(String, u64)>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.emoji_usage(guild_id, since))
+    }
+
+    /// Get the stickers sent in messages in the guild since the given time and
+    /// the number of times they were sent, the most sent first
+    #[cfg(feature = "stats")]
+    pub fn sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.sticker_usage(guild_id, since))
+    }
+
+    /// Get the most recent `limit` changes to a channel or role, the most
+    /// recent first
+    #[cfg(feature = "changelog")]
+    pub fn entity_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.entity_changelog(kind, entity_id, limit))
+    }
+
+    /// Get a cached channel or thread by its ID, returning an error if it
+    /// isn't cached
+    pub fn try_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<CachedChannel, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_channel(channel_id))
+    }
+
+    /// Get a cached message by its ID, returning an error if it isn't cached
+    pub fn try_message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<CachedMessage, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_message(message_id))
+    }
+
+    /// Get a cached member by its guild ID and user ID, returning an error if
+    /// it isn't cached
+    pub fn try_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedMember, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.try_member(user_id, guild_id))
+    }
+
+    /// Get a cached guild by its ID, returning an error if it isn't cached
+    pub fn try_guild(&self, guild_id: Id<GuildMarker>) -> Result<CachedGuild, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_guild(guild_id))
+    }
+
+    /// Get a cached role by its ID, returning an error if it isn't cached
+    pub fn try_role(&self, role_id: Id<RoleMarker>) -> Result<CachedRole, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_role(role_id))
+    }
+
+    /// Get a cached emoji by its ID, returning an error if it isn't cached
+    pub fn try_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<CachedEmoji, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_emoji(emoji_id))
+    }
+
+    /// Get a cached sticker by its ID, returning an error if it isn't cached
+    pub fn try_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<CachedSticker, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_sticker(sticker_id))
+    }
+
+    /// Get a cached stage instance by its ID, returning an error if it isn't
+    /// cached
+    pub fn try_stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<StageInstance, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.try_stage_instance(stage_id))
+    }
+
+    /// Get a guild's channels and threads by its ID, returning an error if
+    /// the guild isn't cached
+    pub fn try_guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.try_guild_channels(guild_id))
+    }
+
+    /// Get a guild's members by its ID, returning an error if the guild isn't
+    /// cached
+    pub fn try_guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_guild_members(guild_id))
+    }
+
+    /// Get a guild's roles by its ID, returning an error if the guild isn't
+    /// cached
+    pub fn try_guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_guild_roles(guild_id))
+    }
+
+    /// Get a guild's emojis by its ID, returning an error if the guild isn't
+    /// cached
+    pub fn try_guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<C::Error>> {
+        self.handle.block_on(self.inner.try_guild_emojis(guild_id))
+    }
+
+    /// Get a guild's stickers by its ID, returning an error if the guild isn't
+    /// cached
+    pub fn try_guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.try_guild_stickers(guild_id))
+    }
+
+    /// Get a channel's most recent `limit` messages by its ID, returning an
+    /// error if the channel isn't cached
+    pub fn try_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<C::Error>> {
+        self.handle
+            .block_on(self.inner.try_channel_messages(channel_id, limit))
+    }
+}
@@ -1,35 +1,92 @@
+use core::{future::Future, mem, time::Duration};
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+use std::{borrow::Cow, sync::Arc};
+
 use async_trait::async_trait;
-pub use error::Error;
+pub use error::{EntityKind, Error};
+use futures::{stream, StreamExt, TryFutureExt, TryStreamExt};
+use time::OffsetDateTime;
 use twilight_model::{
     channel::{
-        message::ReactionType, permission_overwrite::PermissionOverwrite, Channel, StageInstance,
+        message::{sticker::MessageSticker, Embed, MessageType},
+        permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+        Attachment, Channel, ChannelType, Message, StageInstance,
+    },
+    gateway::{
+        event::Event,
+        payload::incoming::{MessageUpdate, Ready, ThreadListSync},
+        presence::Status,
     },
-    gateway::event::Event,
-    guild::Permissions,
+    guild::{Guild, GuildFeature, Permissions, Role},
     id::{
         marker::{
-            ChannelMarker, EmojiMarker, GuildMarker, MessageMarker, RoleMarker, StageMarker,
-            StickerMarker, UserMarker,
+            ChannelMarker, EmojiMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker,
+            StageMarker, StickerMarker, UserMarker, WebhookMarker,
         },
         Id,
     },
+    oauth::PartialApplication,
     user::CurrentUser,
+    util::Timestamp,
 };
 use twilight_util::permission_calculator::PermissionCalculator;
 
+#[cfg(feature = "journal")]
+use core::ops::Range;
+
+#[cfg(feature = "gateway")]
+use twilight_gateway::{shard::Events, Shard};
+#[cfg(feature = "http")]
+use twilight_http::Client;
+#[cfg(feature = "gateway")]
+use twilight_model::gateway::payload::outgoing::RequestGuildMembers;
+
+#[cfg(feature = "http")]
+use crate::drift::{CacheDrift, EntityDrift};
+#[cfg(feature = "metrics")]
+use crate::instrumentation;
+#[cfg(feature = "stats")]
+use crate::stats;
+#[cfg(feature = "gateway")]
+use crate::unique_id;
 use crate::{
+    config::{CacheConfig, EventFilter, MessageKindFilter, SweepOptions, DEFAULT_CONFIG},
+    counts::CacheStats,
+    event,
+    hooks::{CacheHooks, UpdateOutcome},
+    integrity::IntegrityReport,
     model::{
         CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField,
-        CachedEmoji, CachedGuild, CachedMember, CachedMessage, CachedPermissionOverwrite,
-        CachedPresence, CachedReaction, CachedRole, CachedSticker,
+        CachedEmoji, CachedGuild, CachedInteraction, CachedMember, CachedMessage,
+        CachedMessageRevision, CachedPermissionOverwrite, CachedPresence, CachedPrivateChannel,
+        CachedReaction, CachedReactionEmoji, CachedRole, CachedRoleAssignment, CachedSession,
+        CachedShardState, CachedSticker, ChannelRolePermissions, GuildPatch, MemberPatch,
+        ReactionCount,
     },
+    pool::{ArcPool, Invalidation},
+    queue::{Priority, UpdateQueue},
+    schema::CURRENT_SCHEMA_VERSION,
+    structure::{ChannelStructure, GuildStructure},
+    validation::{Synthetic, ValidationFailure, ValidationReport},
     Backend,
 };
+#[cfg(feature = "changelog")]
+use twilight_model::id::marker::GenericMarker;
+
+#[cfg(feature = "autocomplete")]
+use crate::autocomplete::{AutocompleteIndex, Suggestion};
+#[cfg(feature = "changelog")]
+use crate::{changelog, model::CachedChangelogEntry};
+#[cfg(feature = "journal")]
+use crate::{journal, model::CachedJournalEntry};
 
 /// Put into a mod to allow lints
 #[allow(clippy::std_instead_of_core)]
 mod error {
     use thiserror::Error;
+    #[cfg(feature = "journal")]
+    use twilight_model::id::marker::GenericMarker;
     use twilight_model::id::{
         marker::{ChannelMarker, GuildMarker, RoleMarker, UserMarker},
         Id,
@@ -37,6 +94,30 @@ mod error {
 
     use crate::model::{CachedChannel, CachedMember};
 
+    /// The kind of a cached entity, used in [`Error::NotCached`]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum EntityKind {
+        /// A channel or thread
+        Channel,
+        /// A message
+        Message,
+        /// A member, the ID is the member's user ID
+        Member,
+        /// A guild
+        Guild,
+        /// A role
+        Role,
+        /// An emoji
+        Emoji,
+        /// A sticker
+        Sticker,
+        /// A stage instance
+        StageInstance,
+        /// A presence, the ID is the user ID of the presence
+        Presence,
+    }
+
     /// The errors the cache might return
     #[derive(Error, Debug)]
     pub enum Error<E: Send> {
@@ -46,6 +127,9 @@ mod error {
         /// The current user isn't in the cache
         #[error("The current user isn't in the cache")]
         CurrentUserMissing,
+        /// The current application isn't in the cache
+        #[error("The current application isn't in the cache")]
+        CurrentApplicationMissing,
         /// One of the roles of the member to cache isn't in the cache
         #[error(
             "One of the roles of the member to cache isn't in the cache:\nUser ID: {user_id}, \
@@ -84,10 +168,90 @@ mod error {
             "The everyone role in the guild to calculate permissions for isn't in the cache:\n{0}"
         )]
         PermissionsGuildEveryoneRoleMissing(Id<GuildMarker>),
+        /// None of the roles in the guild to calculate permissions for are in
+        /// the cache, usually because the guild's create event wasn't received
+        #[error(
+            "None of the roles in the guild to calculate permissions for are in the cache:\n{0}"
+        )]
+        PermissionsGuildRolesMissing(Id<GuildMarker>),
         /// The given channel to calculate permissions for doesn't have a guild
         /// ID
         #[error("The given channel to calculate permissions for doesn't have a guild ID:\n{0:?}")]
         PermissionsChannelNotInGuild(Box<CachedChannel>),
+        /// The data in the backend was written with a newer schema version
+        /// than the one this version of the crate uses
+        #[error(
+            "The data in the backend was written with a newer schema version than the one this \
+             version of the crate uses:\n{0}"
+        )]
+        SchemaVersionNewer(u32),
+        /// The entity requested with one of the `try_` getters isn't in the
+        /// cache
+        #[error("The {0:?} requested isn't in the cache:\n{1}")]
+        NotCached(EntityKind, u64),
+        /// The journal entry replayed with [`crate::Cache::replay`] isn't a
+        /// valid event
+        #[cfg(feature = "journal")]
+        #[error("The journal entry replayed isn't a valid event:\n{0}")]
+        JournalEntryInvalid(Id<GenericMarker>),
+        /// The embeds of the message stored as JSON with
+        /// [`crate::config::CacheConfig::embeds_json`] couldn't be serialized
+        /// or deserialized
+        #[cfg(feature = "embeds-json")]
+        #[error("The embeds of the message stored as JSON aren't valid:\n{0}")]
+        EmbedsJsonInvalid(Id<MessageMarker>),
+        /// An error was returned while fetching data with the HTTP client in
+        /// [`crate::Cache::diff_against_http`]
+        #[cfg(feature = "http")]
+        #[error("An error was returned while fetching data with the HTTP client:\n{0}")]
+        Http(Box<dyn std::error::Error + Send + Sync>),
+        /// An error was returned while sending a command to the gateway in
+        /// [`crate::Cache::request_missing_members`]
+        #[cfg(feature = "gateway")]
+        #[error("An error was returned while sending a command to the gateway:\n{0}")]
+        Gateway(Box<dyn std::error::Error + Send + Sync>),
+    }
+
+    impl<E: Send> Error<E> {
+        /// Convert the error returned by the backend with the given function,
+        /// keeping the other errors as they are
+        pub(crate) fn map_backend<F: Send>(self, f: impl FnOnce(E) -> F) -> Error<F> {
+            match self {
+                Self::Backend(err) => Error::Backend(f(err)),
+                Self::CurrentUserMissing => Error::CurrentUserMissing,
+                Self::CurrentApplicationMissing => Error::CurrentApplicationMissing,
+                Self::MemberRoleMissing { user_id, role_id } => {
+                    Error::MemberRoleMissing { user_id, role_id }
+                }
+                Self::MemberBadTimeoutTimestamp(member) => Error::MemberBadTimeoutTimestamp(member),
+                Self::PermissionsChannelMissing(channel_id) => {
+                    Error::PermissionsChannelMissing(channel_id)
+                }
+                Self::PermissionsGuildMissing(guild_id) => Error::PermissionsGuildMissing(guild_id),
+                Self::PermissionsMemberMissing { user_id, guild_id } => {
+                    Error::PermissionsMemberMissing { user_id, guild_id }
+                }
+                Self::PermissionsGuildEveryoneRoleMissing(guild_id) => {
+                    Error::PermissionsGuildEveryoneRoleMissing(guild_id)
+                }
+                Self::PermissionsGuildRolesMissing(guild_id) => {
+                    Error::PermissionsGuildRolesMissing(guild_id)
+                }
+                Self::PermissionsChannelNotInGuild(channel) => {
+                    Error::PermissionsChannelNotInGuild(channel)
+                }
+                Self::SchemaVersionNewer(version) => Error::SchemaVersionNewer(version),
+                Self::NotCached(kind, id) => Error::NotCached(kind, id),
+                #[cfg(feature = "journal")]
+                Self::JournalEntryInvalid(id) => Error::JournalEntryInvalid(id),
+                #[cfg(feature = "embeds-json")]
+                Self::EmbedsJsonInvalid(id) => Error::EmbedsJsonInvalid(id),
+                #[cfg(feature = "http")]
+                Self::Http(err) => Error::Http(err),
+                #[cfg(feature = "gateway")]
+                Self::Gateway(err) => Error::Gateway(err),
+            }
+        }
     }
 }
 
@@ -95,6 +259,15 @@ mod error {
 ///
 /// This is for the users of the cache
 ///
+/// # Queries
+///
+/// The methods that aren't implemented by the backend only call the methods
+/// of [`Backend`], so the queries they run are the ones described there, the
+/// ones that call multiple backend methods, such as [`Self::embeds`] or the
+/// permission calculations, list them in their documentation, implement
+/// [`Backend::prepare`] to prepare for the queries when [`Self::init`] is
+/// called
+///
 /// # Example
 ///
 /// ```ignore
@@ -104,7 +277,53 @@ mod error {
 /// ```
 #[async_trait]
 pub trait Cache: Backend {
-    // noinspection DuplicatedCode
+    /// Get the configuration of how the cache is updated
+    ///
+    /// Returns the default configuration, override this method to return your
+    /// own [`CacheConfig`]
+    fn config(&self) -> &CacheConfig {
+        &DEFAULT_CONFIG
+    }
+
+    /// Get the hooks to run when the cache is updated
+    ///
+    /// Returns `None`, override this method to return your own
+    /// [`CacheHooks`]
+    fn hooks(&self) -> Option<&dyn CacheHooks> {
+        None
+    }
+
+    /// Get the queue used to drop low-priority events when the backend falls
+    /// behind
+    ///
+    /// Returns `None`, override this method to return your own
+    /// [`UpdateQueue`], without one [`Self::update_with_priority`] never drops
+    /// events
+    fn update_queue(&self) -> Option<&UpdateQueue> {
+        None
+    }
+
+    /// Get the pool the guilds, channels and roles returned by
+    /// [`Self::guild_ref`], [`Self::channel_ref`] and [`Self::role_ref`] are
+    /// shared through
+    ///
+    /// Returns `None`, override this method to return your own [`ArcPool`],
+    /// without one those methods read from the backend every time
+    fn arc_pool(&self) -> Option<&ArcPool> {
+        None
+    }
+
+    /// Get the index the roles, channels and emojis returned by
+    /// [`Self::suggest_roles`], [`Self::suggest_channels`] and
+    /// [`Self::suggest_emojis`] are looked up in
+    ///
+    /// Returns `None`, override this method to return your own
+    /// [`AutocompleteIndex`], without one those methods return nothing
+    #[cfg(feature = "autocomplete")]
+    fn autocomplete_index(&self) -> Option<&AutocompleteIndex> {
+        None
+    }
+
     /// Update the cache with the given event, should be called for every event
     /// to keep the cache valid
     ///
@@ -112,200 +331,374 @@ pub trait Cache: Backend {
     ///
     /// Many events don't require the event to be cloned, so the event parameter
     /// is taken by a reference, if an event does require a clone (usually
-    /// add and update events), it will clone the required fields
+    /// add and update events), it will clone the required fields, use
+    /// [`Self::update_owned`] if you don't need the event afterwards to avoid
+    /// these clones
+    ///
+    /// # Hooks
+    ///
+    /// The hooks returned by [`Self::hooks`] are run before and after the
+    /// cache is updated
     ///
     /// # Errors
     ///
     /// Returns the error the backend might return
-    #[allow(clippy::too_many_lines)]
     async fn update(&self, event: &Event) -> Result<(), Error<Self::Error>> {
-        match event {
+        self.update_event(Cow::Borrowed(event)).await
+    }
+
+    /// Update the cache with the given event, moving its fields into the
+    /// cache if it's owned and isn't needed after the cache is updated
+    ///
+    /// The event is needed after the cache is updated when [`Self::hooks`]
+    /// returns hooks or a TTL is set with [`CacheConfig::ttl`]
+    ///
+    /// This method is used internally in [`Self::update`] and
+    /// [`Self::update_owned`]
+    #[doc(hidden)]
+    async fn update_event(&self, event: Cow<'_, Event>) -> Result<(), Error<Self::Error>> {
+        let dedup_key = self.dedup_key(&event);
+        if let Some(key) = &dedup_key {
+            if self.is_duplicate(key.clone()).await? {
+                if let Some(cache_hooks) = self.hooks() {
+                    cache_hooks
+                        .after_update(&event, &UpdateOutcome::Duplicate)
+                        .await;
+                }
+                return Ok(());
+            }
+        }
+
+        if let Some(cache_hooks) = self.hooks() {
+            cache_hooks.before_update(&event).await;
+        }
+
+        #[cfg(feature = "journal")]
+        self.add_journal_entry(&event).await?;
+
+        #[cfg(feature = "changelog")]
+        let changelog_target = changelog::target(&event);
+        #[cfg(feature = "changelog")]
+        let changelog_before = match changelog_target {
+            Some((kind, entity_id)) => self.changelog_snapshot(kind, entity_id).await?,
+            None => None,
+        };
+
+        #[cfg(feature = "metrics")]
+        let event_type = event.kind();
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let invalidation = Invalidation::of(&event);
+        #[cfg(feature = "autocomplete")]
+        if let Some(index) = self.autocomplete_index() {
+            index.update(&event);
+        }
+
+        #[cfg(feature = "metrics")]
+        let write_started = Instant::now();
+        let mut result;
+        let retained_event = if self.hooks().is_some() || self.config().has_ttls() {
+            result = self.apply_event(Cow::Borrowed(&*event)).await;
+            Some(event)
+        } else {
+            result = self.apply_event(event).await;
+            None
+        };
+        if let Some(applied_event) = &retained_event {
+            if result.is_ok() && self.config().has_ttls() {
+                result = self.apply_ttls(applied_event).await;
+            }
+        }
+        #[cfg(feature = "metrics")]
+        instrumentation::record_backend_write(event_type, write_started.elapsed());
+        if let Some(pool) = self.arc_pool() {
+            pool.invalidate(invalidation);
+        }
+
+        #[cfg(feature = "changelog")]
+        if let Some((kind, entity_id)) = changelog_target.filter(|_| result.is_ok()) {
+            result = self
+                .add_changelog_entry(kind, entity_id, changelog_before)
+                .await;
+        }
+
+        if let (Err(_), Some(key)) = (&result, dedup_key) {
+            self.forget_event(key).await?;
+        }
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_update(event_type, started.elapsed(), result.is_ok());
+
+        if let (Some(cache_hooks), Some(applied_event)) = (self.hooks(), &retained_event) {
+            let outcome = if result.is_ok() {
+                UpdateOutcome::Applied
+            } else {
+                UpdateOutcome::Failed
+            };
+            cache_hooks.after_update(applied_event, &outcome).await;
+        }
+
+        result
+    }
+
+    /// Update the cache with the given event without running the hooks
+    ///
+    /// If the event is owned, its vectors that are written to the cache as
+    /// they are are moved instead of cloned
+    ///
+    /// This method is used internally in [`Self::update`]
+    #[doc(hidden)]
+    #[allow(clippy::too_many_lines)]
+    async fn apply_event(&self, mut event: Cow<'_, Event>) -> Result<(), Error<Self::Error>> {
+        let owned_fields = match &mut event {
+            Cow::Owned(owned_event) => OwnedFields::take(owned_event),
+            Cow::Borrowed(_) => OwnedFields::default(),
+        };
+
+        match &*event {
             Event::ChannelCreate(channel) => {
                 self.add_channel(channel).await?;
             }
             Event::ChannelUpdate(channel) => {
-                self.delete_channel_permission_overwrites(channel.id)
-                    .await?;
-                self.add_channel(channel).await?;
+                if !self.is_channel_unchanged(channel).await? {
+                    self.delete_channel_permission_overwrites(channel.id)
+                        .await?;
+                    if channel.kind == ChannelType::Private {
+                        self.delete_private_channel(channel.id).await?;
+                    }
+                    self.add_channel(channel).await?;
+                }
             }
             Event::ChannelDelete(channel) => {
-                self.delete_channel_permission_overwrites(channel.id)
-                    .await?;
+                if !self.supports_cascade() {
+                    self.delete_channel_permission_overwrites(channel.id)
+                        .await?;
+                    if channel.kind == ChannelType::Private {
+                        self.delete_private_channel(channel.id).await?;
+                    }
+                }
                 self.delete_channel(channel.id).await?;
             }
             Event::ThreadCreate(thread) => {
                 self.add_channel(thread).await?;
+                if thread.newly_created == Some(true) {
+                    self.link_thread_starter_message(thread.id).await?;
+                }
             }
             Event::ThreadUpdate(thread) => {
-                self.add_channel(thread).await?;
+                if !self.is_channel_unchanged(thread).await? {
+                    self.add_channel(thread).await?;
+                }
             }
             Event::ThreadDelete(thread) => {
                 self.delete_channel(thread.id).await?;
             }
+            Event::ThreadListSync(sync) => {
+                self.sync_threads(sync).await?;
+            }
             Event::GuildCreate(guild) => {
-                for channel in guild.channels.iter().chain(&guild.threads) {
-                    self.add_channel(channel).await?;
-                }
-                for emoji in &guild.emojis {
-                    self.upsert_emoji(CachedEmoji::from_emoji(emoji, guild.id))
-                        .await?;
-                }
-                // for sticker in &guild.stickers {
-                //     self.upsert_sticker(sticker.into()).await?;
-                // }
-                for role in &guild.roles {
-                    self.insert_role(CachedRole::from_role(role.clone(), guild.id))
-                        .await?;
-                }
-                for member in &guild.members {
-                    self.add_member_roles(member.user.id, member.roles.clone())
-                        .await?;
-                    self.upsert_member(member.into()).await?;
-                }
-                for presence in &guild.presences {
-                    self.upsert_presence(presence.into()).await?;
-                }
-                for stage in &guild.stage_instances {
-                    self.upsert_stage_instance(stage.clone()).await?;
-                }
-                self.upsert_guild(CachedGuild::from(&guild.0)).await?;
+                self.add_guild(
+                    &guild.0,
+                    owned_fields.roles.unwrap_or_else(|| guild.roles.clone()),
+                    owned_fields
+                        .stage_instances
+                        .unwrap_or_else(|| guild.stage_instances.clone()),
+                )
+                .await?;
             }
             Event::GuildUpdate(guild) => {
-                if let Some(mut cached_guild) = self.guild(guild.id).await? {
-                    cached_guild.update(guild);
-                    self.upsert_guild(cached_guild).await?;
+                if let Some(cached_guild) = self.guild(guild.id).await? {
+                    let patch = GuildPatch::new(&cached_guild, &guild.0);
+                    if !patch.is_empty() {
+                        self.patch_guild(guild.id, patch).await?;
+                    }
                 }
             }
             Event::GuildDelete(guild) => {
-                if !guild.unavailable {
-                    for channel in self.guild_channels(guild.id).await? {
-                        self.delete_channel_permission_overwrites(channel.id)
-                            .await?;
-                    }
-                    self.delete_guild_channels(guild.id).await?;
-                    self.delete_guild_emojis(guild.id).await?;
-                    // self.delete_guild_stickers(guild.id).await?;
-                    self.delete_guild_members(guild.id).await?;
-                    self.delete_guild_presences(guild.id).await?;
-                    self.delete_guild_roles(guild.id).await?;
-                    self.delete_guild_stage_instances(guild.id).await?;
-                    self.delete_guild(guild.id).await?;
+                if guild.unavailable {
+                    self.mark_guild_unavailable(guild.id).await?;
+                } else {
+                    self.remove_guild(guild.id).await?;
                 }
             }
             Event::GuildEmojisUpdate(emojis) => {
-                self.delete_guild_emojis(emojis.guild_id).await?;
+                let cached_emojis = self.guild_emojis(emojis.guild_id).await?;
+                for cached_emoji in &cached_emojis {
+                    if !emojis
+                        .emojis
+                        .iter()
+                        .any(|emoji| emoji.id == cached_emoji.id)
+                    {
+                        self.delete_emoji(cached_emoji.id).await?;
+                    }
+                }
                 for emoji in &emojis.emojis {
-                    self.upsert_emoji(CachedEmoji::from_emoji(emoji, emojis.guild_id))
-                        .await?;
+                    let emoji = CachedEmoji::from_emoji(emoji, emojis.guild_id);
+                    if !cached_emojis.contains(&emoji) {
+                        self.upsert_emoji(emoji).await?;
+                    }
+                }
+            }
+            Event::GuildStickersUpdate(stickers) => {
+                let cached_stickers = self.guild_stickers(stickers.guild_id).await?;
+                for cached_sticker in &cached_stickers {
+                    if !stickers
+                        .stickers
+                        .iter()
+                        .any(|sticker| sticker.id == cached_sticker.id)
+                    {
+                        self.delete_sticker(cached_sticker.id).await?;
+                    }
+                }
+                for sticker in &stickers.stickers {
+                    let sticker = CachedSticker::from(sticker);
+                    if !cached_stickers.contains(&sticker) {
+                        self.upsert_sticker(sticker).await?;
+                    }
                 }
             }
-            // Event::GuildStickersUpdate(stickers) => {
-            // self.delete_guild_stickers(stickers.guild_id).await?;
-            // for sticker in &stickers.stickers {
-            //     self.upsert_sticker(sticker.into()).await?;
-            // }
-            // }
             Event::MemberAdd(member) => {
-                self.add_member_roles(member.user.id, member.roles.clone())
+                self.add_member_roles(member.guild_id, member.user.id, &member.roles)
                     .await?;
                 self.upsert_member(CachedMember::from(&member.0)).await?;
             }
             Event::MemberChunk(members) => {
                 for member in &members.members {
-                    self.add_member_roles(member.user.id, member.roles.clone())
+                    self.add_member_roles(members.guild_id, member.user.id, &member.roles)
                         .await?;
                     self.upsert_member(member.into()).await?;
                 }
             }
             Event::MemberUpdate(member) => {
-                if let Some(mut cached_member) =
-                    self.member(member.user.id, member.guild_id).await?
-                {
-                    cached_member.update(member);
-                    self.upsert_member(cached_member).await?;
-                    self.delete_member_roles(member.guild_id, member.user.id)
+                if let Some(cached_member) = self.member(member.user.id, member.guild_id).await? {
+                    let patch = MemberPatch::new(&cached_member, member);
+                    if !patch.is_empty() {
+                        self.patch_member(member.guild_id, member.user.id, patch)
+                            .await?;
+                    }
+                    if !self
+                        .are_member_roles_unchanged(member.guild_id, member.user.id, &member.roles)
+                        .await?
+                    {
+                        self.delete_member_role_assignments(member.guild_id, member.user.id)
+                            .await?;
+                        self.add_member_roles(member.guild_id, member.user.id, &member.roles)
+                            .await?;
+                    }
+                } else if self.config().lazy_members.is_some() {
+                    self.add_member_roles(member.guild_id, member.user.id, &member.roles)
                         .await?;
-                    self.add_member_roles(member.user.id, member.roles.clone())
+                    self.upsert_member(CachedMember::from_member_update(member))
                         .await?;
                 }
             }
             Event::MemberRemove(member) => {
                 self.delete_member(member.user.id, member.guild_id).await?;
-                self.delete_member_roles(member.guild_id, member.user.id)
-                    .await?;
+                if !self.supports_cascade() {
+                    self.delete_member_role_assignments(member.guild_id, member.user.id)
+                        .await?;
+                }
             }
             Event::MessageCreate(message) => {
-                for attachment in message.attachments.clone() {
-                    self.upsert_attachment(CachedAttachment::from_attachment(
-                        attachment, message.id,
-                    ))
-                    .await?;
-                }
-                // for message_sticker in message.sticker_items.clone() {
-                //     let sticker =
-                //         if let Some(mut cached_sticker) =
-                // self.sticker(message_sticker.id).await? {
-                // cached_sticker.message_id = Some(message.id);
-                // cached_sticker         } else {
-                //             CachedSticker::from_message_sticker(message_sticker, message.id)
-                //         };
-                //     self.upsert_sticker(sticker).await?;
-                // }
-                for embed in message.embeds.clone() {
-                    let fields = embed.fields.clone();
-                    let cached_embed = CachedEmbed::from_embed(embed, message.id);
-                    for field in fields {
-                        self.upsert_embed_field(CachedEmbedField::from_embed_field(
-                            field,
-                            cached_embed.id,
-                        ))
+                #[cfg(feature = "stats")]
+                self.add_sticker_uses(
+                    message,
+                    owned_fields
+                        .sticker_items
+                        .as_deref()
+                        .unwrap_or(&message.sticker_items),
+                )
+                .await?;
+                if self.config().channel_activity {
+                    self.set_channel_last_activity(message.channel_id, message.timestamp)
                         .await?;
+                }
+                if self.config().lazy_members.is_some() {
+                    self.add_message_author(message).await?;
+                }
+                if self.config().retains_messages(message.guild_id) {
+                    self.add_attachments(
+                        message.id,
+                        owned_fields
+                            .attachments
+                            .unwrap_or_else(|| message.attachments.clone()),
+                    )
+                    .await?;
+                    self.add_message_stickers(
+                        message.id,
+                        owned_fields
+                            .sticker_items
+                            .unwrap_or_else(|| message.sticker_items.clone()),
+                    )
+                    .await?;
+                    self.add_embeds(
+                        message.id,
+                        owned_fields
+                            .embeds
+                            .unwrap_or_else(|| message.embeds.clone()),
+                    )
+                    .await?;
+                    let mut cached_message = CachedMessage::from(&message.0);
+                    cached_message.anonymize(self.config().message_content);
+                    if self.config().message_authors {
+                        cached_message.set_author_snapshot(&message.author);
                     }
-                    self.upsert_embed(cached_embed).await?;
+                    self.upsert_message(cached_message).await?;
                 }
-                self.upsert_message(CachedMessage::from(&message.0)).await?;
             }
             Event::MessageUpdate(message) => {
                 if let Some(mut cached_message) = self.message(message.id).await? {
+                    if self.config().ordered_updates
+                        && matches!(
+                            (message.edited_timestamp, cached_message.edited_timestamp),
+                            (Some(edited_at), Some(cached_edited_at)) if edited_at < cached_edited_at
+                        )
+                    {
+                        return Ok(());
+                    }
+                    self.add_message_revision(&cached_message, message).await?;
                     cached_message.update(message);
+                    cached_message.anonymize(self.config().message_content);
                     if let Some(attachments) = &message.attachments {
                         self.delete_message_attachments(message.id).await?;
-                        for attachment in attachments.clone() {
-                            self.upsert_attachment(CachedAttachment::from_attachment(
-                                attachment, message.id,
-                            ))
+                        self.add_attachments(message.id, attachments.clone())
                             .await?;
-                        }
                     }
                     if let Some(embeds) = &message.embeds {
-                        let cached_embeds = self.embeds(message.id).await?;
-                        for (embed, _) in cached_embeds {
-                            self.delete_embed_fields(embed.id).await?;
-                            self.delete_embed(embed.id).await?;
+                        self.remove_embeds(message.id).await?;
+                        self.add_embeds(message.id, embeds.clone()).await?;
+                    }
+                    self.upsert_message(cached_message).await?;
+                } else if self.config().partial_messages
+                    && self.config().retains_messages(message.guild_id)
+                {
+                    if let Some(mut cached_message) = CachedMessage::from_message_update(message) {
+                        cached_message.anonymize(self.config().message_content);
+                        if self.config().message_authors {
+                            if let Some(author) = &message.author {
+                                cached_message.set_author_snapshot(author);
+                            }
                         }
-                        for embed in embeds.clone() {
-                            let fields = embed.fields.clone();
-                            let cached_embed = CachedEmbed::from_embed(embed, message.id);
-                            for field in fields {
-                                self.upsert_embed_field(CachedEmbedField::from_embed_field(
-                                    field,
-                                    cached_embed.id,
-                                ))
+                        if let Some(attachments) = &message.attachments {
+                            self.add_attachments(message.id, attachments.clone())
                                 .await?;
-                            }
-                            self.upsert_embed(cached_embed).await?;
                         }
+                        if let Some(embeds) = &message.embeds {
+                            self.add_embeds(message.id, embeds.clone()).await?;
+                        }
+                        self.upsert_message(cached_message).await?;
                     }
-                    self.upsert_message(cached_message).await?;
                 }
             }
             Event::MessageDelete(message) => {
                 self.remove_message(message.id).await?;
             }
             Event::MessageDeleteBulk(messages) => {
-                for message_id in &messages.ids {
-                    self.remove_message(*message_id).await?;
+                if !self.supports_cascade() {
+                    self.delete_messages_data(&messages.ids).await?;
                 }
+                self.delete_messages(&messages.ids).await?;
             }
             Event::PresenceUpdate(presence) => {
                 self.delete_user_activities(presence.guild_id, presence.user.id())
@@ -322,27 +715,23 @@ pub trait Cache: Backend {
                     .await?;
             }
             Event::ReactionAdd(reaction) => {
-                self.upsert_reaction(CachedReaction::from(&reaction.0))
-                    .await?;
+                let cached_reaction = CachedReaction::from(&reaction.0);
+                #[cfg(feature = "stats")]
+                self.add_emoji_use(&cached_reaction).await?;
+                self.upsert_reaction(cached_reaction).await?;
             }
             Event::ReactionRemove(reaction) => {
                 self.delete_reaction(
                     reaction.message_id,
                     reaction.user_id,
-                    match &reaction.emoji {
-                        ReactionType::Custom { id, .. } => id.to_string(),
-                        ReactionType::Unicode { name } => name.clone(),
-                    },
+                    CachedReactionEmoji::from(&reaction.emoji).key(),
                 )
                 .await?;
             }
             Event::ReactionRemoveEmoji(reaction) => {
                 self.delete_message_reactions_by_emoji(
                     reaction.message_id,
-                    match &reaction.emoji {
-                        ReactionType::Custom { id, .. } => id.to_string(),
-                        ReactionType::Unicode { name } => name.clone(),
-                    },
+                    CachedReactionEmoji::from(&reaction.emoji).key(),
                 )
                 .await?;
             }
@@ -350,50 +739,1175 @@ pub trait Cache: Backend {
                 self.delete_message_reactions(reaction.message_id).await?;
             }
             Event::Ready(ready) => {
+                self.reconcile_ready(ready).await?;
+                self.upsert_session(CachedSession::from(&**ready)).await?;
+                self.set_current_application(ready.application.clone())
+                    .await?;
                 self.set_current_user(ready.user.clone()).await?;
             }
             Event::UserUpdate(user) => {
                 self.set_current_user(user.0.clone()).await?;
             }
             Event::RoleCreate(role) => {
-                self.insert_role(CachedRole::from_role(role.role.clone(), role.guild_id))
-                    .await?;
+                self.upsert_role(CachedRole::from_role(
+                    owned_fields.role.unwrap_or_else(|| role.role.clone()),
+                    role.guild_id,
+                ))
+                .await?;
             }
             Event::RoleUpdate(role) => {
-                self.update_roles(CachedRole::from_role(role.role.clone(), role.guild_id))
-                    .await?;
+                self.update_role(CachedRole::from_role(
+                    owned_fields.role.unwrap_or_else(|| role.role.clone()),
+                    role.guild_id,
+                ))
+                .await?;
             }
             Event::RoleDelete(role) => {
+                if !self.supports_cascade() {
+                    self.delete_role_assignments(role.role_id).await?;
+                }
                 self.delete_role(role.role_id).await?;
             }
             Event::StageInstanceCreate(stage) => {
-                self.upsert_stage_instance(stage.clone().0).await?;
+                self.upsert_stage_instance(
+                    owned_fields
+                        .stage_instance
+                        .unwrap_or_else(|| stage.0.clone()),
+                )
+                .await?;
             }
             Event::StageInstanceUpdate(stage) => {
-                self.upsert_stage_instance(stage.clone().0).await?;
+                self.update_stage_instance(
+                    owned_fields
+                        .stage_instance
+                        .unwrap_or_else(|| stage.0.clone()),
+                )
+                .await?;
             }
             Event::StageInstanceDelete(stage) => {
                 self.delete_stage_instance(stage.id).await?;
             }
+            Event::InteractionCreate(interaction) => {
+                if self.config().interactions {
+                    if let Some(token_expires_at) = expiry_timestamp(INTERACTION_TOKEN_LIFETIME) {
+                        self.upsert_interaction(CachedInteraction::from_interaction(
+                            interaction,
+                            token_expires_at,
+                        ))
+                        .await?;
+                    }
+                }
+            }
             _ => {}
         }
 
         Ok(())
     }
 
-    /// Get permissions of the current user in the given channel
+    /// Set the expiry of the entities cached by the given event, for the kinds
+    /// that have a TTL set with [`CacheConfig::ttl`]
     ///
-    /// This is a convenience method for [`Self::channel_permissions`] with the
-    /// current user's ID
+    /// This method is used internally in [`Self::update`]
+    #[doc(hidden)]
+    async fn apply_ttls(&self, event: &Event) -> Result<(), Error<Self::Error>> {
+        match event {
+            Event::ChannelCreate(channel) => {
+                self.expire(EntityKind::Channel, channel.id.get(), None)
+                    .await?;
+            }
+            Event::ChannelUpdate(channel) => {
+                self.expire(EntityKind::Channel, channel.id.get(), None)
+                    .await?;
+            }
+            Event::ThreadCreate(thread) => {
+                self.expire(EntityKind::Channel, thread.id.get(), None)
+                    .await?;
+            }
+            Event::ThreadUpdate(thread) => {
+                self.expire(EntityKind::Channel, thread.id.get(), None)
+                    .await?;
+            }
+            Event::ThreadListSync(sync) => {
+                for thread in &sync.threads {
+                    self.expire(EntityKind::Channel, thread.id.get(), None)
+                        .await?;
+                }
+            }
+            Event::GuildCreate(guild) => {
+                let guild_id = Some(guild.id);
+                let expiries =
+                    guild
+                        .channels
+                        .iter()
+                        .chain(&guild.threads)
+                        .map(|channel| (EntityKind::Channel, channel.id.get(), None))
+                        .chain(
+                            guild
+                                .emojis
+                                .iter()
+                                .map(|emoji| (EntityKind::Emoji, emoji.id.get(), None)),
+                        )
+                        .chain(
+                            guild
+                                .stickers
+                                .iter()
+                                .map(|sticker| (EntityKind::Sticker, sticker.id.get(), None)),
+                        )
+                        .chain(
+                            guild
+                                .roles
+                                .iter()
+                                .map(|role| (EntityKind::Role, role.id.get(), None)),
+                        )
+                        .chain(
+                            guild
+                                .members
+                                .iter()
+                                .map(|member| (EntityKind::Member, member.user.id.get(), guild_id)),
+                        )
+                        .chain(guild.presences.iter().map(|presence| {
+                            (EntityKind::Presence, presence.user.id().get(), guild_id)
+                        }))
+                        .chain(
+                            guild
+                                .stage_instances
+                                .iter()
+                                .map(|stage| (EntityKind::StageInstance, stage.id.get(), None)),
+                        )
+                        .chain([(EntityKind::Guild, guild.id.get(), None)]);
+                run_concurrently(
+                    expiries
+                        .map(|(kind, entity_id, guild_id)| self.expire(kind, entity_id, guild_id)),
+                    self.config().write_concurrency,
+                )
+                .await?;
+            }
+            Event::GuildUpdate(guild) => {
+                self.expire(EntityKind::Guild, guild.id.get(), None).await?;
+            }
+            Event::GuildEmojisUpdate(emojis) => {
+                for emoji in &emojis.emojis {
+                    self.expire(EntityKind::Emoji, emoji.id.get(), None).await?;
+                }
+            }
+            Event::GuildStickersUpdate(stickers) => {
+                for sticker in &stickers.stickers {
+                    self.expire(EntityKind::Sticker, sticker.id.get(), None)
+                        .await?;
+                }
+            }
+            Event::MemberAdd(member) => {
+                self.expire(
+                    EntityKind::Member,
+                    member.user.id.get(),
+                    Some(member.guild_id),
+                )
+                .await?;
+            }
+            Event::MemberChunk(members) => {
+                for member in &members.members {
+                    self.expire(
+                        EntityKind::Member,
+                        member.user.id.get(),
+                        Some(members.guild_id),
+                    )
+                    .await?;
+                }
+            }
+            Event::MemberUpdate(member) => {
+                self.expire(
+                    EntityKind::Member,
+                    member.user.id.get(),
+                    Some(member.guild_id),
+                )
+                .await?;
+            }
+            Event::MessageCreate(message) => {
+                self.expire(EntityKind::Message, message.id.get(), None)
+                    .await?;
+            }
+            Event::MessageUpdate(message) => {
+                self.expire(EntityKind::Message, message.id.get(), None)
+                    .await?;
+            }
+            Event::PresenceUpdate(presence) => {
+                self.expire(
+                    EntityKind::Presence,
+                    presence.user.id().get(),
+                    Some(presence.guild_id),
+                )
+                .await?;
+            }
+            Event::RoleCreate(role) => {
+                self.expire(EntityKind::Role, role.role.id.get(), None)
+                    .await?;
+            }
+            Event::RoleUpdate(role) => {
+                self.expire(EntityKind::Role, role.role.id.get(), None)
+                    .await?;
+            }
+            Event::StageInstanceCreate(stage) => {
+                self.expire(EntityKind::StageInstance, stage.id.get(), None)
+                    .await?;
+            }
+            Event::StageInstanceUpdate(stage) => {
+                self.expire(EntityKind::StageInstance, stage.id.get(), None)
+                    .await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Sets the expiry of the entity to its kind's TTL from now, does nothing
+    /// if its kind doesn't have a TTL
+    #[doc(hidden)]
+    async fn expire(
+        &self,
+        kind: EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+    ) -> Result<(), Error<Self::Error>> {
+        if let Some(expires_at) = self.config().ttl_of(kind).and_then(expiry_timestamp) {
+            self.set_expiry(kind, entity_id, guild_id, expires_at)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the cache with the given event unless the backend is falling
+    /// behind and the event's priority is too low
+    ///
+    /// The event is dropped when the queue returned by [`Self::update_queue`]
+    /// is full and the priority is lower than [`UpdateQueue::min_priority`],
+    /// use [`Priority::of`] for the default priority of an event
+    ///
+    /// Dropped events are counted by [`UpdateQueue::dropped`] and passed to
+    /// [`CacheHooks::after_update`] with [`UpdateOutcome::Dropped`]
     ///
     /// # Errors
     ///
     /// Returns the error the backend might return
-    ///
-    /// Returns [`Error::PermissionsChannelMissing`],
-    /// [`Error::PermissionsChannelNotInGuild`],
-    /// [`Error::PermissionsGuildMissing`] or
-    /// [`Error::PermissionsGuildEveryoneRoleMissing`]
+    async fn update_with_priority(
+        &self,
+        event: &Event,
+        priority: Priority,
+    ) -> Result<(), Error<Self::Error>> {
+        let Some(queue) = self.update_queue() else {
+            return self.update(event).await;
+        };
+
+        let Some(_slot) = queue.enter(priority) else {
+            #[cfg(feature = "metrics")]
+            instrumentation::record_dropped(event);
+
+            if let Some(cache_hooks) = self.hooks() {
+                cache_hooks
+                    .after_update(event, &UpdateOutcome::Dropped)
+                    .await;
+            }
+
+            return Ok(());
+        };
+
+        self.update(event).await
+    }
+
+    /// Update the cache with the given event if the filter allows it
+    ///
+    /// The filter is checked before anything is written to the backend, refer
+    /// to [`EventFilter`] for more
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn update_filtered(
+        &self,
+        event: &Event,
+        filter: &EventFilter,
+    ) -> Result<(), Error<Self::Error>> {
+        if filter.allows(event) {
+            self.update(event).await?;
+        } else if let Some(cache_hooks) = self.hooks() {
+            cache_hooks
+                .after_update(event, &UpdateOutcome::Filtered)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Update the cache with the given event received by the given shard,
+    /// tracking the sequence numbers of the shard
+    ///
+    /// `sequence` is the sequence number of the gateway payload the event was
+    /// received in, since it's not part of [`Event`], it should be `None` for
+    /// events without one, such as heartbeat acknowledgements
+    ///
+    /// Returns the number of events the shard missed before this event, which
+    /// is the number of sequence numbers skipped since the shard's previous
+    /// event, when it's not 0, the data of the shard's guilds might be
+    /// outdated, so you can request them again, for example by reconnecting
+    /// the shard
+    ///
+    /// A ready event resets the shard's state, since sequence numbers restart
+    /// in a new session, the states are returned by [`Self::shard_states`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn update_sharded(
+        &self,
+        shard_id: u64,
+        sequence: Option<u64>,
+        event: &Event,
+    ) -> Result<u64, Error<Self::Error>> {
+        let mut state = match (event, self.shard_state(shard_id).await?) {
+            (Event::Ready(_), _) | (_, None) => CachedShardState {
+                shard_id,
+                sequence: 0,
+                events: 0,
+                missed_events: 0,
+            },
+            (_, Some(state)) => state,
+        };
+
+        let missed_events = sequence.map_or(0, |sequence| {
+            sequence.saturating_sub(state.sequence).saturating_sub(1)
+        });
+        state.sequence = state.sequence.max(sequence.unwrap_or_default());
+        state.events = state.events.saturating_add(1);
+        state.missed_events = state.missed_events.saturating_add(missed_events);
+        self.set_shard_state(state).await?;
+
+        if self.config().ordered_updates {
+            if let Event::Ready(_) = event {
+                self.delete_shard_update_sequences(shard_id).await?;
+            } else if let (Some(sequence), Some(key)) = (sequence, event::sequence_key(event)) {
+                if !self
+                    .advance_update_sequence(key, shard_id, sequence)
+                    .await?
+                {
+                    if let Some(cache_hooks) = self.hooks() {
+                        cache_hooks
+                            .after_update(event, &UpdateOutcome::Outdated)
+                            .await;
+                    }
+                    return Ok(missed_events);
+                }
+            }
+        }
+
+        self.update(event).await?;
+
+        Ok(missed_events)
+    }
+
+    /// Update the cache with the given event, taking ownership of it
+    ///
+    /// This is the same as [`Self::update`], but since the event is owned, its
+    /// fields, such as the roles of a guild, the attachments of a message or
+    /// the role of a role update, are moved into the cache instead of being
+    /// cloned, which is preferable when the event isn't used after updating
+    /// the cache, for example when consuming events from the gateway
+    ///
+    /// When [`Self::hooks`] returns hooks or a TTL is set with
+    /// [`CacheConfig::ttl`], the fields are still cloned, since the event is
+    /// needed after the cache is updated
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn update_owned(&self, event: Event) -> Result<(), Error<Self::Error>> {
+        self.update_event(Cow::Owned(event)).await
+    }
+
+    /// Replace the cached message with the given message if the cached one is
+    /// partial, for example with the message fetched with HTTP, returning
+    /// whether it was replaced
+    ///
+    /// Partial messages are cached from the updates of uncached messages if
+    /// [`CacheConfig::partial_messages`] is enabled, the fields that aren't in
+    /// the update have their default values until they're backfilled
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn backfill_message(&self, message: &Message) -> Result<bool, Error<Self::Error>> {
+        if !self
+            .message(message.id)
+            .await?
+            .map_or(false, |cached_message| cached_message.partial)
+        {
+            return Ok(false);
+        }
+
+        self.remove_embeds(message.id).await?;
+        self.delete_message_attachments(message.id).await?;
+        self.delete_message_stickers(message.id).await?;
+        self.add_attachments(message.id, message.attachments.clone())
+            .await?;
+        self.add_message_stickers(message.id, message.sticker_items.clone())
+            .await?;
+        self.add_embeds(message.id, message.embeds.clone()).await?;
+        let mut cached_message = CachedMessage::from(message);
+        cached_message.anonymize(self.config().message_content);
+        if self.config().message_authors {
+            cached_message.set_author_snapshot(&message.author);
+        }
+        self.upsert_message(cached_message).await?;
+
+        Ok(true)
+    }
+
+    /// Reconcile the cache with the given ready event
+    ///
+    /// The cached guilds that aren't in the ready event are removed, since the
+    /// bot was removed from them while it was offline, the other guilds are
+    /// marked as unavailable until their guild create events are received,
+    /// when their contents are replaced with the new data
+    ///
+    /// Only the guilds in the ready event's shard are reconciled, so that the
+    /// ready event of a shard doesn't remove the guilds of other shards
+    ///
+    /// This is called when the cache is updated with a ready event
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn reconcile_ready(&self, ready: &Ready) -> Result<(), Error<Self::Error>> {
+        let guild_ids: Vec<_> = ready.guilds.iter().map(|guild| guild.id).collect();
+        self.reconcile_guilds(&guild_ids, ready.shard).await
+    }
+
+    /// Reconcile the cached guilds with the given guild IDs of the given shard
+    ///
+    /// This method is used internally in [`Self::reconcile_ready`]
+    #[doc(hidden)]
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    async fn reconcile_guilds(
+        &self,
+        guild_ids: &[Id<GuildMarker>],
+        shard: Option<[u64; 2]>,
+    ) -> Result<(), Error<Self::Error>> {
+        for cached_guild_id in self.guild_ids().await? {
+            if let Some([shard_id, shard_count]) = shard {
+                if shard_count != 0 && (cached_guild_id.get() >> 22_u8) % shard_count != shard_id {
+                    continue;
+                }
+            }
+
+            if guild_ids.contains(&cached_guild_id) {
+                self.mark_guild_unavailable(cached_guild_id).await?;
+            } else {
+                self.remove_guild(cached_guild_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepare the cache to be used, this should be called once when the bot
+    /// starts, before the cache is updated
+    ///
+    /// Migrates the data in the backend with [`Self::migrate_schema`], then
+    /// calls [`Backend::prepare`] so the backend can prepare for the queries
+    /// the cache runs
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaVersionNewer`] if the data was written with a
+    /// newer version of this crate, or the error the backend might return
+    async fn init(&self) -> Result<(), Error<Self::Error>> {
+        self.migrate_schema().await?;
+        self.prepare().await?;
+
+        Ok(())
+    }
+
+    /// Check the schema version the data in the backend was written with and
+    /// migrate it to [`CURRENT_SCHEMA_VERSION`] if it's older
+    ///
+    /// This should be called when the bot starts, before the cache is
+    /// updated, so that changes to the models are detected here instead of
+    /// when deserializing the data, [`Self::init`] calls it
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SchemaVersionNewer`] if the data was written with a
+    /// newer version of this crate, or the error the backend might return
+    async fn migrate_schema(&self) -> Result<(), Error<Self::Error>> {
+        match self.schema_version().await? {
+            Some(version) if version > CURRENT_SCHEMA_VERSION => {
+                Err(Error::SchemaVersionNewer(version))
+            }
+            Some(version) if version < CURRENT_SCHEMA_VERSION => {
+                self.migrate(version, CURRENT_SCHEMA_VERSION).await?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Remove the data chosen by the options from the cache
+    ///
+    /// This is useful to run periodically, for example in a background task,
+    /// to keep the cache from growing indefinitely, refer to [`SweepOptions`]
+    /// for what can be removed
+    ///
+    /// If a TTL is set with [`CacheConfig::ttl`], the expired entities are also
+    /// removed, if [`CacheConfig::interactions`] is enabled, the interactions
+    /// whose tokens expired are also removed, the messages older than the
+    /// retentions set with [`CacheConfig::guild_message_retention`] are
+    /// always removed
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn sweep(&self, options: SweepOptions) -> Result<(), Error<Self::Error>> {
+        let mut messages_deleted = false;
+        if let Some(timestamp) = options.message_max_age.and_then(oldest_timestamp) {
+            self.delete_messages_before(timestamp).await?;
+            messages_deleted = true;
+        }
+        for (guild_id, retention) in &self.config().guild_message_retention {
+            if let Some(timestamp) = oldest_timestamp(*retention) {
+                self.delete_guild_messages_before(*guild_id, timestamp)
+                    .await?;
+                messages_deleted = true;
+            }
+        }
+        if self.config().has_ttls() {
+            if let Ok(now) = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp()) {
+                self.delete_expired(now).await?;
+                messages_deleted = true;
+            }
+        }
+        if self.config().interactions {
+            if let Ok(now) = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp()) {
+                self.delete_expired_interactions(now).await?;
+            }
+        }
+        if let Some(timestamp) = self.config().event_dedup.and_then(oldest_timestamp) {
+            self.delete_seen_events_before(timestamp).await?;
+        }
+        if messages_deleted || options.orphaned_message_data {
+            self.delete_orphaned_message_data().await?;
+        }
+        if options.memberless_presences {
+            self.delete_memberless_presences().await?;
+        }
+        if options.guild_remnants {
+            self.delete_guild_remnants().await?;
+        }
+        if let Some(pool) = self.arc_pool() {
+            pool.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Get the number of cached entities of every kind
+    ///
+    /// This is useful to monitor how large the cache grows and to decide how
+    /// often to sweep it with [`Cache::sweep`]
+    ///
+    /// With the `metrics` feature, the counts are also recorded in the
+    /// `sparkle_cache_entities` and `sparkle_cache_entity_bytes` gauges
+    ///
+    /// Queries [`Backend::entity_counts`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn stats(&self) -> Result<CacheStats, Error<Self::Error>> {
+        let entities = self.entity_counts().await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_entity_counts(&entities);
+
+        Ok(CacheStats { entities })
+    }
+
+    /// Scan the cache for referential breaks, such as embed fields without
+    /// embeds, reactions of messages that aren't cached or roles of guilds that
+    /// aren't cached
+    ///
+    /// If `repair` is true, the data with referential breaks is removed from
+    /// the cache, the returned report still contains what was found before
+    /// removing it
+    ///
+    /// This is useful after the backend crashes or after a partial migration
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn verify_integrity(&self, repair: bool) -> Result<IntegrityReport, Error<Self::Error>> {
+        let report = IntegrityReport {
+            embed_fields_without_embed: self.select_orphaned_embed_fields().await?,
+            embeds_without_message: self.select_orphaned_embeds().await?,
+            attachments_without_message: self.select_orphaned_attachments().await?,
+            reactions_without_message: self.select_orphaned_reactions().await?,
+            roles_without_guild: self.select_orphaned_roles().await?,
+        };
+
+        if repair {
+            for &embed_id in &report.embed_fields_without_embed {
+                self.delete_embed_fields(embed_id).await?;
+            }
+            for &message_id in &report.embeds_without_message {
+                self.remove_embeds(message_id).await?;
+            }
+            for &message_id in &report.attachments_without_message {
+                self.delete_message_attachments(message_id).await?;
+            }
+            for &message_id in &report.reactions_without_message {
+                self.delete_message_reactions(message_id).await?;
+            }
+            for &guild_id in &report.roles_without_guild {
+                self.delete_guild_role_assignments(guild_id).await?;
+                self.delete_guild_roles(guild_id).await?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Write a synthetic entity of every cached type, read it back and delete
+    /// it, returning the methods that errored or returned different data
+    ///
+    /// This is meant to be called at startup, so that mistakes in the backend,
+    /// such as a field mapped to the wrong column, are caught before the cache
+    /// is updated with live events
+    ///
+    /// Every method is called even if the previous ones failed, so that all
+    /// the failures are reported at once
+    ///
+    /// # Warnings
+    ///
+    /// The synthetic entities use the ID `1`, which Discord never uses, but
+    /// the synthetic guild is returned by [`Cache::guild_ids`] until it's
+    /// deleted at the end, so this shouldn't be called while the cache is
+    /// being updated
+    #[allow(clippy::too_many_lines)]
+    async fn validate_backend(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        let Some(synthetic) = Synthetic::new() else {
+            report.failures.push(ValidationFailure {
+                method: "validate_backend",
+                error: "the timestamp of the synthetic entities couldn't be created".to_owned(),
+            });
+            return report;
+        };
+        let guild_id = synthetic.guild.id;
+        let channel_id = synthetic.channel.id;
+        let message_id = synthetic.message.id;
+        let user_id = synthetic.member.id;
+        let embed_id = synthetic.embed.id;
+
+        report.record(
+            "upsert_guild",
+            self.upsert_guild(synthetic.guild.clone()).await,
+        );
+        report.record(
+            "upsert_channel",
+            self.upsert_channel(synthetic.channel.clone()).await,
+        );
+        report.record(
+            "upsert_permission_overwrite",
+            self.upsert_permission_overwrite(synthetic.permission_overwrite.clone())
+                .await,
+        );
+        report.record(
+            "upsert_private_channel",
+            self.upsert_private_channel(synthetic.private_channel.clone())
+                .await,
+        );
+        report.record(
+            "upsert_message",
+            self.upsert_message(synthetic.message.clone()).await,
+        );
+        report.record(
+            "upsert_embed",
+            self.upsert_embed(synthetic.embed.clone()).await,
+        );
+        report.record(
+            "upsert_embed_field",
+            self.upsert_embed_field(synthetic.embed_field.clone()).await,
+        );
+        report.record(
+            "upsert_attachment",
+            self.upsert_attachment(synthetic.attachment.clone()).await,
+        );
+        report.record(
+            "upsert_reaction",
+            self.upsert_reaction(synthetic.reaction.clone()).await,
+        );
+        report.record(
+            "upsert_member",
+            self.upsert_member(synthetic.member.clone()).await,
+        );
+        report.record(
+            "upsert_presence",
+            self.upsert_presence(synthetic.presence.clone()).await,
+        );
+        report.record(
+            "upsert_activity",
+            self.upsert_activity(synthetic.activity.clone()).await,
+        );
+        report.record(
+            "upsert_role",
+            self.upsert_role(synthetic.role.clone()).await,
+        );
+        report.record(
+            "upsert_role_assignment",
+            self.upsert_role_assignment(synthetic.role_assignment).await,
+        );
+        report.record(
+            "upsert_emoji",
+            self.upsert_emoji(synthetic.emoji.clone()).await,
+        );
+        report.record(
+            "upsert_sticker",
+            self.upsert_sticker(synthetic.sticker.clone()).await,
+        );
+        report.record(
+            "upsert_stage_instance",
+            self.upsert_stage_instance(synthetic.stage_instance.clone())
+                .await,
+        );
+
+        report.check(
+            "guild",
+            self.guild(guild_id).await,
+            &Some(synthetic.guild.clone()),
+        );
+        report.check(
+            "guilds_with_feature",
+            self.guilds_with_feature(GuildFeature::Community)
+                .await
+                .map(|guilds| {
+                    guilds
+                        .into_iter()
+                        .filter(|guild| guild.id == guild_id)
+                        .collect::<Vec<_>>()
+                }),
+            &vec![synthetic.guild.clone()],
+        );
+        report.check(
+            "channel",
+            self.channel(channel_id).await,
+            &Some(synthetic.channel.clone()),
+        );
+        report.check(
+            "permission_overwrites",
+            self.permission_overwrites(channel_id).await,
+            &vec![synthetic.permission_overwrite.clone()],
+        );
+        report.check(
+            "member_overwrites",
+            self.member_overwrites(channel_id, user_id).await,
+            &Some(synthetic.permission_overwrite.clone()),
+        );
+        report.check(
+            "private_channel_users",
+            self.private_channel_users(channel_id).await,
+            &vec![user_id],
+        );
+        report.check(
+            "message",
+            self.message(message_id).await,
+            &Some(synthetic.message.clone()),
+        );
+        report.check(
+            "embeds",
+            self.embeds(message_id).await,
+            &vec![(synthetic.embed.clone(), vec![synthetic.embed_field.clone()])],
+        );
+        report.check(
+            "attachments",
+            self.attachments(message_id).await,
+            &vec![synthetic.attachment.clone()],
+        );
+        let (attachment_blob, attachment_post) = synthetic.attachment.clone().split();
+        let file_hash = attachment_blob.file_hash.clone();
+        report.record(
+            "upsert_attachment_blob",
+            self.upsert_attachment_blob(attachment_blob).await,
+        );
+        report.record(
+            "upsert_attachment_post",
+            self.upsert_attachment_post(attachment_post).await,
+        );
+        report.check(
+            "attachment_posters",
+            self.attachment_posters(&file_hash).await,
+            &vec![synthetic.message.clone()],
+        );
+        report.check(
+            "reactions",
+            self.reactions(message_id).await,
+            &vec![synthetic.reaction.clone()],
+        );
+        report.check(
+            "member",
+            self.member(user_id, guild_id).await,
+            &Some(synthetic.member.clone()),
+        );
+        report.check(
+            "mutual_guilds",
+            self.mutual_guilds(user_id, user_id).await.map(|guild_ids| {
+                guild_ids
+                    .into_iter()
+                    .filter(|&id| id == guild_id)
+                    .collect::<Vec<_>>()
+            }),
+            &vec![guild_id],
+        );
+        report.check(
+            "presence",
+            self.presence(user_id).await,
+            &Some(synthetic.presence.clone()),
+        );
+        report.check(
+            "member_activities",
+            self.member_activities(user_id).await,
+            &vec![synthetic.activity.clone()],
+        );
+        report.check(
+            "role",
+            self.role(synthetic.role.id).await,
+            &Some(synthetic.role.clone()),
+        );
+        report.check(
+            "everyone_role",
+            self.everyone_role(guild_id).await,
+            &Some(synthetic.role.clone()),
+        );
+        report.check(
+            "member_roles",
+            self.member_roles(user_id, guild_id).await,
+            &vec![synthetic.role.clone()],
+        );
+        report.check(
+            "emoji",
+            self.emoji(synthetic.emoji.id).await,
+            &Some(synthetic.emoji.clone()),
+        );
+        report.check(
+            "sticker",
+            self.sticker(synthetic.sticker.id).await,
+            &Some(synthetic.sticker.clone()),
+        );
+        report.check(
+            "stage_instance",
+            self.stage_instance(synthetic.stage_instance.id).await,
+            &Some(synthetic.stage_instance.clone()),
+        );
+
+        report.record(
+            "delete_stage_instance",
+            self.delete_stage_instance(synthetic.stage_instance.id)
+                .await,
+        );
+        report.record(
+            "delete_guild_stickers",
+            self.delete_guild_stickers(guild_id).await,
+        );
+        report.record("delete_emoji", self.delete_emoji(synthetic.emoji.id).await);
+        report.record(
+            "delete_member_role_assignments",
+            self.delete_member_role_assignments(guild_id, user_id).await,
+        );
+        report.record("delete_role", self.delete_role(synthetic.role.id).await);
+        report.record(
+            "delete_user_activities",
+            self.delete_user_activities(guild_id, user_id).await,
+        );
+        report.record(
+            "delete_presence",
+            self.delete_presence(guild_id, user_id).await,
+        );
+        report.record("delete_member", self.delete_member(user_id, guild_id).await);
+        report.record(
+            "delete_message_reactions",
+            self.delete_message_reactions(message_id).await,
+        );
+        report.record(
+            "delete_message_attachments",
+            self.delete_message_attachments(message_id).await,
+        );
+        report.record(
+            "delete_embed_fields",
+            self.delete_embed_fields(embed_id).await,
+        );
+        report.record("delete_embed", self.delete_embed(embed_id).await);
+        report.record("delete_message", self.delete_message(message_id).await);
+        report.record(
+            "delete_private_channel",
+            self.delete_private_channel(channel_id).await,
+        );
+        report.record(
+            "delete_channel_permission_overwrites",
+            self.delete_channel_permission_overwrites(channel_id).await,
+        );
+        report.record("delete_channel", self.delete_channel(channel_id).await);
+        report.record("delete_guild", self.delete_guild(guild_id).await);
+
+        report.check(
+            "delete_stage_instance",
+            self.stage_instance(synthetic.stage_instance.id).await,
+            &None,
+        );
+        report.check(
+            "delete_guild_stickers",
+            self.sticker(synthetic.sticker.id).await,
+            &None,
+        );
+        report.check("delete_emoji", self.emoji(synthetic.emoji.id).await, &None);
+        report.check(
+            "delete_member_role_assignments",
+            self.member_roles(user_id, guild_id).await,
+            &vec![],
+        );
+        report.check("delete_role", self.role(synthetic.role.id).await, &None);
+        report.check(
+            "delete_user_activities",
+            self.member_activities(user_id).await,
+            &vec![],
+        );
+        report.check("delete_presence", self.presence(user_id).await, &None);
+        report.check("delete_member", self.member(user_id, guild_id).await, &None);
+        report.check(
+            "delete_message_reactions",
+            self.reactions(message_id).await,
+            &vec![],
+        );
+        report.check(
+            "delete_message_attachments",
+            self.attachments(message_id).await,
+            &vec![],
+        );
+        report.check(
+            "delete_embed_fields",
+            self.select_embed_fields(embed_id).await,
+            &vec![],
+        );
+        report.check("delete_embed", self.embeds(message_id).await, &vec![]);
+        report.check("delete_message", self.message(message_id).await, &None);
+        report.check(
+            "delete_private_channel",
+            self.private_channel_users(channel_id).await,
+            &vec![],
+        );
+        report.check(
+            "delete_channel_permission_overwrites",
+            self.permission_overwrites(channel_id).await,
+            &vec![],
+        );
+        report.check("delete_channel", self.channel(channel_id).await, &None);
+        report.check("delete_guild", self.guild(guild_id).await, &None);
+
+        report
+    }
+
+    /// Compare the cached channels, roles, members and emojis of the guild with
+    /// the ones fetched with the HTTP client, returning the differences
+    ///
+    /// This is useful to check whether the cache is in sync in production, for
+    /// example after reconnecting, every member of the guild is fetched so
+    /// it's slow in large guilds
+    ///
+    /// Queries [`Self::guild_channels`], [`Self::guild_roles`],
+    /// [`Self::guild_members`] and [`Self::guild_emojis`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::Http`] if fetching the data failed
+    #[cfg(feature = "http")]
+    async fn diff_against_http(
+        &self,
+        http: &Client,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CacheDrift, Error<Self::Error>> {
+        let channels: Vec<_> = http
+            .guild_channels(guild_id)
+            .await
+            .map_err(http_error)?
+            .models()
+            .await
+            .map_err(http_error)?
+            .iter()
+            .map(CachedChannel::from)
+            .collect();
+        let cached_channels: Vec<_> = self
+            .guild_channels(guild_id)
+            .await?
+            .into_iter()
+            .filter(|channel| channel.thread_archived.is_none())
+            .collect();
+
+        let roles: Vec<_> = http
+            .roles(guild_id)
+            .await
+            .map_err(http_error)?
+            .models()
+            .await
+            .map_err(http_error)?
+            .into_iter()
+            .map(|role| CachedRole::from_role(role, guild_id))
+            .collect();
+
+        let mut members: Vec<CachedMember> = Vec::new();
+        loop {
+            let mut request = http
+                .guild_members(guild_id)
+                .limit(MEMBERS_PAGE_SIZE)
+                .map_err(http_error)?;
+            if let Some(last_member) = members.last() {
+                request = request.after(last_member.id);
+            }
+            let page = request
+                .await
+                .map_err(http_error)?
+                .models()
+                .await
+                .map_err(http_error)?;
+            let is_last_page = page.len() < usize::from(MEMBERS_PAGE_SIZE);
+            members.extend(page.iter().map(CachedMember::from));
+            if is_last_page {
+                break;
+            }
+        }
+
+        let emojis: Vec<_> = http
+            .emojis(guild_id)
+            .await
+            .map_err(http_error)?
+            .models()
+            .await
+            .map_err(http_error)?
+            .iter()
+            .map(|emoji| CachedEmoji::from_emoji(emoji, guild_id))
+            .collect();
+
+        Ok(CacheDrift {
+            channels: EntityDrift::new(&channels, &cached_channels, |channel| channel.id),
+            roles: EntityDrift::new(&roles, &self.guild_roles(guild_id).await?, |role| role.id),
+            members: EntityDrift::new(&members, &self.guild_members(guild_id).await?, |member| {
+                member.id
+            }),
+            emojis: EntityDrift::new(&emojis, &self.guild_emojis(guild_id).await?, |emoji| {
+                emoji.id
+            }),
+        })
+    }
+
+    /// Get the IDs of the given users that aren't cached as members of the
+    /// guild
+    ///
+    /// This is useful when members are loaded lazily, to know which ones to
+    /// request, `Cache::request_missing_members` of the `gateway` feature also
+    /// requests them
+    ///
+    /// Queries [`Self::member`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn missing_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[Id<UserMarker>],
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        let mut missing = vec![];
+        for &user_id in user_ids {
+            if self.member(user_id, guild_id).await?.is_none() {
+                missing.push(user_id);
+            }
+        }
+
+        Ok(missing)
+    }
+
+    /// Request the given users that aren't cached as members of the guild
+    /// from the gateway, wait until their member chunks are cached and return
+    /// the IDs of the users that still aren't cached, because they aren't in
+    /// the guild
+    ///
+    /// The members are requested in batches of 100 with a unique nonce, and
+    /// the events received from `events` while waiting are cached as usual, so
+    /// no events are lost, so this should be called in place of your event
+    /// loop, not alongside it
+    ///
+    /// Waiting stops early if `events` ends, for example when the shard is shut
+    /// down
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::Gateway`] if sending the request failed
+    #[cfg(feature = "gateway")]
+    async fn request_missing_members(
+        &self,
+        shard: &Shard,
+        events: &mut Events,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[Id<UserMarker>],
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        let missing = self.missing_members(guild_id, user_ids).await?;
+
+        let mut pending_nonces = vec![];
+        for batch in missing.chunks(MEMBER_REQUEST_SIZE) {
+            let nonce = unique_id().to_string();
+            let request = RequestGuildMembers::builder(guild_id)
+                .nonce(nonce.clone())
+                .user_ids(batch.to_vec())
+                .map_err(gateway_error)?;
+            shard.command(&request).await.map_err(gateway_error)?;
+            pending_nonces.push(nonce);
+        }
+
+        while !pending_nonces.is_empty() {
+            let Some(event) = events.next().await else {
+                break;
+            };
+            self.update(&event).await?;
+
+            if let Event::MemberChunk(chunk) = &event {
+                if chunk.chunk_index.saturating_add(1) >= chunk.chunk_count {
+                    pending_nonces.retain(|nonce| chunk.nonce.as_ref() != Some(nonce));
+                }
+            }
+        }
+
+        self.missing_members(guild_id, &missing).await
+    }
+
+    /// Get permissions of the current user in the given channel
+    ///
+    /// This is a convenience method for [`Self::channel_permissions`] with the
+    /// current user's ID
+    ///
+    /// Queries [`Self::current_user`], [`Self::channel`], [`Self::guild`],
+    /// [`Backend::select_everyone_role`], [`Self::guild_roles`],
+    /// [`Self::member_roles`], [`Self::permission_overwrites`] and
+    /// [`Self::member`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsChannelMissing`],
+    /// [`Error::PermissionsChannelNotInGuild`],
+    /// [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`] or
+    /// [`Error::PermissionsGuildEveryoneRoleMissing`]
     async fn self_channel_permissions(
         &self,
         channel_id: Id<ChannelMarker>,
@@ -407,11 +1921,17 @@ pub trait Cache: Backend {
     /// This is a convenience method for [`Self::guild_permissions`] with the
     /// current user's ID
     ///
+    /// Queries [`Self::current_user`], [`Self::guild`],
+    /// [`Backend::select_everyone_role`], [`Self::guild_roles`],
+    /// [`Self::member_roles`], [`Self::permission_overwrites`] and
+    /// [`Self::member`]
+    ///
     /// # Errors
     ///
     /// Returns the error the backend might return
     ///
-    /// Returns [`Error::PermissionsGuildMissing`] or
+    /// Returns [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`] or
     /// [`Error::PermissionsGuildEveryoneRoleMissing`]
     async fn self_guild_permissions(
         &self,
@@ -423,6 +1943,11 @@ pub trait Cache: Backend {
 
     /// Get the permissions of the given user and channel
     ///
+    /// Queries [`Self::channel`], [`Self::guild`],
+    /// [`Backend::select_everyone_role`], [`Self::guild_roles`],
+    /// [`Self::member_roles`], [`Self::permission_overwrites`] and
+    /// [`Self::member`]
+    ///
     /// # Errors
     ///
     /// Returns the error the backend might return
@@ -430,6 +1955,7 @@ pub trait Cache: Backend {
     /// Returns [`Error::PermissionsChannelMissing`],
     /// [`Error::PermissionsChannelNotInGuild`],
     /// [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`],
     /// [`Error::PermissionsGuildEveryoneRoleMissing`],
     /// [`Error::PermissionsMemberMissing`] or
     /// [`Error::MemberBadTimeoutTimestamp`]
@@ -438,6 +1964,9 @@ pub trait Cache: Backend {
         user_id: Id<UserMarker>,
         channel_id: Id<ChannelMarker>,
     ) -> Result<Permissions, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
         let channel = self
             .channel(channel_id)
             .await?
@@ -445,16 +1974,26 @@ pub trait Cache: Backend {
         let guild_id = channel
             .guild_id
             .ok_or_else(|| Error::PermissionsChannelNotInGuild(Box::new(channel.clone())))?;
-        self.permissions(user_id, guild_id, Some(channel)).await
+        let permissions = self.permissions(user_id, guild_id, Some(channel)).await;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("channel_permissions", started.elapsed());
+
+        permissions
     }
 
     /// Get the permissions of the given user and guild
     ///
+    /// Queries [`Self::guild`], [`Backend::select_everyone_role`],
+    /// [`Self::guild_roles`], [`Self::member_roles`],
+    /// [`Self::permission_overwrites`] and [`Self::member`]
+    ///
     /// # Errors
     ///
     /// Returns the error the backend might return
     ///
     /// Returns [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`],
     /// [`Error::PermissionsGuildEveryoneRoleMissing`],
     /// [`Error::PermissionsMemberMissing`] or
     /// [`Error::MemberBadTimeoutTimestamp`]
@@ -463,18 +2002,281 @@ pub trait Cache: Backend {
         user_id: Id<UserMarker>,
         guild_id: Id<GuildMarker>,
     ) -> Result<Permissions, Error<Self::Error>> {
-        self.permissions(user_id, guild_id, None).await
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let permissions = self.permissions(user_id, guild_id, None).await;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("guild_permissions", started.elapsed());
+
+        permissions
     }
 
-    /// Get the permissions with the given parameters
+    /// Get whether the given user owns the guild
+    ///
+    /// Queries [`Self::guild`]
     ///
     /// # Errors
     ///
     /// Returns the error the backend might return
     ///
-    /// Returns [`Error::PermissionsGuildMissing`],
-    /// [`Error::PermissionsGuildEveryoneRoleMissing`],
-    /// [`Error::PermissionsMemberMissing`] or
+    /// Returns [`Error::PermissionsGuildMissing`]
+    async fn is_owner(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<Self::Error>> {
+        let guild = self
+            .guild(guild_id)
+            .await?
+            .ok_or(Error::PermissionsGuildMissing(guild_id))?;
+
+        Ok(guild.owner_id == user_id)
+    }
+
+    /// Get whether the given user owns the guild or has a role with the
+    /// administrator permission in it
+    ///
+    /// This is faster than checking [`Self::guild_permissions`], since it
+    /// returns as soon as one of the conditions is met and doesn't query the
+    /// member
+    ///
+    /// Queries [`Self::guild`], [`Backend::select_everyone_role`],
+    /// [`Self::guild_roles`] and [`Self::member_roles`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`] or
+    /// [`Error::PermissionsGuildEveryoneRoleMissing`]
+    async fn is_admin(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<Self::Error>> {
+        if self.is_owner(user_id, guild_id).await? {
+            return Ok(true);
+        }
+
+        if self
+            .permissions_everyone_role(guild_id)
+            .await?
+            .permissions
+            .contains(Permissions::ADMINISTRATOR)
+        {
+            return Ok(true);
+        }
+
+        Ok(self
+            .member_roles(user_id, guild_id)
+            .await?
+            .iter()
+            .any(|role| role.permissions.contains(Permissions::ADMINISTRATOR)))
+    }
+
+    /// Get the roles in the guild that grant the given permissions, for example
+    /// to audit which roles have [`Permissions::ADMINISTRATOR`]
+    ///
+    /// Roles with [`Permissions::ADMINISTRATOR`] are included for any
+    /// permissions since they grant all of them, the everyone role is included
+    /// if it grants the permissions
+    ///
+    /// Queries [`Self::guild_roles`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn roles_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        let mut roles = self.guild_roles(guild_id).await?;
+        roles.retain(|role| {
+            role.permissions.contains(permissions)
+                || role.permissions.contains(Permissions::ADMINISTRATOR)
+        });
+
+        Ok(roles)
+    }
+
+    /// Get the IDs of the members in the guild that have the given permissions
+    /// from their roles, for example to audit who can mention everyone
+    ///
+    /// This is computed from the guild's role assignments of the roles
+    /// returned by [`Self::roles_with_permission`], the guild's owner is always
+    /// included, if the everyone role grants the permissions, all of the
+    /// guild's cached members are returned, channel permission overwrites and
+    /// timeouts aren't considered
+    ///
+    /// The IDs are sorted and unique, the result depends on the members being
+    /// cached, so it may be incomplete in guilds whose members are cached
+    /// lazily
+    ///
+    /// Queries [`Self::guild`], [`Self::guild_roles`],
+    /// [`Backend::select_everyone_role`], and [`Self::guild_members`] if the
+    /// everyone role grants the permissions, or
+    /// [`Backend::select_guild_role_assignments`] otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsGuildMissing`]
+    async fn members_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        let guild = self
+            .guild(guild_id)
+            .await?
+            .ok_or(Error::PermissionsGuildMissing(guild_id))?;
+
+        let roles = self.roles_with_permission(guild_id, permissions).await?;
+        let mut user_ids = vec![guild.owner_id];
+        if roles.is_empty() {
+            return Ok(user_ids);
+        }
+
+        let everyone_role_id = self.everyone_role(guild_id).await?.map(|role| role.id);
+        if roles.iter().any(|role| Some(role.id) == everyone_role_id) {
+            user_ids.extend(
+                self.guild_members(guild_id)
+                    .await?
+                    .iter()
+                    .map(|member| member.id),
+            );
+        } else {
+            user_ids.extend(
+                self.select_guild_role_assignments(guild_id)
+                    .await?
+                    .iter()
+                    .filter(|assignment| roles.iter().any(|role| role.id == assignment.role_id))
+                    .map(|assignment| assignment.user_id),
+            );
+        }
+        user_ids.sort_unstable();
+        user_ids.dedup();
+
+        Ok(user_ids)
+    }
+
+    /// Get the channels in the guild that the given user can view
+    ///
+    /// This is the same as filtering the guild's channels by whether
+    /// [`Self::channel_permissions`] contains [`Permissions::VIEW_CHANNEL`],
+    /// but the guild, roles and member are only queried once
+    ///
+    /// Threads aren't included
+    ///
+    /// Queries [`Self::guild`], [`Backend::select_everyone_role`],
+    /// [`Self::guild_roles`], [`Self::member`], [`Self::member_roles`],
+    /// [`Self::guild_channels`] and [`Self::permission_overwrites`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`],
+    /// [`Error::PermissionsGuildEveryoneRoleMissing`] or
+    /// [`Error::PermissionsMemberMissing`]
+    async fn visible_channels(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let guild = self
+            .guild(guild_id)
+            .await?
+            .ok_or(Error::PermissionsGuildMissing(guild_id))?;
+        let everyone_role = self.permissions_everyone_role(guild_id).await?;
+        if self.member(user_id, guild_id).await?.is_none() {
+            return Err(Error::PermissionsMemberMissing { user_id, guild_id });
+        }
+        let roles: Vec<_> = self
+            .member_roles(user_id, guild_id)
+            .await?
+            .iter()
+            .map(|role| (role.id, role.permissions))
+            .collect();
+
+        let calculator =
+            PermissionCalculator::new(guild_id, user_id, everyone_role.permissions, &roles)
+                .owner_id(guild.owner_id);
+        let mut channels = vec![];
+        for channel in self.guild_channels(guild_id).await? {
+            if channel.thread_archived.is_some() {
+                continue;
+            }
+            let overwrites = self
+                .permission_overwrites(channel.id)
+                .await?
+                .iter()
+                .map(|overwrite| PermissionOverwrite {
+                    allow: overwrite.allow,
+                    deny: overwrite.deny,
+                    id: overwrite.id,
+                    kind: overwrite.kind,
+                })
+                .collect::<Vec<_>>();
+            if calculator
+                .clone()
+                .in_channel(channel.kind, &overwrites)
+                .contains(Permissions::VIEW_CHANNEL)
+            {
+                channels.push(channel);
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("visible_channels", started.elapsed());
+
+        Ok(channels)
+    }
+
+    /// Get the everyone role to calculate permissions with
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsGuildRolesMissing`] if none of the guild's
+    /// roles are cached, or [`Error::PermissionsGuildEveryoneRoleMissing`] if
+    /// only the everyone role isn't cached
+    #[doc(hidden)]
+    async fn permissions_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedRole, Error<Self::Error>> {
+        if let Some(everyone_role) = self.everyone_role(guild_id).await? {
+            return Ok(everyone_role);
+        }
+
+        if self.guild_roles(guild_id).await?.is_empty() {
+            Err(Error::PermissionsGuildRolesMissing(guild_id))
+        } else {
+            Err(Error::PermissionsGuildEveryoneRoleMissing(guild_id))
+        }
+    }
+
+    /// Get the permissions with the given parameters
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsGuildMissing`],
+    /// [`Error::PermissionsGuildRolesMissing`],
+    /// [`Error::PermissionsGuildEveryoneRoleMissing`],
+    /// [`Error::PermissionsMemberMissing`] or
     /// [`Error::MemberBadTimeoutTimestamp`]
     #[doc(hidden)]
     async fn permissions(
@@ -487,10 +2289,7 @@ pub trait Cache: Backend {
             .guild(guild_id)
             .await?
             .ok_or(Error::PermissionsGuildMissing(guild_id))?;
-        let everyone_role = self
-            .role(guild_id.cast())
-            .await?
-            .ok_or(Error::PermissionsGuildEveryoneRoleMissing(guild_id))?;
+        let everyone_role = self.permissions_everyone_role(guild_id).await?;
         let roles: Vec<_> = self
             .member_roles(user_id, guild_id)
             .await?
@@ -544,6 +2343,52 @@ pub trait Cache: Backend {
     /// is received
     async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>>;
 
+    /// Get the current application information of the bot
+    ///
+    /// This is useful for interaction bots, which need the application ID to
+    /// respond to interactions
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CurrentApplicationMissing`] when called before the
+    /// ready event is received
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>>;
+
+    /// Get the cached sessions of the shards, one for each shard that
+    /// received a ready event
+    ///
+    /// This should be something like `SELECT * FROM sessions ORDER BY
+    /// shard_id`
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>>;
+
+    /// Get the cached state of a shard, updated by [`Self::update_sharded`]
+    ///
+    /// This should be something like `SELECT * FROM shard_states WHERE
+    /// shard_id = ?`
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>>;
+
+    /// Get the cached states of the shards, one for each shard that updated
+    /// the cache with [`Self::update_sharded`], for example to show them in a
+    /// dashboard
+    ///
+    /// This should be something like `SELECT * FROM shard_states ORDER BY
+    /// shard_id`
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>>;
+
+    /// Get a cached interaction by its ID, cached if
+    /// [`CacheConfig::interactions`] is enabled
+    ///
+    /// The interaction is returned even if its token expired but it wasn't
+    /// removed with [`Self::sweep`] yet, check
+    /// [`CachedInteraction::token_expires_at`] before using its token
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>>;
+
     /// Get a cached channel or thread by its ID
     ///
     /// The users that are joined in a thread aren't cached, as caching them is
@@ -553,163 +2398,2134 @@ pub trait Cache: Backend {
         channel_id: Id<ChannelMarker>,
     ) -> Result<Option<CachedChannel>, Error<Self::Error>>;
 
+    /// Get a cached channel or thread by its ID, shared through the pool
+    /// returned by [`Self::arc_pool`]
+    ///
+    /// The channel is read from the backend only if it isn't in the pool, so
+    /// reading the same channel repeatedly doesn't clone it, without a pool
+    /// this is the same as [`Self::channel`]
+    ///
+    /// Queries [`Self::channel`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_ref(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<Arc<CachedChannel>>, Error<Self::Error>> {
+        let Some(pool) = self.arc_pool() else {
+            return Ok(self.channel(channel_id).await?.map(Arc::new));
+        };
+        if let Some(channel) = pool.channel(channel_id) {
+            return Ok(Some(channel));
+        }
+
+        let generation = pool.generation();
+        let channel = self.channel(channel_id).await?.map(Arc::new);
+        if let Some(pooled_channel) = &channel {
+            pool.insert_channel(pooled_channel, generation);
+        }
+
+        Ok(channel)
+    }
+
     /// Get a cached permission overwrites of a channel by its ID
     async fn permission_overwrites(
         &self,
         channel_id: Id<ChannelMarker>,
     ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>>;
 
-    /// Get a guild's channels and threads by its ID
-    async fn guild_channels(
+    /// Get the cached permission overwrite of a channel for the given member
+    ///
+    /// Queries [`Backend::select_overwrites_by_target`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn member_overwrites(
         &self,
-        guild_id: Id<GuildMarker>,
-    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<Self::Error>> {
+        Ok(self
+            .select_overwrites_by_target(
+                channel_id,
+                user_id.cast(),
+                PermissionOverwriteType::Member,
+            )
+            .await?)
+    }
 
-    /// Get a cached message by its ID
+    /// Get the cached permission overwrite of a channel for the given role
     ///
-    /// The returned message doesn't contain embeds, attachments, reactions or
-    /// stickers, since they're cached separately and the method doesn't query
-    /// them for you to reduce overhead in case you don't need them
-    async fn message(
+    /// Queries [`Backend::select_overwrites_by_target`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn role_overwrites(
         &self,
-        message_id: Id<MessageMarker>,
-    ) -> Result<Option<CachedMessage>, Error<Self::Error>>;
+        channel_id: Id<ChannelMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<Self::Error>> {
+        Ok(self
+            .select_overwrites_by_target(channel_id, role_id.cast(), PermissionOverwriteType::Role)
+            .await?)
+    }
 
-    /// Get cached embeds of a message by its ID
-    async fn embeds(
+    /// Get whether a channel's permission overwrites are synced with its
+    /// category's, like Discord shows in the channel's settings
+    ///
+    /// Returns `None` if the channel isn't cached, isn't in a category or its
+    /// category isn't cached
+    ///
+    /// Queries [`Self::channel`] and [`Self::permission_overwrites`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_synced_with_category(
         &self,
-        message_id: Id<MessageMarker>,
-    ) -> Result<Vec<(CachedEmbed, Vec<CachedEmbedField>)>, Error<Self::Error>> {
-        let mut embeds = vec![];
-        let cached_embeds = self.select_message_embeds(message_id).await?;
-        for embed in cached_embeds {
-            let fields = self.select_embed_fields(embed.id).await?;
-            embeds.push((embed, fields));
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<bool>, Error<Self::Error>> {
+        let Some(parent_id) = self
+            .channel(channel_id)
+            .await?
+            .and_then(|channel| channel.parent_id)
+        else {
+            return Ok(None);
+        };
+        if !self
+            .channel(parent_id)
+            .await?
+            .map_or(false, |parent| parent.kind == ChannelType::GuildCategory)
+        {
+            return Ok(None);
         }
-        Ok(embeds)
+
+        let mut overwrites = overwrite_keys(&self.permission_overwrites(channel_id).await?);
+        let mut parent_overwrites = overwrite_keys(&self.permission_overwrites(parent_id).await?);
+        overwrites.sort_unstable();
+        parent_overwrites.sort_unstable();
+
+        Ok(Some(overwrites == parent_overwrites))
     }
 
-    /// Get cached attachments of a message by its ID
-    async fn attachments(
+    /// Get the effective permissions in the channel of every role with a
+    /// permission overwrite in it, for example for a command that shows a
+    /// channel's permissions
+    ///
+    /// Threads use their parent channel's overwrites, the overwrites of
+    /// categories are already copied to their channels by Discord, so they
+    /// aren't applied again, the everyone role is included if it has an
+    /// overwrite, roles that aren't cached are skipped
+    ///
+    /// The guild's roles and the channel's overwrites are only queried once,
+    /// returns an empty vector if the channel isn't cached
+    ///
+    /// Queries [`Self::channel`], [`Backend::select_everyone_role`],
+    /// [`Self::guild_roles`] and [`Self::permission_overwrites`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    ///
+    /// Returns [`Error::PermissionsChannelNotInGuild`],
+    /// [`Error::PermissionsGuildRolesMissing`] or
+    /// [`Error::PermissionsGuildEveryoneRoleMissing`]
+    async fn channel_permission_matrix(
         &self,
-        message_id: Id<MessageMarker>,
-    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>>;
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<ChannelRolePermissions>, Error<Self::Error>> {
+        let Some(channel) = self.channel(channel_id).await? else {
+            return Ok(vec![]);
+        };
+        let guild_id = channel
+            .guild_id
+            .ok_or_else(|| Error::PermissionsChannelNotInGuild(Box::new(channel.clone())))?;
+        let overwrites_channel_id = if channel.thread_archived.is_some() {
+            channel.parent_id.unwrap_or(channel.id)
+        } else {
+            channel.id
+        };
 
-    /// Get cached reactions of a message by its ID
-    async fn reactions(
-        &self,
-        message_id: Id<MessageMarker>,
-    ) -> Result<Vec<CachedReaction>, Error<Self::Error>>;
+        let everyone_role = self.permissions_everyone_role(guild_id).await?;
+        let roles = self.guild_roles(guild_id).await?;
+        let mut overwrites = self.permission_overwrites(overwrites_channel_id).await?;
+        overwrites.retain(|overwrite| overwrite.kind == PermissionOverwriteType::Role);
+        let everyone_overwrite = overwrites
+            .iter()
+            .find(|overwrite| overwrite.id == everyone_role.id.cast());
 
-    /// Get cached stickers of a message by its ID
-    async fn stickers(
-        &self,
-        message_id: Id<MessageMarker>,
-    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+        let mut matrix = vec![];
+        for role_overwrite in &overwrites {
+            let Some(role) = roles
+                .iter()
+                .find(|role| role.id == role_overwrite.id.cast())
+            else {
+                continue;
+            };
+            let is_everyone = role.id == everyone_role.id;
+            let role_overwrites: Vec<_> = everyone_overwrite
+                .into_iter()
+                .chain((!is_everyone).then_some(role_overwrite))
+                .map(|overwrite| PermissionOverwrite {
+                    allow: overwrite.allow,
+                    deny: overwrite.deny,
+                    id: overwrite.id,
+                    kind: overwrite.kind,
+                })
+                .collect();
+            let role_permissions = if is_everyone {
+                vec![]
+            } else {
+                vec![(role.id, role.permissions)]
+            };
 
-    /// Get a channel's most recent `limit` messages by its ID
+            matrix.push(ChannelRolePermissions {
+                role_id: role.id,
+                allow: role_overwrite.allow,
+                deny: role_overwrite.deny,
+                permissions: PermissionCalculator::new(
+                    guild_id,
+                    guild_id.cast(),
+                    everyone_role.permissions,
+                    &role_permissions,
+                )
+                .in_channel(channel.kind, &role_overwrites),
+            });
+        }
+
+        Ok(matrix)
+    }
+
+    /// Get the cached DM channel with the given user
     ///
-    /// A limit of 0 means to return all messages
+    /// Discord doesn't send channel create events for DM channels to bots, so
+    /// they're only cached when the cache is updated with a channel create
+    /// event yourself, for example with the channel returned when creating
+    /// the DM channel:
     ///
-    /// The messages are ordered from most recent to least recent
-    async fn channel_messages(
+    /// ```ignore
+    /// let channel = http.create_private_channel(user_id).await?.model().await?;
+    /// cache
+    ///     .update(&Event::ChannelCreate(Box::new(ChannelCreate(channel))))
+    ///     .await?;
+    /// ```
+    ///
+    /// The channel is removed from the cache when the cache is updated with
+    /// its channel delete event
+    ///
+    /// This should be something like `SELECT channels.* FROM channels JOIN
+    /// private_channels ON channels.id = private_channels.channel_id WHERE
+    /// private_channels.user_id = ?`
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>>;
+
+    /// Get the IDs of a cached DM channel's recipients by its ID
+    ///
+    /// This should be something like `SELECT user_id FROM private_channels
+    /// WHERE channel_id = ?`
+    async fn private_channel_users(
         &self,
         channel_id: Id<ChannelMarker>,
-        limit: u16,
-    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>>;
+
+    /// Get a guild's channels and threads by its ID
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's archived threads by its ID
+    ///
+    /// Threads are marked as archived when a thread update event archives
+    /// them, or when a thread list sync event doesn't include them even though
+    /// it syncs their parent channel
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's channels whose latest message is older than the given
+    /// timestamp, for example to archive them
+    ///
+    /// This requires [`CacheConfig::channel_activity`], channels without a
+    /// message since it was enabled aren't returned, since it's unknown when
+    /// their latest message was sent
+    ///
+    /// This should be something like `SELECT channels.* FROM channels JOIN
+    /// channel_activity ON channel_activity.channel_id = channels.id WHERE
+    /// channels.guild_id = ? AND channel_activity.last_activity < ?`
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a channel's threads that aren't archived by the channel's ID
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's announcement channels, for example for bots that publish
+    /// the messages in them
+    ///
+    /// Queries [`Self::guild_channels`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn announcement_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        let mut channels = self.guild_channels(guild_id).await?;
+        channels.retain(|channel| channel.kind == ChannelType::GuildAnnouncement);
+
+        Ok(channels)
+    }
+
+    /// Get a guild's channels in the order the Discord client shows them
+    ///
+    /// Channels without a category come first, then every category followed
+    /// by its channels, text channels come before voice and stage channels,
+    /// and channels are sorted by their position, then by their ID, since
+    /// positions may be tied, for example while the update events of channels
+    /// that were dragged arrive one by one
+    ///
+    /// Threads aren't included, channels whose category isn't cached are
+    /// treated as channels without a category
+    ///
+    /// Queries [`Self::guild_channels`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn guild_channels_ordered(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        let mut channels = self.guild_channels(guild_id).await?;
+        channels.retain(|channel| channel.thread_archived.is_none());
+        channels.sort_by_key(channel_order_key);
+
+        let (categories, channels): (Vec<_>, Vec<_>) = channels
+            .into_iter()
+            .partition(|channel| channel.kind == ChannelType::GuildCategory);
+        let (mut ordered, mut categorized): (Vec<_>, Vec<_>) =
+            channels.into_iter().partition(|channel| {
+                channel.parent_id.map_or(true, |parent_id| {
+                    !categories.iter().any(|category| category.id == parent_id)
+                })
+            });
+
+        for category in categories {
+            let category_id = category.id;
+            let (children, rest): (Vec<_>, Vec<_>) = categorized
+                .into_iter()
+                .partition(|channel| channel.parent_id == Some(category_id));
+            ordered.push(category);
+            ordered.extend(children);
+            categorized = rest;
+        }
+
+        Ok(ordered)
+    }
+
+    /// Get the layout of a guild, its settings, roles, channels and
+    /// permission overwrites, `None` if the guild isn't cached
+    ///
+    /// Threads aren't included
+    ///
+    /// Queries [`Self::guild`], [`Self::guild_roles`], [`Self::guild_channels`]
+    /// and [`Self::permission_overwrites`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn guild_structure_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildStructure>, Error<Self::Error>> {
+        let Some(guild) = self.guild(guild_id).await? else {
+            return Ok(None);
+        };
+
+        let mut roles = self.guild_roles(guild_id).await?;
+        roles.sort_by_key(|role| (role.position, role.id));
+
+        let mut channels = vec![];
+        for channel in self.guild_channels_ordered(guild_id).await? {
+            let permission_overwrites = self.permission_overwrites(channel.id).await?;
+            channels.push(ChannelStructure {
+                channel,
+                permission_overwrites,
+            });
+        }
+
+        Ok(Some(GuildStructure {
+            guild,
+            roles,
+            channels,
+        }))
+    }
+
+    /// Get the slowmode of a channel in seconds, `None` if the channel isn't
+    /// cached or doesn't have slowmode
+    ///
+    /// Queries [`Self::channel`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_slowmode(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<u16>, Error<Self::Error>> {
+        Ok(self
+            .channel(channel_id)
+            .await?
+            .and_then(|channel| channel.rate_limit_per_user)
+            .filter(|&slowmode| slowmode != 0))
+    }
+
+    /// Get whether the slowmode of a channel lets the user send a message now,
+    /// based on the last cached message the user sent in it
+    ///
+    /// Returns `true` if the channel isn't cached, doesn't have slowmode or
+    /// the user's last message in it isn't cached
+    ///
+    /// The permissions that bypass slowmode aren't checked, use
+    /// [`Self::channel_permissions`] for that
+    ///
+    /// Queries [`Self::channel`] and [`Backend::select_user_last_message`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn can_send_now(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<bool, Error<Self::Error>> {
+        let Some(slowmode) = self.channel_slowmode(channel_id).await? else {
+            return Ok(true);
+        };
+        let Some(last_message) = self.select_user_last_message(channel_id, user_id).await? else {
+            return Ok(true);
+        };
+
+        Ok(last_message
+            .timestamp
+            .as_secs()
+            .saturating_add(i64::from(slowmode))
+            <= OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Get a cached message by its ID
+    ///
+    /// The returned message doesn't contain embeds, attachments, reactions or
+    /// stickers, since they're cached separately and the method doesn't query
+    /// them for you to reduce overhead in case you don't need them
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the message a thread was started from by the thread's ID
+    ///
+    /// Threads started from a message have the same ID as the message, so this
+    /// returns the cached message with the thread's ID, `None` if the thread
+    /// wasn't started from a message or the message isn't cached, the
+    /// message's [`CachedMessage::thread`] is set when the thread is created
+    ///
+    /// Queries [`Self::message`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn thread_starter_message(
+        &self,
+        thread_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>> {
+        self.message(thread_id.cast()).await
+    }
+
+    /// Get the chain of replies ending at the given message, starting with the
+    /// message itself, followed by the message it replies to, the message that
+    /// one replies to and so on, for example to give a moderation or AI bot
+    /// the context of a conversation
+    ///
+    /// At most `depth` replied messages are followed, the chain ends early at
+    /// a message that isn't a reply or whose replied message isn't cached,
+    /// returns an empty vector if the given message isn't cached
+    ///
+    /// Queries [`Self::message`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn reply_chain(
+        &self,
+        message_id: Id<MessageMarker>,
+        depth: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        let mut chain: Vec<CachedMessage> = vec![];
+        let mut next_id = Some(message_id);
+        while let Some(id) = next_id {
+            if chain.len() > usize::from(depth) || chain.iter().any(|message| message.id == id) {
+                break;
+            }
+            let Some(message) = self.message(id).await? else {
+                break;
+            };
+            next_id = if message.kind == MessageType::Reply {
+                message.referenced_message.or(message.reference_message_id)
+            } else {
+                None
+            };
+            chain.push(message);
+        }
+
+        Ok(chain)
+    }
+
+    /// Get cached embeds of a message by its ID
+    ///
+    /// Queries [`Backend::select_message_embeds`] and
+    /// [`Backend::select_embed_fields_bulk`], which returns the fields of all
+    /// the embeds at once, so this makes two backend calls however many embeds
+    /// the message has, with the `embeds-json` feature,
+    /// `Backend::select_message_embeds_json` is queried first
+    async fn embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<(CachedEmbed, Vec<CachedEmbedField>)>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        #[cfg(feature = "embeds-json")]
+        if let Some(embeds_json) = self.select_message_embeds_json(message_id).await? {
+            let embeds = serde_json::from_value(embeds_json)
+                .map_err(|_| Error::EmbedsJsonInvalid(message_id))?;
+
+            #[cfg(feature = "metrics")]
+            instrumentation::record_getter("embeds", started.elapsed());
+
+            return Ok(embeds);
+        }
+
+        let cached_embeds = self.select_message_embeds(message_id).await?;
+        let embed_ids: Vec<_> = cached_embeds.iter().map(|embed| embed.id).collect();
+        let mut fields = self.select_embed_fields_bulk(&embed_ids).await?;
+        let mut embeds = Vec::with_capacity(cached_embeds.len());
+        for embed in cached_embeds {
+            let embed_fields;
+            (embed_fields, fields) = fields
+                .into_iter()
+                .partition(|field| field.embed_id == embed.id);
+            embeds.push((embed, embed_fields));
+        }
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("embeds", started.elapsed());
+
+        Ok(embeds)
+    }
+
+    /// Get the cached previous versions of a message's content by its ID, the
+    /// oldest first
+    ///
+    /// They're only cached when [`CacheConfig::message_revisions`] is enabled
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>>;
+
+    /// Get cached attachments of a message by its ID
+    ///
+    /// When [`CacheConfig::attachment_dedup`] is enabled, the attachments are
+    /// made from the message's attachment posts and their blobs
+    ///
+    /// This should be something like `SELECT * FROM attachments WHERE
+    /// message_id = ?`, combined with `attachment_posts` joined with
+    /// `attachment_blobs`
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>>;
+
+    /// Get the cached messages an attachment file was posted in by the hash
+    /// of its name, size, content type, dimensions and duration, returned by
+    /// [`crate::model::CachedAttachmentBlob::file_hash`]
+    ///
+    /// This is useful to detect reposts, the attachment posts are only cached
+    /// when [`CacheConfig::attachment_dedup`] is enabled
+    ///
+    /// Since the file's contents aren't hashed, different files with the same
+    /// name, size, content type, dimensions and duration collide, for example
+    /// two screenshots with the default name and the same resolution that
+    /// happen to compress to the same size, so the messages might include
+    /// posts of a different file
+    ///
+    /// The messages are ordered from least recent to most recent
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE id IN
+    /// (SELECT message_id FROM attachment_posts WHERE file_hash = ?)`
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get cached reactions of a message by its ID
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>>;
+
+    /// Get the reactions of a message aggregated by their emojis, like the
+    /// reactions in Discord's message payloads
+    ///
+    /// The emojis are in the order the backend returned their first reaction,
+    /// `me` and `me_burst` are `false` if the current user isn't cached
+    ///
+    /// Queries [`Self::current_user`] and [`Self::reactions`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    async fn reaction_counts(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<ReactionCount>, Error<Self::Error>> {
+        let current_user_id = match self.current_user().await {
+            Ok(current_user) => Some(current_user.id),
+            Err(Error::CurrentUserMissing) => None,
+            Err(err) => return Err(err),
+        };
+
+        let mut counts: Vec<ReactionCount> = vec![];
+        for reaction in self.reactions(message_id).await? {
+            let position = if let Some(position) = counts
+                .iter()
+                .position(|count| count.emoji == reaction.emoji)
+            {
+                position
+            } else {
+                counts.push(ReactionCount {
+                    emoji: reaction.emoji.clone(),
+                    count: 0,
+                    burst_count: 0,
+                    me: false,
+                    me_burst: false,
+                    burst_colors: None,
+                });
+                counts.len() - 1
+            };
+            let Some(count) = counts.get_mut(position) else {
+                continue;
+            };
+
+            let is_me = current_user_id == Some(reaction.user_id);
+            if reaction.burst {
+                count.burst_count = count.burst_count.saturating_add(1);
+                count.me_burst |= is_me;
+                if count.burst_colors.is_none() {
+                    count.burst_colors = reaction.burst_colors;
+                }
+            } else {
+                count.count = count.count.saturating_add(1);
+                count.me |= is_me;
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Get cached stickers of a message by its ID
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages by its ID
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages sent after `after` and
+    /// before `before`, excluding both, `None` meaning no bound
+    ///
+    /// This is the same as [`Self::channel_messages`] with a time range, for
+    /// example to get the messages sent in the last 10 minutes without
+    /// fetching them by count and filtering them
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// Queries [`Backend::select_channel_messages_range`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(self
+            .select_channel_messages_range(channel_id, after, before, limit)
+            .await?)
+    }
+
+    /// Get a channel's most recent `limit` messages that pass the filter, for
+    /// example to exclude system messages and the messages of bots
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// Queries [`Backend::select_filtered_channel_messages`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_messages_filtered(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(self
+            .select_filtered_channel_messages(channel_id, filter, limit)
+            .await?)
+    }
+
+    /// Get a channel's most recent `limit` messages sent by users, excluding
+    /// system messages and the messages of bots and webhooks
+    ///
+    /// This is a convenience method for [`Self::channel_messages_filtered`]
+    ///
+    /// Queries [`Backend::select_filtered_channel_messages`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn channel_user_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.channel_messages_filtered(
+            channel_id,
+            MessageKindFilter::new()
+                .exclude_system()
+                .exclude_bots()
+                .exclude_webhooks(),
+            limit,
+        )
+        .await
+    }
+
+    /// Get the most recent `limit` messages that have the given sticker
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This should be something like `SELECT * FROM messages JOIN stickers ON
+    /// messages.id = stickers.message_id WHERE stickers.id = ?`
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages that were sent by webhooks
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE channel_id
+    /// = ? AND webhook_id IS NOT NULL`
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the most recent `limit` messages that were sent by the given
+    /// webhook
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This should be something like `SELECT * FROM messages WHERE webhook_id
+    /// = ?`
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a guild's most recent `limit` messages that have a reaction with the
+    /// given emoji
+    ///
+    /// The emoji is either the ID or the name of the emoji, the same as
+    /// [`CachedReaction::emoji`]
+    ///
+    /// A limit of 0 means to return all messages
+    ///
+    /// The messages are ordered from most recent to least recent
+    ///
+    /// This should be something like `SELECT DISTINCT messages.* FROM messages
+    /// JOIN reactions ON messages.id = reactions.message_id WHERE
+    /// reactions.emoji = ? AND messages.guild_id = ?`
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
 
     /// Get a cached member by its guild ID and user ID
     async fn member(
         &self,
-        user_id: Id<UserMarker>,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>>;
+
+    /// Get cached roles of a member by their ID
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+
+    /// Get cached presence of a member by their ID
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>>;
+
+    /// Get cached activities of a member by their ID
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>>;
+
+    /// Get cached activities of a member in the given guild by their ID
+    ///
+    /// This should be something like `SELECT * FROM activities WHERE guild_id
+    /// = ? AND user_id = ?`
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>>;
+
+    /// Get the IDs of the cached guilds
+    ///
+    /// This should be something like `SELECT id FROM guilds`
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>>;
+
+    /// Get the IDs of the cached guilds that are unavailable
+    ///
+    /// Guilds become unavailable during outages, their contents are kept in
+    /// the cache until they become available again, when they're replaced with
+    /// the new data
+    ///
+    /// This should be something like `SELECT id FROM guilds WHERE unavailable`
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>>;
+
+    /// Get a guild's members by its ID
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+
+    /// Get the IDs of the guilds that both users are cached as members of
+    ///
+    /// The result depends on the members being cached, so it may be incomplete
+    /// in guilds whose members are cached lazily
+    ///
+    /// Queries [`Backend::select_mutual_guilds`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let guild_ids = self.select_mutual_guilds(user_a, user_b).await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("mutual_guilds", started.elapsed());
+
+        Ok(guild_ids)
+    }
+
+    /// Get up to `limit` members of the guild whose nickname or username
+    /// starts with the prefix, ignoring case
+    ///
+    /// This is the same as Discord's member search endpoint, but it's served
+    /// from the cache, so it's fast enough for autocomplete interactions,
+    /// which have to be responded to in 3 seconds
+    ///
+    /// The result depends on the members being cached, so it may be incomplete
+    /// in guilds whose members are cached lazily
+    ///
+    /// A limit of 0 means to return all matching members
+    ///
+    /// The members are ordered by their nickname, or their username if they
+    /// don't have one
+    ///
+    /// Queries [`Backend::select_members_by_prefix`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn search_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let members = self
+            .select_members_by_prefix(guild_id, prefix, limit)
+            .await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("search_members", started.elapsed());
+
+        Ok(members)
+    }
+
+    /// Get up to `limit` roles of the guild whose names match the query, the
+    /// best matches first, to respond to autocomplete interactions
+    ///
+    /// The roles are looked up in the index returned by
+    /// [`Self::autocomplete_index`] without querying the backend, refer to
+    /// [`AutocompleteIndex`] for how names are matched, without an index this
+    /// returns nothing
+    ///
+    /// A limit of 0 means to return all matching roles
+    #[cfg(feature = "autocomplete")]
+    fn suggest_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<RoleMarker>> {
+        self.autocomplete_index()
+            .map(|index| index.roles(guild_id, query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Get up to `limit` channels and threads of the guild whose names match
+    /// the query, the best matches first, to respond to autocomplete
+    /// interactions
+    ///
+    /// This is the same as [`Self::suggest_roles`] for channels
+    #[cfg(feature = "autocomplete")]
+    fn suggest_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<ChannelMarker>> {
+        self.autocomplete_index()
+            .map(|index| index.channels(guild_id, query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Get up to `limit` emojis of the guild whose names match the query, the
+    /// best matches first, to respond to autocomplete interactions
+    ///
+    /// This is the same as [`Self::suggest_roles`] for emojis
+    #[cfg(feature = "autocomplete")]
+    fn suggest_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<EmojiMarker>> {
+        self.autocomplete_index()
+            .map(|index| index.emojis(guild_id, query, limit))
+            .unwrap_or_default()
+    }
+
+    /// Get the members of the guild that look inactive since the given time,
+    /// to preview which members pruning the guild would remove
+    ///
+    /// A member is inactive if they joined before the given time, aren't a
+    /// bot, don't have a cached message in the guild sent since then and
+    /// don't have a cached presence that isn't offline
+    ///
+    /// This is only an approximation, since only the cached messages are
+    /// checked, so sweeping messages or not caching the guild's channels makes
+    /// members look inactive, and Discord also counts activity the cache can't
+    /// see, filter the members by their roles yourself if you prune only the
+    /// members without roles
+    ///
+    /// Queries [`Backend::select_guild_message_authors`],
+    /// [`Self::guild_members`] and [`Self::presence`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn inactive_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        let active_user_ids = self.select_guild_message_authors(guild_id, since).await?;
+
+        let mut members = vec![];
+        for member in self.guild_members(guild_id).await? {
+            if member.bot
+                || member.joined_at.as_micros() >= since.as_micros()
+                || active_user_ids.contains(&member.id)
+            {
+                continue;
+            }
+            let is_online = self.presence(member.id).await?.map_or(false, |presence| {
+                !matches!(presence.status, Status::Offline | Status::Invisible)
+            });
+            if !is_online {
+                members.push(member);
+            }
+        }
+
+        Ok(members)
+    }
+
+    /// Get a cached guild by its ID
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>>;
+
+    /// Get a cached guild by its ID, shared through the pool returned by
+    /// [`Self::arc_pool`]
+    ///
+    /// The guild is read from the backend only if it isn't in the pool, so
+    /// reading the same guild repeatedly doesn't clone it, without a pool this
+    /// is the same as [`Self::guild`]
+    ///
+    /// Queries [`Self::guild`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn guild_ref(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<Arc<CachedGuild>>, Error<Self::Error>> {
+        let Some(pool) = self.arc_pool() else {
+            return Ok(self.guild(guild_id).await?.map(Arc::new));
+        };
+        if let Some(guild) = pool.guild(guild_id) {
+            return Ok(Some(guild));
+        }
+
+        let generation = pool.generation();
+        let guild = self.guild(guild_id).await?.map(Arc::new);
+        if let Some(pooled_guild) = &guild {
+            pool.insert_guild(pooled_guild, generation);
+        }
+
+        Ok(guild)
+    }
+
+    /// Get the URL of a cached guild's vanity invite by its ID, `None` if the
+    /// guild isn't cached or doesn't have a vanity invite
+    ///
+    /// Queries [`Self::guild`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn guild_vanity_url(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<String>, Error<Self::Error>> {
+        Ok(self
+            .guild(guild_id)
+            .await?
+            .and_then(|guild| guild.vanity_url_code)
+            .map(|code| format!("https://discord.gg/{code}")))
+    }
+
+    /// Get the cached guilds that have the given feature, for example
+    /// [`GuildFeature::Community`] or [`GuildFeature::Discoverable`]
+    ///
+    /// Queries [`Backend::select_guilds_by_feature`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn guilds_with_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let guilds = self.select_guilds_by_feature(feature).await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("guilds_with_feature", started.elapsed());
+
+        Ok(guilds)
+    }
+
+    /// Get a cached role by its ID
+    async fn role(&self, role_id: Id<RoleMarker>)
+        -> Result<Option<CachedRole>, Error<Self::Error>>;
+
+    /// Get a cached role by its ID, shared through the pool returned by
+    /// [`Self::arc_pool`]
+    ///
+    /// The role is read from the backend only if it isn't in the pool, so
+    /// reading the same role repeatedly doesn't clone it, without a pool this
+    /// is the same as [`Self::role`]
+    ///
+    /// Queries [`Self::role`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn role_ref(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<Arc<CachedRole>>, Error<Self::Error>> {
+        let Some(pool) = self.arc_pool() else {
+            return Ok(self.role(role_id).await?.map(Arc::new));
+        };
+        if let Some(role) = pool.role(role_id) {
+            return Ok(Some(role));
+        }
+
+        let generation = pool.generation();
+        let role = self.role(role_id).await?.map(Arc::new);
+        if let Some(pooled_role) = &role {
+            pool.insert_role(pooled_role, generation);
+        }
+
+        Ok(role)
+    }
+
+    /// Get the everyone role of a guild by the guild's ID
+    ///
+    /// Queries [`Backend::select_everyone_role`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    async fn everyone_role(
+        &self,
         guild_id: Id<GuildMarker>,
-    ) -> Result<Option<CachedMember>, Error<Self::Error>>;
+    ) -> Result<Option<CachedRole>, Error<Self::Error>> {
+        Ok(self.select_everyone_role(guild_id).await?)
+    }
 
-    /// Get cached roles of a member by their ID
-    async fn member_roles(
+    /// Get a guild's roles by its ID
+    async fn guild_roles(
         &self,
-        user_id: Id<UserMarker>,
         guild_id: Id<GuildMarker>,
     ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
 
-    /// Get cached presence of a member by their ID
-    async fn presence(
+    /// Get the IDs of the members that have the given role
+    ///
+    /// The everyone role isn't assigned to members explicitly, so no IDs are
+    /// returned for it
+    ///
+    /// Queries [`Backend::select_role_members`]
+    async fn role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let user_ids = self.select_role_members(role_id).await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("role_members", started.elapsed());
+
+        Ok(user_ids)
+    }
+
+    /// Get a cached emoji by its ID
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>>;
+
+    /// Get a guild's emojis by its ID
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>>;
+
+    /// Get a cached sticker by its ID
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a guild's stickers by its ID
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a cached stage instance by its ID
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>>;
+
+    /// Get the emojis used in reactions in the guild since the given time and
+    /// the number of times they were used, the most used first
+    ///
+    /// The emojis are either the ID or the name of the emoji, the same as
+    /// [`CachedReaction::emoji`]
+    ///
+    /// Uses are counted per day in UTC, so the uses earlier on the day of
+    /// `since` are also counted
+    ///
+    /// Queries [`Backend::select_emoji_usage`]
+    #[cfg(feature = "stats")]
+    async fn emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(String, u64)>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let usage = self
+            .select_emoji_usage(guild_id, stats::day(since.as_secs()))
+            .await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("emoji_usage", started.elapsed());
+
+        Ok(usage)
+    }
+
+    /// Get the stickers sent in messages in the guild since the given time and
+    /// the number of times they were sent, the most sent first
+    ///
+    /// Uses are counted per day in UTC, so the uses earlier on the day of
+    /// `since` are also counted
+    ///
+    /// Queries [`Backend::select_sticker_usage`]
+    #[cfg(feature = "stats")]
+    async fn sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Error<Self::Error>> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+
+        let usage = self
+            .select_sticker_usage(guild_id, stats::day(since.as_secs()))
+            .await?;
+
+        #[cfg(feature = "metrics")]
+        instrumentation::record_getter("sticker_usage", started.elapsed());
+
+        Ok(usage)
+    }
+
+    /// Update the cache with the events recorded in the journal in the given
+    /// time range, in the order they were recorded in
+    ///
+    /// This is useful to rebuild the cache after its data is corrupted, or to
+    /// debug how the cache got to its current state
+    ///
+    /// The hooks aren't run and the replayed events aren't recorded in the
+    /// journal again
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::JournalEntryInvalid`] if an entry isn't a valid event,
+    /// or the error the backend might return
+    #[cfg(feature = "journal")]
+    async fn replay(&self, range: Range<Timestamp>) -> Result<(), Error<Self::Error>> {
+        for entry in self.select_journal(range.start, range.end).await? {
+            let event = journal::deserialize(&entry.kind, &entry.data)
+                .ok_or(Error::JournalEntryInvalid(entry.id))?;
+            #[cfg(feature = "autocomplete")]
+            if let Some(index) = self.autocomplete_index() {
+                index.update(&event);
+            }
+            self.apply_event(Cow::Owned(event)).await?;
+        }
+
+        if let Some(pool) = self.arc_pool() {
+            pool.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Get the most recent `limit` changes to a channel or role, the most
+    /// recent first
+    ///
+    /// A limit of 0 means to return all changes
+    ///
+    /// Queries [`Backend::select_changelog`]
+    ///
+    /// # Errors
+    ///
+    /// Returns the error the backend might return
+    #[cfg(feature = "changelog")]
+    async fn entity_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Error<Self::Error>> {
+        Ok(self.select_changelog(kind, entity_id, limit).await?)
+    }
+
+    /// Get a cached channel or thread by its ID, returning an error if it
+    /// isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the channel isn't in the cache, or the
+    /// error the backend might return
+    async fn try_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<CachedChannel, Error<Self::Error>> {
+        self.channel(channel_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Channel, channel_id.get()))
+    }
+
+    /// Get a cached message by its ID, returning an error if it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the message isn't in the cache, or the
+    /// error the backend might return
+    async fn try_message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<CachedMessage, Error<Self::Error>> {
+        self.message(message_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Message, message_id.get()))
+    }
+
+    /// Get a cached member by its guild ID and user ID, returning an error if
+    /// it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] with the user ID if the member isn't in
+    /// the cache, or the error the backend might return
+    async fn try_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedMember, Error<Self::Error>> {
+        self.member(user_id, guild_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Member, user_id.get()))
+    }
+
+    /// Get a cached guild by its ID, returning an error if it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedGuild, Error<Self::Error>> {
+        self.guild(guild_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Guild, guild_id.get()))
+    }
+
+    /// Get a cached role by its ID, returning an error if it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the role isn't in the cache, or the
+    /// error the backend might return
+    async fn try_role(&self, role_id: Id<RoleMarker>) -> Result<CachedRole, Error<Self::Error>> {
+        self.role(role_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Role, role_id.get()))
+    }
+
+    /// Get a cached emoji by its ID, returning an error if it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the emoji isn't in the cache, or the
+    /// error the backend might return
+    async fn try_emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<CachedEmoji, Error<Self::Error>> {
+        self.emoji(emoji_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Emoji, emoji_id.get()))
+    }
+
+    /// Get a cached sticker by its ID, returning an error if it isn't cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the sticker isn't in the cache, or the
+    /// error the backend might return
+    async fn try_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<CachedSticker, Error<Self::Error>> {
+        self.sticker(sticker_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::Sticker, sticker_id.get()))
+    }
+
+    /// Get a cached stage instance by its ID, returning an error if it isn't
+    /// cached
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the stage instance isn't in the cache,
+    /// or the error the backend might return
+    async fn try_stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<StageInstance, Error<Self::Error>> {
+        self.stage_instance(stage_id)
+            .await?
+            .ok_or(Error::NotCached(EntityKind::StageInstance, stage_id.get()))
+    }
+
+    /// Get a guild's channels and threads by its ID, returning an error if
+    /// the guild isn't cached
+    ///
+    /// Unlike [`Self::guild_channels`], this makes it possible to tell an
+    /// unknown guild apart from a guild without channels
+    ///
+    /// Queries [`Self::guild`] and [`Self::guild_channels`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.try_guild(guild_id).await?;
+        self.guild_channels(guild_id).await
+    }
+
+    /// Get a guild's members by its ID, returning an error if the guild isn't
+    /// cached
+    ///
+    /// Unlike [`Self::guild_members`], this makes it possible to tell an
+    /// unknown guild apart from a guild without cached members
+    ///
+    /// Queries [`Self::guild`] and [`Self::guild_members`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        self.try_guild(guild_id).await?;
+        self.guild_members(guild_id).await
+    }
+
+    /// Get a guild's roles by its ID, returning an error if the guild isn't
+    /// cached
+    ///
+    /// Unlike [`Self::guild_roles`], this makes it possible to tell an unknown
+    /// guild apart from a guild without roles
+    ///
+    /// Queries [`Self::guild`] and [`Self::guild_roles`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        self.try_guild(guild_id).await?;
+        self.guild_roles(guild_id).await
+    }
+
+    /// Get a guild's emojis by its ID, returning an error if the guild isn't
+    /// cached
+    ///
+    /// Unlike [`Self::guild_emojis`], this makes it possible to tell an
+    /// unknown guild apart from a guild without emojis
+    ///
+    /// Queries [`Self::guild`] and [`Self::guild_emojis`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>> {
+        self.try_guild(guild_id).await?;
+        self.guild_emojis(guild_id).await
+    }
+
+    /// Get a guild's stickers by its ID, returning an error if the guild isn't
+    /// cached
+    ///
+    /// Unlike [`Self::guild_stickers`], this makes it possible to tell an
+    /// unknown guild apart from a guild without stickers
+    ///
+    /// Queries [`Self::guild`] and [`Self::guild_stickers`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the guild isn't in the cache, or the
+    /// error the backend might return
+    async fn try_guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        self.try_guild(guild_id).await?;
+        self.guild_stickers(guild_id).await
+    }
+
+    /// Get a channel's most recent `limit` messages by its ID, returning an
+    /// error if the channel isn't cached
+    ///
+    /// Unlike [`Self::channel_messages`], this makes it possible to tell an
+    /// unknown channel apart from a channel without cached messages
+    ///
+    /// Queries [`Self::channel`] and [`Self::channel_messages`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NotCached`] if the channel isn't in the cache, or the
+    /// error the backend might return
+    async fn try_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.try_channel(channel_id).await?;
+        self.channel_messages(channel_id, limit).await
+    }
+
+    /// Updates the cache with the channel
+    ///
+    /// If the channel is a DM channel, its recipients are added too
+    #[doc(hidden)]
+    async fn add_channel(&self, channel: &Channel) -> Result<(), Error<Self::Error>> {
+        if channel.kind == ChannelType::Private {
+            for recipient in channel.recipients.as_ref().unwrap_or(&Vec::new()) {
+                self.upsert_private_channel(CachedPrivateChannel {
+                    channel_id: channel.id,
+                    user_id: recipient.id,
+                })
+                .await?;
+            }
+        }
+        for overwrite in channel
+            .permission_overwrites
+            .as_ref()
+            .unwrap_or(&Vec::new())
+        {
+            self.upsert_permission_overwrite(CachedPermissionOverwrite::from_permission_overwrite(
+                overwrite, channel.id,
+            ))
+            .await?;
+        }
+        self.upsert_channel(CachedChannel::from(channel)).await?;
+
+        Ok(())
+    }
+
+    /// Updates the cache with the threads in the thread list sync event
+    ///
+    /// The cached threads in the synced channels (or the whole guild if no
+    /// channels are given) that aren't in the event are marked as archived,
+    /// since the event includes all the active threads in them
+    #[doc(hidden)]
+    async fn sync_threads(&self, sync: &ThreadListSync) -> Result<(), Error<Self::Error>> {
+        for thread in &sync.threads {
+            self.add_channel(thread).await?;
+        }
+
+        for mut cached_thread in self.guild_channels(sync.guild_id).await? {
+            if cached_thread.thread_archived != Some(false)
+                || sync
+                    .threads
+                    .iter()
+                    .any(|thread| thread.id == cached_thread.id)
+                || !(sync.channel_ids.is_empty()
+                    || cached_thread
+                        .parent_id
+                        .map_or(false, |parent_id| sync.channel_ids.contains(&parent_id)))
+            {
+                continue;
+            }
+            cached_thread.thread_archived = Some(true);
+            self.upsert_channel(cached_thread).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the channel and its permission overwrites are the same
+    /// as the cached ones, so that updating the cache with it can be skipped
+    ///
+    /// Always returns `false` for private channels, since their recipients
+    /// aren't compared
+    #[doc(hidden)]
+    async fn is_channel_unchanged(&self, channel: &Channel) -> Result<bool, Error<Self::Error>> {
+        if channel.kind == ChannelType::Private
+            || self.channel(channel.id).await? != Some(CachedChannel::from(channel))
+        {
+            return Ok(false);
+        }
+
+        let cached_overwrites = self.permission_overwrites(channel.id).await?;
+        let overwrites = channel.permission_overwrites.as_deref().unwrap_or_default();
+        Ok(overwrites.len() == cached_overwrites.len()
+            && overwrites.iter().all(|overwrite| {
+                cached_overwrites.contains(&CachedPermissionOverwrite::from_permission_overwrite(
+                    overwrite, channel.id,
+                ))
+            }))
+    }
+
+    /// Returns whether the IDs of the cached roles of the member are the same
+    /// as the given role IDs, so that updating the member's roles can be
+    /// skipped
+    #[doc(hidden)]
+    async fn are_member_roles_unchanged(
         &self,
+        guild_id: Id<GuildMarker>,
         user_id: Id<UserMarker>,
-    ) -> Result<Option<CachedPresence>, Error<Self::Error>>;
+        role_ids: &[Id<RoleMarker>],
+    ) -> Result<bool, Error<Self::Error>> {
+        let cached_roles = self.member_roles(user_id, guild_id).await?;
+        Ok(role_ids.len() == cached_roles.len()
+            && cached_roles.iter().all(|role| role_ids.contains(&role.id)))
+    }
 
-    /// Get cached activities of a member by their ID
-    async fn member_activities(
+    /// Updates the cache with the role, skipping the write if it's the same as
+    /// the cached role
+    #[doc(hidden)]
+    async fn update_role(&self, role: CachedRole) -> Result<(), Error<Self::Error>> {
+        if self.role(role.id).await?.as_ref() != Some(&role) {
+            self.upsert_role(role).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the cache with the stage instance, skipping the write if it's
+    /// the same as the cached stage instance
+    #[doc(hidden)]
+    async fn update_stage_instance(&self, stage: StageInstance) -> Result<(), Error<Self::Error>> {
+        if self.stage_instance(stage.id).await?.as_ref() != Some(&stage) {
+            self.upsert_stage_instance(stage).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the cache with the guild and everything in it
+    ///
+    /// The roles and stage instances are taken separately so that they can be
+    /// moved instead of cloned when the event is owned
+    ///
+    /// The writes of each type are run concurrently, limited by
+    /// [`CacheConfig::write_concurrency`], members are added after the roles
+    /// since adding them requires the roles to be cached
+    ///
+    /// If the guild is unavailable, the cached guild is only marked as
+    /// unavailable, or the guild is cached without its contents if it isn't
+    /// cached, so that it's returned by [`Self::unavailable_guilds`], if the
+    /// cached guild was unavailable, it's removed with its contents before
+    /// adding the new ones, since they may be stale
+    #[doc(hidden)]
+    async fn add_guild(
         &self,
-        user_id: Id<UserMarker>,
-    ) -> Result<Vec<CachedActivity>, Error<Self::Error>>;
+        guild: &Guild,
+        roles: Vec<Role>,
+        stage_instances: Vec<StageInstance>,
+    ) -> Result<(), Error<Self::Error>> {
+        if guild.unavailable {
+            let mut cached_guild = self
+                .guild(guild.id)
+                .await?
+                .unwrap_or_else(|| CachedGuild::from(guild));
+            cached_guild.unavailable = true;
+            self.upsert_guild(cached_guild).await?;
+            return Ok(());
+        }
+        if self
+            .guild(guild.id)
+            .await?
+            .map_or(false, |cached_guild| cached_guild.unavailable)
+        {
+            self.remove_guild(guild.id).await?;
+        }
 
-    /// Get a guild's members by its ID
-    async fn guild_members(
+        let concurrency = self.config().write_concurrency;
+
+        run_concurrently(
+            guild
+                .channels
+                .iter()
+                .chain(&guild.threads)
+                .map(|channel| self.add_channel(channel)),
+            concurrency,
+        )
+        .await?;
+        run_concurrently(
+            guild.emojis.iter().map(|emoji| {
+                self.upsert_emoji(CachedEmoji::from_emoji(emoji, guild.id))
+                    .err_into()
+            }),
+            concurrency,
+        )
+        .await?;
+        run_concurrently(
+            guild
+                .stickers
+                .iter()
+                .map(|sticker| self.upsert_sticker(sticker.into()).err_into()),
+            concurrency,
+        )
+        .await?;
+        run_concurrently(
+            roles.into_iter().map(|role| {
+                self.upsert_role(CachedRole::from_role(role, guild.id))
+                    .err_into()
+            }),
+            concurrency,
+        )
+        .await?;
+        let members_partial = self
+            .config()
+            .lazy_members
+            .zip(guild.member_count)
+            .map_or(false, |(threshold, member_count)| member_count > threshold);
+        if !members_partial {
+            run_concurrently(
+                guild.members.iter().map(|member| async move {
+                    self.add_member_roles(guild.id, member.user.id, &member.roles)
+                        .await?;
+                    self.upsert_member(member.into()).await?;
+                    Ok::<_, Error<Self::Error>>(())
+                }),
+                concurrency,
+            )
+            .await?;
+        }
+        run_concurrently(
+            guild
+                .presences
+                .iter()
+                .map(|presence| self.upsert_presence(presence.into()).err_into()),
+            concurrency,
+        )
+        .await?;
+        run_concurrently(
+            stage_instances
+                .into_iter()
+                .map(|stage| self.upsert_stage_instance(stage).err_into()),
+            concurrency,
+        )
+        .await?;
+        let mut cached_guild = CachedGuild::from(guild);
+        cached_guild.members_partial = members_partial;
+        self.upsert_guild(cached_guild).await?;
+
+        Ok(())
+    }
+
+    /// Updates the cache with the member of the message's author if the
+    /// message has one and the member isn't cached
+    #[doc(hidden)]
+    async fn add_message_author(&self, message: &Message) -> Result<(), Error<Self::Error>> {
+        let (Some(guild_id), Some(member)) = (message.guild_id, &message.member) else {
+            return Ok(());
+        };
+        if self.member(message.author.id, guild_id).await?.is_some() {
+            return Ok(());
+        }
+
+        self.add_member_roles(guild_id, message.author.id, &member.roles)
+            .await?;
+        self.upsert_member(CachedMember::from_partial_member(
+            member,
+            &message.author,
+            guild_id,
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks the cached guild as unavailable, does nothing if the guild isn't
+    /// cached
+    #[doc(hidden)]
+    async fn mark_guild_unavailable(
         &self,
         guild_id: Id<GuildMarker>,
-    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+    ) -> Result<(), Error<Self::Error>> {
+        if let Some(mut cached_guild) = self.guild(guild_id).await? {
+            cached_guild.unavailable = true;
+            self.upsert_guild(cached_guild).await?;
+        }
 
-    /// Get a cached guild by its ID
-    async fn guild(
+        Ok(())
+    }
+
+    /// Removes the guild and everything in it from the cache
+    ///
+    /// If the backend supports cascading deletes, only the guild is removed,
+    /// otherwise the permission overwrites of its channels are removed, since
+    /// they're removed by their channel IDs, then the rest is removed with
+    /// [`Backend::delete_guild_cascade`]
+    #[doc(hidden)]
+    async fn remove_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Error<Self::Error>> {
+        if self.supports_cascade() {
+            self.delete_guild(guild_id).await?;
+        } else {
+            for channel in self.guild_channels(guild_id).await? {
+                self.delete_channel_permission_overwrites(channel.id)
+                    .await?;
+            }
+            self.delete_guild_cascade(guild_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the cache with the message's attachments
+    #[doc(hidden)]
+    async fn add_attachments(
         &self,
-        guild_id: Id<GuildMarker>,
-    ) -> Result<Option<CachedGuild>, Error<Self::Error>>;
+        message_id: Id<MessageMarker>,
+        attachments: Vec<Attachment>,
+    ) -> Result<(), Error<Self::Error>> {
+        for attachment in attachments {
+            let attachment = CachedAttachment::from_attachment(attachment, message_id);
+            if self.config().attachment_dedup {
+                let (blob, post) = attachment.split();
+                self.upsert_attachment_blob(blob).await?;
+                self.upsert_attachment_post(post).await?;
+            } else {
+                self.upsert_attachment(attachment).await?;
+            }
+        }
 
-    /// Get a cached role by its ID
-    async fn role(&self, role_id: Id<RoleMarker>)
-        -> Result<Option<CachedRole>, Error<Self::Error>>;
+        Ok(())
+    }
 
-    /// Get a guild's roles by its ID
-    async fn guild_roles(
+    /// Updates the cache with the message's stickers
+    ///
+    /// If the sticker is cached as a guild sticker, its cached fields are used,
+    /// since message stickers only have the ID, name and format type
+    #[doc(hidden)]
+    async fn add_message_stickers(
         &self,
-        guild_id: Id<GuildMarker>,
-    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+        message_id: Id<MessageMarker>,
+        message_stickers: Vec<MessageSticker>,
+    ) -> Result<(), Error<Self::Error>> {
+        for message_sticker in message_stickers {
+            let sticker = if let Some(mut cached_sticker) = self.sticker(message_sticker.id).await?
+            {
+                cached_sticker.message_id = Some(message_id);
+                cached_sticker
+            } else {
+                CachedSticker::from_message_sticker(message_sticker, message_id)
+            };
+            self.upsert_sticker(sticker).await?;
+        }
 
-    /// Get a cached emoji by its ID
-    async fn emoji(
+        Ok(())
+    }
+
+    /// Returns the key to deduplicate the event by, `None` if
+    /// [`CacheConfig::event_dedup`] isn't enabled or the event isn't
+    /// deduplicated
+    #[doc(hidden)]
+    fn dedup_key(&self, event: &Event) -> Option<String> {
+        self.config()
+            .event_dedup
+            .and_then(|_| event::dedup_key(event))
+    }
+
+    /// Returns whether the event with the given key was already applied,
+    /// recording it as applied otherwise
+    ///
+    /// The key is recorded before the event is applied so that concurrent
+    /// replays of it are skipped, [`Self::update_event`] removes it if the
+    /// event couldn't be applied so that it's applied when it's received again
+    #[doc(hidden)]
+    async fn is_duplicate(&self, key: String) -> Result<bool, Error<Self::Error>> {
+        let Ok(now) = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp()) else {
+            return Ok(false);
+        };
+
+        Ok(self.seen_event(key, now).await?)
+    }
+
+    /// Sets the thread of the message the newly created thread was started
+    /// from, if the message is cached
+    #[doc(hidden)]
+    async fn link_thread_starter_message(
         &self,
-        emoji_id: Id<EmojiMarker>,
-    ) -> Result<Option<CachedEmoji>, Error<Self::Error>>;
+        thread_id: Id<ChannelMarker>,
+    ) -> Result<(), Error<Self::Error>> {
+        if let Some(mut message) = self.message(thread_id.cast()).await? {
+            if message.thread != Some(thread_id) {
+                message.thread = Some(thread_id);
+                self.upsert_message(message).await?;
+            }
+        }
 
-    /// Get a guild's emojis by its ID
-    async fn guild_emojis(
+        Ok(())
+    }
+
+    /// Updates the journal with the event, if it updates the cache
+    #[cfg(feature = "journal")]
+    #[doc(hidden)]
+    async fn add_journal_entry(&self, event: &Event) -> Result<(), Error<Self::Error>> {
+        let recorded_at = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp());
+        if let (Some((kind, data)), Ok(recorded_at)) = (journal::serialize(event), recorded_at) {
+            self.append_journal(CachedJournalEntry::new(kind.to_owned(), data, recorded_at))
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the JSON of the cached entity to record in the changelog, `None`
+    /// if it isn't cached
+    #[cfg(feature = "changelog")]
+    #[doc(hidden)]
+    async fn changelog_snapshot(
         &self,
-        guild_id: Id<GuildMarker>,
-    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>>;
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+    ) -> Result<Option<String>, Error<Self::Error>> {
+        Ok(match kind {
+            EntityKind::Channel => self
+                .channel(entity_id.cast())
+                .await?
+                .and_then(|channel| serde_json::to_string(&channel).ok()),
+            EntityKind::Role => self
+                .role(entity_id.cast())
+                .await?
+                .and_then(|role| serde_json::to_string(&role).ok()),
+            _ => None,
+        })
+    }
 
-    /// Get a cached sticker by its ID
-    async fn sticker(
+    /// Updates the changelog with the snapshots of the entity before and after
+    /// the event, if the event changed it
+    #[cfg(feature = "changelog")]
+    #[doc(hidden)]
+    async fn add_changelog_entry(
         &self,
-        sticker_id: Id<StickerMarker>,
-    ) -> Result<Option<CachedSticker>, Error<Self::Error>>;
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        before: Option<String>,
+    ) -> Result<(), Error<Self::Error>> {
+        let after = self.changelog_snapshot(kind, entity_id).await?;
+        if before == after {
+            return Ok(());
+        }
 
-    /// Get a guild's stickers by its ID
-    async fn guild_stickers(
+        if let Ok(recorded_at) = Timestamp::from_secs(OffsetDateTime::now_utc().unix_timestamp()) {
+            self.append_changelog(CachedChangelogEntry::new(
+                kind,
+                entity_id,
+                before,
+                after,
+                recorded_at,
+            ))
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the usage statistics with the reaction, if it's in a guild
+    #[cfg(feature = "stats")]
+    #[doc(hidden)]
+    async fn add_emoji_use(&self, reaction: &CachedReaction) -> Result<(), Error<Self::Error>> {
+        if let Some(guild_id) = reaction.guild_id {
+            self.increment_emoji_use(
+                guild_id,
+                reaction.emoji.clone(),
+                stats::day(OffsetDateTime::now_utc().unix_timestamp()),
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the usage statistics with the message's stickers, if it's in a
+    /// guild
+    ///
+    /// The stickers are taken separately since they're moved out of the
+    /// message when the event is owned
+    #[cfg(feature = "stats")]
+    #[doc(hidden)]
+    async fn add_sticker_uses(
         &self,
-        guild_id: Id<GuildMarker>,
-    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+        message: &Message,
+        stickers: &[MessageSticker],
+    ) -> Result<(), Error<Self::Error>> {
+        if let Some(guild_id) = message.guild_id {
+            for sticker in stickers {
+                self.increment_sticker_use(
+                    guild_id,
+                    sticker.id,
+                    stats::day(message.timestamp.as_secs()),
+                )
+                .await?;
+            }
+        }
 
-    /// Get a cached stage instance by its ID
-    async fn stage_instance(
+        Ok(())
+    }
+
+    /// Updates the cache with the revision of the message's current content, if
+    /// [`CacheConfig::message_revisions`] is enabled and the update changes
+    /// the content
+    #[doc(hidden)]
+    async fn add_message_revision(
         &self,
-        stage_id: Id<StageMarker>,
-    ) -> Result<Option<StageInstance>, Error<Self::Error>>;
+        cached_message: &CachedMessage,
+        message: &MessageUpdate,
+    ) -> Result<(), Error<Self::Error>> {
+        if !self.config().message_revisions {
+            return Ok(());
+        }
 
-    /// Updates the cache with the channel
+        if let (Some(content), Some(edited_at)) = (&message.content, message.edited_timestamp) {
+            if self.config().message_content.apply(content) != cached_message.content {
+                self.insert_message_revision(CachedMessageRevision::from_message(
+                    cached_message,
+                    edited_at,
+                ))
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates the cache with the message's embeds and their fields
     #[doc(hidden)]
-    async fn add_channel(&self, channel: &Channel) -> Result<(), Error<Self::Error>> {
-        for overwrite in channel
-            .permission_overwrites
-            .as_ref()
-            .unwrap_or(&Vec::new())
-        {
-            self.upsert_permission_overwrite(CachedPermissionOverwrite::from_permission_overwrite(
-                overwrite, channel.id,
-            ))
-            .await?;
+    async fn add_embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: Vec<Embed>,
+    ) -> Result<(), Error<Self::Error>> {
+        #[cfg(feature = "embeds-json")]
+        if self.config().embeds_json {
+            let cached_embeds: Vec<_> = embeds
+                .into_iter()
+                .map(|mut embed| {
+                    let fields = mem::take(&mut embed.fields);
+                    let cached_embed = CachedEmbed::from_embed(embed, message_id);
+                    let cached_fields: Vec<_> = fields
+                        .into_iter()
+                        .map(|field| CachedEmbedField::from_embed_field(field, cached_embed.id))
+                        .collect();
+                    (cached_embed, cached_fields)
+                })
+                .collect();
+            let embeds_json = serde_json::to_value(cached_embeds)
+                .map_err(|_| Error::EmbedsJsonInvalid(message_id))?;
+            self.upsert_message_embeds_json(message_id, embeds_json)
+                .await?;
+            return Ok(());
         }
-        self.upsert_channel(CachedChannel::from(channel)).await?;
+
+        for mut embed in embeds {
+            let fields = mem::take(&mut embed.fields);
+            let cached_embed = CachedEmbed::from_embed(embed, message_id);
+            for field in fields {
+                self.upsert_embed_field(CachedEmbedField::from_embed_field(field, cached_embed.id))
+                    .await?;
+            }
+            self.upsert_embed(cached_embed).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes the message's embeds and their fields from the cache
+    #[doc(hidden)]
+    async fn remove_embeds(&self, message_id: Id<MessageMarker>) -> Result<(), Error<Self::Error>> {
+        #[cfg(feature = "embeds-json")]
+        self.delete_message_embeds_json(message_id).await?;
+
+        let embed_ids: Vec<_> = self
+            .select_message_embeds(message_id)
+            .await?
+            .iter()
+            .map(|embed| embed.id)
+            .collect();
+        self.delete_embed_fields_bulk(&embed_ids).await?;
+        self.delete_embeds_bulk(&embed_ids).await?;
 
         Ok(())
     }
@@ -718,16 +4534,20 @@ pub trait Cache: Backend {
     #[doc(hidden)]
     async fn add_member_roles(
         &self,
+        guild_id: Id<GuildMarker>,
         user_id: Id<UserMarker>,
-        role_ids: Vec<Id<RoleMarker>>,
+        role_ids: &[Id<RoleMarker>],
     ) -> Result<(), Error<Self::Error>> {
-        for role_id in role_ids {
-            let mut role = self
-                .role(role_id)
-                .await?
-                .ok_or(Error::MemberRoleMissing { user_id, role_id })?;
-            role.user_id = Some(user_id);
-            self.insert_role(role).await?;
+        for &role_id in role_ids {
+            if self.role(role_id).await?.is_none() {
+                return Err(Error::MemberRoleMissing { user_id, role_id });
+            }
+            self.upsert_role_assignment(CachedRoleAssignment {
+                guild_id,
+                user_id,
+                role_id,
+            })
+            .await?;
         }
 
         Ok(())
@@ -739,15 +4559,183 @@ pub trait Cache: Backend {
         &self,
         message_id: Id<MessageMarker>,
     ) -> Result<(), Error<Self::Error>> {
-        let embeds = self.embeds(message_id).await?;
-        for (embed, _) in embeds {
-            self.delete_embed_fields(embed.id).await?;
-            self.delete_embed(embed.id).await?;
-        }
-        self.delete_message_attachments(message_id).await?;
-        self.delete_message_reactions(message_id).await?;
-        // self.delete_message_stickers(message_id).await?;
+        if !self.supports_cascade() {
+            self.remove_embeds(message_id).await?;
+            self.delete_message_attachments(message_id).await?;
+            self.delete_message_reactions(message_id).await?;
+            self.delete_message_stickers(message_id).await?;
+            self.delete_message_revisions(message_id).await?;
+        }
         self.delete_message(message_id).await?;
         Ok(())
     }
 }
+
+/// The maximum number of members to fetch in one request in
+/// [`Cache::diff_against_http`]
+#[cfg(feature = "http")]
+const MEMBERS_PAGE_SIZE: u16 = 1000;
+
+/// Wraps the error returned while fetching data with the HTTP client
+#[cfg(feature = "http")]
+#[allow(clippy::std_instead_of_core)]
+fn http_error<E: Send>(err: impl std::error::Error + Send + Sync + 'static) -> Error<E> {
+    Error::Http(Box::new(err))
+}
+
+/// Returns the fields of the permission overwrites that are compared to check
+/// whether a channel is synced with its category, without the channel ID
+fn overwrite_keys(overwrites: &[CachedPermissionOverwrite]) -> Vec<(u64, u8, u64, u64)> {
+    overwrites
+        .iter()
+        .map(|overwrite| {
+            (
+                overwrite.id.get(),
+                u8::from(overwrite.kind),
+                overwrite.allow.bits(),
+                overwrite.deny.bits(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the key to sort channels by in [`Cache::guild_channels_ordered`],
+/// voice and stage channels are sorted after other channels
+fn channel_order_key(channel: &CachedChannel) -> (bool, i32, Id<ChannelMarker>) {
+    (
+        matches!(
+            channel.kind,
+            ChannelType::GuildVoice | ChannelType::GuildStageVoice
+        ),
+        channel.position.unwrap_or_default(),
+        channel.id,
+    )
+}
+
+/// The maximum number of users to request in one request in
+/// [`Cache::request_missing_members`]
+#[cfg(feature = "gateway")]
+const MEMBER_REQUEST_SIZE: usize = 100;
+
+/// Wraps the error returned while sending a command to the gateway
+#[cfg(feature = "gateway")]
+#[allow(clippy::std_instead_of_core)]
+fn gateway_error<E: Send>(err: impl std::error::Error + Send + Sync + 'static) -> Error<E> {
+    Error::Gateway(Box::new(err))
+}
+
+/// How long the token of an interaction can be used for after it's created
+const INTERACTION_TOKEN_LIFETIME: Duration = Duration::from_secs(900);
+
+/// Returns the timestamp the given TTL from now, `None` if it overflows
+fn expiry_timestamp(ttl: Duration) -> Option<Timestamp> {
+    time::Duration::try_from(ttl)
+        .ok()
+        .and_then(|ttl| OffsetDateTime::now_utc().checked_add(ttl))
+        .and_then(|expires_at| Timestamp::from_secs(expires_at.unix_timestamp()).ok())
+}
+
+/// Returns the timestamp the given age ago, `None` if it overflows
+fn oldest_timestamp(max_age: Duration) -> Option<Timestamp> {
+    time::Duration::try_from(max_age)
+        .ok()
+        .and_then(|max_age| OffsetDateTime::now_utc().checked_sub(max_age))
+        .and_then(|oldest| Timestamp::from_secs(oldest.unix_timestamp()).ok())
+}
+
+/// The fields moved out of an owned event in [`Cache::apply_event`], so
+/// that they're written to the cache without being cloned
+///
+/// Only the fields that are written as they are are moved, the rest of the
+/// event is still read by reference
+#[derive(Debug, Default)]
+struct OwnedFields {
+    /// The roles of a guild create event
+    roles: Option<Vec<Role>>,
+    /// The stage instances of a guild create event
+    stage_instances: Option<Vec<StageInstance>>,
+    /// The attachments of a message create event
+    attachments: Option<Vec<Attachment>>,
+    /// The embeds of a message create event
+    embeds: Option<Vec<Embed>>,
+    /// The stickers of a message create event
+    sticker_items: Option<Vec<MessageSticker>>,
+    /// The role of a role create or update event
+    role: Option<Role>,
+    /// The stage instance of a stage instance create or update event
+    stage_instance: Option<StageInstance>,
+}
+
+impl OwnedFields {
+    /// Move the fields out of the event, leaving them empty
+    fn take(event: &mut Event) -> Self {
+        match event {
+            Event::GuildCreate(guild) => Self {
+                roles: Some(mem::take(&mut guild.roles)),
+                stage_instances: Some(mem::take(&mut guild.stage_instances)),
+                ..Self::default()
+            },
+            Event::MessageCreate(message) => Self {
+                attachments: Some(mem::take(&mut message.attachments)),
+                embeds: Some(mem::take(&mut message.embeds)),
+                sticker_items: Some(mem::take(&mut message.sticker_items)),
+                ..Self::default()
+            },
+            Event::RoleCreate(role_create) => Self {
+                role: Some(Self::take_role(&mut role_create.role)),
+                ..Self::default()
+            },
+            Event::RoleUpdate(role_update) => Self {
+                role: Some(Self::take_role(&mut role_update.role)),
+                ..Self::default()
+            },
+            Event::StageInstanceCreate(stage) => Self {
+                stage_instance: Some(Self::take_stage_instance(&mut stage.0)),
+                ..Self::default()
+            },
+            Event::StageInstanceUpdate(stage) => Self {
+                stage_instance: Some(Self::take_stage_instance(&mut stage.0)),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Move the strings out of the role, returning a role with them and the
+    /// rest of its fields, which don't allocate when cloned
+    fn take_role(role: &mut Role) -> Role {
+        let name = mem::take(&mut role.name);
+        let unicode_emoji = role.unicode_emoji.take();
+
+        Role {
+            name,
+            unicode_emoji,
+            ..role.clone()
+        }
+    }
+
+    /// Move the topic out of the stage instance, returning a stage instance
+    /// with it and the rest of its fields, which don't allocate when cloned
+    fn take_stage_instance(stage: &mut StageInstance) -> StageInstance {
+        let topic = mem::take(&mut stage.topic);
+
+        StageInstance {
+            topic,
+            ..stage.clone()
+        }
+    }
+}
+
+/// Runs the given futures concurrently, at most `limit` of them at once,
+/// returning the first error
+async fn run_concurrently<I, F, E>(futures: I, limit: usize) -> Result<(), Error<E>>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = Result<(), Error<E>>>,
+    E: Send,
+{
+    stream::iter(futures)
+        .buffer_unordered(limit)
+        .try_collect()
+        .await
+}
@@ -0,0 +1,23 @@
+use twilight_model::{
+    gateway::event::Event,
+    id::{marker::GenericMarker, Id},
+};
+
+use crate::cache::EntityKind;
+
+/// Returns the kind and the ID of the entity the event changes, if it's
+/// recorded in the changelog
+///
+/// Only the create, update and delete events of channels and roles are
+/// recorded, `None` is returned for the other events
+pub(crate) fn target(event: &Event) -> Option<(EntityKind, Id<GenericMarker>)> {
+    match event {
+        Event::ChannelCreate(channel) => Some((EntityKind::Channel, channel.id.cast())),
+        Event::ChannelUpdate(channel) => Some((EntityKind::Channel, channel.id.cast())),
+        Event::ChannelDelete(channel) => Some((EntityKind::Channel, channel.id.cast())),
+        Event::RoleCreate(role) => Some((EntityKind::Role, role.role.id.cast())),
+        Event::RoleUpdate(role) => Some((EntityKind::Role, role.role.id.cast())),
+        Event::RoleDelete(role) => Some((EntityKind::Role, role.role_id.cast())),
+        _ => None,
+    }
+}
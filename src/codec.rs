@@ -0,0 +1,101 @@
+use std::error::Error;
+
+#[cfg(feature = "codec-msgpack")]
+pub use error::MessagePackError;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Put into a mod to allow lints
+#[cfg(feature = "codec-msgpack")]
+#[allow(clippy::std_instead_of_core)]
+mod error {
+    use thiserror::Error;
+
+    /// The error returned by [`super::MessagePackCodec`]
+    #[derive(Error, Debug)]
+    pub enum MessagePackError {
+        /// The model couldn't be encoded
+        #[error("The model couldn't be encoded:\n{0}")]
+        Encode(#[from] rmp_serde::encode::Error),
+        /// The bytes couldn't be decoded into the model
+        #[error("The bytes couldn't be decoded into the model:\n{0}")]
+        Decode(#[from] rmp_serde::decode::Error),
+    }
+}
+
+/// Encodes the cached models into bytes and decodes them back
+///
+/// Backends that store documents or key-value pairs can use it to store every
+/// model with one generic function instead of mapping each of them by hand,
+/// it's implemented by [`JsonCodec`] with the `codec-json` feature and
+/// [`MessagePackCodec`] with the `codec-msgpack` feature
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::codec::{JsonCodec, ModelCodec};
+///
+/// async fn store<T: Serialize>(&self, key: String, model: &T) -> Result<(), MyError> {
+///     self.client.set(key, JsonCodec.encode(model)?).await
+/// }
+/// ```
+pub trait ModelCodec {
+    /// The error returned when a model can't be encoded or decoded
+    type Error: Error + Send + Sync + 'static;
+
+    /// Encode the model into bytes
+    ///
+    /// # Errors
+    ///
+    /// Returns the codec's error if the model can't be encoded
+    fn encode<T: Serialize>(&self, model: &T) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decode the bytes returned by [`ModelCodec::encode`] into the model
+    ///
+    /// # Errors
+    ///
+    /// Returns the codec's error if the bytes aren't an encoded model of the
+    /// given type
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// A codec that encodes the models as JSON, which is readable but larger than
+/// the binary codecs
+#[cfg(feature = "codec-json")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonCodec;
+
+#[cfg(feature = "codec-json")]
+impl ModelCodec for JsonCodec {
+    type Error = serde_json::Error;
+
+    fn encode<T: Serialize>(&self, model: &T) -> Result<Vec<u8>, Self::Error> {
+        serde_json::to_vec(model)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+/// A codec that encodes the models as MessagePack maps, which is compact
+/// while keeping the field names, so models encoded before a field was added
+/// can still be decoded
+///
+/// A self-describing format is used instead of bincode, since Twilight's IDs
+/// and timestamps can only be deserialized from self-describing formats
+#[cfg(feature = "codec-msgpack")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MessagePackCodec;
+
+#[cfg(feature = "codec-msgpack")]
+impl ModelCodec for MessagePackCodec {
+    type Error = MessagePackError;
+
+    fn encode<T: Serialize>(&self, model: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(rmp_serde::to_vec_named(model)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
@@ -0,0 +1,660 @@
+#![allow(clippy::std_instead_of_alloc)]
+
+use core::time::Duration;
+use std::collections::{BTreeMap, BTreeSet};
+
+use twilight_model::{
+    channel::message::MessageType,
+    gateway::event::{Event, EventType},
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{cache::EntityKind, event, model::CachedMessage};
+
+/// The configuration returned by [`crate::Cache::config`] unless it's
+/// overridden
+pub(crate) static DEFAULT_CONFIG: CacheConfig = CacheConfig::new();
+
+/// Configuration of how the cache is updated
+///
+/// To use a configuration other than the default, override
+/// [`crate::Cache::config`] to return it
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::config::CacheConfig;
+///
+/// struct MyCache {
+///     config: CacheConfig,
+/// }
+///
+/// impl MyCache {
+///     fn new() -> Self {
+///         Self {
+///             config: CacheConfig::new().write_concurrency(32),
+///         }
+///     }
+/// }
+///
+/// impl Cache for MyCache {
+///     fn config(&self) -> &CacheConfig {
+///         &self.config
+///     }
+///     // Implement the other methods here
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    /// The maximum number of backend writes to run at once when handling
+    /// events with many independent writes
+    pub(crate) write_concurrency: usize,
+    /// Whether to cache the previous content of messages when they're edited
+    pub(crate) message_revisions: bool,
+    /// Whether to cache partial messages from the updates of uncached messages
+    pub(crate) partial_messages: bool,
+    /// Whether to cache interactions
+    pub(crate) interactions: bool,
+    /// Whether to store a snapshot of the author in cached messages
+    pub(crate) message_authors: bool,
+    /// Whether to store the files of attachments once however many times
+    /// they're posted
+    pub(crate) attachment_dedup: bool,
+    /// Whether to store the embeds of messages as one JSON document
+    #[cfg(feature = "embeds-json")]
+    pub(crate) embeds_json: bool,
+    /// How the content of cached messages is stored
+    pub(crate) message_content: MessageContentMode,
+    /// Whether to track the time of the latest message in each channel
+    pub(crate) channel_activity: bool,
+    /// Whether to skip the updates that are older than the cached data
+    pub(crate) ordered_updates: bool,
+    /// How long the keys of applied events are kept to skip their replays,
+    /// `None` if events aren't deduplicated
+    pub(crate) event_dedup: Option<Duration>,
+    /// The member count above which the members in guild create events aren't
+    /// cached
+    pub(crate) lazy_members: Option<u64>,
+    /// How long the entities of each kind are kept in the cache after they're
+    /// last updated, indexed by [`ttl_index`]
+    pub(crate) ttls: [Option<Duration>; 9],
+    /// How long the messages in each guild are kept in the cache, overriding
+    /// [`SweepOptions::message_max_age`] for these guilds
+    pub(crate) guild_message_retention: BTreeMap<Id<GuildMarker>, Duration>,
+}
+
+impl CacheConfig {
+    /// Create the default configuration
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            write_concurrency: 16,
+            message_revisions: false,
+            partial_messages: false,
+            interactions: false,
+            message_authors: false,
+            attachment_dedup: false,
+            #[cfg(feature = "embeds-json")]
+            embeds_json: false,
+            message_content: MessageContentMode::Plaintext,
+            channel_activity: false,
+            ordered_updates: false,
+            event_dedup: None,
+            lazy_members: None,
+            ttls: [None; 9],
+            guild_message_retention: BTreeMap::new(),
+        }
+    }
+
+    /// Set the maximum number of backend writes to run at once when handling
+    /// events with many independent writes, such as guild create events
+    ///
+    /// Defaults to 16, a value of 0 is treated as 1, meaning writes are run
+    /// sequentially
+    #[must_use]
+    pub const fn write_concurrency(mut self, write_concurrency: usize) -> Self {
+        self.write_concurrency = if write_concurrency == 0 {
+            1
+        } else {
+            write_concurrency
+        };
+        self
+    }
+
+    /// Cache the previous content of messages when they're edited, returned by
+    /// [`crate::Cache::message_revisions`], for example to show the edits of a
+    /// message in moderation logs
+    ///
+    /// Disabled by default, since it keeps growing as messages are edited
+    #[must_use]
+    pub const fn message_revisions(mut self) -> Self {
+        self.message_revisions = true;
+        self
+    }
+
+    /// Cache a partial message created with
+    /// [`crate::model::CachedMessage::from_message_update`] when a message
+    /// that isn't cached is updated, so that the content and author of
+    /// recently edited old messages can still be returned
+    ///
+    /// Disabled by default, since the fields that aren't in the update, such
+    /// as the message's reference, have their default values, check
+    /// [`crate::model::CachedMessage::partial`] to know if that's the case
+    #[must_use]
+    pub const fn partial_messages(mut self) -> Self {
+        self.partial_messages = true;
+        self
+    }
+
+    /// Cache interactions, returned by [`crate::Cache::interaction`], so that
+    /// bots running in multiple processes can send follow-up messages to
+    /// interactions received by another process
+    ///
+    /// Disabled by default, since interactions are only useful until their
+    /// tokens expire, the expired ones are removed on [`crate::Cache::sweep`]
+    #[must_use]
+    pub const fn interactions(mut self) -> Self {
+        self.interactions = true;
+        self
+    }
+
+    /// Store a snapshot of the author's name, discriminator and avatar in
+    /// cached messages, so that messages can be shown without getting their
+    /// author, which often isn't cached
+    ///
+    /// Disabled by default, since the snapshot is duplicated in every message
+    /// and it isn't updated when the author changes their name or avatar
+    #[must_use]
+    pub const fn message_authors(mut self) -> Self {
+        self.message_authors = true;
+        self
+    }
+
+    /// Store the files of attachments in
+    /// [`crate::model::CachedAttachmentBlob`]s keyed by the hash of their
+    /// name, size, content type, dimensions and duration, with a
+    /// [`crate::model::CachedAttachmentPost`] for each message they're posted
+    /// in, so that re-posted files don't duplicate their data
+    ///
+    /// Discord doesn't expose a hash of the file's content, so different files
+    /// with the same name, size, content type, dimensions and duration share a
+    /// blob, which has the metadata of the one posted last
+    ///
+    /// [`crate::Cache::attachment_posters`] returns the messages a file was
+    /// posted in, for example to detect reposts, and
+    /// [`crate::Cache::attachments`] still returns the attachments of a
+    /// message
+    ///
+    /// Disabled by default, since it's only useful when the same files are
+    /// posted often
+    #[must_use]
+    pub const fn attachment_dedup(mut self) -> Self {
+        self.attachment_dedup = true;
+        self
+    }
+
+    /// Store the embeds of each message, along with their fields, as one JSON
+    /// document with [`crate::Backend::upsert_message_embeds_json`] instead of
+    /// a row for each embed and field, so that [`crate::Cache::embeds`] reads
+    /// them in one query
+    ///
+    /// The embeds of messages cached before this is enabled are still read
+    /// from their rows
+    ///
+    /// Disabled by default, since the embeds can't be queried by their fields
+    #[cfg(feature = "embeds-json")]
+    #[must_use]
+    pub const fn embeds_json(mut self) -> Self {
+        self.embeds_json = true;
+        self
+    }
+
+    /// Set how the content of cached messages is stored, for example to store
+    /// a hash of it in privacy-sensitive deployments that still need to
+    /// detect repeated messages
+    ///
+    /// The other fields of messages, such as their authors and timestamps,
+    /// are cached as usual, the content of message revisions is stored the
+    /// same way
+    ///
+    /// Defaults to [`MessageContentMode::Plaintext`]
+    #[must_use]
+    pub const fn message_content(mut self, mode: MessageContentMode) -> Self {
+        self.message_content = mode;
+        self
+    }
+
+    /// Track the time of the latest message in each channel with
+    /// [`crate::Backend::set_channel_last_activity`], so that
+    /// [`crate::Cache::inactive_channels`] can return the channels nobody
+    /// talked in recently, for example to archive them
+    ///
+    /// Only the time is stored, unlike the ID of the last message, which
+    /// isn't cached since it'd require a write to the channel for every
+    /// message
+    ///
+    /// Disabled by default, since it adds a backend call for every message
+    #[must_use]
+    pub const fn channel_activity(mut self) -> Self {
+        self.channel_activity = true;
+        self
+    }
+
+    /// Skip the updates that are older than the cached data, so that an update
+    /// that's delayed, for example when events are handled concurrently,
+    /// can't overwrite the data of a newer update
+    ///
+    /// Message updates are compared by their edited timestamps, guild and
+    /// member updates are compared by the sequence numbers of their gateway
+    /// payloads, which are recorded with
+    /// [`crate::Backend::advance_update_sequence`], so they're only compared
+    /// when the cache is updated with [`crate::Cache::update_sharded`]
+    ///
+    /// Disabled by default, since it adds a backend call for every guild and
+    /// member update
+    #[must_use]
+    pub const fn ordered_updates(mut self) -> Self {
+        self.ordered_updates = true;
+        self
+    }
+
+    /// Skip the events that were already applied, such as the events replayed
+    /// after the gateway reconnects, so that usage statistics and the
+    /// changelog aren't recorded twice
+    ///
+    /// Events are keyed by their type, the ID of the entity they create and
+    /// the timestamp of the update, which are recorded with
+    /// [`crate::Backend::seen_event`], only message creates, updates and
+    /// deletes, channel and thread creates, member adds and interaction
+    /// creates are deduplicated, the keys older than the window are removed on
+    /// [`crate::Cache::sweep`], the key of an event that couldn't be applied
+    /// is removed with [`crate::Backend::forget_event`] so that its replay is
+    /// applied
+    ///
+    /// Disabled by default, since it adds a backend call for every
+    /// deduplicated event
+    #[must_use]
+    pub const fn event_dedup(mut self, window: Duration) -> Self {
+        self.event_dedup = Some(window);
+        self
+    }
+
+    /// Don't cache the members in the guild create events of guilds with more
+    /// members than the threshold, since caching them takes up most of the
+    /// time it takes to handle the guild create events of large guilds
+    ///
+    /// The members of these guilds are instead cached when they're seen, from
+    /// member update events and the authors of messages, or when they're
+    /// requested, for example with `Cache::request_missing_members`, and their
+    /// [`crate::model::CachedGuild::members_partial`] is `true`
+    ///
+    /// Disabled by default, since with it [`crate::Cache::guild_members`]
+    /// returns only some of the members of large guilds
+    #[must_use]
+    pub const fn lazy_members(mut self, threshold: u64) -> Self {
+        self.lazy_members = Some(threshold);
+        self
+    }
+
+    /// Remove the entities of the given kind from the cache when they aren't
+    /// updated for the given duration, for example to let presences or
+    /// messages age out automatically
+    ///
+    /// Every time an entity of this kind is cached,
+    /// [`crate::backend::Backend::set_expiry`] is called with the time it
+    /// expires at, backends with native expiry such as Redis can set the key's
+    /// TTL, other backends should store it so that it's removed on
+    /// [`crate::Cache::sweep`]
+    ///
+    /// Entities don't expire by default
+    #[must_use]
+    pub fn ttl(mut self, kind: EntityKind, ttl: Duration) -> Self {
+        if let Some(kind_ttl) = self.ttls.get_mut(ttl_index(kind)) {
+            *kind_ttl = Some(ttl);
+        }
+        self
+    }
+
+    /// Keep the messages in the given guild for the given duration, for
+    /// example to honor the privacy settings of each server in multi-tenant
+    /// bots
+    ///
+    /// The messages older than the retention are removed on
+    /// [`crate::Cache::sweep`], even if [`SweepOptions::message_max_age`]
+    /// isn't set or is longer, with a retention of zero the messages in the
+    /// guild aren't cached at all
+    ///
+    /// Messages are kept until they're swept by default
+    #[must_use]
+    pub fn guild_message_retention(
+        mut self,
+        guild_id: Id<GuildMarker>,
+        retention: Duration,
+    ) -> Self {
+        self.guild_message_retention.insert(guild_id, retention);
+        self
+    }
+
+    /// Return whether the messages in the given guild should be cached,
+    /// `false` if the guild's message retention is zero
+    pub(crate) fn retains_messages(&self, guild_id: Option<Id<GuildMarker>>) -> bool {
+        guild_id
+            .and_then(|id| self.guild_message_retention.get(&id))
+            .map_or(true, |retention| !retention.is_zero())
+    }
+
+    /// Return how long the entities of the given kind are kept in the cache,
+    /// `None` if they don't expire
+    pub(crate) fn ttl_of(&self, kind: EntityKind) -> Option<Duration> {
+        self.ttls.get(ttl_index(kind)).copied().flatten()
+    }
+
+    /// Return whether the entities of any kind expire
+    pub(crate) fn has_ttls(&self) -> bool {
+        self.ttls.iter().any(Option::is_some)
+    }
+}
+
+/// Return the index of the entity kind in [`CacheConfig::ttls`]
+const fn ttl_index(kind: EntityKind) -> usize {
+    match kind {
+        EntityKind::Channel => 0,
+        EntityKind::Message => 1,
+        EntityKind::Member => 2,
+        EntityKind::Guild => 3,
+        EntityKind::Role => 4,
+        EntityKind::Emoji => 5,
+        EntityKind::Sticker => 6,
+        EntityKind::StageInstance => 7,
+        EntityKind::Presence => 8,
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How the content of cached messages is stored, set with
+/// [`CacheConfig::message_content`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MessageContentMode {
+    /// Store the content as it is
+    #[default]
+    Plaintext,
+    /// Store a hash of the content as 16 hexadecimal digits, so that messages
+    /// with the same content can still be found
+    ///
+    /// The hash is FNV-1a, it's stable across versions but it isn't
+    /// cryptographic, so short content can be guessed from it
+    Hashed,
+    /// Store empty content
+    Omitted,
+}
+
+impl MessageContentMode {
+    /// Return the content as it's stored in this mode, for example to hash
+    /// some content to compare it with the content of cached messages
+    ///
+    /// Empty content is returned as it is in every mode
+    #[must_use]
+    pub fn apply(self, content: &str) -> String {
+        if content.is_empty() {
+            return String::new();
+        }
+
+        match self {
+            Self::Plaintext => content.to_owned(),
+            Self::Hashed => crate::fnv_hash(content),
+            Self::Omitted => String::new(),
+        }
+    }
+}
+
+/// Filter to skip some events when updating the cache
+///
+/// Pass it to [`crate::Cache::update_filtered`], the filter is checked before
+/// anything is written to the backend
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::config::EventFilter;
+/// use twilight_model::{gateway::event::EventType, id::Id};
+///
+/// let filter = EventFilter::new()
+///     .ignore_kind(EventType::PresenceUpdate)
+///     .ignore_channel(Id::new(123));
+/// cache.update_filtered(&event, &filter).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct EventFilter {
+    /// The types of the events to skip
+    ignored_kinds: Vec<EventType>,
+    /// The IDs of the guilds to skip the events in
+    ignored_guilds: BTreeSet<Id<GuildMarker>>,
+    /// The IDs of the channels to skip the events in
+    ignored_channels: BTreeSet<Id<ChannelMarker>>,
+}
+
+impl EventFilter {
+    /// Create a filter that allows every event
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ignored_kinds: Vec::new(),
+            ignored_guilds: BTreeSet::new(),
+            ignored_channels: BTreeSet::new(),
+        }
+    }
+
+    /// Skip the events of the given type
+    #[must_use]
+    pub fn ignore_kind(mut self, kind: EventType) -> Self {
+        self.ignored_kinds.push(kind);
+        self
+    }
+
+    /// Skip the events in the given guild
+    #[must_use]
+    pub fn ignore_guild(mut self, guild_id: Id<GuildMarker>) -> Self {
+        self.ignored_guilds.insert(guild_id);
+        self
+    }
+
+    /// Skip the events in the given channel or thread, for example to not
+    /// cache the messages of a channel used for logging
+    #[must_use]
+    pub fn ignore_channel(mut self, channel_id: Id<ChannelMarker>) -> Self {
+        self.ignored_channels.insert(channel_id);
+        self
+    }
+
+    /// Return whether the event should update the cache
+    #[must_use]
+    pub fn allows(&self, event: &Event) -> bool {
+        !self.ignored_kinds.contains(&event.kind())
+            && event::guild_id(event)
+                .map_or(true, |guild_id| !self.ignored_guilds.contains(&guild_id))
+            && event::channel_id(event).map_or(true, |channel_id| {
+                !self.ignored_channels.contains(&channel_id)
+            })
+    }
+}
+
+/// Options of what to remove from the cache when sweeping it
+///
+/// Pass it to [`crate::Cache::sweep`], by default nothing is removed, use the
+/// methods to choose what to remove
+///
+/// # Example
+///
+/// ```ignore
+/// use std::time::Duration;
+///
+/// use sparkle_cache::config::SweepOptions;
+///
+/// let options = SweepOptions::new()
+///     .message_max_age(Duration::from_secs(60 * 60 * 24))
+///     .memberless_presences()
+///     .orphaned_message_data()
+///     .guild_remnants();
+/// cache.sweep(options).await?;
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SweepOptions {
+    /// The age of the messages to remove
+    pub(crate) message_max_age: Option<Duration>,
+    /// Whether to remove the presences of users with no cached member
+    pub(crate) memberless_presences: bool,
+    /// Whether to remove the message data whose messages aren't cached
+    pub(crate) orphaned_message_data: bool,
+    /// Whether to remove the guild data whose guilds aren't cached
+    pub(crate) guild_remnants: bool,
+}
+
+impl SweepOptions {
+    /// Create options that don't remove anything
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            message_max_age: None,
+            memberless_presences: false,
+            orphaned_message_data: false,
+            guild_remnants: false,
+        }
+    }
+
+    /// Remove the messages older than the given age, along with their embeds,
+    /// attachments, reactions and stickers
+    #[must_use]
+    pub const fn message_max_age(mut self, max_age: Duration) -> Self {
+        self.message_max_age = Some(max_age);
+        self
+    }
+
+    /// Remove the presences and activities of users whose members aren't
+    /// cached
+    #[must_use]
+    pub const fn memberless_presences(mut self) -> Self {
+        self.memberless_presences = true;
+        self
+    }
+
+    /// Remove the embeds, embed fields, attachments, reactions and stickers
+    /// whose messages aren't cached
+    #[must_use]
+    pub const fn orphaned_message_data(mut self) -> Self {
+        self.orphaned_message_data = true;
+        self
+    }
+
+    /// Remove the channels, members, roles and other guild data whose guilds
+    /// aren't cached
+    #[must_use]
+    pub const fn guild_remnants(mut self) -> Self {
+        self.guild_remnants = true;
+        self
+    }
+}
+
+/// Filter of which kinds of messages to return from
+/// [`crate::Cache::channel_messages_filtered`]
+///
+/// By default every message is returned, use the methods to choose what to
+/// exclude
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::config::MessageKindFilter;
+///
+/// let filter = MessageKindFilter::new().exclude_system().exclude_bots();
+/// let messages = cache.channel_messages_filtered(channel_id, filter, 100).await?;
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MessageKindFilter {
+    /// Whether to exclude system messages, such as join messages
+    pub(crate) system: bool,
+    /// Whether to exclude the messages of bots and webhooks
+    pub(crate) bots: bool,
+    /// Whether to exclude the messages of webhooks
+    pub(crate) webhooks: bool,
+}
+
+impl MessageKindFilter {
+    /// Create a filter that doesn't exclude anything
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            system: false,
+            bots: false,
+            webhooks: false,
+        }
+    }
+
+    /// Exclude system messages, the messages whose kind is checked with
+    /// [`Self::is_system_kind`]
+    #[must_use]
+    pub const fn exclude_system(mut self) -> Self {
+        self.system = true;
+        self
+    }
+
+    /// Exclude the messages of bots, including webhooks
+    #[must_use]
+    pub const fn exclude_bots(mut self) -> Self {
+        self.bots = true;
+        self
+    }
+
+    /// Exclude the messages of webhooks
+    #[must_use]
+    pub const fn exclude_webhooks(mut self) -> Self {
+        self.webhooks = true;
+        self
+    }
+
+    /// Return whether system messages are excluded
+    #[must_use]
+    pub const fn excludes_system(self) -> bool {
+        self.system
+    }
+
+    /// Return whether the messages of bots are excluded
+    #[must_use]
+    pub const fn excludes_bots(self) -> bool {
+        self.bots
+    }
+
+    /// Return whether the messages of webhooks are excluded
+    #[must_use]
+    pub const fn excludes_webhooks(self) -> bool {
+        self.webhooks
+    }
+
+    /// Return whether the message kind is a system message, meaning it isn't
+    /// a regular message, a reply or the response to a command
+    #[must_use]
+    pub const fn is_system_kind(kind: MessageType) -> bool {
+        !matches!(
+            kind,
+            MessageType::Regular
+                | MessageType::Reply
+                | MessageType::ChatInputCommand
+                | MessageType::ContextMenuCommand
+        )
+    }
+
+    /// Return whether the message passes the filter
+    #[must_use]
+    pub const fn matches(self, message: &CachedMessage) -> bool {
+        !(self.system && Self::is_system_kind(message.kind)
+            || self.bots && message.author_bot
+            || self.webhooks && message.webhook_id.is_some())
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use crate::cache::EntityKind;
+
+/// The number of cached entities of a kind, returned by
+/// [`crate::Backend::entity_counts`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EntityCount {
+    /// The number of cached entities, such as the number of rows in a table
+    pub rows: u64,
+    /// The storage the entities take up in bytes, `None` if the backend can't
+    /// tell
+    pub bytes: Option<u64>,
+}
+
+/// The number of cached entities of every kind, returned by
+/// [`crate::Cache::stats`]
+///
+/// This is useful to monitor how large the cache grows and to decide how
+/// often to sweep it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// The counts of every kind the backend returned
+    pub entities: HashMap<EntityKind, EntityCount>,
+}
+
+impl CacheStats {
+    /// Return the count of the kind, `None` if the backend didn't return it
+    #[must_use]
+    pub fn count(&self, kind: EntityKind) -> Option<EntityCount> {
+        self.entities.get(&kind).copied()
+    }
+
+    /// Return the total number of cached entities
+    #[must_use]
+    pub fn rows(&self) -> u64 {
+        self.entities
+            .values()
+            .fold(0, |rows, count| rows.saturating_add(count.rows))
+    }
+
+    /// Return the total storage the cached entities take up in bytes, `None`
+    /// if the backend can't tell the size of any kind
+    #[must_use]
+    pub fn bytes(&self) -> Option<u64> {
+        self.entities
+            .values()
+            .map(|count| count.bytes)
+            .try_fold(0_u64, |bytes, count_bytes| {
+                count_bytes.map(|count_bytes| bytes.saturating_add(count_bytes))
+            })
+    }
+}
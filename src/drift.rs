@@ -0,0 +1,87 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, EmojiMarker, RoleMarker, UserMarker},
+    Id,
+};
+
+/// The differences between the cached entities of a kind and the ones
+/// returned by the HTTP API
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityDrift<T> {
+    /// IDs of the entities that exist but aren't cached
+    pub missing: Vec<Id<T>>,
+    /// IDs of the entities that are cached but don't exist anymore
+    pub extra: Vec<Id<T>>,
+    /// IDs of the entities whose cached data is different from their current
+    /// data
+    pub mismatched: Vec<Id<T>>,
+}
+
+impl<T> EntityDrift<T> {
+    /// Compare the live entities with the cached ones, using the given
+    /// function to get their IDs
+    pub(crate) fn new<M: PartialEq>(live: &[M], cached: &[M], id: impl Fn(&M) -> Id<T>) -> Self {
+        let mut drift = Self {
+            missing: vec![],
+            extra: vec![],
+            mismatched: vec![],
+        };
+
+        for live_model in live {
+            match cached
+                .iter()
+                .find(|cached_model| id(cached_model) == id(live_model))
+            {
+                None => drift.missing.push(id(live_model)),
+                Some(cached_model) if cached_model != live_model => {
+                    drift.mismatched.push(id(live_model));
+                }
+                Some(_) => {}
+            }
+        }
+        for cached_model in cached {
+            if !live
+                .iter()
+                .any(|live_model| id(live_model) == id(cached_model))
+            {
+                drift.extra.push(id(cached_model));
+            }
+        }
+
+        drift
+    }
+
+    /// Return whether the cached entities are the same as the live ones
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty() && self.extra.is_empty() && self.mismatched.is_empty()
+    }
+}
+
+/// The differences between a cached guild and its current data, returned by
+/// [`crate::Cache::diff_against_http`]
+///
+/// These usually happen when events are missed, for example while the bot is
+/// reconnecting or when the cache is updated with some events filtered out
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheDrift {
+    /// The differences of the guild's channels, threads aren't compared since
+    /// they aren't returned with the guild's channels
+    pub channels: EntityDrift<ChannelMarker>,
+    /// The differences of the guild's roles
+    pub roles: EntityDrift<RoleMarker>,
+    /// The differences of the guild's members
+    pub members: EntityDrift<UserMarker>,
+    /// The differences of the guild's emojis
+    pub emojis: EntityDrift<EmojiMarker>,
+}
+
+impl CacheDrift {
+    /// Return whether the cached guild is the same as its current data
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+            && self.roles.is_empty()
+            && self.members.is_empty()
+            && self.emojis.is_empty()
+    }
+}
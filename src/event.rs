@@ -0,0 +1,122 @@
+use twilight_model::{
+    gateway::event::Event,
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+/// Returns the ID of the guild the event is related to
+///
+/// Only the events that update the cache are handled, `None` is returned for
+/// the other events and the events that aren't in a guild
+pub(crate) fn guild_id(event: &Event) -> Option<Id<GuildMarker>> {
+    match event {
+        Event::ChannelCreate(channel) => channel.0.guild_id,
+        Event::ChannelUpdate(channel) => channel.0.guild_id,
+        Event::ChannelDelete(channel) => channel.0.guild_id,
+        Event::ThreadCreate(thread) => thread.0.guild_id,
+        Event::ThreadUpdate(thread) => thread.0.guild_id,
+        Event::ThreadDelete(thread) => Some(thread.guild_id),
+        Event::ThreadListSync(sync) => Some(sync.guild_id),
+        Event::GuildCreate(guild) => Some(guild.0.id),
+        Event::GuildUpdate(guild) => Some(guild.0.id),
+        Event::GuildDelete(guild) => Some(guild.id),
+        Event::GuildEmojisUpdate(emojis) => Some(emojis.guild_id),
+        Event::GuildStickersUpdate(stickers) => Some(stickers.guild_id),
+        Event::MemberAdd(member) => Some(member.0.guild_id),
+        Event::MemberChunk(members) => Some(members.guild_id),
+        Event::MemberUpdate(member) => Some(member.guild_id),
+        Event::MemberRemove(member) => Some(member.guild_id),
+        Event::MessageCreate(message) => message.0.guild_id,
+        Event::MessageUpdate(message) => message.guild_id,
+        Event::MessageDelete(message) => message.guild_id,
+        Event::MessageDeleteBulk(messages) => messages.guild_id,
+        Event::PresenceUpdate(presence) => Some(presence.0.guild_id),
+        Event::ReactionAdd(reaction) => reaction.0.guild_id,
+        Event::ReactionRemove(reaction) => reaction.0.guild_id,
+        Event::ReactionRemoveEmoji(reaction) => Some(reaction.guild_id),
+        Event::ReactionRemoveAll(reaction) => reaction.guild_id,
+        Event::RoleCreate(role) => Some(role.guild_id),
+        Event::RoleUpdate(role) => Some(role.guild_id),
+        Event::RoleDelete(role) => Some(role.guild_id),
+        Event::StageInstanceCreate(stage) => Some(stage.0.guild_id),
+        Event::StageInstanceUpdate(stage) => Some(stage.0.guild_id),
+        Event::StageInstanceDelete(stage) => Some(stage.0.guild_id),
+        Event::InteractionCreate(interaction) => interaction.guild_id,
+        _ => None,
+    }
+}
+
+/// Returns the ID of the channel the event is related to
+///
+/// Only the events that update the cache are handled, `None` is returned for
+/// the other events and the events that aren't in a channel
+pub(crate) fn channel_id(event: &Event) -> Option<Id<ChannelMarker>> {
+    match event {
+        Event::ChannelCreate(channel) => Some(channel.0.id),
+        Event::ChannelUpdate(channel) => Some(channel.0.id),
+        Event::ChannelDelete(channel) => Some(channel.0.id),
+        Event::ThreadCreate(thread) => Some(thread.0.id),
+        Event::ThreadUpdate(thread) => Some(thread.0.id),
+        Event::ThreadDelete(thread) => Some(thread.id),
+        Event::MessageCreate(message) => Some(message.0.channel_id),
+        Event::MessageUpdate(message) => Some(message.channel_id),
+        Event::MessageDelete(message) => Some(message.channel_id),
+        Event::MessageDeleteBulk(messages) => Some(messages.channel_id),
+        Event::ReactionAdd(reaction) => Some(reaction.0.channel_id),
+        Event::ReactionRemove(reaction) => Some(reaction.0.channel_id),
+        Event::ReactionRemoveEmoji(reaction) => Some(reaction.channel_id),
+        Event::ReactionRemoveAll(reaction) => Some(reaction.channel_id),
+        Event::StageInstanceCreate(stage) => Some(stage.0.channel_id),
+        Event::StageInstanceUpdate(stage) => Some(stage.0.channel_id),
+        Event::StageInstanceDelete(stage) => Some(stage.0.channel_id),
+        Event::InteractionCreate(interaction) => interaction.channel_id,
+        _ => None,
+    }
+}
+
+/// Returns the key that identifies the event when it's replayed, for example
+/// after the gateway reconnects
+///
+/// Only the events whose replays can be told apart from new events are
+/// handled, by the ID of the entity they create or the timestamp of the
+/// update, `None` is returned for the other events, such as reaction events,
+/// since a reaction can be added again after it's removed
+pub(crate) fn dedup_key(event: &Event) -> Option<String> {
+    match event {
+        Event::ChannelCreate(channel) => Some(format!("CHANNEL_CREATE:{}", channel.0.id)),
+        Event::ThreadCreate(thread) => Some(format!("THREAD_CREATE:{}", thread.0.id)),
+        Event::MemberAdd(member) => Some(format!(
+            "GUILD_MEMBER_ADD:{}:{}:{}",
+            member.0.guild_id,
+            member.0.user.id,
+            member.0.joined_at.as_micros()
+        )),
+        Event::MessageCreate(message) => Some(format!("MESSAGE_CREATE:{}", message.0.id)),
+        Event::MessageUpdate(message) => message
+            .edited_timestamp
+            .map(|edited_at| format!("MESSAGE_UPDATE:{}:{}", message.id, edited_at.as_micros())),
+        Event::MessageDelete(message) => Some(format!("MESSAGE_DELETE:{}", message.id)),
+        Event::InteractionCreate(interaction) => {
+            Some(format!("INTERACTION_CREATE:{}", interaction.id))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the key of the entity the event updates, used to compare the
+/// sequence numbers of the updates applied to it
+///
+/// Only the update events without a timestamp to compare are handled, `None`
+/// is returned for the other events
+pub(crate) fn sequence_key(event: &Event) -> Option<String> {
+    match event {
+        Event::GuildUpdate(guild) => Some(format!("GUILD_UPDATE:{}", guild.0.id)),
+        Event::MemberUpdate(member) => Some(format!(
+            "GUILD_MEMBER_UPDATE:{}:{}",
+            member.guild_id, member.user.id
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use twilight_model::gateway::event::Event;
+
+/// The outcome of updating the cache with an event, passed to
+/// [`CacheHooks::after_update`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpdateOutcome {
+    /// The cache was updated with the event without errors, this is also the
+    /// outcome of events that aren't cached
+    Applied,
+    /// The event was skipped because the filter passed to
+    /// [`crate::Cache::update_filtered`] didn't allow it
+    Filtered,
+    /// The event was skipped because it was already applied, refer to
+    /// [`crate::config::CacheConfig::event_dedup`]
+    Duplicate,
+    /// The event was skipped because the cached data is newer, refer to
+    /// [`crate::config::CacheConfig::ordered_updates`]
+    Outdated,
+    /// The event was dropped because the backend fell behind and the event's
+    /// priority was too low, refer to [`crate::Cache::update_with_priority`]
+    Dropped,
+    /// The backend returned an error while updating the cache, the error is
+    /// returned by [`crate::Cache::update`]
+    Failed,
+}
+
+/// Callbacks that are run before and after the cache is updated with an event
+///
+/// This is useful to add metrics, replication or other side effects without
+/// handling the events yourself, every method does nothing by default so you
+/// can implement only the ones you need
+///
+/// To use it, override [`crate::Cache::hooks`] to return your hooks
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::hooks::{CacheHooks, UpdateOutcome};
+///
+/// struct Logger;
+///
+/// #[async_trait]
+/// impl CacheHooks for Logger {
+///     async fn after_update(&self, event: &Event, outcome: &UpdateOutcome) {
+///         println!("{:?}: {outcome:?}", event.kind());
+///     }
+/// }
+///
+/// impl Cache for MyCache {
+///     fn hooks(&self) -> Option<&dyn CacheHooks> {
+///         Some(&self.logger)
+///     }
+///     // Implement the other methods here
+/// }
+/// ```
+#[async_trait]
+pub trait CacheHooks: Send + Sync {
+    /// Called before the cache is updated with the event
+    async fn before_update(&self, _event: &Event) {}
+
+    /// Called after the cache is updated with the event
+    async fn after_update(&self, _event: &Event, _outcome: &UpdateOutcome) {}
+}
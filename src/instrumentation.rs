@@ -0,0 +1,77 @@
+use core::time::Duration;
+use std::collections::HashMap;
+
+use metrics::{gauge, histogram, increment_counter};
+use twilight_model::gateway::event::{Event, EventType};
+
+use crate::{cache::EntityKind, counts::EntityCount};
+
+/// Records the metrics of updating the cache with the event
+///
+/// - `sparkle_cache_events_total` counter is incremented
+/// - `sparkle_cache_update_errors_total` counter is incremented if the update
+///   failed
+/// - `sparkle_cache_update_duration_seconds` histogram records how long the
+///   whole update took, including deduplicating, journaling and running the
+///   hooks
+///
+/// All of them are labeled with the event's type as `kind`
+pub(crate) fn record_update(event_type: EventType, elapsed: Duration, succeeded: bool) {
+    let kind = event_type.name().unwrap_or("UNKNOWN");
+
+    increment_counter!("sparkle_cache_events_total", "kind" => kind);
+    if !succeeded {
+        increment_counter!("sparkle_cache_update_errors_total", "kind" => kind);
+    }
+    histogram!(
+        "sparkle_cache_update_duration_seconds",
+        elapsed.as_secs_f64(),
+        "kind" => kind
+    );
+}
+
+/// Records how long the backend writes for the event took in the
+/// `sparkle_cache_backend_write_duration_seconds` histogram, labeled with the
+/// event's type as `kind`
+pub(crate) fn record_backend_write(event_type: EventType, elapsed: Duration) {
+    histogram!(
+        "sparkle_cache_backend_write_duration_seconds",
+        elapsed.as_secs_f64(),
+        "kind" => event_type.name().unwrap_or("UNKNOWN")
+    );
+}
+
+/// Records the number of cached entities of each kind in the
+/// `sparkle_cache_entities` gauge and the storage they take up in the
+/// `sparkle_cache_entity_bytes` gauge if the backend can tell, labeled with
+/// the entity kind as `kind`
+#[allow(clippy::as_conversions, clippy::cast_precision_loss)]
+pub(crate) fn record_entity_counts(counts: &HashMap<EntityKind, EntityCount>) {
+    for (kind, count) in counts {
+        let kind = format!("{kind:?}");
+        gauge!("sparkle_cache_entities", count.rows as f64, "kind" => kind.clone());
+        if let Some(bytes) = count.bytes {
+            gauge!("sparkle_cache_entity_bytes", bytes as f64, "kind" => kind);
+        }
+    }
+}
+
+/// Increments the `sparkle_cache_dropped_events_total` counter, labeled with
+/// the event's type as `kind`, for an event dropped because the backend fell
+/// behind
+pub(crate) fn record_dropped(event: &Event) {
+    let kind = event.kind().name().unwrap_or("UNKNOWN");
+
+    increment_counter!("sparkle_cache_dropped_events_total", "kind" => kind);
+}
+
+/// Records how long the getter with the given name took in the
+/// `sparkle_cache_getter_duration_seconds` histogram, labeled with the
+/// getter's name as `getter`
+pub(crate) fn record_getter(getter: &'static str, elapsed: Duration) {
+    histogram!(
+        "sparkle_cache_getter_duration_seconds",
+        elapsed.as_secs_f64(),
+        "getter" => getter
+    );
+}
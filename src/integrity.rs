@@ -0,0 +1,35 @@
+use twilight_model::id::{
+    marker::{GenericMarker, GuildMarker, MessageMarker},
+    Id,
+};
+
+/// Referential breaks found in the cache, returned by
+/// [`crate::Cache::verify_integrity`]
+///
+/// These usually happen after the backend crashes in the middle of an update
+/// or after a partial migration
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// IDs of the embeds that aren't cached but have cached fields
+    pub embed_fields_without_embed: Vec<Id<GenericMarker>>,
+    /// IDs of the messages that aren't cached but have cached embeds
+    pub embeds_without_message: Vec<Id<MessageMarker>>,
+    /// IDs of the messages that aren't cached but have cached attachments
+    pub attachments_without_message: Vec<Id<MessageMarker>>,
+    /// IDs of the messages that aren't cached but have cached reactions
+    pub reactions_without_message: Vec<Id<MessageMarker>>,
+    /// IDs of the guilds that aren't cached but have cached roles
+    pub roles_without_guild: Vec<Id<GuildMarker>>,
+}
+
+impl IntegrityReport {
+    /// Return whether no referential breaks were found
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.embed_fields_without_embed.is_empty()
+            && self.embeds_without_message.is_empty()
+            && self.attachments_without_message.is_empty()
+            && self.reactions_without_message.is_empty()
+            && self.roles_without_guild.is_empty()
+    }
+}
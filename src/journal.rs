@@ -0,0 +1,62 @@
+use serde::de::DeserializeSeed;
+use twilight_model::gateway::event::{Event, GatewayEventDeserializer};
+
+/// Returns the name and the JSON of the data of the event, the same as the `t`
+/// and `d` fields of its gateway payload
+///
+/// Only the events that update the cache are handled, `None` is returned for
+/// the other events and the events that can't be serialized
+pub(crate) fn serialize(event: &Event) -> Option<(&'static str, String)> {
+    let data = match event {
+        Event::ChannelCreate(channel) => serde_json::to_string(channel),
+        Event::ChannelUpdate(channel) => serde_json::to_string(channel),
+        Event::ChannelDelete(channel) => serde_json::to_string(channel),
+        Event::ThreadCreate(thread) => serde_json::to_string(thread),
+        Event::ThreadUpdate(thread) => serde_json::to_string(thread),
+        Event::ThreadDelete(thread) => serde_json::to_string(thread),
+        Event::ThreadListSync(sync) => serde_json::to_string(sync),
+        Event::GuildCreate(guild) => serde_json::to_string(guild),
+        Event::GuildUpdate(guild) => serde_json::to_string(guild),
+        Event::GuildDelete(guild) => serde_json::to_string(guild),
+        Event::GuildEmojisUpdate(emojis) => serde_json::to_string(emojis),
+        Event::GuildStickersUpdate(stickers) => serde_json::to_string(stickers),
+        Event::MemberAdd(member) => serde_json::to_string(member),
+        Event::MemberChunk(members) => serde_json::to_string(members),
+        Event::MemberUpdate(member) => serde_json::to_string(member),
+        Event::MemberRemove(member) => serde_json::to_string(member),
+        Event::MessageCreate(message) => serde_json::to_string(message),
+        Event::MessageUpdate(message) => serde_json::to_string(message),
+        Event::MessageDelete(message) => serde_json::to_string(message),
+        Event::MessageDeleteBulk(messages) => serde_json::to_string(messages),
+        Event::PresenceUpdate(presence) => serde_json::to_string(presence),
+        Event::ReactionAdd(reaction) => serde_json::to_string(reaction),
+        Event::ReactionRemove(reaction) => serde_json::to_string(reaction),
+        Event::ReactionRemoveEmoji(reaction) => serde_json::to_string(reaction),
+        Event::ReactionRemoveAll(reaction) => serde_json::to_string(reaction),
+        Event::Ready(ready) => serde_json::to_string(ready),
+        Event::UserUpdate(user) => serde_json::to_string(user),
+        Event::RoleCreate(role) => serde_json::to_string(role),
+        Event::RoleUpdate(role) => serde_json::to_string(role),
+        Event::RoleDelete(role) => serde_json::to_string(role),
+        Event::StageInstanceCreate(stage) => serde_json::to_string(stage),
+        Event::StageInstanceUpdate(stage) => serde_json::to_string(stage),
+        Event::StageInstanceDelete(stage) => serde_json::to_string(stage),
+        Event::InteractionCreate(interaction) => serde_json::to_string(interaction),
+        _ => return None,
+    };
+
+    Some((event.kind().name()?, data.ok()?))
+}
+
+/// Deserializes the event from its name and the JSON of its data, returned by
+/// [`serialize`]
+///
+/// Returns `None` if the data isn't a valid event of the given name
+pub(crate) fn deserialize(kind: &str, data: &str) -> Option<Event> {
+    let json = format!(r#"{{"op":0,"s":0,"t":"{kind}","d":{data}}}"#);
+    let gateway_event = GatewayEventDeserializer::from_json(&json)?
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .ok()?;
+
+    Some(Event::from(gateway_event))
+}
@@ -61,30 +61,189 @@
 )]
 #![doc = include_str!("../README.md")]
 
-use core::sync::atomic::{AtomicI64, Ordering};
+use core::{
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+use std::collections::hash_map::RandomState;
+
+use time::OffsetDateTime;
 
 pub use backend::Backend;
 pub use cache::Cache;
+pub use reader::CacheReader;
 
+/// A process-local index of the names of roles, channels and emojis, used to
+/// respond to autocomplete interactions without querying the backend
+#[cfg(feature = "autocomplete")]
+pub mod autocomplete;
 /// The trait to define how to get and set data in the backend
 ///
 /// This is for adding support for a backend
 pub mod backend;
+/// A wrapper that buffers frequent writes and writes them in batches
+pub mod batching;
+/// A wrapper with synchronous versions of the methods to get data from the
+/// cache
+#[cfg(feature = "blocking")]
+pub mod blocking;
 /// The trait providing methods to use the cache
 ///
 /// This is for the users of the cache
 pub mod cache;
+/// Helpers to find the entities events change for the changelog
+#[cfg(feature = "changelog")]
+mod changelog;
+/// Encoding the cached models into bytes, used by backends that store
+/// documents or key-value pairs
+#[cfg(feature = "serde")]
+pub mod codec;
+/// Configuration of how the cache is updated
+pub mod config;
+/// The numbers of cached entities, used to monitor the size of the cache
+pub mod counts;
+/// The report of the differences between the cache and the HTTP API
+#[cfg(feature = "http")]
+pub mod drift;
+/// Helpers to get information from events
+mod event;
+/// The trait to run callbacks when the cache is updated
+pub mod hooks;
+/// Functions to record metrics
+#[cfg(feature = "metrics")]
+mod instrumentation;
+/// The report of referential breaks in the cache
+pub mod integrity;
+/// Helpers to serialize events into journal entries and back
+#[cfg(feature = "journal")]
+mod journal;
+/// A backend that records calls instead of storing anything, used to unit
+/// test code that uses the cache
+#[cfg(feature = "mock")]
+pub mod mock;
 /// Definitions of cached structs, used when the cached data is different from
 /// the event data
 pub mod model;
+/// A process-local pool of shared guilds, channels and roles, used to avoid
+/// cloning them on every read
+pub mod pool;
+/// A bounded queue to drop low-priority events when the backend falls behind
+pub mod queue;
+/// The trait providing the methods to get data from the cache, implemented for
+/// every [`Cache`]
+///
+/// This is for the parts of an application that shouldn't update the cache
+pub mod reader;
+/// Helpers to store the cached models in Redis
+#[cfg(feature = "redis-util")]
+pub mod redis_util;
+/// Descriptions of the SQL tables of the cached models, used to generate the
+/// statements to create them
+pub mod schema;
+/// The built-in SQLite backend
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+/// Helpers to record the usage statistics of emojis and stickers
+#[cfg(feature = "stats")]
+mod stats;
+/// The layout of a guild made from the cached data
+pub mod structure;
 /// Functions related to testing the implementor of [`Cache`]
 #[cfg(feature = "tests")]
 pub mod tests;
+/// A cache made of two caches, writing to both and reading from the first one
+/// first
+pub mod tiered;
+/// The report of the backend methods that didn't work as expected
+pub mod validation;
+
+/// The first millisecond of 2015 as a Unix timestamp, used as the epoch of
+/// unique IDs, the same as Discord's
+const ID_EPOCH_MILLIS: i128 = 1_420_070_400_000;
+
+/// The number of bits of unique IDs that are the sequence of the ID in its
+/// millisecond
+const ID_SEQUENCE_BITS: u8 = 12;
+
+/// The number of bits of unique IDs that are random per process, so that the
+/// IDs created by processes sharing the backend in the same millisecond don't
+/// collide
+const ID_PROCESS_BITS: u8 = 10;
+
+/// The milliseconds and sequence of the last ID returned by [`unique_id`],
+/// without its process bits
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// The process bits of the IDs returned by [`unique_id`], [`u64::MAX`] until
+/// they're generated
+static PROCESS_ID_BITS: AtomicU64 = AtomicU64::new(u64::MAX);
+
+/// Returns the random bits of this process to put in unique IDs, generating
+/// them on the first call
+///
+/// They're generated by hashing the process ID with a randomly seeded hasher,
+/// so that they're random across hosts too
+#[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+fn process_id_bits() -> u64 {
+    let bits = PROCESS_ID_BITS.load(Ordering::Relaxed);
+    if bits != u64::MAX {
+        return bits;
+    }
 
-/// Used to create unique IDs when necessary
-static ID_COUNTER: AtomicI64 = AtomicI64::new(1);
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    let new_bits = hasher.finish() & ((1 << ID_PROCESS_BITS) - 1);
+    match PROCESS_ID_BITS.compare_exchange(u64::MAX, new_bits, Ordering::Relaxed, Ordering::Relaxed)
+    {
+        Ok(_) => new_bits,
+        Err(bits) => bits,
+    }
+}
 
-/// Returns a unique ID by adding 1 to [`ID_COUNTER`]
-fn unique_id() -> i64 {
-    ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+/// Returns a unique ID that stays unique across restarts and processes
+///
+/// Like Discord's snowflakes, the ID is the milliseconds since
+/// [`ID_EPOCH_MILLIS`] in the high bits, followed by [`ID_PROCESS_BITS`] bits
+/// that are random per process and [`ID_SEQUENCE_BITS`] bits of the ID's
+/// sequence in its millisecond, if more IDs are created in the same
+/// millisecond than the sequence fits, the sequence carries into the
+/// milliseconds
+///
+/// This means IDs don't collide with the ones created by previous runs as long
+/// as the system time is correct, IDs created by processes sharing the backend
+/// in the same millisecond only collide if their random bits are the same, and
+/// they always fit in an `i64`
+#[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+fn unique_id() -> u64 {
+    let millis = OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000 - ID_EPOCH_MILLIS;
+    let timestamp_id = u64::try_from(millis).unwrap_or_default() << ID_SEQUENCE_BITS;
+    let last_id = LAST_ID
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last_id| {
+            Some(timestamp_id.max(last_id + 1))
+        })
+        .unwrap_or_else(|last_id| last_id);
+    let sequence_id = timestamp_id.max(last_id + 1);
+    let sequence_mask = (1 << ID_SEQUENCE_BITS) - 1;
+
+    ((sequence_id >> ID_SEQUENCE_BITS) << (ID_PROCESS_BITS + ID_SEQUENCE_BITS))
+        | (process_id_bits() << ID_SEQUENCE_BITS)
+        | (sequence_id & sequence_mask)
+}
+
+/// The offset basis of the 64-bit FNV-1a hash
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// The prime of the 64-bit FNV-1a hash
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Returns the 64-bit FNV-1a hash of the given string as 16 hexadecimal
+/// digits
+///
+/// It's used instead of [`std::hash::Hash`] since it's stable across Rust
+/// versions, so hashes stored in the cache can be compared after upgrading
+fn fnv_hash(value: &str) -> String {
+    let hash = value.bytes().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    format!("{hash:016x}")
 }
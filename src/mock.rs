@@ -0,0 +1,1781 @@
+use core::any::Any;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use async_trait::async_trait;
+pub use error::MockError;
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwriteType, StageInstance},
+    guild::GuildFeature,
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GenericMarker, GuildMarker, InteractionMarker,
+            MessageMarker, RoleMarker, StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::{
+    cache::{EntityKind, Error},
+    config::{CacheConfig, MessageKindFilter},
+    counts::EntityCount,
+    model::{
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker, GuildPatch, MemberPatch,
+    },
+    Backend, Cache,
+};
+
+/// Put into a mod to allow lints
+#[allow(clippy::std_instead_of_core)]
+mod error {
+    use thiserror::Error;
+
+    /// The error returned by a [`super::MockBackend`] method that's scripted
+    /// to fail
+    #[derive(Error, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[error("The mock backend's {method} method was scripted to fail")]
+    pub struct MockError {
+        /// The name of the method that failed
+        pub method: &'static str,
+    }
+}
+
+/// A call to a method of a [`MockBackend`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MockCall {
+    /// The name of the method, such as `upsert_message`
+    pub method: &'static str,
+    /// The arguments the method was called with, formatted with [`Debug`],
+    /// as a tuple if there are multiple
+    pub args: String,
+}
+
+/// How many more times a method of a [`MockBackend`] fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Failure {
+    /// The method fails until [`MockBackend::succeed`] is called
+    Always,
+    /// The method fails this many more times
+    Times(usize),
+}
+
+/// A backend that keeps only the models needed to handle ordinary guild
+/// events in memory, used to unit test code that uses the cache without a
+/// database
+///
+/// It records every call to its methods, which can be scripted to fail, and
+/// captures the models passed to them, so you can update it with events and
+/// assert what would be cached
+///
+/// It keeps the channels, permission overwrites, messages, members, role
+/// assignments, presences, guilds and roles passed to it until they're
+/// deleted, so that their getters, such as [`Cache::channel`],
+/// [`Cache::guild_channels`], [`Cache::member_roles`] and [`Cache::presence`],
+/// return them, the permission methods such as [`Cache::is_admin`] work, and
+/// updates that depend on them, such as adding roles to a member, succeed,
+/// [`Cache::current_user`] and [`Cache::current_application`] return the last
+/// ones set, and [`Backend::seen_event`] and
+/// [`Backend::advance_update_sequence`] remember the keys they're called
+/// with, [`Backend::select_orphaned_roles`] returns the guilds of the kept
+/// roles that aren't kept, the other getters return `None` or nothing
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::{mock::MockBackend, model::CachedMessage, Cache};
+///
+/// let cache = MockBackend::new();
+/// handle_event(&cache, &event).await?;
+///
+/// assert_eq!(cache.upserts::<CachedMessage>().len(), 1);
+/// assert_eq!(cache.calls_to("delete_message").len(), 0);
+///
+/// cache.fail("upsert_message");
+/// assert!(handle_event(&cache, &event).await.is_err());
+/// ```
+pub struct MockBackend {
+    /// The configuration returned by [`Cache::config`]
+    config: CacheConfig,
+    /// The calls made to the backend, in order
+    calls: Mutex<Vec<MockCall>>,
+    /// The methods scripted to fail
+    failures: Mutex<HashMap<&'static str, Failure>>,
+    /// The models passed to the backend, in order
+    upserts: Mutex<Vec<Box<dyn Any + Send + Sync>>>,
+    /// The models kept to be returned by the getters
+    store: Mutex<MockStore>,
+}
+
+/// The models a [`MockBackend`] keeps until they're deleted
+#[derive(Debug, Default)]
+struct MockStore {
+    /// The channels by their IDs
+    channels: HashMap<Id<ChannelMarker>, CachedChannel>,
+    /// The messages by their IDs
+    messages: HashMap<Id<MessageMarker>, CachedMessage>,
+    /// The members by their guild and user IDs
+    members: HashMap<(Id<GuildMarker>, Id<UserMarker>), CachedMember>,
+    /// The guilds by their IDs
+    guilds: HashMap<Id<GuildMarker>, CachedGuild>,
+    /// The roles by their IDs
+    roles: HashMap<Id<RoleMarker>, CachedRole>,
+    /// The roles assigned to members
+    role_assignments: HashSet<CachedRoleAssignment>,
+    /// The permission overwrites by their channel and target IDs
+    permission_overwrites:
+        HashMap<(Id<ChannelMarker>, Id<GenericMarker>), CachedPermissionOverwrite>,
+    /// The presences by their guild and user IDs
+    presences: HashMap<(Id<GuildMarker>, Id<UserMarker>), CachedPresence>,
+    /// The keys of the seen events and when they were seen, in microseconds
+    seen_events: HashMap<String, i64>,
+    /// The last sequence of each update sequence key and the shard it's from
+    update_sequences: HashMap<String, (u64, u64)>,
+}
+
+impl Debug for MockBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockBackend")
+            .field("config", &self.config)
+            .field("calls", &lock(&self.calls))
+            .field("failures", &lock(&self.failures))
+            .field("upserts", &lock(&self.upserts).len())
+            .field("store", &lock(&self.store))
+            .finish()
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockBackend {
+    /// Create a mock backend with the default configuration
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            config: CacheConfig::new(),
+            calls: Mutex::new(Vec::new()),
+            failures: Mutex::new(HashMap::new()),
+            upserts: Mutex::new(Vec::new()),
+            store: Mutex::new(MockStore::default()),
+        }
+    }
+
+    /// Set the configuration returned by [`Cache::config`]
+    #[must_use]
+    pub fn with_config(mut self, config: CacheConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Make the method with the given name fail until [`MockBackend::succeed`]
+    /// is called
+    pub fn fail(&self, method: &'static str) {
+        lock(&self.failures).insert(method, Failure::Always);
+    }
+
+    /// Make the method with the given name fail the given number of times
+    pub fn fail_times(&self, method: &'static str, times: usize) {
+        lock(&self.failures).insert(method, Failure::Times(times));
+    }
+
+    /// Stop the method with the given name from failing
+    pub fn succeed(&self, method: &str) {
+        lock(&self.failures).remove(method);
+    }
+
+    /// Return the calls made to the backend, in order
+    #[must_use]
+    pub fn calls(&self) -> Vec<MockCall> {
+        lock(&self.calls).clone()
+    }
+
+    /// Return the calls made to the method with the given name, in order
+    #[must_use]
+    pub fn calls_to(&self, method: &str) -> Vec<MockCall> {
+        lock(&self.calls)
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Return the models of the given type passed to the backend, in order
+    ///
+    /// The models are captured from the methods that add or update them, such
+    /// as [`Backend::upsert_message`] for [`CachedMessage`] and
+    /// [`Backend::patch_member`] for [`MemberPatch`]
+    #[must_use]
+    pub fn upserts<T: Clone + 'static>(&self) -> Vec<T> {
+        lock(&self.upserts)
+            .iter()
+            .filter_map(|upsert| upsert.downcast_ref::<T>())
+            .cloned()
+            .collect()
+    }
+
+    /// Remove the recorded calls and captured models, keeping the scripted
+    /// failures and the models returned by the getters
+    pub fn clear(&self) {
+        lock(&self.calls).clear();
+        lock(&self.upserts).clear();
+    }
+
+    /// Record a call to the method, returning an error if it's scripted to
+    /// fail
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    fn record(&self, method: &'static str, args: String) -> Result<(), MockError> {
+        lock(&self.calls).push(MockCall { method, args });
+
+        let mut failures = lock(&self.failures);
+        match failures.get(method).copied() {
+            Some(Failure::Always) => Err(MockError { method }),
+            Some(Failure::Times(times)) if times > 0 => {
+                failures.insert(method, Failure::Times(times - 1));
+                Err(MockError { method })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Capture a model passed to the backend
+    fn capture<T: Send + Sync + 'static>(&self, model: T) {
+        lock(&self.upserts).push(Box::new(model));
+    }
+}
+
+/// Lock the mutex, ignoring poisoning since the mock's state is never left
+/// invalid
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[async_trait]
+impl Backend for MockBackend {
+    type Error = MockError;
+
+    async fn set_current_user(&self, current_user: CurrentUser) -> Result<(), Self::Error> {
+        self.record("set_current_user", format!("{current_user:?}"))?;
+        self.capture(current_user);
+
+        Ok(())
+    }
+
+    async fn set_current_application(
+        &self,
+        application: PartialApplication,
+    ) -> Result<(), Self::Error> {
+        self.record("set_current_application", format!("{application:?}"))?;
+        self.capture(application);
+
+        Ok(())
+    }
+
+    async fn upsert_session(&self, session: CachedSession) -> Result<(), Self::Error> {
+        self.record("upsert_session", format!("{session:?}"))?;
+        self.capture(session);
+
+        Ok(())
+    }
+
+    async fn set_shard_state(&self, state: CachedShardState) -> Result<(), Self::Error> {
+        self.record("set_shard_state", format!("{state:?}"))?;
+        self.capture(state);
+
+        Ok(())
+    }
+
+    async fn upsert_interaction(&self, interaction: CachedInteraction) -> Result<(), Self::Error> {
+        self.record("upsert_interaction", format!("{interaction:?}"))?;
+        self.capture(interaction);
+
+        Ok(())
+    }
+
+    async fn delete_expired_interactions(&self, now: Timestamp) -> Result<(), Self::Error> {
+        self.record("delete_expired_interactions", format!("{now:?}"))?;
+
+        Ok(())
+    }
+
+    async fn seen_event(&self, key: String, now: Timestamp) -> Result<bool, Self::Error> {
+        self.record("seen_event", format!("{:?}", (&key, now)))?;
+
+        Ok(lock(&self.store)
+            .seen_events
+            .insert(key, now.as_micros())
+            .is_some())
+    }
+
+    async fn forget_event(&self, key: String) -> Result<(), Self::Error> {
+        self.record("forget_event", format!("{key:?}"))?;
+        lock(&self.store).seen_events.remove(&key);
+
+        Ok(())
+    }
+
+    async fn delete_seen_events_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        self.record("delete_seen_events_before", format!("{timestamp:?}"))?;
+        lock(&self.store)
+            .seen_events
+            .retain(|_, seen_at| *seen_at >= timestamp.as_micros());
+
+        Ok(())
+    }
+
+    async fn advance_update_sequence(
+        &self,
+        key: String,
+        shard_id: u64,
+        sequence: u64,
+    ) -> Result<bool, Self::Error> {
+        self.record(
+            "advance_update_sequence",
+            format!("{:?}", (&key, shard_id, sequence)),
+        )?;
+
+        let mut store = lock(&self.store);
+        if store
+            .update_sequences
+            .get(&key)
+            .map_or(false, |(_, last_sequence)| *last_sequence >= sequence)
+        {
+            return Ok(false);
+        }
+        store.update_sequences.insert(key, (shard_id, sequence));
+
+        Ok(true)
+    }
+
+    async fn delete_shard_update_sequences(&self, shard_id: u64) -> Result<(), Self::Error> {
+        self.record("delete_shard_update_sequences", format!("{shard_id:?}"))?;
+        lock(&self.store)
+            .update_sequences
+            .retain(|_, (sequence_shard_id, _)| *sequence_shard_id != shard_id);
+
+        Ok(())
+    }
+
+    async fn set_extension(
+        &self,
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error> {
+        self.record("set_extension", format!("{:?}", (namespace, key, value)))?;
+
+        Ok(())
+    }
+
+    async fn get_extension(
+        &self,
+        namespace: String,
+        key: String,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.record("get_extension", format!("{:?}", (namespace, key)))?;
+
+        Ok(None)
+    }
+
+    async fn delete_extension(&self, namespace: String, key: String) -> Result<(), Self::Error> {
+        self.record("delete_extension", format!("{:?}", (namespace, key)))?;
+
+        Ok(())
+    }
+
+    async fn set_channel_last_activity(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "set_channel_last_activity",
+            format!("{:?}", (channel_id, timestamp)),
+        )?;
+
+        Ok(())
+    }
+
+    async fn upsert_channel(&self, channel: CachedChannel) -> Result<(), Self::Error> {
+        self.record("upsert_channel", format!("{channel:?}"))?;
+        lock(&self.store)
+            .channels
+            .insert(channel.id, channel.clone());
+        self.capture(channel);
+
+        Ok(())
+    }
+
+    async fn delete_channel(&self, channel_id: Id<ChannelMarker>) -> Result<(), Self::Error> {
+        self.record("delete_channel", format!("{channel_id:?}"))?;
+        lock(&self.store).channels.remove(&channel_id);
+
+        Ok(())
+    }
+
+    async fn delete_guild_channels(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_channels", format!("{guild_id:?}"))?;
+        lock(&self.store)
+            .channels
+            .retain(|_, channel| channel.guild_id != Some(guild_id));
+
+        Ok(())
+    }
+
+    async fn upsert_permission_overwrite(
+        &self,
+        permission_overwrite: CachedPermissionOverwrite,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "upsert_permission_overwrite",
+            format!("{permission_overwrite:?}"),
+        )?;
+        lock(&self.store).permission_overwrites.insert(
+            (permission_overwrite.channel_id, permission_overwrite.id),
+            permission_overwrite,
+        );
+        self.capture(permission_overwrite);
+
+        Ok(())
+    }
+
+    async fn delete_channel_permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_channel_permission_overwrites",
+            format!("{channel_id:?}"),
+        )?;
+        lock(&self.store)
+            .permission_overwrites
+            .retain(|(overwrite_channel_id, _), _| *overwrite_channel_id != channel_id);
+
+        Ok(())
+    }
+
+    async fn select_overwrites_by_target(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        target_id: Id<GenericMarker>,
+        kind: PermissionOverwriteType,
+    ) -> Result<Option<CachedPermissionOverwrite>, Self::Error> {
+        self.record(
+            "select_overwrites_by_target",
+            format!("{:?}", (channel_id, target_id, kind)),
+        )?;
+
+        Ok(lock(&self.store)
+            .permission_overwrites
+            .get(&(channel_id, target_id))
+            .filter(|overwrite| overwrite.kind == kind)
+            .copied())
+    }
+
+    async fn upsert_private_channel(
+        &self,
+        private_channel: CachedPrivateChannel,
+    ) -> Result<(), Self::Error> {
+        self.record("upsert_private_channel", format!("{private_channel:?}"))?;
+        self.capture(private_channel);
+
+        Ok(())
+    }
+
+    async fn delete_private_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_private_channel", format!("{channel_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_message(&self, message: CachedMessage) -> Result<(), Self::Error> {
+        self.record("upsert_message", format!("{message:?}"))?;
+        lock(&self.store)
+            .messages
+            .insert(message.id, message.clone());
+        self.capture(message);
+
+        Ok(())
+    }
+
+    async fn upsert_messages(&self, messages: Vec<CachedMessage>) -> Result<(), Self::Error> {
+        self.record("upsert_messages", format!("{messages:?}"))?;
+        for message in messages {
+            lock(&self.store)
+                .messages
+                .insert(message.id, message.clone());
+            self.capture(message);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_message(&self, message_id: Id<MessageMarker>) -> Result<(), Self::Error> {
+        self.record("delete_message", format!("{message_id:?}"))?;
+        lock(&self.store).messages.remove(&message_id);
+
+        Ok(())
+    }
+
+    async fn delete_messages_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        self.record("delete_messages_before", format!("{timestamp:?}"))?;
+        lock(&self.store)
+            .messages
+            .retain(|_, message| message.timestamp.as_micros() >= timestamp.as_micros());
+
+        Ok(())
+    }
+
+    async fn delete_guild_messages_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_guild_messages_before",
+            format!("{:?}", (guild_id, timestamp)),
+        )?;
+        lock(&self.store).messages.retain(|_, message| {
+            message.guild_id != Some(guild_id)
+                || message.timestamp.as_micros() >= timestamp.as_micros()
+        });
+
+        Ok(())
+    }
+
+    async fn select_guild_message_authors(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        self.record(
+            "select_guild_message_authors",
+            format!("{:?}", (guild_id, since)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_user_last_message(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedMessage>, Self::Error> {
+        self.record(
+            "select_user_last_message",
+            format!("{:?}", (channel_id, user_id)),
+        )?;
+
+        Ok(None)
+    }
+
+    async fn select_channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        self.record(
+            "select_channel_messages_range",
+            format!("{:?}", (channel_id, after, before, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_filtered_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        self.record(
+            "select_filtered_channel_messages",
+            format!("{:?}", (channel_id, filter, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn delete_orphaned_message_data(&self) -> Result<(), Self::Error> {
+        self.record("delete_orphaned_message_data", String::new())?;
+
+        Ok(())
+    }
+
+    async fn insert_message_revision(
+        &self,
+        revision: CachedMessageRevision,
+    ) -> Result<(), Self::Error> {
+        self.record("insert_message_revision", format!("{revision:?}"))?;
+        self.capture(revision);
+
+        Ok(())
+    }
+
+    async fn delete_message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_message_revisions", format!("{message_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_embed(&self, embed: CachedEmbed) -> Result<(), Self::Error> {
+        self.record("upsert_embed", format!("{embed:?}"))?;
+        self.capture(embed);
+
+        Ok(())
+    }
+
+    async fn delete_embed(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        self.record("delete_embed", format!("{embed_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_embed_field(&self, embed_field: CachedEmbedField) -> Result<(), Self::Error> {
+        self.record("upsert_embed_field", format!("{embed_field:?}"))?;
+        self.capture(embed_field);
+
+        Ok(())
+    }
+
+    async fn delete_embed_fields(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        self.record("delete_embed_fields", format!("{embed_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn select_message_embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedEmbed>, Self::Error> {
+        self.record("select_message_embeds", format!("{message_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_embed_fields(
+        &self,
+        embed_id: Id<GenericMarker>,
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        self.record("select_embed_fields", format!("{embed_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_orphaned_embed_fields(&self) -> Result<Vec<Id<GenericMarker>>, Self::Error> {
+        self.record("select_orphaned_embed_fields", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_orphaned_embeds(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.record("select_orphaned_embeds", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_orphaned_attachments(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.record("select_orphaned_attachments", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_orphaned_reactions(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        self.record("select_orphaned_reactions", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn upsert_attachment(&self, attachment: CachedAttachment) -> Result<(), Self::Error> {
+        self.record("upsert_attachment", format!("{attachment:?}"))?;
+        self.capture(attachment);
+
+        Ok(())
+    }
+
+    async fn upsert_attachment_blob(&self, blob: CachedAttachmentBlob) -> Result<(), Self::Error> {
+        self.record("upsert_attachment_blob", format!("{blob:?}"))?;
+        self.capture(blob);
+
+        Ok(())
+    }
+
+    async fn upsert_attachment_post(&self, post: CachedAttachmentPost) -> Result<(), Self::Error> {
+        self.record("upsert_attachment_post", format!("{post:?}"))?;
+        self.capture(post);
+
+        Ok(())
+    }
+
+    async fn delete_message_attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_message_attachments", format!("{message_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_reaction(&self, reaction: CachedReaction) -> Result<(), Self::Error> {
+        self.record("upsert_reaction", format!("{reaction:?}"))?;
+        self.capture(reaction);
+
+        Ok(())
+    }
+
+    async fn upsert_reactions(&self, reactions: Vec<CachedReaction>) -> Result<(), Self::Error> {
+        self.record("upsert_reactions", format!("{reactions:?}"))?;
+        for reaction in reactions {
+            self.capture(reaction);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_reaction(
+        &self,
+        message_id: Id<MessageMarker>,
+        user_id: Id<UserMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_reaction",
+            format!("{:?}", (message_id, user_id, emoji)),
+        )?;
+
+        Ok(())
+    }
+
+    async fn delete_message_reactions_by_emoji(
+        &self,
+        message_id: Id<MessageMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_message_reactions_by_emoji",
+            format!("{:?}", (message_id, emoji)),
+        )?;
+
+        Ok(())
+    }
+
+    async fn delete_message_reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_message_reactions", format!("{message_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_member(&self, member: CachedMember) -> Result<(), Self::Error> {
+        self.record("upsert_member", format!("{member:?}"))?;
+        lock(&self.store)
+            .members
+            .insert((member.guild_id, member.id), member.clone());
+        self.capture(member);
+
+        Ok(())
+    }
+
+    async fn upsert_members(&self, members: Vec<CachedMember>) -> Result<(), Self::Error> {
+        self.record("upsert_members", format!("{members:?}"))?;
+        for member in members {
+            lock(&self.store)
+                .members
+                .insert((member.guild_id, member.id), member.clone());
+            self.capture(member);
+        }
+
+        Ok(())
+    }
+
+    async fn patch_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        patch: MemberPatch,
+    ) -> Result<(), Self::Error> {
+        self.record("patch_member", format!("{:?}", (guild_id, user_id, &patch)))?;
+        if let Some(member) = lock(&self.store).members.get_mut(&(guild_id, user_id)) {
+            member.apply(&patch);
+        }
+        self.capture(patch);
+
+        Ok(())
+    }
+
+    async fn delete_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_member", format!("{:?}", (user_id, guild_id)))?;
+        lock(&self.store).members.remove(&(guild_id, user_id));
+
+        Ok(())
+    }
+
+    async fn delete_guild_members(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_members", format!("{guild_id:?}"))?;
+        lock(&self.store)
+            .members
+            .retain(|(member_guild_id, _), _| *member_guild_id != guild_id);
+
+        Ok(())
+    }
+
+    async fn select_mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        self.record("select_mutual_guilds", format!("{:?}", (user_a, user_b)))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn select_members_by_prefix(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Self::Error> {
+        self.record(
+            "select_members_by_prefix",
+            format!("{:?}", (guild_id, prefix, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn upsert_presence(&self, presence: CachedPresence) -> Result<(), Self::Error> {
+        self.record("upsert_presence", format!("{presence:?}"))?;
+        lock(&self.store)
+            .presences
+            .insert((presence.guild_id, presence.user), presence);
+        self.capture(presence);
+
+        Ok(())
+    }
+
+    async fn upsert_presences(&self, presences: Vec<CachedPresence>) -> Result<(), Self::Error> {
+        self.record("upsert_presences", format!("{presences:?}"))?;
+        for presence in presences {
+            lock(&self.store)
+                .presences
+                .insert((presence.guild_id, presence.user), presence);
+            self.capture(presence);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_presence(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_presence", format!("{:?}", (guild_id, user_id)))?;
+        lock(&self.store).presences.remove(&(guild_id, user_id));
+
+        Ok(())
+    }
+
+    async fn delete_guild_presences(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_presences", format!("{guild_id:?}"))?;
+        lock(&self.store)
+            .presences
+            .retain(|(presence_guild_id, _), _| *presence_guild_id != guild_id);
+
+        Ok(())
+    }
+
+    async fn delete_memberless_presences(&self) -> Result<(), Self::Error> {
+        self.record("delete_memberless_presences", String::new())?;
+        let mut store = lock(&self.store);
+        let MockStore {
+            members, presences, ..
+        } = &mut *store;
+        presences.retain(|key, _| members.contains_key(key));
+
+        Ok(())
+    }
+
+    async fn set_expiry(
+        &self,
+        kind: EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+        expires_at: Timestamp,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "set_expiry",
+            format!("{:?}", (kind, entity_id, guild_id, expires_at)),
+        )?;
+
+        Ok(())
+    }
+
+    async fn delete_expired(&self, now: Timestamp) -> Result<(), Self::Error> {
+        self.record("delete_expired", format!("{now:?}"))?;
+
+        Ok(())
+    }
+
+    async fn entity_counts(&self) -> Result<HashMap<EntityKind, EntityCount>, Self::Error> {
+        self.record("entity_counts", String::new())?;
+
+        Ok(HashMap::new())
+    }
+
+    async fn upsert_activity(&self, activity: CachedActivity) -> Result<(), Self::Error> {
+        self.record("upsert_activity", format!("{activity:?}"))?;
+        self.capture(activity);
+
+        Ok(())
+    }
+
+    async fn upsert_activities(&self, activities: Vec<CachedActivity>) -> Result<(), Self::Error> {
+        self.record("upsert_activities", format!("{activities:?}"))?;
+        for activity in activities {
+            self.capture(activity);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_user_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_user_activities",
+            format!("{:?}", (guild_id, user_id)),
+        )?;
+
+        Ok(())
+    }
+
+    async fn upsert_guild(&self, guild: CachedGuild) -> Result<(), Self::Error> {
+        self.record("upsert_guild", format!("{guild:?}"))?;
+        lock(&self.store).guilds.insert(guild.id, guild.clone());
+        self.capture(guild);
+
+        Ok(())
+    }
+
+    async fn patch_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+        patch: GuildPatch,
+    ) -> Result<(), Self::Error> {
+        self.record("patch_guild", format!("{:?}", (guild_id, &patch)))?;
+        if let Some(guild) = lock(&self.store).guilds.get_mut(&guild_id) {
+            guild.apply(&patch);
+        }
+        self.capture(patch);
+
+        Ok(())
+    }
+
+    async fn select_guilds_by_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Self::Error> {
+        self.record("select_guilds_by_feature", format!("{feature:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn delete_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild", format!("{guild_id:?}"))?;
+        lock(&self.store).guilds.remove(&guild_id);
+
+        Ok(())
+    }
+
+    async fn delete_guild_remnants(&self) -> Result<(), Self::Error> {
+        self.record("delete_guild_remnants", String::new())?;
+
+        Ok(())
+    }
+
+    async fn upsert_role(&self, role: CachedRole) -> Result<(), Self::Error> {
+        self.record("upsert_role", format!("{role:?}"))?;
+        lock(&self.store).roles.insert(role.id, role.clone());
+        self.capture(role);
+
+        Ok(())
+    }
+
+    async fn delete_role(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        self.record("delete_role", format!("{role_id:?}"))?;
+        lock(&self.store).roles.remove(&role_id);
+
+        Ok(())
+    }
+
+    async fn select_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Self::Error> {
+        self.record("select_everyone_role", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store).roles.get(&guild_id.cast()).cloned())
+    }
+
+    async fn select_orphaned_roles(&self) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        self.record("select_orphaned_roles", String::new())?;
+        let store = lock(&self.store);
+        let mut guild_ids: Vec<_> = store
+            .roles
+            .values()
+            .map(|role| role.guild_id)
+            .filter(|guild_id| !store.guilds.contains_key(guild_id))
+            .collect();
+        guild_ids.sort_unstable();
+        guild_ids.dedup();
+
+        Ok(guild_ids)
+    }
+
+    async fn delete_guild_roles(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_roles", format!("{guild_id:?}"))?;
+        lock(&self.store)
+            .roles
+            .retain(|_, role| role.guild_id != guild_id);
+
+        Ok(())
+    }
+
+    async fn upsert_role_assignment(
+        &self,
+        assignment: CachedRoleAssignment,
+    ) -> Result<(), Self::Error> {
+        self.record("upsert_role_assignment", format!("{assignment:?}"))?;
+        lock(&self.store).role_assignments.insert(assignment);
+        self.capture(assignment);
+
+        Ok(())
+    }
+
+    async fn upsert_role_assignments(
+        &self,
+        assignments: Vec<CachedRoleAssignment>,
+    ) -> Result<(), Self::Error> {
+        self.record("upsert_role_assignments", format!("{assignments:?}"))?;
+        for assignment in assignments {
+            lock(&self.store).role_assignments.insert(assignment);
+            self.capture(assignment);
+        }
+
+        Ok(())
+    }
+
+    async fn delete_role_assignments(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        self.record("delete_role_assignments", format!("{role_id:?}"))?;
+        lock(&self.store)
+            .role_assignments
+            .retain(|assignment| assignment.role_id != role_id);
+
+        Ok(())
+    }
+
+    async fn delete_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_guild_role_assignments", format!("{guild_id:?}"))?;
+        lock(&self.store)
+            .role_assignments
+            .retain(|assignment| assignment.guild_id != guild_id);
+
+        Ok(())
+    }
+
+    async fn delete_member_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "delete_member_role_assignments",
+            format!("{:?}", (guild_id, user_id)),
+        )?;
+        lock(&self.store)
+            .role_assignments
+            .retain(|assignment| assignment.guild_id != guild_id || assignment.user_id != user_id);
+
+        Ok(())
+    }
+
+    async fn select_role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        self.record("select_role_members", format!("{role_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .role_assignments
+            .iter()
+            .filter(|assignment| assignment.role_id == role_id)
+            .map(|assignment| assignment.user_id)
+            .collect())
+    }
+
+    async fn select_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRoleAssignment>, Self::Error> {
+        self.record("select_guild_role_assignments", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .role_assignments
+            .iter()
+            .filter(|assignment| assignment.guild_id == guild_id)
+            .copied()
+            .collect())
+    }
+
+    async fn upsert_emoji(&self, emoji: CachedEmoji) -> Result<(), Self::Error> {
+        self.record("upsert_emoji", format!("{emoji:?}"))?;
+        self.capture(emoji);
+
+        Ok(())
+    }
+
+    async fn delete_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<(), Self::Error> {
+        self.record("delete_emoji", format!("{emoji_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_emojis(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_emojis", format!("{guild_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_sticker(&self, sticker: CachedSticker) -> Result<(), Self::Error> {
+        self.record("upsert_sticker", format!("{sticker:?}"))?;
+        self.capture(sticker);
+
+        Ok(())
+    }
+
+    async fn delete_message_stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_message_stickers", format!("{message_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn delete_sticker(&self, sticker_id: Id<StickerMarker>) -> Result<(), Self::Error> {
+        self.record("delete_sticker", format!("{sticker_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_stickers(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        self.record("delete_guild_stickers", format!("{guild_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn upsert_stage_instance(&self, stage: StageInstance) -> Result<(), Self::Error> {
+        self.record("upsert_stage_instance", format!("{stage:?}"))?;
+        self.capture(stage);
+
+        Ok(())
+    }
+
+    async fn delete_stage_instance(&self, stage_id: Id<StageMarker>) -> Result<(), Self::Error> {
+        self.record("delete_stage_instance", format!("{stage_id:?}"))?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_stage_instances(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_guild_stage_instances", format!("{guild_id:?}"))?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_emoji_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        emoji: String,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "increment_emoji_use",
+            format!("{:?}", (guild_id, emoji, day)),
+        )?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_sticker_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sticker_id: Id<StickerMarker>,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "increment_sticker_use",
+            format!("{:?}", (guild_id, sticker_id, day)),
+        )?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(String, u64)>, Self::Error> {
+        self.record("select_emoji_usage", format!("{:?}", (guild_id, since_day)))?;
+
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Self::Error> {
+        self.record(
+            "select_sticker_usage",
+            format!("{:?}", (guild_id, since_day)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "journal")]
+    async fn append_journal(&self, entry: CachedJournalEntry) -> Result<(), Self::Error> {
+        self.record("append_journal", format!("{entry:?}"))?;
+        self.capture(entry);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "journal")]
+    async fn select_journal(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<CachedJournalEntry>, Self::Error> {
+        self.record("select_journal", format!("{:?}", (start, end)))?;
+
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn append_changelog(&self, entry: CachedChangelogEntry) -> Result<(), Self::Error> {
+        self.record("append_changelog", format!("{entry:?}"))?;
+        self.capture(entry);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn select_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Self::Error> {
+        self.record(
+            "select_changelog",
+            format!("{:?}", (kind, entity_id, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn upsert_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: serde_json::Value,
+    ) -> Result<(), Self::Error> {
+        self.record(
+            "upsert_message_embeds_json",
+            format!("{:?}", (message_id, embeds)),
+        )?;
+        self.capture(embeds);
+
+        Ok(())
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn select_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<serde_json::Value>, Self::Error> {
+        self.record("select_message_embeds_json", format!("{message_id:?}"))?;
+
+        Ok(None)
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn delete_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        self.record("delete_message_embeds_json", format!("{message_id:?}"))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Cache for MockBackend {
+    fn config(&self) -> &CacheConfig {
+        &self.config
+    }
+
+    async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>> {
+        self.record("current_user", String::new())?;
+
+        self.upserts::<CurrentUser>()
+            .pop()
+            .ok_or(Error::CurrentUserMissing)
+    }
+
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>> {
+        self.record("current_application", String::new())?;
+
+        self.upserts::<PartialApplication>()
+            .pop()
+            .ok_or(Error::CurrentApplicationMissing)
+    }
+
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>> {
+        self.record("session_info", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>> {
+        self.record("shard_state", format!("{shard_id:?}"))?;
+
+        Ok(None)
+    }
+
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>> {
+        self.record("shard_states", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>> {
+        self.record("interaction", format!("{interaction_id:?}"))?;
+
+        Ok(None)
+    }
+
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        self.record("channel", format!("{channel_id:?}"))?;
+
+        Ok(lock(&self.store).channels.get(&channel_id).cloned())
+    }
+
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>> {
+        self.record("permission_overwrites", format!("{channel_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .permission_overwrites
+            .values()
+            .filter(|overwrite| overwrite.channel_id == channel_id)
+            .copied()
+            .collect())
+    }
+
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        self.record("private_channel", format!("{user_id:?}"))?;
+
+        Ok(None)
+    }
+
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        self.record("private_channel_users", format!("{channel_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.record("guild_channels", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .channels
+            .values()
+            .filter(|channel| channel.guild_id == Some(guild_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.record("archived_threads", format!("{guild_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.record("inactive_channels", format!("{:?}", (guild_id, since)))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        self.record("active_threads", format!("{channel_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>> {
+        self.record("message", format!("{message_id:?}"))?;
+
+        Ok(lock(&self.store).messages.get(&message_id).cloned())
+    }
+
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>> {
+        self.record("message_revisions", format!("{message_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>> {
+        self.record("attachments", format!("{message_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record("attachment_posters", format!("{file_hash:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>> {
+        self.record("reactions", format!("{message_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        self.record("stickers", format!("{message_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record("channel_messages", format!("{:?}", (channel_id, limit)))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record(
+            "messages_with_sticker",
+            format!("{:?}", (sticker_id, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record(
+            "channel_webhook_messages",
+            format!("{:?}", (channel_id, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record("messages_by_webhook", format!("{:?}", (webhook_id, limit)))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        self.record(
+            "messages_with_emoji_reaction",
+            format!("{:?}", (emoji, guild_id, limit)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>> {
+        self.record("member", format!("{:?}", (user_id, guild_id)))?;
+
+        Ok(lock(&self.store).members.get(&(guild_id, user_id)).cloned())
+    }
+
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        self.record("member_roles", format!("{:?}", (user_id, guild_id)))?;
+        let store = lock(&self.store);
+
+        Ok(store
+            .role_assignments
+            .iter()
+            .filter(|assignment| assignment.guild_id == guild_id && assignment.user_id == user_id)
+            .filter_map(|assignment| store.roles.get(&assignment.role_id).cloned())
+            .collect())
+    }
+
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>> {
+        self.record("presence", format!("{user_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .presences
+            .values()
+            .find(|presence| presence.user == user_id)
+            .copied())
+    }
+
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        self.record("member_activities", format!("{user_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        self.record(
+            "guild_member_activities",
+            format!("{:?}", (guild_id, user_id)),
+        )?;
+
+        Ok(Vec::new())
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        self.record("guild_ids", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        self.record("unavailable_guilds", String::new())?;
+
+        Ok(Vec::new())
+    }
+
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        self.record("guild_members", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .members
+            .values()
+            .filter(|member| member.guild_id == guild_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>> {
+        self.record("guild", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store).guilds.get(&guild_id).cloned())
+    }
+
+    async fn role(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedRole>, Error<Self::Error>> {
+        self.record("role", format!("{role_id:?}"))?;
+
+        Ok(lock(&self.store).roles.get(&role_id).cloned())
+    }
+
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        self.record("guild_roles", format!("{guild_id:?}"))?;
+
+        Ok(lock(&self.store)
+            .roles
+            .values()
+            .filter(|role| role.guild_id == guild_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>> {
+        self.record("emoji", format!("{emoji_id:?}"))?;
+
+        Ok(None)
+    }
+
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>> {
+        self.record("guild_emojis", format!("{guild_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>> {
+        self.record("sticker", format!("{sticker_id:?}"))?;
+
+        Ok(None)
+    }
+
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        self.record("guild_stickers", format!("{guild_id:?}"))?;
+
+        Ok(Vec::new())
+    }
+
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>> {
+        self.record("stage_instance", format!("{stage_id:?}"))?;
+
+        Ok(None)
+    }
+}
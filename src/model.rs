@@ -5,31 +5,77 @@
     clippy::struct_excessive_bools
 )]
 
-pub use channel::{CachedChannel, CachedPermissionOverwrite};
+#[cfg(feature = "changelog")]
+pub use changelog::CachedChangelogEntry;
+pub use channel::{CachedChannel, CachedPermissionOverwrite, CachedPrivateChannel};
 pub use emoji::CachedEmoji;
-pub use guild::CachedGuild;
-pub use member::CachedMember;
-pub use message::{CachedAttachment, CachedEmbed, CachedEmbedField, CachedMessage};
+pub use guild::{CachedGuild, GuildPatch};
+pub use interaction::CachedInteraction;
+#[cfg(feature = "journal")]
+pub use journal::CachedJournalEntry;
+pub use member::{CachedMember, MemberPatch};
+pub use message::{
+    CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost, CachedEmbed, CachedEmbedField,
+    CachedMessage, CachedMessageRevision,
+};
 pub use presence::{CachedActivity, CachedPresence};
-pub use reaction::CachedReaction;
-pub use role::CachedRole;
+#[cfg(feature = "emoji-normalization")]
+pub use reaction::normalize_unicode_emoji;
+pub use reaction::{CachedReaction, CachedReactionEmoji, ReactionCount};
+pub use role::{CachedRole, CachedRoleAssignment, ChannelRolePermissions};
+pub use session::{CachedSession, CachedShardState};
+#[cfg(feature = "stats")]
+pub use stats::{CachedEmojiUse, CachedStickerUse};
 pub use sticker::CachedSticker;
 
+/// Definition and implementations for [`CachedChangelogEntry`]
+#[cfg(feature = "changelog")]
+mod changelog;
 /// Definition and implementations for [`CachedChannel`] and its fields
 mod channel;
 /// Definition and implementations for [`CachedEmoji`]
 mod emoji;
-/// Definition and implementations for [`CachedGuild`]
+/// Definition and implementations for [`CachedGuild`] and [`GuildPatch`]
 mod guild;
-/// Definition and implementations for [`CachedMember`]
+/// Definition and implementations for [`CachedInteraction`]
+mod interaction;
+/// Definition and implementations for [`CachedJournalEntry`]
+#[cfg(feature = "journal")]
+mod journal;
+/// Definition and implementations for [`CachedMember`] and [`MemberPatch`]
 mod member;
 /// Definition and implementations for [`CachedMessage`] and its fields
 mod message;
 /// Definition and implementations for [`CachedPresence`] and its fields
 mod presence;
-/// Definition and implementations for [`CachedReaction`]
+/// Definitions and implementations for [`CachedReaction`],
+/// [`CachedReactionEmoji`] and [`ReactionCount`]
 mod reaction;
-/// Definition and implementations for [`CachedRole`]
+/// Definitions and implementations for [`CachedRole`],
+/// [`CachedRoleAssignment`] and [`ChannelRolePermissions`]
 mod role;
+/// Definition and implementations for [`CachedSession`] and
+/// [`CachedShardState`]
+mod session;
+/// Definitions for [`CachedEmojiUse`] and [`CachedStickerUse`]
+#[cfg(feature = "stats")]
+mod stats;
 /// Definition and implementations for [`CachedSticker`]
 mod sticker;
+
+/// Return the new value if it's different from the cached one, used to create
+/// the patches of the cached models
+fn changed<T: Clone + PartialEq>(cached: &T, new: &T) -> Option<T> {
+    (cached != new).then(|| new.clone())
+}
+
+/// Deserialize a present field of a patch as `Some`, even if it's `null`, so
+/// that a removed optional field isn't deserialized as unchanged
+#[cfg(feature = "serde")]
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: serde::Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
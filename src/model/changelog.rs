@@ -0,0 +1,52 @@
+use twilight_model::{
+    id::{marker::GenericMarker, Id},
+    util::Timestamp,
+};
+
+use crate::{cache::EntityKind, unique_id};
+
+/// A change to a cached channel or role, recorded in the changelog
+///
+/// - `id` is unique and increases with every entry, so entries are ordered by
+///   it
+///
+/// - `recorded_at` is when the cache was updated with the change, with seconds
+///   precision
+///
+/// - `kind` and `entity_id` are the kind and the ID of the changed entity
+///
+/// - `before` and `after` are the JSON of the cached entity before and after
+///   the change, `before` is `None` if the entity wasn't cached and `after` is
+///   `None` if it was removed
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedChangelogEntry {
+    pub id: Id<GenericMarker>,
+    pub recorded_at: Timestamp,
+    pub kind: EntityKind,
+    pub entity_id: Id<GenericMarker>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl CachedChangelogEntry {
+    /// Create a changelog entry from the snapshots of an entity before and
+    /// after a change, recorded at the given time
+    #[must_use]
+    pub fn new(
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        before: Option<String>,
+        after: Option<String>,
+        recorded_at: Timestamp,
+    ) -> Self {
+        Self {
+            id: Id::new(unique_id()),
+            recorded_at,
+            kind,
+            entity_id,
+            before,
+            after,
+        }
+    }
+}
@@ -20,8 +20,8 @@ use twilight_model::{
 ///
 /// - `channel_id` field is added, making it possible to return a channel's
 ///   permission overwrites
-#[derive(Clone, Copy, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedPermissionOverwrite {
     pub channel_id: Id<ChannelMarker>,
     pub allow: Permissions,
@@ -48,12 +48,23 @@ impl CachedPermissionOverwrite {
     }
 }
 
+/// A cached recipient of a DM channel
+///
+/// There's one for each recipient of the channel, the channel itself is
+/// cached as a [`CachedChannel`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedPrivateChannel {
+    pub channel_id: Id<ChannelMarker>,
+    pub user_id: Id<UserMarker>,
+}
+
 /// A cached channel
 ///
 /// It's the same as [`twilight_model::channel::Channel`] except:
 ///
-/// - `recipients` field is removed, as it's only sent in DM channels, which are
-///   cached separately
+/// - `recipients` field is removed, as it's only sent in DM channels, their
+///   recipients are cached separately as [`CachedPrivateChannel`]
 ///
 /// - `permission_overwrites` field is removed, as they're cached separately
 ///
@@ -65,8 +76,12 @@ impl CachedPermissionOverwrite {
 ///   some HTTP endpoints
 ///
 /// - `thread_metadata` field is flattened, making this struct easier to cache
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+///
+/// - `partial` field is added, it's `true` if the channel was created from an
+///   incomplete payload, in which case the fields that weren't in it have
+///   their default values until the channel is updated with a full channel
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedChannel {
     pub application_id: Option<Id<ApplicationMarker>>,
     pub bitrate: Option<u32>,
@@ -92,6 +107,16 @@ pub struct CachedChannel {
     pub topic: Option<String>,
     pub user_limit: Option<u32>,
     pub video_quality_mode: Option<VideoQualityMode>,
+    pub partial: bool,
+}
+
+impl CachedChannel {
+    /// Return the string that mentions the channel in a message, such as
+    /// `<#123>`
+    #[must_use]
+    pub fn mention(&self) -> String {
+        format!("<#{}>", self.id)
+    }
 }
 
 impl From<&Channel> for CachedChannel {
@@ -136,6 +161,7 @@ impl From<&Channel> for CachedChannel {
             user_limit: channel.user_limit,
             video_quality_mode: channel.video_quality_mode,
             thread_locked: None,
+            partial: false,
         }
     }
 }
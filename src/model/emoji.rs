@@ -17,8 +17,8 @@ use twilight_model::{
 ///
 /// - `roles` field is removed, as caching it is likely unnecessary, if you need
 ///   this field, please create an issue
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedEmoji {
     pub guild_id: Id<GuildMarker>,
     pub animated: bool,
@@ -45,4 +45,25 @@ impl CachedEmoji {
             user: emoji.user.as_ref().map(|user| user.id),
         }
     }
+
+    /// Return the string the emoji is given as when reacting to a message,
+    /// such as `name:123`
+    ///
+    /// To use the emoji in a message, use [`Self::mention`] instead
+    #[must_use]
+    pub fn to_reaction_string(&self) -> String {
+        format!("{}:{}", self.name, self.id)
+    }
+
+    /// Return the string that shows the emoji in a message, such as
+    /// `<:name:123>` or `<a:name:123>` if it's animated
+    #[must_use]
+    pub fn mention(&self) -> String {
+        format!(
+            "<{}:{}:{}>",
+            if self.animated { "a" } else { "" },
+            self.name,
+            self.id
+        )
+    }
 }
@@ -10,6 +10,8 @@ use twilight_model::{
     util::{ImageHash, Timestamp},
 };
 
+use super::changed;
+
 /// A cached guild
 ///
 /// It's the same as [`twilight_model::guild::Guild`] except:
@@ -26,8 +28,18 @@ use twilight_model::{
 ///
 /// - `voice_states` field is removed, as voice-related caching is not handled
 ///   by this library
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+///
+/// - `max_stage_video_channel_users`, `public_updates_channel_id` and
+///   `safety_alerts_channel_id` fields are added, `twilight_model` doesn't
+///   deserialize them yet, so they're always `None` when created from a
+///   [`Guild`], and they're kept as they are in [`Self::update`]
+///
+/// - `members_partial` field is added, it's `true` if the guild's members
+///   weren't cached from its guild create event because of
+///   [`crate::config::CacheConfig::lazy_members`], in which case only the
+///   members that were requested or seen since are cached
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedGuild {
     pub afk_channel_id: Option<Id<ChannelMarker>>,
     pub afk_timeout: u64,
@@ -44,6 +56,7 @@ pub struct CachedGuild {
     pub large: bool,
     pub max_members: Option<u64>,
     pub max_presences: Option<u64>,
+    pub max_stage_video_channel_users: Option<u64>,
     pub max_video_channel_users: Option<u64>,
     pub mfa_level: MfaLevel,
     pub name: String,
@@ -55,7 +68,9 @@ pub struct CachedGuild {
     pub premium_progress_bar_enabled: bool,
     pub premium_subscription_count: Option<u64>,
     pub premium_tier: PremiumTier,
+    pub public_updates_channel_id: Option<Id<ChannelMarker>>,
     pub rules_channel_id: Option<Id<ChannelMarker>>,
+    pub safety_alerts_channel_id: Option<Id<ChannelMarker>>,
     pub splash: Option<ImageHash>,
     pub system_channel_flags: SystemChannelFlags,
     pub system_channel_id: Option<Id<ChannelMarker>>,
@@ -64,42 +79,109 @@ pub struct CachedGuild {
     pub verification_level: VerificationLevel,
     pub widget_channel_id: Option<Id<ChannelMarker>>,
     pub widget_enabled: Option<bool>,
+    pub members_partial: bool,
 }
 
 impl CachedGuild {
     /// Update the cached guild with the partial guild
     pub fn update(&mut self, guild: &PartialGuild) {
         self.id = guild.id;
-        self.afk_channel_id = guild.afk_channel_id;
-        self.afk_timeout = guild.afk_timeout;
-        self.application_id = guild.application_id;
-        self.banner = guild.banner;
-        self.default_message_notifications = guild.default_message_notifications;
-        self.description.clone_from(&guild.description);
-        self.discovery_splash = guild.discovery_splash;
-        self.explicit_content_filter = guild.explicit_content_filter;
-        self.features.clone_from(&guild.features);
-        self.icon.clone_from(&guild.icon);
-        self.max_members = guild.max_members;
-        self.max_presences = guild.max_presences;
-        self.mfa_level = guild.mfa_level;
-        self.name.clone_from(&guild.name);
-        self.nsfw_level = guild.nsfw_level;
-        self.owner_id = guild.owner_id;
-        self.owner = guild.owner;
-        self.permissions = guild.permissions;
-        self.preferred_locale.clone_from(&guild.preferred_locale);
-        self.premium_progress_bar_enabled = guild.premium_progress_bar_enabled;
-        self.premium_subscription_count = guild.premium_subscription_count;
-        self.premium_tier = guild.premium_tier;
-        self.rules_channel_id = guild.rules_channel_id;
-        self.splash = guild.splash;
-        self.system_channel_flags = guild.system_channel_flags;
-        self.system_channel_id = guild.system_channel_id;
-        self.verification_level = guild.verification_level;
-        self.vanity_url_code.clone_from(&guild.vanity_url_code);
-        self.widget_channel_id = guild.widget_channel_id;
-        self.widget_enabled = guild.widget_enabled;
+        let patch = GuildPatch::new(self, guild);
+        self.apply(&patch);
+    }
+
+    /// Update the cached guild with the fields in the patch that are `Some`
+    pub fn apply(&mut self, patch: &GuildPatch) {
+        if let Some(afk_channel_id) = patch.afk_channel_id {
+            self.afk_channel_id = afk_channel_id;
+        }
+        if let Some(afk_timeout) = patch.afk_timeout {
+            self.afk_timeout = afk_timeout;
+        }
+        if let Some(application_id) = patch.application_id {
+            self.application_id = application_id;
+        }
+        if let Some(banner) = patch.banner {
+            self.banner = banner;
+        }
+        if let Some(default_message_notifications) = patch.default_message_notifications {
+            self.default_message_notifications = default_message_notifications;
+        }
+        if let Some(description) = &patch.description {
+            self.description.clone_from(description);
+        }
+        if let Some(discovery_splash) = patch.discovery_splash {
+            self.discovery_splash = discovery_splash;
+        }
+        if let Some(explicit_content_filter) = patch.explicit_content_filter {
+            self.explicit_content_filter = explicit_content_filter;
+        }
+        if let Some(features) = &patch.features {
+            self.features.clone_from(features);
+        }
+        if let Some(icon) = patch.icon {
+            self.icon = icon;
+        }
+        if let Some(max_members) = patch.max_members {
+            self.max_members = max_members;
+        }
+        if let Some(max_presences) = patch.max_presences {
+            self.max_presences = max_presences;
+        }
+        if let Some(mfa_level) = patch.mfa_level {
+            self.mfa_level = mfa_level;
+        }
+        if let Some(name) = &patch.name {
+            self.name.clone_from(name);
+        }
+        if let Some(nsfw_level) = patch.nsfw_level {
+            self.nsfw_level = nsfw_level;
+        }
+        if let Some(owner_id) = patch.owner_id {
+            self.owner_id = owner_id;
+        }
+        if let Some(owner) = patch.owner {
+            self.owner = owner;
+        }
+        if let Some(permissions) = patch.permissions {
+            self.permissions = permissions;
+        }
+        if let Some(preferred_locale) = &patch.preferred_locale {
+            self.preferred_locale.clone_from(preferred_locale);
+        }
+        if let Some(premium_progress_bar_enabled) = patch.premium_progress_bar_enabled {
+            self.premium_progress_bar_enabled = premium_progress_bar_enabled;
+        }
+        if let Some(premium_subscription_count) = patch.premium_subscription_count {
+            self.premium_subscription_count = premium_subscription_count;
+        }
+        if let Some(premium_tier) = patch.premium_tier {
+            self.premium_tier = premium_tier;
+        }
+        if let Some(rules_channel_id) = patch.rules_channel_id {
+            self.rules_channel_id = rules_channel_id;
+        }
+        if let Some(splash) = patch.splash {
+            self.splash = splash;
+        }
+        if let Some(system_channel_flags) = patch.system_channel_flags {
+            self.system_channel_flags = system_channel_flags;
+        }
+        if let Some(system_channel_id) = patch.system_channel_id {
+            self.system_channel_id = system_channel_id;
+        }
+        if let Some(verification_level) = patch.verification_level {
+            self.verification_level = verification_level;
+        }
+        if let Some(vanity_url_code) = &patch.vanity_url_code {
+            self.vanity_url_code.clone_from(vanity_url_code);
+        }
+        if let Some(widget_channel_id) = patch.widget_channel_id {
+            self.widget_channel_id = widget_channel_id;
+        }
+        if let Some(widget_enabled) = patch.widget_enabled {
+            self.widget_enabled = widget_enabled;
+        }
     }
 }
 
@@ -121,6 +203,7 @@ impl From<&Guild> for CachedGuild {
             large: guild.large,
             max_members: guild.max_members,
             max_presences: guild.max_presences,
+            max_stage_video_channel_users: None,
             max_video_channel_users: guild.max_video_channel_users,
             mfa_level: guild.mfa_level,
             name: guild.name.clone(),
@@ -132,7 +215,9 @@ impl From<&Guild> for CachedGuild {
             premium_progress_bar_enabled: guild.premium_progress_bar_enabled,
             premium_subscription_count: guild.premium_subscription_count,
             premium_tier: guild.premium_tier,
+            public_updates_channel_id: None,
             rules_channel_id: guild.rules_channel_id,
+            safety_alerts_channel_id: None,
             splash: guild.splash,
             system_channel_flags: guild.system_channel_flags,
             system_channel_id: guild.system_channel_id,
@@ -141,6 +226,356 @@ impl From<&Guild> for CachedGuild {
             verification_level: guild.verification_level,
             widget_channel_id: guild.widget_channel_id,
             widget_enabled: guild.widget_enabled,
+            members_partial: false,
         }
     }
 }
+
+/// The fields of a guild that are changed in a guild update event
+///
+/// It's created from the cached guild and a [`PartialGuild`] and contains the
+/// fields [`CachedGuild::update`] changes, the other fields are `None`, so
+/// that backends can update only these fields with
+/// [`crate::backend::Backend::patch_guild`] instead of replacing the whole
+/// guild
+///
+/// The fields that are optional in [`CachedGuild`] are `Some(None)` when the
+/// update removes them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GuildPatch {
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub afk_channel_id: Option<Option<Id<ChannelMarker>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub afk_timeout: Option<u64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub application_id: Option<Option<Id<ApplicationMarker>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub banner: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub default_message_notifications: Option<DefaultMessageNotificationLevel>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub description: Option<Option<String>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub discovery_splash: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub explicit_content_filter: Option<ExplicitContentFilter>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub features: Option<Vec<GuildFeature>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub icon: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub max_members: Option<Option<u64>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub max_presences: Option<Option<u64>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub mfa_level: Option<MfaLevel>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub nsfw_level: Option<NSFWLevel>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub owner_id: Option<Id<UserMarker>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub owner: Option<Option<bool>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub permissions: Option<Option<Permissions>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub preferred_locale: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub premium_progress_bar_enabled: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub premium_subscription_count: Option<Option<u64>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub premium_tier: Option<PremiumTier>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub rules_channel_id: Option<Option<Id<ChannelMarker>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub splash: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub system_channel_flags: Option<SystemChannelFlags>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub system_channel_id: Option<Option<Id<ChannelMarker>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub verification_level: Option<VerificationLevel>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub vanity_url_code: Option<Option<String>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub widget_channel_id: Option<Option<Id<ChannelMarker>>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub widget_enabled: Option<Option<bool>>,
+}
+
+impl GuildPatch {
+    /// Create the patch of the fields of the cached guild that the partial
+    /// guild changes
+    #[must_use]
+    pub fn new(cached_guild: &CachedGuild, guild: &PartialGuild) -> Self {
+        Self {
+            afk_channel_id: changed(&cached_guild.afk_channel_id, &guild.afk_channel_id),
+            afk_timeout: changed(&cached_guild.afk_timeout, &guild.afk_timeout),
+            application_id: changed(&cached_guild.application_id, &guild.application_id),
+            banner: changed(&cached_guild.banner, &guild.banner),
+            default_message_notifications: changed(
+                &cached_guild.default_message_notifications,
+                &guild.default_message_notifications,
+            ),
+            description: changed(&cached_guild.description, &guild.description),
+            discovery_splash: changed(&cached_guild.discovery_splash, &guild.discovery_splash),
+            explicit_content_filter: changed(
+                &cached_guild.explicit_content_filter,
+                &guild.explicit_content_filter,
+            ),
+            features: changed(&cached_guild.features, &guild.features),
+            icon: changed(&cached_guild.icon, &guild.icon),
+            max_members: changed(&cached_guild.max_members, &guild.max_members),
+            max_presences: changed(&cached_guild.max_presences, &guild.max_presences),
+            mfa_level: changed(&cached_guild.mfa_level, &guild.mfa_level),
+            name: changed(&cached_guild.name, &guild.name),
+            nsfw_level: changed(&cached_guild.nsfw_level, &guild.nsfw_level),
+            owner_id: changed(&cached_guild.owner_id, &guild.owner_id),
+            owner: changed(&cached_guild.owner, &guild.owner),
+            permissions: changed(&cached_guild.permissions, &guild.permissions),
+            preferred_locale: changed(&cached_guild.preferred_locale, &guild.preferred_locale),
+            premium_progress_bar_enabled: changed(
+                &cached_guild.premium_progress_bar_enabled,
+                &guild.premium_progress_bar_enabled,
+            ),
+            premium_subscription_count: changed(
+                &cached_guild.premium_subscription_count,
+                &guild.premium_subscription_count,
+            ),
+            premium_tier: changed(&cached_guild.premium_tier, &guild.premium_tier),
+            rules_channel_id: changed(&cached_guild.rules_channel_id, &guild.rules_channel_id),
+            splash: changed(&cached_guild.splash, &guild.splash),
+            system_channel_flags: changed(
+                &cached_guild.system_channel_flags,
+                &guild.system_channel_flags,
+            ),
+            system_channel_id: changed(&cached_guild.system_channel_id, &guild.system_channel_id),
+            verification_level: changed(
+                &cached_guild.verification_level,
+                &guild.verification_level,
+            ),
+            vanity_url_code: changed(&cached_guild.vanity_url_code, &guild.vanity_url_code),
+            widget_channel_id: changed(&cached_guild.widget_channel_id, &guild.widget_channel_id),
+            widget_enabled: changed(&cached_guild.widget_enabled, &guild.widget_enabled),
+        }
+    }
+
+    /// Return whether the patch doesn't change any field
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
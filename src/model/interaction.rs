@@ -0,0 +1,65 @@
+use twilight_model::{
+    application::interaction::{Interaction, InteractionData, InteractionType},
+    id::{
+        marker::{ApplicationMarker, ChannelMarker, GuildMarker, InteractionMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+/// A cached interaction
+///
+/// It's the same as
+/// [`twilight_model::application::interaction::Interaction`] except:
+///
+/// - `member` and `user` fields are changed to the ID of the user that
+///   created the interaction, since they're cached separately
+///
+/// - `data` field is changed to the name of the command, it's `None` if the
+///   interaction isn't an application command or autocomplete interaction
+///
+/// - `app_permissions`, `guild_locale`, `locale` and `message` fields are
+///   removed, as they're only needed to respond to the interaction right away
+///
+/// - `token_expires_at` field is added, after which the token can't be used to
+///   send follow-up messages
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedInteraction {
+    pub id: Id<InteractionMarker>,
+    pub application_id: Id<ApplicationMarker>,
+    pub channel_id: Option<Id<ChannelMarker>>,
+    pub command_name: Option<String>,
+    pub guild_id: Option<Id<GuildMarker>>,
+    pub kind: InteractionType,
+    pub token: String,
+    pub token_expires_at: Timestamp,
+    pub user_id: Option<Id<UserMarker>>,
+}
+
+impl CachedInteraction {
+    /// Create a cached interaction from a given interaction and the time its
+    /// token expires at
+    #[must_use]
+    pub fn from_interaction(interaction: &Interaction, token_expires_at: Timestamp) -> Self {
+        Self {
+            id: interaction.id,
+            application_id: interaction.application_id,
+            channel_id: interaction.channel_id,
+            command_name: match &interaction.data {
+                Some(InteractionData::ApplicationCommand(command)) => Some(command.name.clone()),
+                _ => None,
+            },
+            guild_id: interaction.guild_id,
+            kind: interaction.kind,
+            token: interaction.token.clone(),
+            token_expires_at,
+            user_id: interaction
+                .member
+                .as_ref()
+                .and_then(|member| member.user.as_ref())
+                .or(interaction.user.as_ref())
+                .map(|user| user.id),
+        }
+    }
+}
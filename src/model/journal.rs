@@ -0,0 +1,39 @@
+use twilight_model::{
+    id::{marker::GenericMarker, Id},
+    util::Timestamp,
+};
+
+use crate::unique_id;
+
+/// An event the cache was updated with, recorded in the journal
+///
+/// - `id` is unique and increases with every entry, so entries are ordered by
+///   it
+///
+/// - `recorded_at` is when the cache was updated with the event, with seconds
+///   precision
+///
+/// - `kind` and `data` are the `t` and `d` fields of the event's gateway
+///   payload, `data` being JSON
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedJournalEntry {
+    pub id: Id<GenericMarker>,
+    pub recorded_at: Timestamp,
+    pub kind: String,
+    pub data: String,
+}
+
+impl CachedJournalEntry {
+    /// Create a journal entry from the name and the JSON of the data of an
+    /// event, recorded at the given time
+    #[must_use]
+    pub fn new(kind: String, data: String, recorded_at: Timestamp) -> Self {
+        Self {
+            id: Id::new(unique_id()),
+            recorded_at,
+            kind,
+            data,
+        }
+    }
+}
@@ -1,15 +1,17 @@
 use time::{error::ComponentRange, OffsetDateTime};
 use twilight_model::{
     gateway::payload::incoming::MemberUpdate,
-    guild::Member,
+    guild::{Member, PartialMember},
     id::{
         marker::{GuildMarker, UserMarker},
         Id,
     },
-    user::{PremiumType, UserFlags},
+    user::{PremiumType, User, UserFlags},
     util::{ImageHash, Timestamp},
 };
 
+use super::changed;
+
 /// A cached member
 ///
 /// It's the same as [`twilight_model::guild::member::Member`] except:
@@ -22,8 +24,8 @@ use twilight_model::{
 ///
 /// - `email` and `verified` fields are removed, as they're only sent in some
 ///   HTTP endpoints
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedMember {
     pub guild_avatar: Option<ImageHash>,
     pub communication_disabled_until: Option<Timestamp>,
@@ -69,31 +71,143 @@ impl CachedMember {
         }
     }
 
+    /// Return the name the member is displayed with in the guild, which is
+    /// their nickname if they have one, or their username otherwise
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        self.nick.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Create a cached member from a member update event, for members that
+    /// aren't cached yet
+    ///
+    /// `deaf` and `mute` are `false` if they're not in the event
+    #[must_use]
+    pub fn from_member_update(member: &MemberUpdate) -> Self {
+        Self {
+            guild_avatar: member.avatar,
+            communication_disabled_until: member.communication_disabled_until,
+            deaf: member.deaf.unwrap_or_default(),
+            guild_id: member.guild_id,
+            joined_at: member.joined_at,
+            mute: member.mute.unwrap_or_default(),
+            nick: member.nick.clone(),
+            pending: member.pending,
+            premium_since: member.premium_since,
+            accent_color: member.user.accent_color,
+            avatar: member.user.avatar,
+            banner: member.user.banner,
+            bot: member.user.bot,
+            discriminator: member.user.discriminator,
+            flags: member.user.flags,
+            id: member.user.id,
+            locale: member.user.locale.clone(),
+            mfa_enabled: member.user.mfa_enabled,
+            name: member.user.name.clone(),
+            premium_type: member.user.premium_type,
+            public_flags: member.user.public_flags,
+            system: member.user.system,
+        }
+    }
+
+    /// Create a cached member from the partial member and author of a message
+    ///
+    /// `pending` is `false`, since partial members don't have it
+    #[must_use]
+    pub fn from_partial_member(
+        member: &PartialMember,
+        user: &User,
+        guild_id: Id<GuildMarker>,
+    ) -> Self {
+        Self {
+            guild_avatar: member.avatar,
+            communication_disabled_until: member.communication_disabled_until,
+            deaf: member.deaf,
+            guild_id,
+            joined_at: member.joined_at,
+            mute: member.mute,
+            nick: member.nick.clone(),
+            pending: false,
+            premium_since: member.premium_since,
+            accent_color: user.accent_color,
+            avatar: user.avatar,
+            banner: user.banner,
+            bot: user.bot,
+            discriminator: user.discriminator,
+            flags: user.flags,
+            id: user.id,
+            locale: user.locale.clone(),
+            mfa_enabled: user.mfa_enabled,
+            name: user.name.clone(),
+            premium_type: user.premium_type,
+            public_flags: user.public_flags,
+            system: user.system,
+        }
+    }
+
     /// Update the cached member with the partial member
     pub fn update(&mut self, member: &MemberUpdate) {
-        self.guild_avatar = member.avatar;
-        self.communication_disabled_until = member.communication_disabled_until;
-        if let Some(deaf) = member.deaf {
+        self.id = member.user.id;
+        let patch = MemberPatch::new(self, member);
+        self.apply(&patch);
+    }
+
+    /// Update the cached member with the fields in the patch that are `Some`
+    pub fn apply(&mut self, patch: &MemberPatch) {
+        if let Some(guild_avatar) = patch.guild_avatar {
+            self.guild_avatar = guild_avatar;
+        }
+        if let Some(communication_disabled_until) = patch.communication_disabled_until {
+            self.communication_disabled_until = communication_disabled_until;
+        }
+        if let Some(deaf) = patch.deaf {
             self.deaf = deaf;
         }
-        if let Some(mute) = member.mute {
+        if let Some(mute) = patch.mute {
             self.mute = mute;
         }
-        self.nick.clone_from(&member.nick);
-        self.pending = member.pending;
-        self.premium_since = member.premium_since;
-        self.accent_color = member.user.accent_color;
-        self.avatar = member.user.avatar;
-        self.banner = member.user.banner;
-        self.discriminator = member.user.discriminator;
-        self.flags = member.user.flags;
-        self.id = member.user.id;
-        self.locale.clone_from(&member.user.locale);
-        self.mfa_enabled = member.user.mfa_enabled;
-        self.name.clone_from(&member.user.name);
-        self.premium_type = member.user.premium_type;
-        self.public_flags = member.user.public_flags;
-        self.system = member.user.system;
+        if let Some(nick) = &patch.nick {
+            self.nick.clone_from(nick);
+        }
+        if let Some(pending) = patch.pending {
+            self.pending = pending;
+        }
+        if let Some(premium_since) = patch.premium_since {
+            self.premium_since = premium_since;
+        }
+        if let Some(accent_color) = patch.accent_color {
+            self.accent_color = accent_color;
+        }
+        if let Some(avatar) = patch.avatar {
+            self.avatar = avatar;
+        }
+        if let Some(banner) = patch.banner {
+            self.banner = banner;
+        }
+        if let Some(discriminator) = patch.discriminator {
+            self.discriminator = discriminator;
+        }
+        if let Some(flags) = patch.flags {
+            self.flags = flags;
+        }
+        if let Some(locale) = &patch.locale {
+            self.locale.clone_from(locale);
+        }
+        if let Some(mfa_enabled) = patch.mfa_enabled {
+            self.mfa_enabled = mfa_enabled;
+        }
+        if let Some(name) = &patch.name {
+            self.name.clone_from(name);
+        }
+        if let Some(premium_type) = patch.premium_type {
+            self.premium_type = premium_type;
+        }
+        if let Some(public_flags) = patch.public_flags {
+            self.public_flags = public_flags;
+        }
+        if let Some(system) = patch.system {
+            self.system = system;
+        }
     }
 }
 
@@ -125,3 +239,218 @@ impl From<&Member> for CachedMember {
         }
     }
 }
+
+/// The fields of a member that are changed in a member update event
+///
+/// It's created from the cached member and a [`MemberUpdate`] and contains the
+/// fields [`CachedMember::update`] changes, the other fields are `None`, so
+/// that backends can update only these fields with
+/// [`crate::backend::Backend::patch_member`] instead of replacing the whole
+/// member
+///
+/// The fields that are optional in [`CachedMember`] are `Some(None)` when the
+/// update removes them, `deaf` and `mute` are also `None` when the event
+/// doesn't include them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemberPatch {
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub guild_avatar: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub communication_disabled_until: Option<Option<Timestamp>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub deaf: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub mute: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub nick: Option<Option<String>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub pending: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub premium_since: Option<Option<Timestamp>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub accent_color: Option<Option<u32>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub avatar: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub banner: Option<Option<ImageHash>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub discriminator: Option<u16>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub flags: Option<Option<UserFlags>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub locale: Option<Option<String>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub mfa_enabled: Option<Option<bool>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub premium_type: Option<Option<PremiumType>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub public_flags: Option<Option<UserFlags>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "super::deserialize_some",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub system: Option<Option<bool>>,
+}
+
+impl MemberPatch {
+    /// Create the patch of the fields of the cached member that the member
+    /// update changes
+    #[must_use]
+    pub fn new(cached_member: &CachedMember, member: &MemberUpdate) -> Self {
+        Self {
+            guild_avatar: changed(&cached_member.guild_avatar, &member.avatar),
+            communication_disabled_until: changed(
+                &cached_member.communication_disabled_until,
+                &member.communication_disabled_until,
+            ),
+            deaf: member.deaf.filter(|deaf| *deaf != cached_member.deaf),
+            mute: member.mute.filter(|mute| *mute != cached_member.mute),
+            nick: changed(&cached_member.nick, &member.nick),
+            pending: changed(&cached_member.pending, &member.pending),
+            premium_since: changed(&cached_member.premium_since, &member.premium_since),
+            accent_color: changed(&cached_member.accent_color, &member.user.accent_color),
+            avatar: changed(&cached_member.avatar, &member.user.avatar),
+            banner: changed(&cached_member.banner, &member.user.banner),
+            discriminator: changed(&cached_member.discriminator, &member.user.discriminator),
+            flags: changed(&cached_member.flags, &member.user.flags),
+            locale: changed(&cached_member.locale, &member.user.locale),
+            mfa_enabled: changed(&cached_member.mfa_enabled, &member.user.mfa_enabled),
+            name: changed(&cached_member.name, &member.user.name),
+            premium_type: changed(&cached_member.premium_type, &member.user.premium_type),
+            public_flags: changed(&cached_member.public_flags, &member.user.public_flags),
+            system: changed(&cached_member.system, &member.user.system),
+        }
+    }
+
+    /// Return whether the patch doesn't change any field
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
@@ -11,18 +11,19 @@ use twilight_model::{
         },
         Id,
     },
+    user::User,
     util::{ImageHash, Timestamp},
 };
 
-use crate::unique_id;
+use crate::{config::MessageContentMode, unique_id};
 
 /// A cached embed field
 ///
 /// It's the same as [`twilight_model::channel::embed::EmbedField`] except:
 ///
 /// - `embed_id` field is added, making it possible to return an embed's fields
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedEmbedField {
     pub embed_id: Id<GenericMarker>,
     pub inline: bool,
@@ -56,8 +57,8 @@ impl CachedEmbedField {
 ///
 /// - `author`, `footer`, `image`, `provider`, `thumbnail` and `video` fields
 ///   are flattened, making this struct easier to cache
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedEmbed {
     pub id: Id<GenericMarker>,
     pub message_id: Id<MessageMarker>,
@@ -92,11 +93,10 @@ pub struct CachedEmbed {
 
 impl CachedEmbed {
     /// Create a cached embed from a given embed and message ID
-    #[allow(clippy::cast_sign_loss, clippy::as_conversions)]
     #[must_use]
     pub fn from_embed(embed: Embed, message_id: Id<MessageMarker>) -> Self {
         Self {
-            id: Id::new(unique_id() as u64),
+            id: Id::new(unique_id()),
             message_id,
             author_icon_url: embed
                 .author
@@ -171,19 +171,32 @@ impl CachedEmbed {
 ///
 /// - `message_id` field is added, making it possible to return a message's
 ///   attachments
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+///
+/// - `duration_millis`, `flags` and `waveform` fields are added for voice
+///   messages, `twilight_model` doesn't deserialize them yet, so they're
+///   always `None` when created with [`Self::from_attachment`]
+///
+/// - `duration_millis` is the duration of the voice message in milliseconds,
+///   since Discord's `duration_secs` is a float, which can't be compared or
+///   hashed
+///
+/// - `flags` is the bits of the attachment's flags
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedAttachment {
     pub message_id: Id<MessageMarker>,
     pub content_type: Option<String>,
+    pub duration_millis: Option<u64>,
     pub ephemeral: bool,
     pub filename: String,
+    pub flags: Option<u64>,
     pub description: Option<String>,
     pub height: Option<u64>,
     pub id: Id<AttachmentMarker>,
     pub proxy_url: String,
     pub size: u64,
     pub url: String,
+    pub waveform: Option<String>,
     pub width: Option<u64>,
 }
 
@@ -195,17 +208,161 @@ impl CachedAttachment {
         Self {
             message_id,
             content_type: attachment.content_type,
+            duration_millis: None,
             ephemeral: attachment.ephemeral,
             filename: attachment.filename,
+            flags: None,
             description: attachment.description,
             height: attachment.height,
             id: attachment.id,
             proxy_url: attachment.proxy_url,
             size: attachment.size,
             url: attachment.url,
+            waveform: None,
             width: attachment.width,
         }
     }
+
+    /// Split the attachment into the data of its file and the data of its
+    /// post in the message, used when
+    /// [`crate::config::CacheConfig::attachment_dedup`] is enabled
+    #[must_use]
+    pub fn split(self) -> (CachedAttachmentBlob, CachedAttachmentPost) {
+        let file_hash = CachedAttachmentBlob::file_hash(
+            &self.filename,
+            self.size,
+            self.content_type.as_deref(),
+            self.width,
+            self.height,
+            self.duration_millis,
+        );
+        (
+            CachedAttachmentBlob {
+                file_hash: file_hash.clone(),
+                content_type: self.content_type,
+                duration_millis: self.duration_millis,
+                filename: self.filename,
+                flags: self.flags,
+                height: self.height,
+                size: self.size,
+                waveform: self.waveform,
+                width: self.width,
+            },
+            CachedAttachmentPost {
+                file_hash,
+                message_id: self.message_id,
+                description: self.description,
+                ephemeral: self.ephemeral,
+                id: self.id,
+                proxy_url: self.proxy_url,
+                url: self.url,
+            },
+        )
+    }
+}
+
+/// The file of a cached attachment, stored once however many times it's
+/// posted when [`crate::config::CacheConfig::attachment_dedup`] is enabled
+///
+/// It's the fields of [`CachedAttachment`] that describe the file, with the
+/// `file_hash` field added as its key, created with [`CachedAttachment::split`]
+///
+/// The URLs aren't included since they have the ID of the attachment in them,
+/// so they're different every time the file is posted
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedAttachmentBlob {
+    pub file_hash: String,
+    pub content_type: Option<String>,
+    pub duration_millis: Option<u64>,
+    pub filename: String,
+    pub flags: Option<u64>,
+    pub height: Option<u64>,
+    pub size: u64,
+    pub waveform: Option<String>,
+    pub width: Option<u64>,
+}
+
+impl CachedAttachmentBlob {
+    /// Return the hash of the file's name, size, content type, dimensions and
+    /// duration, the key of the blobs
+    ///
+    /// The URL of the attachment isn't used, since it has the ID of the
+    /// attachment in it, so it's different every time the file is posted,
+    /// because of this, different files with the same name, size, content
+    /// type, dimensions and duration have the same hash
+    #[must_use]
+    pub fn file_hash(
+        filename: &str,
+        size: u64,
+        content_type: Option<&str>,
+        width: Option<u64>,
+        height: Option<u64>,
+        duration_millis: Option<u64>,
+    ) -> String {
+        let optional = |value: Option<u64>| value.map(|value| value.to_string());
+        crate::fnv_hash(&format!(
+            "{filename}\0{size}\0{}\0{}\0{}\0{}",
+            content_type.unwrap_or_default(),
+            optional(width).unwrap_or_default(),
+            optional(height).unwrap_or_default(),
+            optional(duration_millis).unwrap_or_default()
+        ))
+    }
+}
+
+/// A post of a [`CachedAttachmentBlob`] in a message, cached when
+/// [`crate::config::CacheConfig::attachment_dedup`] is enabled
+///
+/// It's the fields of [`CachedAttachment`] that are specific to the message,
+/// including its URLs, with the `file_hash` field added to refer to the blob,
+/// created with [`CachedAttachment::split`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedAttachmentPost {
+    pub file_hash: String,
+    pub message_id: Id<MessageMarker>,
+    pub description: Option<String>,
+    pub ephemeral: bool,
+    pub id: Id<AttachmentMarker>,
+    pub proxy_url: String,
+    pub url: String,
+}
+
+/// A previous version of a cached message's content
+///
+/// They're only cached when [`crate::config::CacheConfig::message_revisions`]
+/// is enabled, a revision is added when a message update changes the content
+/// of a cached message
+///
+/// - `revision_id` is a unique ID created for the revision, revisions created
+///   later have greater IDs
+///
+/// - `edited_at` is the time the content was replaced, the edited timestamp of
+///   the message update
+///
+/// - `content` is the content before the message update
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedMessageRevision {
+    pub message_id: Id<MessageMarker>,
+    pub revision_id: Id<GenericMarker>,
+    pub edited_at: Timestamp,
+    pub content: String,
+}
+
+impl CachedMessageRevision {
+    /// Create a revision of the cached message's current content, replaced at
+    /// the given time
+    #[must_use]
+    pub fn from_message(message: &CachedMessage, edited_at: Timestamp) -> Self {
+        Self {
+            message_id: message.id,
+            revision_id: Id::new(unique_id()),
+            edited_at,
+            content: message.content.clone(),
+        }
+    }
 }
 
 /// A cached message
@@ -224,8 +381,23 @@ impl CachedAttachment {
 ///
 /// - `member`, `reactions`, `attachments`, `embeds` and `sticker_items` fields
 ///   are removed, since they are cached separately
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+///
+/// - `partial` field is added, it's `true` if the message was created from a
+///   message update event with [`Self::from_message_update`], in which case
+///   the fields that aren't in the event have their default values
+///
+/// - `author_name`, `author_discriminator` and `author_avatar` fields are
+///   added, they're a snapshot of the author when the message was sent, set
+///   with [`Self::set_author_snapshot`] when
+///   [`crate::config::CacheConfig::message_authors`] is enabled, so that the
+///   message can be shown even if the author isn't cached, they're `None`
+///   otherwise
+///
+/// - `author_bot` field is added, it's `true` if the message was sent by a bot
+///   or a webhook, so that their messages can be filtered out without
+///   querying their users
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedMessage {
     pub activity_type: Option<MessageActivityType>,
     pub activity_party_id: Option<String>,
@@ -236,6 +408,10 @@ pub struct CachedMessage {
     pub application_name: Option<String>,
     pub interaction_application_id: Option<Id<ApplicationMarker>>,
     pub author: Id<UserMarker>,
+    pub author_bot: bool,
+    pub author_name: Option<String>,
+    pub author_discriminator: Option<u16>,
+    pub author_avatar: Option<ImageHash>,
     pub channel_id: Id<ChannelMarker>,
     pub content: String,
     pub edited_timestamp: Option<Timestamp>,
@@ -254,9 +430,95 @@ pub struct CachedMessage {
     pub thread: Option<Id<ChannelMarker>>,
     pub tts: bool,
     pub webhook_id: Option<Id<WebhookMarker>>,
+    pub partial: bool,
 }
 
 impl CachedMessage {
+    /// Create a partial cached message from a message update, for messages
+    /// that aren't cached
+    ///
+    /// Returns `None` if the update doesn't have the message's author or
+    /// timestamp, since they can't be defaulted
+    #[must_use]
+    pub fn from_message_update(message: &MessageUpdate) -> Option<Self> {
+        Some(Self {
+            activity_type: None,
+            activity_party_id: None,
+            application_cover_image: None,
+            application_description: None,
+            application_icon: None,
+            application_id: None,
+            application_name: None,
+            interaction_application_id: None,
+            author: message.author.as_ref()?.id,
+            author_bot: message.author.as_ref()?.bot,
+            author_name: None,
+            author_discriminator: None,
+            author_avatar: None,
+            channel_id: message.channel_id,
+            content: message.content.clone().unwrap_or_default(),
+            edited_timestamp: message.edited_timestamp,
+            flags: None,
+            guild_id: message.guild_id,
+            id: message.id,
+            kind: message.kind.unwrap_or(MessageType::Regular),
+            mention_everyone: message.mention_everyone.unwrap_or_default(),
+            pinned: message.pinned.unwrap_or_default(),
+            reference_channel_id: None,
+            reference_guild_id: None,
+            reference_message_id: None,
+            reference_fail_if_not_exists: None,
+            referenced_message: None,
+            timestamp: message.timestamp?,
+            thread: None,
+            tts: message.tts.unwrap_or_default(),
+            webhook_id: None,
+            partial: true,
+        })
+    }
+
+    /// Set the snapshot of the author's name, discriminator and avatar to the
+    /// given user's
+    pub fn set_author_snapshot(&mut self, author: &User) {
+        self.author_name = Some(author.name.clone());
+        self.author_discriminator = Some(author.discriminator);
+        self.author_avatar = author.avatar;
+    }
+
+    /// Replace the content with the content as it's stored in the given mode
+    pub fn anonymize(&mut self, mode: MessageContentMode) {
+        if mode != MessageContentMode::Plaintext {
+            self.content = mode.apply(&self.content);
+        }
+    }
+
+    /// Return whether the message is a crosspost of a message in a followed
+    /// announcement channel
+    #[must_use]
+    pub fn is_crosspost(&self) -> bool {
+        self.has_flag(MessageFlags::IS_CROSSPOST)
+    }
+
+    /// Return whether the message is only visible to the user who invoked its
+    /// interaction
+    #[must_use]
+    pub fn is_ephemeral(&self) -> bool {
+        self.has_flag(MessageFlags::EPHEMERAL)
+    }
+
+    /// Return whether the embeds of the message are suppressed, so they aren't
+    /// shown
+    #[must_use]
+    pub fn is_suppressed_embeds(&self) -> bool {
+        self.has_flag(MessageFlags::SUPPRESS_EMBEDS)
+    }
+
+    /// Return whether the message has the given flag, `false` if its flags
+    /// aren't known
+    fn has_flag(&self, flag: MessageFlags) -> bool {
+        self.flags.map_or(false, |flags| flags.contains(flag))
+    }
+
     /// Update the cached message with the message update
     pub fn update(&mut self, message: &MessageUpdate) {
         if let Some(content) = &message.content {
@@ -304,6 +566,10 @@ impl From<&Message> for CachedMessage {
                 .map(|application| application.name.clone()),
             interaction_application_id: message.application_id,
             author: message.author.id,
+            author_bot: message.author.bot,
+            author_name: None,
+            author_discriminator: None,
+            author_avatar: None,
             channel_id: message.channel_id,
             content: message.content.clone(),
             edited_timestamp: message.edited_timestamp,
@@ -337,6 +603,7 @@ impl From<&Message> for CachedMessage {
             tts: message.tts,
             flags: message.flags,
             webhook_id: message.webhook_id,
+            partial: false,
         }
     }
 }
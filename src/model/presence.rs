@@ -1,5 +1,5 @@
 use twilight_model::{
-    gateway::presence::{Activity, ActivityFlags, ActivityType, Presence, Status},
+    gateway::presence::{Activity, ActivityButton, ActivityFlags, ActivityType, Presence, Status},
     id::{
         marker::{ApplicationMarker, GuildMarker, UserMarker},
         Id,
@@ -15,14 +15,16 @@ use twilight_model::{
 /// - `guild_id` field is added, making it possible to return the activities of
 ///   a guild's members
 ///
-/// - `buttons` field is removed, as caching it is likely unnecessary, if you
-///   need this field, please create an issue
+/// - `buttons` field is flattened into `button_1` and `button_2`, the labels
+///   of the buttons, since bots only receive the labels and an activity can
+///   have at most two buttons
 ///
 /// - `assets`, `emoji`, `party` and `timestamps` fields are flattened, making
 ///   this struct easier to cache
 ///
 /// - `secrets` field is removed, as it's not sent to bots
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedActivity {
     pub user_id: Id<UserMarker>,
     pub guild_id: Id<GuildMarker>,
@@ -31,6 +33,8 @@ pub struct CachedActivity {
     pub asset_large_text: Option<String>,
     pub asset_small_image: Option<String>,
     pub asset_small_text: Option<String>,
+    pub button_1: Option<String>,
+    pub button_2: Option<String>,
     pub created_at: Option<u64>,
     pub details: Option<String>,
     pub emoji_animated: Option<bool>,
@@ -78,6 +82,16 @@ impl CachedActivity {
                 .assets
                 .as_ref()
                 .and_then(|asset| asset.small_text.clone()),
+            button_1: activity
+                .buttons
+                .first()
+                .and_then(ActivityButton::label)
+                .map(ToOwned::to_owned),
+            button_2: activity
+                .buttons
+                .get(1)
+                .and_then(ActivityButton::label)
+                .map(ToOwned::to_owned),
             created_at: activity.created_at,
             details: activity.details.clone(),
             emoji_animated: activity.emoji.as_ref().and_then(|emoji| emoji.animated),
@@ -121,7 +135,8 @@ impl CachedActivity {
 ///   you need this field, please create an issue
 ///
 /// - `activities` field is removed, since they're cached separately
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedPresence {
     pub guild_id: Id<GuildMarker>,
     pub status: Status,
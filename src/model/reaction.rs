@@ -2,7 +2,7 @@ use twilight_model::{
     channel::message::ReactionType,
     gateway::GatewayReaction,
     id::{
-        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
+        marker::{ChannelMarker, EmojiMarker, GuildMarker, MessageMarker, UserMarker},
         Id,
     },
 };
@@ -13,29 +13,387 @@ use twilight_model::{
 ///
 /// - `member` field is removed, as members are cached separately
 ///
-/// - `emoji` field is changed to a string that is either the ID or the name of
-///   the emoji
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+/// - `emoji` field is changed to a string that is either the ID of the custom
+///   emoji or the name of the Unicode emoji, reactions are matched by it
+///
+/// - `emoji_name` and `emoji_animated` fields are added from the emoji, the
+///   name of the custom emoji, `None` if it was deleted, and whether it's
+///   animated, they're both `None` for Unicode emojis, use
+///   [`Self::reaction_emoji`] to get them along with the ID
+///
+/// - `burst` and `burst_colors` fields are added from the gateway reaction,
+///   `burst_colors` is the hex colors of the super reaction's animation
+///   separated by commas
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedReaction {
     pub channel_id: Id<ChannelMarker>,
     pub emoji: String,
+    pub emoji_name: Option<String>,
+    pub emoji_animated: Option<bool>,
     pub guild_id: Option<Id<GuildMarker>>,
     pub message_id: Id<MessageMarker>,
     pub user_id: Id<UserMarker>,
+    pub burst: bool,
+    pub burst_colors: Option<String>,
+}
+
+impl CachedReaction {
+    /// Return the emoji of the reaction, telling custom and Unicode emojis
+    /// apart
+    ///
+    /// Reactions cached before `emoji_animated` was added are treated as
+    /// Unicode emojis
+    #[must_use]
+    pub fn reaction_emoji(&self) -> CachedReactionEmoji {
+        match self.emoji_animated {
+            Some(animated) => CachedReactionEmoji {
+                id: self.emoji.parse().ok(),
+                name: self.emoji_name.clone(),
+                animated: Some(animated),
+            },
+            None => CachedReactionEmoji {
+                id: None,
+                name: Some(self.emoji.clone()),
+                animated: None,
+            },
+        }
+    }
 }
 
 impl From<&GatewayReaction> for CachedReaction {
     fn from(reaction: &GatewayReaction) -> Self {
+        let emoji = CachedReactionEmoji::from(&reaction.emoji);
         Self {
             channel_id: reaction.channel_id,
-            emoji: match &reaction.emoji {
-                ReactionType::Custom { id, .. } => id.to_string(),
-                ReactionType::Unicode { name } => name.clone(),
-            },
+            emoji: emoji.key(),
+            emoji_name: emoji.id.and(emoji.name),
+            emoji_animated: emoji.animated,
             guild_id: reaction.guild_id,
             message_id: reaction.message_id,
             user_id: reaction.user_id,
+            burst: false,
+            burst_colors: None,
         }
     }
 }
+
+/// The emoji of a reaction, returned by [`CachedReaction::reaction_emoji`]
+///
+/// It's the same as [`ReactionType`] except it's a struct, custom emojis have
+/// an `id` and `animated`, and a `name` unless they were deleted, Unicode
+/// emojis only have a `name`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedReactionEmoji {
+    pub id: Option<Id<EmojiMarker>>,
+    pub name: Option<String>,
+    pub animated: Option<bool>,
+}
+
+impl CachedReactionEmoji {
+    /// Return the string reactions are matched by, the ID of a custom emoji or
+    /// the name of a Unicode emoji, the same as [`CachedReaction::emoji`]
+    ///
+    /// Pass this to the methods that take an emoji as a string, such as
+    /// [`crate::Backend::delete_message_reactions_by_emoji`]
+    #[must_use]
+    pub fn key(&self) -> String {
+        self.id.map_or_else(
+            || self.name.clone().unwrap_or_default(),
+            |id| id.to_string(),
+        )
+    }
+}
+
+impl From<&ReactionType> for CachedReactionEmoji {
+    fn from(emoji: &ReactionType) -> Self {
+        match emoji {
+            ReactionType::Custom { animated, id, name } => Self {
+                id: Some(*id),
+                name: name.clone(),
+                animated: Some(*animated),
+            },
+            ReactionType::Unicode { name } => Self {
+                id: None,
+                #[cfg(feature = "emoji-normalization")]
+                name: Some(normalize_unicode_emoji(name)),
+                #[cfg(not(feature = "emoji-normalization"))]
+                name: Some(name.clone()),
+                animated: None,
+            },
+        }
+    }
+}
+
+/// The reactions of a message with an emoji, aggregated from the cached
+/// reactions by [`crate::Cache::reaction_counts`]
+///
+/// It's the same as [`twilight_model::channel::message::Reaction`] except:
+///
+/// - `emoji` field is changed to a string that is either the ID or the name of
+///   the emoji
+///
+/// - `count` field is split into `count` and `burst_count`, the number of
+///   normal and super reactions
+///
+/// - `me_burst` and `burst_colors` fields are added, `burst_colors` is the hex
+///   colors of the super reaction's animation separated by commas
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: u64,
+    pub burst_count: u64,
+    pub me: bool,
+    pub me_burst: bool,
+    pub burst_colors: Option<String>,
+}
+
+/// Normalize the name of a Unicode emoji so that its different representations
+/// are cached as the same emoji
+///
+/// Discord accepts emojis with and without variation selectors, so for example
+/// `❤` and `❤\u{FE0F}` would otherwise be cached as different reactions,
+/// this removes the text and emoji variation selectors then adds the emoji
+/// variation selector back after the emojis in [`TEXT_PRESENTATION_EMOJIS`]
+/// and before keycaps, which is how Discord's emoji picker sends them
+///
+/// It's applied to the names of Unicode emojis in [`CachedReactionEmoji`], so
+/// it's used by [`CachedReaction::from`] and when reactions are removed
+#[cfg(feature = "emoji-normalization")]
+#[must_use]
+pub fn normalize_unicode_emoji(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut chars = name
+        .chars()
+        .filter(|character| !matches!(character, '\u{FE0E}' | '\u{FE0F}'))
+        .peekable();
+
+    while let Some(character) = chars.next() {
+        normalized.push(character);
+        let next = chars.peek().copied();
+        let is_keycap = next == Some('\u{20E3}');
+        let is_skin_tone = matches!(next, Some('\u{1F3FB}'..='\u{1F3FF}'));
+        if is_keycap
+            || (!is_skin_tone && TEXT_PRESENTATION_EMOJIS.binary_search(&character).is_ok())
+        {
+            normalized.push('\u{FE0F}');
+        }
+    }
+
+    normalized
+}
+
+/// The emojis that are displayed as text unless they're followed by the emoji
+/// variation selector, sorted so that they can be binary searched
+#[cfg(feature = "emoji-normalization")]
+const TEXT_PRESENTATION_EMOJIS: &[char] = &[
+    '\u{00A9}',
+    '\u{00AE}',
+    '\u{203C}',
+    '\u{2049}',
+    '\u{2122}',
+    '\u{2139}',
+    '\u{2194}',
+    '\u{2195}',
+    '\u{2196}',
+    '\u{2197}',
+    '\u{2198}',
+    '\u{2199}',
+    '\u{21A9}',
+    '\u{21AA}',
+    '\u{2328}',
+    '\u{23CF}',
+    '\u{23ED}',
+    '\u{23EE}',
+    '\u{23EF}',
+    '\u{23F1}',
+    '\u{23F2}',
+    '\u{23F8}',
+    '\u{23F9}',
+    '\u{23FA}',
+    '\u{24C2}',
+    '\u{25AA}',
+    '\u{25AB}',
+    '\u{25B6}',
+    '\u{25C0}',
+    '\u{25FB}',
+    '\u{25FC}',
+    '\u{2600}',
+    '\u{2601}',
+    '\u{2602}',
+    '\u{2603}',
+    '\u{2604}',
+    '\u{260E}',
+    '\u{2611}',
+    '\u{2618}',
+    '\u{261D}',
+    '\u{2620}',
+    '\u{2622}',
+    '\u{2623}',
+    '\u{2626}',
+    '\u{262A}',
+    '\u{262E}',
+    '\u{262F}',
+    '\u{2638}',
+    '\u{2639}',
+    '\u{263A}',
+    '\u{2640}',
+    '\u{2642}',
+    '\u{265F}',
+    '\u{2660}',
+    '\u{2663}',
+    '\u{2665}',
+    '\u{2666}',
+    '\u{2668}',
+    '\u{267B}',
+    '\u{267E}',
+    '\u{2692}',
+    '\u{2694}',
+    '\u{2695}',
+    '\u{2696}',
+    '\u{2697}',
+    '\u{2699}',
+    '\u{269B}',
+    '\u{269C}',
+    '\u{26A0}',
+    '\u{26A7}',
+    '\u{26B0}',
+    '\u{26B1}',
+    '\u{26C8}',
+    '\u{26CF}',
+    '\u{26D1}',
+    '\u{26D3}',
+    '\u{26E9}',
+    '\u{26F0}',
+    '\u{26F1}',
+    '\u{26F4}',
+    '\u{26F7}',
+    '\u{26F8}',
+    '\u{26F9}',
+    '\u{2702}',
+    '\u{2708}',
+    '\u{2709}',
+    '\u{270C}',
+    '\u{270D}',
+    '\u{270F}',
+    '\u{2712}',
+    '\u{2714}',
+    '\u{2716}',
+    '\u{271D}',
+    '\u{2721}',
+    '\u{2733}',
+    '\u{2734}',
+    '\u{2744}',
+    '\u{2747}',
+    '\u{2763}',
+    '\u{2764}',
+    '\u{27A1}',
+    '\u{2934}',
+    '\u{2935}',
+    '\u{2B05}',
+    '\u{2B06}',
+    '\u{2B07}',
+    '\u{3030}',
+    '\u{303D}',
+    '\u{3297}',
+    '\u{3299}',
+    '\u{1F170}',
+    '\u{1F171}',
+    '\u{1F17E}',
+    '\u{1F17F}',
+    '\u{1F202}',
+    '\u{1F237}',
+    '\u{1F321}',
+    '\u{1F324}',
+    '\u{1F325}',
+    '\u{1F326}',
+    '\u{1F327}',
+    '\u{1F328}',
+    '\u{1F329}',
+    '\u{1F32A}',
+    '\u{1F32B}',
+    '\u{1F32C}',
+    '\u{1F336}',
+    '\u{1F37D}',
+    '\u{1F396}',
+    '\u{1F397}',
+    '\u{1F399}',
+    '\u{1F39A}',
+    '\u{1F39B}',
+    '\u{1F39E}',
+    '\u{1F39F}',
+    '\u{1F3CB}',
+    '\u{1F3CC}',
+    '\u{1F3CD}',
+    '\u{1F3CE}',
+    '\u{1F3D4}',
+    '\u{1F3D5}',
+    '\u{1F3D6}',
+    '\u{1F3D7}',
+    '\u{1F3D8}',
+    '\u{1F3D9}',
+    '\u{1F3DA}',
+    '\u{1F3DB}',
+    '\u{1F3DC}',
+    '\u{1F3DD}',
+    '\u{1F3DE}',
+    '\u{1F3DF}',
+    '\u{1F3F3}',
+    '\u{1F3F5}',
+    '\u{1F3F7}',
+    '\u{1F43F}',
+    '\u{1F441}',
+    '\u{1F4FD}',
+    '\u{1F549}',
+    '\u{1F54A}',
+    '\u{1F56F}',
+    '\u{1F570}',
+    '\u{1F573}',
+    '\u{1F574}',
+    '\u{1F575}',
+    '\u{1F576}',
+    '\u{1F577}',
+    '\u{1F578}',
+    '\u{1F579}',
+    '\u{1F587}',
+    '\u{1F58A}',
+    '\u{1F58B}',
+    '\u{1F58C}',
+    '\u{1F58D}',
+    '\u{1F590}',
+    '\u{1F5A5}',
+    '\u{1F5A8}',
+    '\u{1F5B1}',
+    '\u{1F5B2}',
+    '\u{1F5BC}',
+    '\u{1F5C2}',
+    '\u{1F5C3}',
+    '\u{1F5C4}',
+    '\u{1F5D1}',
+    '\u{1F5D2}',
+    '\u{1F5D3}',
+    '\u{1F5DC}',
+    '\u{1F5DD}',
+    '\u{1F5DE}',
+    '\u{1F5E1}',
+    '\u{1F5E3}',
+    '\u{1F5E8}',
+    '\u{1F5EF}',
+    '\u{1F5F3}',
+    '\u{1F5FA}',
+    '\u{1F6CB}',
+    '\u{1F6CD}',
+    '\u{1F6CE}',
+    '\u{1F6CF}',
+    '\u{1F6E0}',
+    '\u{1F6E1}',
+    '\u{1F6E2}',
+    '\u{1F6E3}',
+    '\u{1F6E4}',
+    '\u{1F6E5}',
+    '\u{1F6E9}',
+    '\u{1F6F0}',
+    '\u{1F6F3}',
+];
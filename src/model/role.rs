@@ -1,7 +1,7 @@
 use twilight_model::{
     guild::{Permissions, Role},
     id::{
-        marker::{GuildMarker, IntegrationMarker, RoleMarker, UserMarker},
+        marker::{GenericMarker, GuildMarker, IntegrationMarker, RoleMarker, UserMarker},
         Id,
     },
     util::ImageHash,
@@ -13,14 +13,18 @@ use twilight_model::{
 ///
 /// - `guild_id` field is added, making it possible to return a guild's roles
 ///
-/// - `user_id` field is added, making it possible to return a member's roles
-///
 /// - `tags` field is flattened, making this struct easier to cache
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+///
+/// - `flags`, `tags_available_for_purchase`, `tags_guild_connections` and
+///   `tags_subscription_listing_id` fields are added, `twilight_model` doesn't
+///   deserialize them yet, so they're always `None` when created with
+///   [`Self::from_role`]
+///
+/// - `flags` is the bits of the role's flags
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedRole {
     pub guild_id: Id<GuildMarker>,
-    pub user_id: Option<Id<UserMarker>>,
     pub color: u32,
     pub hoist: bool,
     pub icon: Option<ImageHash>,
@@ -30,9 +34,13 @@ pub struct CachedRole {
     pub name: String,
     pub permissions: Permissions,
     pub position: i64,
+    pub flags: Option<u64>,
+    pub tags_available_for_purchase: Option<bool>,
     pub tags_bot_id: Option<Id<UserMarker>>,
+    pub tags_guild_connections: Option<bool>,
     pub tags_integration_id: Option<Id<IntegrationMarker>>,
     pub tags_premium_subscriber: Option<bool>,
+    pub tags_subscription_listing_id: Option<Id<GenericMarker>>,
     pub unicode_emoji: Option<String>,
 }
 
@@ -43,7 +51,6 @@ impl CachedRole {
     pub fn from_role(role: Role, guild_id: Id<GuildMarker>) -> Self {
         Self {
             guild_id,
-            user_id: None,
             color: role.color,
             hoist: role.hoist,
             icon: role.icon,
@@ -53,10 +60,52 @@ impl CachedRole {
             name: role.name,
             permissions: role.permissions,
             position: role.position,
+            flags: None,
+            tags_available_for_purchase: None,
             tags_bot_id: role.tags.as_ref().and_then(|tags| tags.bot_id),
+            tags_guild_connections: None,
             tags_integration_id: role.tags.as_ref().and_then(|tags| tags.integration_id),
             tags_premium_subscriber: role.tags.map(|tags| tags.premium_subscriber),
+            tags_subscription_listing_id: None,
             unicode_emoji: role.unicode_emoji,
         }
     }
+
+    /// Return the string that mentions the role in a message, such as
+    /// `<@&123>`
+    #[must_use]
+    pub fn mention(&self) -> String {
+        format!("<@&{}>", self.id)
+    }
+}
+
+/// A role assigned to a member
+///
+/// This is cached separately from [`CachedRole`] so that a role is cached only
+/// once no matter how many members it's assigned to, making it possible to
+/// return a member's roles and a role's members
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedRoleAssignment {
+    pub guild_id: Id<GuildMarker>,
+    pub user_id: Id<UserMarker>,
+    pub role_id: Id<RoleMarker>,
+}
+
+/// The permissions of a role in a channel, returned by
+/// [`crate::Cache::channel_permission_matrix`]
+///
+/// - `allow` and `deny` are the permissions the role's overwrite in the
+///   channel allows and denies
+///
+/// - `permissions` are the role's effective permissions in the channel, after
+///   the everyone role's and the role's overwrites are applied, the permissions
+///   of a member that only has this role
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelRolePermissions {
+    pub role_id: Id<RoleMarker>,
+    pub allow: Permissions,
+    pub deny: Permissions,
+    pub permissions: Permissions,
 }
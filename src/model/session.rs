@@ -0,0 +1,53 @@
+use twilight_model::gateway::payload::incoming::Ready;
+
+/// A cached gateway session of a shard
+///
+/// It's the same as [`twilight_model::gateway::payload::incoming::Ready`]
+/// except:
+///
+/// - `shard` field is flattened into `shard_id` and `shard_count`, a ready
+///   event without it is cached as shard 0 of 1
+///
+/// - `application`, `guilds` and `user` fields are removed, as they're cached
+///   separately
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedSession {
+    pub session_id: String,
+    pub shard_id: u64,
+    pub shard_count: u64,
+    pub version: u64,
+}
+
+impl From<&Ready> for CachedSession {
+    fn from(ready: &Ready) -> Self {
+        let [shard_id, shard_count] = ready.shard.unwrap_or([0, 1]);
+        Self {
+            session_id: ready.session_id.clone(),
+            shard_id,
+            shard_count,
+            version: ready.version,
+        }
+    }
+}
+
+/// The state of the events received by a shard, updated by
+/// [`crate::Cache::update_sharded`]
+///
+/// - `sequence` is the highest sequence number the shard received since its
+///   last ready event, 0 if it didn't receive any
+///
+/// - `events` is the number of events the shard received since its last ready
+///   event
+///
+/// - `missed_events` is the number of sequence numbers skipped since the
+///   shard's last ready event, when it's not 0, the data of the shard's guilds
+///   might be outdated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedShardState {
+    pub shard_id: u64,
+    pub sequence: u64,
+    pub events: u64,
+    pub missed_events: u64,
+}
@@ -0,0 +1,30 @@
+use twilight_model::id::{
+    marker::{GuildMarker, StickerMarker},
+    Id,
+};
+
+/// The number of times an emoji was used in reactions in a guild on a day
+///
+/// `emoji` is either the ID or the name of the emoji, the same as
+/// [`super::CachedReaction::emoji`], and `day` is the number of days since the
+/// Unix epoch in UTC
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedEmojiUse {
+    pub guild_id: Id<GuildMarker>,
+    pub emoji: String,
+    pub day: u64,
+    pub uses: u64,
+}
+
+/// The number of times a sticker was sent in messages in a guild on a day
+///
+/// `day` is the number of days since the Unix epoch in UTC
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedStickerUse {
+    pub guild_id: Id<GuildMarker>,
+    pub sticker_id: Id<StickerMarker>,
+    pub day: u64,
+    pub uses: u64,
+}
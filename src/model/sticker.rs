@@ -21,8 +21,8 @@ use twilight_model::{
 ///
 /// - `available`, `kind` and `tags` fields are made optional, as they're not
 ///   present in message stickers
-#[derive(Clone, Debug)]
-#[cfg_attr(feature = "tests", derive(PartialEq, Eq))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CachedSticker {
     pub message_id: Option<Id<MessageMarker>>,
     pub available: Option<bool>,
@@ -0,0 +1,217 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use twilight_model::{
+    gateway::event::Event,
+    id::{
+        marker::{ChannelMarker, GuildMarker, RoleMarker},
+        Id,
+    },
+};
+
+use crate::model::{CachedChannel, CachedGuild, CachedRole};
+
+/// A process-local pool of the guilds, channels and roles read from the cache,
+/// shared with [`Arc`]s so that reading the same entity again doesn't clone it
+///
+/// It's used by [`crate::Cache::guild_ref`], [`crate::Cache::channel_ref`] and
+/// [`crate::Cache::role_ref`], the entities are added when they're read and
+/// removed when [`crate::Cache::update`] is called with an event that changes
+/// them, when a guild is removed, its channels and roles are also removed
+///
+/// Entities written to the backend without [`crate::Cache::update`], for
+/// example with [`crate::Backend::upsert_channel`], aren't removed from the
+/// pool, call [`ArcPool::clear`] after writing to the backend yourself
+///
+/// To use it, keep it in your cache and override [`crate::Cache::arc_pool`]
+/// to return it
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::pool::ArcPool;
+///
+/// let pool = ArcPool::new(10_000);
+/// let channel = cache.channel_ref(channel_id).await?;
+/// ```
+#[derive(Debug)]
+pub struct ArcPool {
+    /// The maximum number of entities of each kind in the pool
+    capacity: usize,
+    /// Incremented when entities are removed, so that entities read before
+    /// they were removed aren't added back
+    generation: AtomicU64,
+    /// The pooled guilds
+    guilds: Mutex<HashMap<Id<GuildMarker>, Arc<CachedGuild>>>,
+    /// The pooled channels
+    channels: Mutex<HashMap<Id<ChannelMarker>, Arc<CachedChannel>>>,
+    /// The pooled roles
+    roles: Mutex<HashMap<Id<RoleMarker>, Arc<CachedRole>>>,
+}
+
+impl ArcPool {
+    /// Create a pool that holds up to the given number of guilds, channels and
+    /// roles each
+    ///
+    /// Entities read when the pool is full aren't pooled, they're still
+    /// returned in an [`Arc`]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            generation: AtomicU64::new(0),
+            guilds: Mutex::new(HashMap::new()),
+            channels: Mutex::new(HashMap::new()),
+            roles: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the number of pooled guilds, channels and roles
+    #[must_use]
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub fn len(&self) -> usize {
+        lock(&self.guilds).len() + lock(&self.channels).len() + lock(&self.roles).len()
+    }
+
+    /// Return whether the pool is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every entity from the pool
+    pub fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        lock(&self.guilds).clear();
+        lock(&self.channels).clear();
+        lock(&self.roles).clear();
+    }
+
+    /// Return the current generation, to pass to the insert methods after
+    /// reading the entity from the backend
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Return the pooled guild
+    pub(crate) fn guild(&self, guild_id: Id<GuildMarker>) -> Option<Arc<CachedGuild>> {
+        lock(&self.guilds).get(&guild_id).cloned()
+    }
+
+    /// Return the pooled channel
+    pub(crate) fn channel(&self, channel_id: Id<ChannelMarker>) -> Option<Arc<CachedChannel>> {
+        lock(&self.channels).get(&channel_id).cloned()
+    }
+
+    /// Return the pooled role
+    pub(crate) fn role(&self, role_id: Id<RoleMarker>) -> Option<Arc<CachedRole>> {
+        lock(&self.roles).get(&role_id).cloned()
+    }
+
+    /// Add the guild read at the given generation to the pool
+    pub(crate) fn insert_guild(&self, guild: &Arc<CachedGuild>, generation: u64) {
+        self.insert(&self.guilds, guild.id, guild, generation);
+    }
+
+    /// Add the channel read at the given generation to the pool
+    pub(crate) fn insert_channel(&self, channel: &Arc<CachedChannel>, generation: u64) {
+        self.insert(&self.channels, channel.id, channel, generation);
+    }
+
+    /// Add the role read at the given generation to the pool
+    pub(crate) fn insert_role(&self, role: &Arc<CachedRole>, generation: u64) {
+        self.insert(&self.roles, role.id, role, generation);
+    }
+
+    /// Remove the entities the update changed from the pool
+    ///
+    /// This should be called after the update is written to the backend, so
+    /// that the entities can't be read and pooled again before they're written
+    pub(crate) fn invalidate(&self, invalidation: Invalidation) {
+        match invalidation {
+            Invalidation::None => {}
+            Invalidation::Guild(guild_id) => {
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                lock(&self.guilds).remove(&guild_id);
+                lock(&self.channels).retain(|_, channel| channel.guild_id != Some(guild_id));
+                lock(&self.roles).retain(|_, role| role.guild_id != guild_id);
+            }
+            Invalidation::Channel(channel_id) => {
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                lock(&self.channels).remove(&channel_id);
+            }
+            Invalidation::Role(role_id) => {
+                self.generation.fetch_add(1, Ordering::AcqRel);
+                lock(&self.roles).remove(&role_id);
+            }
+            Invalidation::All => self.clear(),
+        }
+    }
+
+    /// Add the entity to the map if it's not full and nothing was removed
+    /// since the given generation
+    ///
+    /// The generation is checked while the map is locked, so an entity removed
+    /// after the check is still removed from the map
+    fn insert<K: Eq + Hash, V>(
+        &self,
+        map: &Mutex<HashMap<K, Arc<V>>>,
+        key: K,
+        value: &Arc<V>,
+        generation: u64,
+    ) {
+        let mut entries = lock(map);
+        if self.generation() == generation && entries.len() < self.capacity {
+            entries.insert(key, Arc::clone(value));
+        }
+    }
+}
+
+/// The entities an update changes, which should be removed from an
+/// [`ArcPool`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Invalidation {
+    /// The update doesn't change pooled entities
+    None,
+    /// The update changes the guild, its channels or its roles
+    Guild(Id<GuildMarker>),
+    /// The update changes the channel
+    Channel(Id<ChannelMarker>),
+    /// The update changes the role
+    Role(Id<RoleMarker>),
+    /// The update might change any entity
+    All,
+}
+
+impl Invalidation {
+    /// Return the entities the event changes
+    pub(crate) fn of(event: &Event) -> Self {
+        match event {
+            Event::ChannelCreate(channel) => Self::Channel(channel.id),
+            Event::ChannelUpdate(channel) => Self::Channel(channel.id),
+            Event::ChannelDelete(channel) => Self::Channel(channel.id),
+            Event::ThreadCreate(thread) => Self::Channel(thread.id),
+            Event::ThreadUpdate(thread) => Self::Channel(thread.id),
+            Event::ThreadDelete(thread) => Self::Channel(thread.id),
+            Event::ThreadListSync(sync) => Self::Guild(sync.guild_id),
+            Event::GuildCreate(guild) => Self::Guild(guild.id),
+            Event::GuildUpdate(guild) => Self::Guild(guild.id),
+            Event::GuildDelete(guild) => Self::Guild(guild.id),
+            Event::UnavailableGuild(guild) => Self::Guild(guild.id),
+            Event::RoleCreate(role) => Self::Role(role.role.id),
+            Event::RoleUpdate(role) => Self::Role(role.role.id),
+            Event::RoleDelete(role) => Self::Role(role.role_id),
+            Event::Ready(_) => Self::All,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Lock the mutex, ignoring poisoning since the pool is never left invalid
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
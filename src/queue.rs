@@ -0,0 +1,148 @@
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use twilight_model::gateway::event::{Event, EventType};
+
+/// How important it is to cache an event, events with a lower priority are
+/// dropped first when the backend falls behind
+///
+/// Passed to [`crate::Cache::update_with_priority`], use [`Priority::of`] for
+/// the default priority of an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Priority {
+    /// Events that are frequent and quickly outdated, such as presence updates
+    /// and typing starts
+    Low,
+    /// Most events
+    #[default]
+    Normal,
+    /// Events that the cache is invalid without, such as ready and guild create
+    /// events
+    High,
+}
+
+impl Priority {
+    /// Return the default priority of the event
+    ///
+    /// Presence updates, presence replacements and typing starts are
+    /// [`Priority::Low`], ready, resumed, guild create and guild delete events
+    /// are [`Priority::High`], and the rest are [`Priority::Normal`]
+    #[must_use]
+    pub const fn of(event: &Event) -> Self {
+        match event.kind() {
+            EventType::PresenceUpdate | EventType::PresencesReplace | EventType::TypingStart => {
+                Self::Low
+            }
+            EventType::Ready
+            | EventType::Resumed
+            | EventType::GuildCreate
+            | EventType::GuildDelete => Self::High,
+            _ => Self::Normal,
+        }
+    }
+}
+
+/// A bounded queue of the updates in progress, used to drop low-priority events
+/// when the backend falls behind
+///
+/// The queue doesn't hold the events, it counts the updates started with
+/// [`crate::Cache::update_with_priority`] that haven't finished yet, when
+/// there are as many of them as the queue's capacity, events with a priority
+/// lower than [`UpdateQueue::min_priority`] are dropped instead of being
+/// cached
+///
+/// To use it, keep it in your cache and override
+/// [`crate::Cache::update_queue`] to return it
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::queue::{Priority, UpdateQueue};
+///
+/// let queue = UpdateQueue::new(256).min_priority(Priority::Normal);
+/// cache.update_with_priority(&event, Priority::of(&event)).await?;
+/// ```
+#[derive(Debug)]
+pub struct UpdateQueue {
+    /// The number of updates in progress that drops low-priority events
+    capacity: usize,
+    /// The lowest priority that isn't dropped when the queue is full
+    min_priority: Priority,
+    /// The number of updates in progress
+    depth: AtomicUsize,
+    /// The number of events dropped
+    dropped: AtomicU64,
+}
+
+impl UpdateQueue {
+    /// Create a queue with the given capacity that drops [`Priority::Low`]
+    /// events when full
+    #[must_use]
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            min_priority: Priority::Normal,
+            depth: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Set the lowest priority that isn't dropped when the queue is full
+    ///
+    /// Setting this to [`Priority::High`] drops everything but high-priority
+    /// events when the queue is full
+    #[must_use]
+    pub const fn min_priority(mut self, min_priority: Priority) -> Self {
+        self.min_priority = min_priority;
+        self
+    }
+
+    /// Return the number of updates in progress after which low-priority
+    /// events are dropped
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the number of updates in progress
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of events dropped since the queue was created
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Start an update with the given priority, returning `None` if the event
+    /// should be dropped
+    ///
+    /// The returned slot ends the update when it's dropped, so the depth is
+    /// correct even if the update is cancelled
+    pub(crate) fn enter(&self, priority: Priority) -> Option<QueueSlot<'_>> {
+        let depth = self.depth.fetch_add(1, Ordering::AcqRel);
+        let slot = QueueSlot { queue: self };
+
+        if depth >= self.capacity && priority < self.min_priority {
+            drop(slot);
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        Some(slot)
+    }
+}
+
+/// An update in progress in an [`UpdateQueue`], ends the update when dropped
+#[derive(Debug)]
+pub(crate) struct QueueSlot<'queue> {
+    /// The queue the update is in
+    queue: &'queue UpdateQueue,
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.queue.depth.fetch_sub(1, Ordering::AcqRel);
+    }
+}
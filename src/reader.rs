@@ -0,0 +1,1465 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use async_trait::async_trait;
+#[cfg(feature = "http")]
+use twilight_http::Client;
+#[cfg(feature = "changelog")]
+use twilight_model::id::marker::GenericMarker;
+use twilight_model::{
+    channel::StageInstance,
+    guild::{GuildFeature, Permissions},
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker,
+            StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+#[cfg(feature = "autocomplete")]
+use crate::autocomplete::Suggestion;
+#[cfg(feature = "changelog")]
+use crate::cache::EntityKind;
+#[cfg(feature = "http")]
+use crate::drift::CacheDrift;
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+use crate::{
+    backend::Backend,
+    cache::Error,
+    config::MessageKindFilter,
+    counts::CacheStats,
+    integrity::IntegrityReport,
+    model::{
+        CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField,
+        CachedEmoji, CachedGuild, CachedInteraction, CachedMember, CachedMessage,
+        CachedMessageRevision, CachedPermissionOverwrite, CachedPresence, CachedReaction,
+        CachedRole, CachedSession, CachedShardState, CachedSticker, ChannelRolePermissions,
+        ReactionCount,
+    },
+    structure::GuildStructure,
+    Cache,
+};
+
+/// A read-only view of the cache, with only the methods to get data from it
+///
+/// It's implemented for every [`Cache`], so you can pass the cache to the parts
+/// of your application that should only read from it, such as command
+/// handlers, as a `&dyn CacheReader<Error = E>` or a generic bound, while only
+/// the task receiving gateway events can update it
+///
+/// The methods are the same as the ones of [`Cache`], so don't import both of
+/// the traits in the same module, otherwise calling them is ambiguous
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::CacheReader;
+///
+/// async fn info_command(cache: &dyn CacheReader<Error = sqlx::Error>) -> Result<String, Error> {
+///     let current_user = cache.current_user().await?;
+///     Ok(format!("I'm {}", current_user.name))
+/// }
+/// ```
+#[async_trait]
+pub trait CacheReader {
+    /// The error type the backend returns, the same as [`Backend::Error`]
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Get the number of cached entities of every kind
+    async fn stats(&self) -> Result<CacheStats, Error<Self::Error>>;
+
+    /// Scan the cache for referential breaks, such as embed fields without
+    /// embeds, reactions of messages that aren't cached or roles of guilds that
+    /// aren't cached, without repairing them
+    async fn verify_integrity(&self) -> Result<IntegrityReport, Error<Self::Error>>;
+
+    /// Compare the cached channels, roles, members and emojis of the guild with
+    /// the ones fetched with the HTTP client, returning the differences
+    #[cfg(feature = "http")]
+    async fn diff_against_http(
+        &self,
+        http: &Client,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CacheDrift, Error<Self::Error>>;
+
+    /// Get the IDs of the given users that aren't cached as members of the
+    /// guild
+    async fn missing_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[Id<UserMarker>],
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>>;
+
+    /// Get permissions of the current user in the given channel
+    async fn self_channel_permissions(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<Self::Error>>;
+
+    /// Get permissions of the current user in the given guild
+    async fn self_guild_permissions(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<Self::Error>>;
+
+    /// Get the permissions of the given user and channel
+    async fn channel_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<Self::Error>>;
+
+    /// Get the permissions of the given user and guild
+    async fn guild_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<Self::Error>>;
+
+    /// Get whether the given user owns the guild
+    async fn is_owner(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<Self::Error>>;
+
+    /// Get whether the given user owns the guild or has a role with the
+    /// administrator permission in it
+    async fn is_admin(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<Self::Error>>;
+
+    /// Get the roles in the guild that grant the given permissions, for example
+    /// to audit which roles have [`Permissions::ADMINISTRATOR`]
+    async fn roles_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+
+    /// Get the IDs of the members in the guild that have the given permissions
+    /// from their roles, for example to audit who can mention everyone
+    async fn members_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>>;
+
+    /// Get the channels in the guild that the given user can view
+    async fn visible_channels(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get the current user information of the bot
+    async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>>;
+
+    /// Get the current application information of the bot
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>>;
+
+    /// Get the cached sessions of the shards, one for each shard that
+    /// received a ready event
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>>;
+
+    /// Get the cached state of a shard, updated by [`Cache::update_sharded`]
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>>;
+
+    /// Get the cached states of the shards, one for each shard that updated
+    /// the cache with [`Cache::update_sharded`], for example to show them in a
+    /// dashboard
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>>;
+
+    /// Get a cached interaction by its ID, cached if
+    /// [`crate::config::CacheConfig::interactions`] is enabled
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>>;
+
+    /// Get a cached channel or thread by its ID
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a cached channel or thread by its ID, shared through the pool returned by
+    /// [`Cache::arc_pool`]
+    async fn channel_ref(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<Arc<CachedChannel>>, Error<Self::Error>>;
+
+    /// Get a cached permission overwrites of a channel by its ID
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>>;
+
+    /// Get the cached permission overwrite of a channel for the given member
+    async fn member_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<Self::Error>>;
+
+    /// Get the cached permission overwrite of a channel for the given role
+    async fn role_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<Self::Error>>;
+
+    /// Get whether a channel's permission overwrites are synced with its
+    /// category's, like Discord shows in the channel's settings
+    async fn channel_synced_with_category(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<bool>, Error<Self::Error>>;
+
+    /// Get the effective permissions in the channel of every role with a
+    /// permission overwrite in it, for example for a command that shows a
+    /// channel's permissions
+    async fn channel_permission_matrix(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<ChannelRolePermissions>, Error<Self::Error>>;
+
+    /// Get the cached DM channel with the given user
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>>;
+
+    /// Get the IDs of a cached DM channel's recipients by its ID
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>>;
+
+    /// Get a guild's channels and threads by its ID
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's archived threads by its ID
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's channels whose latest message is older than the given
+    /// timestamp, for example to archive them
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a channel's threads that aren't archived by the channel's ID
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's announcement channels, for example for bots that publish
+    /// the messages in them
+    async fn announcement_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's channels in the order the Discord client shows them
+    async fn guild_channels_ordered(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get the layout of a guild, its settings, roles, channels and
+    /// permission overwrites, `None` if the guild isn't cached
+    async fn guild_structure_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildStructure>, Error<Self::Error>>;
+
+    /// Get the slowmode of a channel in seconds, `None` if the channel isn't
+    /// cached or doesn't have slowmode
+    async fn channel_slowmode(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<u16>, Error<Self::Error>>;
+
+    /// Get whether the slowmode of a channel lets the user send a message now,
+    /// based on the last cached message the user sent in it
+    async fn can_send_now(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<bool, Error<Self::Error>>;
+
+    /// Get a cached message by its ID
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the message a thread was started from by the thread's ID
+    async fn thread_starter_message(
+        &self,
+        thread_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the chain of replies ending at the given message, starting with the
+    /// message itself, followed by the message it replies to, the message that
+    /// one replies to and so on, for example to give a moderation or AI bot
+    /// the context of a conversation
+    async fn reply_chain(
+        &self,
+        message_id: Id<MessageMarker>,
+        depth: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get cached embeds of a message by its ID
+    async fn embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<(CachedEmbed, Vec<CachedEmbedField>)>, Error<Self::Error>>;
+
+    /// Get the cached previous versions of a message's content by its ID, the
+    /// oldest first
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>>;
+
+    /// Get cached attachments of a message by its ID
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>>;
+
+    /// Get the cached messages an attachment file was posted in by the hash
+    /// of its name, size, content type, dimensions and duration, returned by
+    /// [`crate::model::CachedAttachmentBlob::file_hash`]
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get cached reactions of a message by its ID
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>>;
+
+    /// Get the reactions of a message aggregated by their emojis, like the
+    /// reactions in Discord's message payloads
+    async fn reaction_counts(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<ReactionCount>, Error<Self::Error>>;
+
+    /// Get cached stickers of a message by its ID
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages by its ID
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages sent after `after` and
+    /// before `before`, excluding both, `None` meaning no bound
+    async fn channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages that pass the filter, for
+    /// example to exclude system messages and the messages of bots
+    async fn channel_messages_filtered(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages sent by users, excluding
+    /// system messages and the messages of bots and webhooks
+    async fn channel_user_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the most recent `limit` messages that have the given sticker
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages that were sent by webhooks
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get the most recent `limit` messages that were sent by the given
+    /// webhook
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a guild's most recent `limit` messages that have a reaction with the
+    /// given emoji
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+
+    /// Get a cached member by its guild ID and user ID
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>>;
+
+    /// Get cached roles of a member by their ID
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+
+    /// Get cached presence of a member by their ID
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>>;
+
+    /// Get cached activities of a member by their ID
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>>;
+
+    /// Get cached activities of a member in the given guild by their ID
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>>;
+
+    /// Get the IDs of the cached guilds
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>>;
+
+    /// Get the IDs of the cached guilds that are unavailable
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>>;
+
+    /// Get a guild's members by its ID
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+
+    /// Get the IDs of the guilds that both users are cached as members of
+    async fn mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>>;
+
+    /// Get up to `limit` members of the guild whose nickname or username
+    /// starts with the prefix, ignoring case
+    async fn search_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+
+    /// Get up to `limit` roles of the guild whose names match the query, the
+    /// best matches first
+    #[cfg(feature = "autocomplete")]
+    fn suggest_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<RoleMarker>>;
+
+    /// Get up to `limit` channels and threads of the guild whose names match
+    /// the query, the best matches first
+    #[cfg(feature = "autocomplete")]
+    fn suggest_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<ChannelMarker>>;
+
+    /// Get up to `limit` emojis of the guild whose names match the query, the
+    /// best matches first
+    #[cfg(feature = "autocomplete")]
+    fn suggest_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<EmojiMarker>>;
+
+    /// Get the members of the guild that look inactive since the given time,
+    /// to preview which members pruning the guild would remove
+    async fn inactive_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+
+    /// Get a cached guild by its ID
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>>;
+
+    /// Get a cached guild by its ID, shared through the pool returned by
+    /// [`Cache::arc_pool`]
+    async fn guild_ref(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<Arc<CachedGuild>>, Error<Self::Error>>;
+
+    /// Get the URL of a cached guild's vanity invite by its ID, `None` if the
+    /// guild isn't cached or doesn't have a vanity invite
+    async fn guild_vanity_url(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<String>, Error<Self::Error>>;
+
+    /// Get the cached guilds that have the given feature, for example
+    /// [`GuildFeature::Community`] or [`GuildFeature::Discoverable`]
+    async fn guilds_with_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Error<Self::Error>>;
+
+    /// Get a cached role by its ID
+    async fn role(&self, role_id: Id<RoleMarker>)
+        -> Result<Option<CachedRole>, Error<Self::Error>>;
+
+    /// Get a cached role by its ID, shared through the pool returned by
+    /// [`Cache::arc_pool`]
+    async fn role_ref(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<Arc<CachedRole>>, Error<Self::Error>>;
+
+    /// Get the everyone role of a guild by the guild's ID
+    async fn everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Error<Self::Error>>;
+
+    /// Get a guild's roles by its ID
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+
+    /// Get the IDs of the members that have the given role
+    async fn role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>>;
+
+    /// Get a cached emoji by its ID
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>>;
+
+    /// Get a guild's emojis by its ID
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>>;
+
+    /// Get a cached sticker by its ID
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a guild's stickers by its ID
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a cached stage instance by its ID
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>>;
+
+    /// Get the emojis used in reactions in the guild since the given time and
+    /// the number of times they were used, the most used first
+    #[cfg(feature = "stats")]
+    async fn emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(String, u64)>, Error<Self::Error>>;
+
+    /// Get the stickers sent in messages in the guild since the given time and
+    /// the number of times they were sent, the most sent first
+    #[cfg(feature = "stats")]
+    async fn sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Error<Self::Error>>;
+
+    /// Get the most recent `limit` changes to a channel or role, the most
+    /// recent first
+    #[cfg(feature = "changelog")]
+    async fn entity_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Error<Self::Error>>;
+
+    /// Get a cached channel or thread by its ID, returning an error if it
+    /// isn't cached
+    async fn try_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<CachedChannel, Error<Self::Error>>;
+
+    /// Get a cached message by its ID, returning an error if it isn't cached
+    async fn try_message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<CachedMessage, Error<Self::Error>>;
+
+    /// Get a cached member by its guild ID and user ID, returning an error if
+    /// it isn't cached
+    async fn try_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedMember, Error<Self::Error>>;
+
+    /// Get a cached guild by its ID, returning an error if it isn't cached
+    async fn try_guild(&self, guild_id: Id<GuildMarker>)
+        -> Result<CachedGuild, Error<Self::Error>>;
+
+    /// Get a cached role by its ID, returning an error if it isn't cached
+    async fn try_role(&self, role_id: Id<RoleMarker>) -> Result<CachedRole, Error<Self::Error>>;
+
+    /// Get a cached emoji by its ID, returning an error if it isn't cached
+    async fn try_emoji(&self, emoji_id: Id<EmojiMarker>)
+        -> Result<CachedEmoji, Error<Self::Error>>;
+
+    /// Get a cached sticker by its ID, returning an error if it isn't cached
+    async fn try_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<CachedSticker, Error<Self::Error>>;
+
+    /// Get a cached stage instance by its ID, returning an error if it isn't
+    /// cached
+    async fn try_stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<StageInstance, Error<Self::Error>>;
+
+    /// Get a guild's channels and threads by its ID, returning an error if
+    /// the guild isn't cached
+    async fn try_guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>>;
+
+    /// Get a guild's members by its ID, returning an error if the guild isn't
+    /// cached
+    async fn try_guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>>;
+
+    /// Get a guild's roles by its ID, returning an error if the guild isn't
+    /// cached
+    async fn try_guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>>;
+
+    /// Get a guild's emojis by its ID, returning an error if the guild isn't
+    /// cached
+    async fn try_guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>>;
+
+    /// Get a guild's stickers by its ID, returning an error if the guild isn't
+    /// cached
+    async fn try_guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>>;
+
+    /// Get a channel's most recent `limit` messages by its ID, returning an
+    /// error if the channel isn't cached
+    async fn try_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>>;
+}
+
+#[async_trait]
+impl<T: Cache + Send + Sync> CacheReader for T {
+    type Error = <T as Backend>::Error;
+
+    async fn stats(&self) -> Result<CacheStats, Error<T::Error>> {
+        Cache::stats(self).await
+    }
+
+    async fn verify_integrity(&self) -> Result<IntegrityReport, Error<T::Error>> {
+        Cache::verify_integrity(self, false).await
+    }
+
+    #[cfg(feature = "http")]
+    async fn diff_against_http(
+        &self,
+        http: &Client,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CacheDrift, Error<T::Error>> {
+        Cache::diff_against_http(self, http, guild_id).await
+    }
+
+    async fn missing_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[Id<UserMarker>],
+    ) -> Result<Vec<Id<UserMarker>>, Error<T::Error>> {
+        Cache::missing_members(self, guild_id, user_ids).await
+    }
+
+    async fn self_channel_permissions(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<T::Error>> {
+        Cache::self_channel_permissions(self, channel_id).await
+    }
+
+    async fn self_guild_permissions(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<T::Error>> {
+        Cache::self_guild_permissions(self, guild_id).await
+    }
+
+    async fn channel_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Permissions, Error<T::Error>> {
+        Cache::channel_permissions(self, user_id, channel_id).await
+    }
+
+    async fn guild_permissions(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Permissions, Error<T::Error>> {
+        Cache::guild_permissions(self, user_id, guild_id).await
+    }
+
+    async fn is_owner(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<T::Error>> {
+        Cache::is_owner(self, user_id, guild_id).await
+    }
+
+    async fn is_admin(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<bool, Error<T::Error>> {
+        Cache::is_admin(self, user_id, guild_id).await
+    }
+
+    async fn roles_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<CachedRole>, Error<T::Error>> {
+        Cache::roles_with_permission(self, guild_id, permissions).await
+    }
+
+    async fn members_with_permission(
+        &self,
+        guild_id: Id<GuildMarker>,
+        permissions: Permissions,
+    ) -> Result<Vec<Id<UserMarker>>, Error<T::Error>> {
+        Cache::members_with_permission(self, guild_id, permissions).await
+    }
+
+    async fn visible_channels(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::visible_channels(self, user_id, guild_id).await
+    }
+
+    async fn current_user(&self) -> Result<CurrentUser, Error<T::Error>> {
+        Cache::current_user(self).await
+    }
+
+    async fn current_application(&self) -> Result<PartialApplication, Error<T::Error>> {
+        Cache::current_application(self).await
+    }
+
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<T::Error>> {
+        Cache::session_info(self).await
+    }
+
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<T::Error>> {
+        Cache::shard_state(self, shard_id).await
+    }
+
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<T::Error>> {
+        Cache::shard_states(self).await
+    }
+
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<T::Error>> {
+        Cache::interaction(self, interaction_id).await
+    }
+
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<T::Error>> {
+        Cache::channel(self, channel_id).await
+    }
+
+    async fn channel_ref(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<Arc<CachedChannel>>, Error<T::Error>> {
+        Cache::channel_ref(self, channel_id).await
+    }
+
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<T::Error>> {
+        Cache::permission_overwrites(self, channel_id).await
+    }
+
+    async fn member_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<T::Error>> {
+        Cache::member_overwrites(self, channel_id, user_id).await
+    }
+
+    async fn role_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedPermissionOverwrite>, Error<T::Error>> {
+        Cache::role_overwrites(self, channel_id, role_id).await
+    }
+
+    async fn channel_synced_with_category(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<bool>, Error<T::Error>> {
+        Cache::channel_synced_with_category(self, channel_id).await
+    }
+
+    async fn channel_permission_matrix(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<ChannelRolePermissions>, Error<T::Error>> {
+        Cache::channel_permission_matrix(self, channel_id).await
+    }
+
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<T::Error>> {
+        Cache::private_channel(self, user_id).await
+    }
+
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<T::Error>> {
+        Cache::private_channel_users(self, channel_id).await
+    }
+
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::guild_channels(self, guild_id).await
+    }
+
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::archived_threads(self, guild_id).await
+    }
+
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::inactive_channels(self, guild_id, since).await
+    }
+
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::active_threads(self, channel_id).await
+    }
+
+    async fn announcement_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::announcement_channels(self, guild_id).await
+    }
+
+    async fn guild_channels_ordered(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::guild_channels_ordered(self, guild_id).await
+    }
+
+    async fn guild_structure_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<GuildStructure>, Error<T::Error>> {
+        Cache::guild_structure_snapshot(self, guild_id).await
+    }
+
+    async fn channel_slowmode(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<u16>, Error<T::Error>> {
+        Cache::channel_slowmode(self, channel_id).await
+    }
+
+    async fn can_send_now(
+        &self,
+        user_id: Id<UserMarker>,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<bool, Error<T::Error>> {
+        Cache::can_send_now(self, user_id, channel_id).await
+    }
+
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<T::Error>> {
+        Cache::message(self, message_id).await
+    }
+
+    async fn thread_starter_message(
+        &self,
+        thread_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedMessage>, Error<T::Error>> {
+        Cache::thread_starter_message(self, thread_id).await
+    }
+
+    async fn reply_chain(
+        &self,
+        message_id: Id<MessageMarker>,
+        depth: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::reply_chain(self, message_id, depth).await
+    }
+
+    async fn embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<(CachedEmbed, Vec<CachedEmbedField>)>, Error<T::Error>> {
+        Cache::embeds(self, message_id).await
+    }
+
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<T::Error>> {
+        Cache::message_revisions(self, message_id).await
+    }
+
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<T::Error>> {
+        Cache::attachments(self, message_id).await
+    }
+
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::attachment_posters(self, file_hash).await
+    }
+
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<T::Error>> {
+        Cache::reactions(self, message_id).await
+    }
+
+    async fn reaction_counts(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<ReactionCount>, Error<T::Error>> {
+        Cache::reaction_counts(self, message_id).await
+    }
+
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<T::Error>> {
+        Cache::stickers(self, message_id).await
+    }
+
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::channel_messages(self, channel_id, limit).await
+    }
+
+    async fn channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::channel_messages_range(self, channel_id, after, before, limit).await
+    }
+
+    async fn channel_messages_filtered(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::channel_messages_filtered(self, channel_id, filter, limit).await
+    }
+
+    async fn channel_user_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::channel_user_messages(self, channel_id, limit).await
+    }
+
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::messages_with_sticker(self, sticker_id, limit).await
+    }
+
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::channel_webhook_messages(self, channel_id, limit).await
+    }
+
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::messages_by_webhook(self, webhook_id, limit).await
+    }
+
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::messages_with_emoji_reaction(self, emoji, guild_id, limit).await
+    }
+
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<T::Error>> {
+        Cache::member(self, user_id, guild_id).await
+    }
+
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<T::Error>> {
+        Cache::member_roles(self, user_id, guild_id).await
+    }
+
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<T::Error>> {
+        Cache::presence(self, user_id).await
+    }
+
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<T::Error>> {
+        Cache::member_activities(self, user_id).await
+    }
+
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<T::Error>> {
+        Cache::guild_member_activities(self, guild_id, user_id).await
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<T::Error>> {
+        Cache::guild_ids(self).await
+    }
+
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<T::Error>> {
+        Cache::unavailable_guilds(self).await
+    }
+
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<T::Error>> {
+        Cache::guild_members(self, guild_id).await
+    }
+
+    async fn mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Error<T::Error>> {
+        Cache::mutual_guilds(self, user_a, user_b).await
+    }
+
+    async fn search_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Error<T::Error>> {
+        Cache::search_members(self, guild_id, prefix, limit).await
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn suggest_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<RoleMarker>> {
+        Cache::suggest_roles(self, guild_id, query, limit)
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn suggest_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<ChannelMarker>> {
+        Cache::suggest_channels(self, guild_id, query, limit)
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn suggest_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+        query: &str,
+        limit: u16,
+    ) -> Vec<Suggestion<EmojiMarker>> {
+        Cache::suggest_emojis(self, guild_id, query, limit)
+    }
+
+    async fn inactive_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedMember>, Error<T::Error>> {
+        Cache::inactive_members(self, guild_id, since).await
+    }
+
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<T::Error>> {
+        Cache::guild(self, guild_id).await
+    }
+
+    async fn guild_ref(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<Arc<CachedGuild>>, Error<T::Error>> {
+        Cache::guild_ref(self, guild_id).await
+    }
+
+    async fn guild_vanity_url(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<String>, Error<T::Error>> {
+        Cache::guild_vanity_url(self, guild_id).await
+    }
+
+    async fn guilds_with_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Error<T::Error>> {
+        Cache::guilds_with_feature(self, feature).await
+    }
+
+    async fn role(&self, role_id: Id<RoleMarker>) -> Result<Option<CachedRole>, Error<T::Error>> {
+        Cache::role(self, role_id).await
+    }
+
+    async fn role_ref(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<Arc<CachedRole>>, Error<T::Error>> {
+        Cache::role_ref(self, role_id).await
+    }
+
+    async fn everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Error<T::Error>> {
+        Cache::everyone_role(self, guild_id).await
+    }
+
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<T::Error>> {
+        Cache::guild_roles(self, guild_id).await
+    }
+
+    async fn role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<T::Error>> {
+        Cache::role_members(self, role_id).await
+    }
+
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<T::Error>> {
+        Cache::emoji(self, emoji_id).await
+    }
+
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<T::Error>> {
+        Cache::guild_emojis(self, guild_id).await
+    }
+
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<T::Error>> {
+        Cache::sticker(self, sticker_id).await
+    }
+
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<T::Error>> {
+        Cache::guild_stickers(self, guild_id).await
+    }
+
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<T::Error>> {
+        Cache::stage_instance(self, stage_id).await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(String, u64)>, Error<T::Error>> {
+        Cache::emoji_usage(self, guild_id, since).await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Error<T::Error>> {
+        Cache::sticker_usage(self, guild_id, since).await
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn entity_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Error<T::Error>> {
+        Cache::entity_changelog(self, kind, entity_id, limit).await
+    }
+
+    async fn try_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<CachedChannel, Error<T::Error>> {
+        Cache::try_channel(self, channel_id).await
+    }
+
+    async fn try_message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<CachedMessage, Error<T::Error>> {
+        Cache::try_message(self, message_id).await
+    }
+
+    async fn try_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<CachedMember, Error<T::Error>> {
+        Cache::try_member(self, user_id, guild_id).await
+    }
+
+    async fn try_guild(&self, guild_id: Id<GuildMarker>) -> Result<CachedGuild, Error<T::Error>> {
+        Cache::try_guild(self, guild_id).await
+    }
+
+    async fn try_role(&self, role_id: Id<RoleMarker>) -> Result<CachedRole, Error<T::Error>> {
+        Cache::try_role(self, role_id).await
+    }
+
+    async fn try_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<CachedEmoji, Error<T::Error>> {
+        Cache::try_emoji(self, emoji_id).await
+    }
+
+    async fn try_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<CachedSticker, Error<T::Error>> {
+        Cache::try_sticker(self, sticker_id).await
+    }
+
+    async fn try_stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<StageInstance, Error<T::Error>> {
+        Cache::try_stage_instance(self, stage_id).await
+    }
+
+    async fn try_guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<T::Error>> {
+        Cache::try_guild_channels(self, guild_id).await
+    }
+
+    async fn try_guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<T::Error>> {
+        Cache::try_guild_members(self, guild_id).await
+    }
+
+    async fn try_guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<T::Error>> {
+        Cache::try_guild_roles(self, guild_id).await
+    }
+
+    async fn try_guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<T::Error>> {
+        Cache::try_guild_emojis(self, guild_id).await
+    }
+
+    async fn try_guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<T::Error>> {
+        Cache::try_guild_stickers(self, guild_id).await
+    }
+
+    async fn try_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<T::Error>> {
+        Cache::try_channel_messages(self, channel_id, limit).await
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{de::DeserializeOwned, ser::Error as _, Serialize};
+use serde_json::{Map, Value};
+
+/// Functions returning the names of the keys
+///
+/// The keys are separated by colons and start with the name of the model or
+/// the model they belong to, for example `guild:1:member:2` for the member
+/// with the user ID `2` in the guild with the ID `1`
+///
+/// The keys of indexes end with the plural name of the models they contain,
+/// for example `guild:1:members`
+pub mod keys;
+/// Implementations of [`RedisModel`] for the cached models
+mod model;
+
+/// A model that's stored in a Redis hash
+///
+/// It's implemented for every cached model that has a unique key, embed fields
+/// and activities don't have one, so they should be stored under the keys
+/// returned by [`keys::embed_field`] and [`keys::activity`] instead
+pub trait RedisModel: Serialize + DeserializeOwned {
+    /// Return the key of the hash the model is stored in
+    fn key(&self) -> String;
+
+    /// Return the indexes the model's key should be added to
+    fn indexes(&self) -> Vec<Index>;
+}
+
+/// A secondary index, which is a set containing the keys of the models that
+/// have something in common, for example the channels of a guild
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Index {
+    /// A set, the model's key should be added with `SADD` and removed with
+    /// `SREM`
+    Set(String),
+    /// A sorted set, the model's key should be added with `ZADD` using the
+    /// given score and removed with `ZREM`
+    SortedSet(String, i64),
+}
+
+impl Index {
+    /// Return the key of the set
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Set(key) | Self::SortedSet(key, _) => key,
+        }
+    }
+}
+
+/// The indexes to update when a model is replaced, returned by
+/// [`index_changes`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexChanges {
+    /// The indexes the model's key should be added to
+    pub added: Vec<Index>,
+    /// The indexes the model's key should be removed from
+    pub removed: Vec<Index>,
+}
+
+/// Return the indexes to update when the old model is replaced with the new
+/// one, or when the new model is added if there's no old model
+///
+/// When a model is removed, its key should be removed from all of
+/// [`RedisModel::indexes`]
+#[must_use]
+pub fn index_changes<T: RedisModel>(old: Option<&T>, new: &T) -> IndexChanges {
+    let old_indexes = old.map(RedisModel::indexes).unwrap_or_default();
+    let new_indexes = new.indexes();
+
+    IndexChanges {
+        added: new_indexes
+            .iter()
+            .filter(|index| !old_indexes.contains(index))
+            .cloned()
+            .collect(),
+        removed: old_indexes
+            .into_iter()
+            .filter(|index| !new_indexes.contains(index))
+            .collect(),
+    }
+}
+
+/// Serialize a model into the fields and values of a hash, to use with
+/// `HSET`
+///
+/// Each value is serialized as JSON, fields that are `None` are skipped, so
+/// the hash should be deleted before it's set again
+///
+/// # Errors
+///
+/// Returns an error when the model couldn't be serialized as a JSON object
+pub fn to_hash<T: Serialize>(model: &T) -> Result<Vec<(String, String)>, serde_json::Error> {
+    let Value::Object(map) = serde_json::to_value(model)? else {
+        return Err(serde_json::Error::custom("the model isn't a struct"));
+    };
+
+    Ok(map
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(field, value)| (field, value.to_string()))
+        .collect())
+}
+
+/// Deserialize a model from the fields and values of a hash, returned by
+/// `HGETALL`
+///
+/// # Errors
+///
+/// Returns an error when a value isn't valid JSON or the hash is missing a
+/// required field
+pub fn from_hash<T, I>(hash: I) -> Result<T, serde_json::Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (String, String)>,
+{
+    let map = hash
+        .into_iter()
+        .map(|(field, value)| Ok((field, serde_json::from_str(&value)?)))
+        .collect::<Result<Map<String, Value>, serde_json::Error>>()?;
+
+    serde_json::from_value(Value::Object(map))
+}
@@ -0,0 +1,372 @@
+use twilight_model::id::{
+    marker::{
+        AttachmentMarker, ChannelMarker, EmojiMarker, GenericMarker, GuildMarker,
+        InteractionMarker, MessageMarker, RoleMarker, StageMarker, StickerMarker, UserMarker,
+    },
+    Id,
+};
+
+use crate::cache::EntityKind;
+
+/// Return the key of the current user
+#[must_use]
+pub fn current_user() -> String {
+    "current_user".to_owned()
+}
+
+/// Return the key of the current application
+#[must_use]
+pub fn current_application() -> String {
+    "current_application".to_owned()
+}
+
+/// Return the key of a shard's session
+#[must_use]
+pub fn session(shard_id: u64) -> String {
+    format!("session:{shard_id}")
+}
+
+/// Return the key of the set of the sessions of all shards
+#[must_use]
+pub fn sessions() -> String {
+    "sessions".to_owned()
+}
+
+/// Return the key of a shard's state
+#[must_use]
+pub fn shard_state(shard_id: u64) -> String {
+    format!("shard_state:{shard_id}")
+}
+
+/// Return the key of the set of the states of all shards
+#[must_use]
+pub fn shard_states() -> String {
+    "shard_states".to_owned()
+}
+
+/// Return the key of an interaction
+#[must_use]
+pub fn interaction(interaction_id: Id<InteractionMarker>) -> String {
+    format!("interaction:{interaction_id}")
+}
+
+/// Return the key of the sorted set of all interactions, scored by when their
+/// tokens expire, so that the expired ones can be removed
+#[must_use]
+pub fn interactions() -> String {
+    "interactions".to_owned()
+}
+
+/// Return the key of a channel
+#[must_use]
+pub fn channel(channel_id: Id<ChannelMarker>) -> String {
+    format!("channel:{channel_id}")
+}
+
+/// Return the key of the set of a guild's channels
+#[must_use]
+pub fn guild_channels(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:channels")
+}
+
+/// Return the key of a channel's permission overwrite for the given role or
+/// user
+#[must_use]
+pub fn permission_overwrite(channel_id: Id<ChannelMarker>, id: Id<GenericMarker>) -> String {
+    format!("channel:{channel_id}:permission_overwrite:{id}")
+}
+
+/// Return the key of the set of a channel's permission overwrites
+#[must_use]
+pub fn permission_overwrites(channel_id: Id<ChannelMarker>) -> String {
+    format!("channel:{channel_id}:permission_overwrites")
+}
+
+/// Return the key of a DM channel's recipient
+#[must_use]
+pub fn private_channel(channel_id: Id<ChannelMarker>, user_id: Id<UserMarker>) -> String {
+    format!("channel:{channel_id}:recipient:{user_id}")
+}
+
+/// Return the key of the set of a DM channel's recipients
+#[must_use]
+pub fn private_channel_users(channel_id: Id<ChannelMarker>) -> String {
+    format!("channel:{channel_id}:recipients")
+}
+
+/// Return the key of the set of the DM channels a user is a recipient of
+#[must_use]
+pub fn user_private_channels(user_id: Id<UserMarker>) -> String {
+    format!("user:{user_id}:private_channels")
+}
+
+/// Return the key of a message
+#[must_use]
+pub fn message(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}")
+}
+
+/// Return the key of the sorted set of a channel's messages, scored by their
+/// timestamps in microseconds
+#[must_use]
+pub fn channel_messages(channel_id: Id<ChannelMarker>) -> String {
+    format!("channel:{channel_id}:messages")
+}
+
+/// Return the key of a message's revision
+#[must_use]
+pub fn message_revision(message_id: Id<MessageMarker>, revision_id: Id<GenericMarker>) -> String {
+    format!("message:{message_id}:revision:{revision_id}")
+}
+
+/// Return the key of the sorted set of a message's revisions, scored by the
+/// time they were edited at
+#[must_use]
+pub fn message_revisions(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}:revisions")
+}
+
+/// Return the key of an embed
+#[must_use]
+pub fn embed(embed_id: Id<GenericMarker>) -> String {
+    format!("embed:{embed_id}")
+}
+
+/// Return the key of the set of a message's embeds
+#[must_use]
+pub fn message_embeds(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}:embeds")
+}
+
+/// Return the key of an embed's field at the given position
+#[must_use]
+pub fn embed_field(embed_id: Id<GenericMarker>, position: usize) -> String {
+    format!("embed:{embed_id}:field:{position}")
+}
+
+/// Return the key of the sorted set of an embed's fields, scored by their
+/// positions
+#[must_use]
+pub fn embed_fields(embed_id: Id<GenericMarker>) -> String {
+    format!("embed:{embed_id}:fields")
+}
+
+/// Return the key of an attachment
+#[must_use]
+pub fn attachment(attachment_id: Id<AttachmentMarker>) -> String {
+    format!("attachment:{attachment_id}")
+}
+
+/// Return the key of the set of a message's attachments
+#[must_use]
+pub fn message_attachments(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}:attachments")
+}
+
+/// Return the key of a user's reaction with the given emoji to a message
+#[must_use]
+pub fn reaction(message_id: Id<MessageMarker>, user_id: Id<UserMarker>, emoji: &str) -> String {
+    format!("message:{message_id}:reaction:{user_id}:{emoji}")
+}
+
+/// Return the key of the set of a message's reactions
+#[must_use]
+pub fn message_reactions(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}:reactions")
+}
+
+/// Return the key of a member
+#[must_use]
+pub fn member(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("guild:{guild_id}:member:{user_id}")
+}
+
+/// Return the key of the set of a guild's members
+#[must_use]
+pub fn guild_members(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:members")
+}
+
+/// Return the key of a member's presence
+#[must_use]
+pub fn presence(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("guild:{guild_id}:presence:{user_id}")
+}
+
+/// Return the key of the set of a guild's presences
+#[must_use]
+pub fn guild_presences(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:presences")
+}
+
+/// Return the key of the set of a user's presences in all guilds
+#[must_use]
+pub fn user_presences(user_id: Id<UserMarker>) -> String {
+    format!("user:{user_id}:presences")
+}
+
+/// Return the key of a member's activity at the given position
+#[must_use]
+pub fn activity(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>, position: usize) -> String {
+    format!("guild:{guild_id}:member:{user_id}:activity:{position}")
+}
+
+/// Return the key of the sorted set of a member's activities, scored by their
+/// positions
+#[must_use]
+pub fn activities(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("guild:{guild_id}:member:{user_id}:activities")
+}
+
+/// Return the key of a guild
+#[must_use]
+pub fn guild(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}")
+}
+
+/// Return the key of the set of all guilds
+#[must_use]
+pub fn guilds() -> String {
+    "guilds".to_owned()
+}
+
+/// Return the key of a role
+#[must_use]
+pub fn role(role_id: Id<RoleMarker>) -> String {
+    format!("role:{role_id}")
+}
+
+/// Return the key of the set of a guild's roles
+#[must_use]
+pub fn guild_roles(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:roles")
+}
+
+/// Return the key of a role assigned to a member
+#[must_use]
+pub fn member_role(
+    guild_id: Id<GuildMarker>,
+    user_id: Id<UserMarker>,
+    role_id: Id<RoleMarker>,
+) -> String {
+    format!("guild:{guild_id}:member:{user_id}:role:{role_id}")
+}
+
+/// Return the key of the set of a member's roles
+#[must_use]
+pub fn member_roles(guild_id: Id<GuildMarker>, user_id: Id<UserMarker>) -> String {
+    format!("guild:{guild_id}:member:{user_id}:roles")
+}
+
+/// Return the key of the set of the members a role is assigned to
+#[must_use]
+pub fn role_members(role_id: Id<RoleMarker>) -> String {
+    format!("role:{role_id}:members")
+}
+
+/// Return the key of an emoji
+#[must_use]
+pub fn emoji(emoji_id: Id<EmojiMarker>) -> String {
+    format!("emoji:{emoji_id}")
+}
+
+/// Return the key of the set of a guild's emojis
+#[must_use]
+pub fn guild_emojis(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:emojis")
+}
+
+/// Return the key of a sticker
+#[must_use]
+pub fn sticker(sticker_id: Id<StickerMarker>) -> String {
+    format!("sticker:{sticker_id}")
+}
+
+/// Return the key of the set of a guild's stickers
+#[must_use]
+pub fn guild_stickers(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:stickers")
+}
+
+/// Return the key of a message's sticker
+#[must_use]
+pub fn message_sticker(message_id: Id<MessageMarker>, sticker_id: Id<StickerMarker>) -> String {
+    format!("message:{message_id}:sticker:{sticker_id}")
+}
+
+/// Return the key of the set of a message's stickers
+#[must_use]
+pub fn message_stickers(message_id: Id<MessageMarker>) -> String {
+    format!("message:{message_id}:stickers")
+}
+
+/// Return the key of a stage instance
+#[must_use]
+pub fn stage_instance(stage_id: Id<StageMarker>) -> String {
+    format!("stage_instance:{stage_id}")
+}
+
+/// Return the key of the set of a guild's stage instances
+#[must_use]
+pub fn guild_stage_instances(guild_id: Id<GuildMarker>) -> String {
+    format!("guild:{guild_id}:stage_instances")
+}
+
+/// Return the key of the entity of the given kind and ID, as passed to
+/// [`crate::Backend::set_expiry`], to set its TTL with `PEXPIREAT`
+///
+/// Returns `None` if the ID is 0, or if the kind is a member or presence and
+/// `guild_id` is `None`
+#[must_use]
+pub fn entity(
+    kind: EntityKind,
+    entity_id: u64,
+    guild_id: Option<Id<GuildMarker>>,
+) -> Option<String> {
+    match kind {
+        EntityKind::Channel => Id::new_checked(entity_id).map(channel),
+        EntityKind::Message => Id::new_checked(entity_id).map(message),
+        EntityKind::Member => Some(member(guild_id?, Id::new_checked(entity_id)?)),
+        EntityKind::Guild => Id::new_checked(entity_id).map(guild),
+        EntityKind::Role => Id::new_checked(entity_id).map(role),
+        EntityKind::Emoji => Id::new_checked(entity_id).map(emoji),
+        EntityKind::Sticker => Id::new_checked(entity_id).map(sticker),
+        EntityKind::StageInstance => Id::new_checked(entity_id).map(stage_instance),
+        EntityKind::Presence => Some(presence(guild_id?, Id::new_checked(entity_id)?)),
+    }
+}
+
+/// Return the key of the sorted set of the emojis used in reactions in a guild
+/// on a day, scored by their number of uses
+///
+/// `day` is the number of days since the Unix epoch in UTC
+#[cfg(feature = "stats")]
+#[must_use]
+pub fn emoji_usage(guild_id: Id<GuildMarker>, day: u64) -> String {
+    format!("guild:{guild_id}:emoji_usage:{day}")
+}
+
+/// Return the key of the sorted set of the stickers sent in messages in a
+/// guild on a day, scored by their number of uses
+///
+/// `day` is the number of days since the Unix epoch in UTC
+#[cfg(feature = "stats")]
+#[must_use]
+pub fn sticker_usage(guild_id: Id<GuildMarker>, day: u64) -> String {
+    format!("guild:{guild_id}:sticker_usage:{day}")
+}
+
+/// Return the key of a journal entry
+#[cfg(feature = "journal")]
+#[must_use]
+pub fn journal_entry(entry_id: Id<GenericMarker>) -> String {
+    format!("journal:{entry_id}")
+}
+
+/// Return the key of the sorted set of the journal entries, scored by the
+/// microseconds since the Unix epoch they were recorded at
+#[cfg(feature = "journal")]
+#[must_use]
+pub fn journal() -> String {
+    "journal".to_owned()
+}
@@ -0,0 +1,265 @@
+use twilight_model::{channel::StageInstance, oauth::PartialApplication, user::CurrentUser};
+
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::{
+    model::{
+        CachedAttachment, CachedChannel, CachedEmbed, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker,
+    },
+    redis_util::{keys, Index, RedisModel},
+};
+
+impl RedisModel for CurrentUser {
+    fn key(&self) -> String {
+        keys::current_user()
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![]
+    }
+}
+
+impl RedisModel for PartialApplication {
+    fn key(&self) -> String {
+        keys::current_application()
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![]
+    }
+}
+
+impl RedisModel for CachedSession {
+    fn key(&self) -> String {
+        keys::session(self.shard_id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::sessions())]
+    }
+}
+
+impl RedisModel for CachedShardState {
+    fn key(&self) -> String {
+        keys::shard_state(self.shard_id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::shard_states())]
+    }
+}
+
+impl RedisModel for CachedInteraction {
+    fn key(&self) -> String {
+        keys::interaction(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::SortedSet(
+            keys::interactions(),
+            self.token_expires_at.as_micros(),
+        )]
+    }
+}
+
+impl RedisModel for CachedChannel {
+    fn key(&self) -> String {
+        keys::channel(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        self.guild_id
+            .map(|guild_id| Index::Set(keys::guild_channels(guild_id)))
+            .into_iter()
+            .collect()
+    }
+}
+
+impl RedisModel for CachedPermissionOverwrite {
+    fn key(&self) -> String {
+        keys::permission_overwrite(self.channel_id, self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::permission_overwrites(self.channel_id))]
+    }
+}
+
+impl RedisModel for CachedPrivateChannel {
+    fn key(&self) -> String {
+        keys::private_channel(self.channel_id, self.user_id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![
+            Index::Set(keys::private_channel_users(self.channel_id)),
+            Index::Set(keys::user_private_channels(self.user_id)),
+        ]
+    }
+}
+
+impl RedisModel for CachedMessage {
+    fn key(&self) -> String {
+        keys::message(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::SortedSet(
+            keys::channel_messages(self.channel_id),
+            self.timestamp.as_micros(),
+        )]
+    }
+}
+
+impl RedisModel for CachedMessageRevision {
+    fn key(&self) -> String {
+        keys::message_revision(self.message_id, self.revision_id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::SortedSet(
+            keys::message_revisions(self.message_id),
+            self.edited_at.as_micros(),
+        )]
+    }
+}
+
+impl RedisModel for CachedEmbed {
+    fn key(&self) -> String {
+        keys::embed(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::message_embeds(self.message_id))]
+    }
+}
+
+impl RedisModel for CachedAttachment {
+    fn key(&self) -> String {
+        keys::attachment(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::message_attachments(self.message_id))]
+    }
+}
+
+impl RedisModel for CachedReaction {
+    fn key(&self) -> String {
+        keys::reaction(self.message_id, self.user_id, &self.emoji)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::message_reactions(self.message_id))]
+    }
+}
+
+impl RedisModel for CachedMember {
+    fn key(&self) -> String {
+        keys::member(self.guild_id, self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::guild_members(self.guild_id))]
+    }
+}
+
+impl RedisModel for CachedPresence {
+    fn key(&self) -> String {
+        keys::presence(self.guild_id, self.user)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![
+            Index::Set(keys::guild_presences(self.guild_id)),
+            Index::Set(keys::user_presences(self.user)),
+        ]
+    }
+}
+
+impl RedisModel for CachedGuild {
+    fn key(&self) -> String {
+        keys::guild(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::guilds())]
+    }
+}
+
+impl RedisModel for CachedRole {
+    fn key(&self) -> String {
+        keys::role(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::guild_roles(self.guild_id))]
+    }
+}
+
+impl RedisModel for CachedRoleAssignment {
+    fn key(&self) -> String {
+        keys::member_role(self.guild_id, self.user_id, self.role_id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![
+            Index::Set(keys::member_roles(self.guild_id, self.user_id)),
+            Index::Set(keys::role_members(self.role_id)),
+        ]
+    }
+}
+
+impl RedisModel for CachedEmoji {
+    fn key(&self) -> String {
+        keys::emoji(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::guild_emojis(self.guild_id))]
+    }
+}
+
+impl RedisModel for CachedSticker {
+    fn key(&self) -> String {
+        match self.message_id {
+            Some(message_id) => keys::message_sticker(message_id, self.id),
+            None => keys::sticker(self.id),
+        }
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        match (self.message_id, self.guild_id) {
+            (Some(message_id), _) => vec![Index::Set(keys::message_stickers(message_id))],
+            (None, Some(guild_id)) => vec![Index::Set(keys::guild_stickers(guild_id))],
+            (None, None) => vec![],
+        }
+    }
+}
+
+impl RedisModel for StageInstance {
+    fn key(&self) -> String {
+        keys::stage_instance(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::Set(keys::guild_stage_instances(self.guild_id))]
+    }
+}
+
+#[cfg(feature = "journal")]
+impl RedisModel for CachedJournalEntry {
+    fn key(&self) -> String {
+        keys::journal_entry(self.id)
+    }
+
+    fn indexes(&self) -> Vec<Index> {
+        vec![Index::SortedSet(
+            keys::journal(),
+            self.recorded_at.as_micros(),
+        )]
+    }
+}
@@ -0,0 +1,498 @@
+use twilight_model::{channel::StageInstance, oauth::PartialApplication, user::CurrentUser};
+
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::model::{
+    CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost, CachedChannel,
+    CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction, CachedMember,
+    CachedMessage, CachedMessageRevision, CachedPermissionOverwrite, CachedPresence,
+    CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment, CachedSession,
+    CachedShardState, CachedSticker,
+};
+#[cfg(feature = "stats")]
+use crate::model::{CachedEmojiUse, CachedStickerUse};
+
+/// Implementations of [`CachedTable`] for the cached models
+mod tables;
+
+/// The version of the schema of the cached models
+///
+/// It's incremented whenever a model or table in this crate changes in a way
+/// that makes the data written by older versions incompatible, the changes of
+/// each version are described in [`MIGRATIONS`]
+pub const CURRENT_SCHEMA_VERSION: u32 = 30;
+
+/// The changes of each schema version, ordered from the oldest to the newest
+///
+/// Use these to implement [`crate::Backend::migrate`]
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "The first versioned schema, the same as the tables described in this module",
+    },
+    Migration {
+        version: 2,
+        description: "Adds the `private_channels` table, storing the recipients of DM channels",
+    },
+    Migration {
+        version: 3,
+        description: "Adds the `current_applications` and `sessions` tables, storing the \
+                      application and the sessions of the shards from ready events",
+    },
+    Migration {
+        version: 4,
+        description: "Removes the `user_id` column from the `roles` table, making `id` its \
+                      primary key, and adds the `role_assignments` table, storing the roles of \
+                      members, to migrate, move the rows with a `user_id` to `role_assignments` \
+                      and delete them from `roles`",
+    },
+    Migration {
+        version: 5,
+        description: "Adds the nullable `button_1` and `button_2` columns to the `activities` \
+                      table, storing the labels of the activity's buttons",
+    },
+    Migration {
+        version: 6,
+        description: "Adds the nullable `duration_millis`, `flags` and `waveform` columns to the \
+                      `attachments` table, storing the data of voice messages",
+    },
+    Migration {
+        version: 7,
+        description: "Adds the nullable `flags`, `tags_available_for_purchase`, \
+                      `tags_guild_connections` and `tags_subscription_listing_id` columns to the \
+                      `roles` table",
+    },
+    Migration {
+        version: 8,
+        description: "Adds the nullable `max_stage_video_channel_users`, \
+                      `public_updates_channel_id` and `safety_alerts_channel_id` columns to the \
+                      `guilds` table",
+    },
+    Migration {
+        version: 9,
+        description: "Adds the `emoji_uses` and `sticker_uses` tables, storing the usage \
+                      statistics of emojis and stickers, they're only used with the `stats` \
+                      feature",
+    },
+    Migration {
+        version: 10,
+        description: "Adds the `message_revisions` table, storing the previous content of edited \
+                      messages",
+    },
+    Migration {
+        version: 11,
+        description: "Adds the nullable `expires_at` column to the `channels`, `messages`, \
+                      `members`, `presences`, `guilds`, `roles`, `emojis`, `stickers` and \
+                      `stage_instances` tables, storing when the rows expire when a TTL is set",
+    },
+    Migration {
+        version: 12,
+        description: "Adds the `shard_states` table, storing the sequence numbers of the shards",
+    },
+    Migration {
+        version: 13,
+        description: "Adds the `journal` table, storing the events the cache was updated with, \
+                      it's only used with the `journal` feature",
+    },
+    Migration {
+        version: 14,
+        description: "Adds the `partial` column to the `messages` table, storing whether the \
+                      message was created from a message update, existing rows should be set to \
+                      `false`",
+    },
+    Migration {
+        version: 15,
+        description: "Adds the `partial` column to the `channels` table, storing whether the \
+                      channel was created from an incomplete payload, existing rows should be \
+                      set to `false`",
+    },
+    Migration {
+        version: 16,
+        description: "Adds the `interactions` table, storing interactions when they're enabled in \
+                      the config",
+    },
+    Migration {
+        version: 17,
+        description:
+            "Adds the `members_partial` column to the `guilds` table, storing whether the \
+                      guild's members weren't cached from its guild create event, existing rows \
+                      should be set to `false`",
+    },
+    Migration {
+        version: 18,
+        description: "Adds the `author_name`, `author_discriminator` and `author_avatar` columns \
+                      to the `messages` table, storing a snapshot of the author when it's enabled \
+                      in the config",
+    },
+    Migration {
+        version: 19,
+        description: "Adds the `burst` and `burst_colors` columns to the `reactions` table, \
+                      storing whether the reaction is a super reaction, existing rows should be \
+                      set to `false`",
+    },
+    Migration {
+        version: 20,
+        description: "Adds the `changelog` table, storing the snapshots of channels and roles \
+                      before and after they're changed, it's only used with the `changelog` \
+                      feature",
+    },
+    Migration {
+        version: 21,
+        description: "Adds the `author_bot` column to the `messages` table, storing whether the \
+                      message was sent by a bot or a webhook, existing rows should be set to \
+                      `false`",
+    },
+    Migration {
+        version: 22,
+        description: "Adds the `attachment_blobs` and `attachment_posts` tables, storing the \
+                      files of attachments once and the messages they're posted in when it's \
+                      enabled in the config",
+    },
+    Migration {
+        version: 23,
+        description: "Adds the `message_embeds` table, storing the embeds of messages as one \
+                      JSON document when it's enabled in the config, it's only used with the \
+                      `embeds-json` feature",
+    },
+    Migration {
+        version: 24,
+        description: "Adds the `seen_events` table, storing the keys of applied events to skip \
+                      their replays when it's enabled in the config",
+    },
+    Migration {
+        version: 25,
+        description: "Adds the `update_sequences` table, storing the sequence numbers of the \
+                      latest guild and member updates to skip older updates when it's enabled in \
+                      the config",
+    },
+    Migration {
+        version: 26,
+        description: "Adds the `channel_activity` table, storing the time of the latest message \
+                      in each channel when it's enabled in the config",
+    },
+    Migration {
+        version: 27,
+        description: "Adds the nullable `emoji_name` and `emoji_animated` columns to the \
+                      `reactions` table, storing the name of custom emojis and whether they're \
+                      animated, existing rows are treated as reactions with Unicode emojis",
+    },
+    Migration {
+        version: 28,
+        description: "Adds the `extensions` table, storing the values layers wrapping the cache \
+                      persist under their namespaces",
+    },
+    Migration {
+        version: 29,
+        description: "Replaces the `channel_id` and `guild_id` indexes of the `messages` table \
+                      with indexes on `channel_id` and `timestamp` and on `guild_id` and \
+                      `timestamp`, since the messages are filtered by those and ordered by \
+                      their timestamp",
+    },
+    Migration {
+        version: 30,
+        description: "Replaces the `url_hash` columns of the `attachment_blobs` and \
+                      `attachment_posts` tables with `file_hash`, the hash of the file's name, \
+                      size, content type, dimensions and duration, since the URL has the \
+                      attachment's ID in it, and \
+                      moves the `url` and `proxy_url` columns from `attachment_blobs` to \
+                      `attachment_posts`, the existing blobs and posts should be dropped",
+    },
+];
+
+/// The tables of all the cached models, in the order they should be created
+///
+/// The tables of the usage statistics are only included with the `stats`
+/// feature, the journal's table with the `journal` feature, the changelog's
+/// table with the `changelog` feature and the table of the embeds stored as
+/// JSON with the `embeds-json` feature
+pub const TABLES: &[Table] = &[
+    CurrentUser::TABLE,
+    PartialApplication::TABLE,
+    CachedSession::TABLE,
+    CachedShardState::TABLE,
+    CachedInteraction::TABLE,
+    CachedChannel::TABLE,
+    CachedPermissionOverwrite::TABLE,
+    CachedPrivateChannel::TABLE,
+    tables::CHANNEL_ACTIVITY,
+    CachedMessage::TABLE,
+    CachedEmbed::TABLE,
+    CachedEmbedField::TABLE,
+    CachedMessageRevision::TABLE,
+    CachedAttachment::TABLE,
+    CachedAttachmentBlob::TABLE,
+    CachedAttachmentPost::TABLE,
+    CachedReaction::TABLE,
+    CachedMember::TABLE,
+    CachedPresence::TABLE,
+    CachedActivity::TABLE,
+    CachedGuild::TABLE,
+    CachedRole::TABLE,
+    CachedRoleAssignment::TABLE,
+    CachedEmoji::TABLE,
+    CachedSticker::TABLE,
+    StageInstance::TABLE,
+    tables::SEEN_EVENTS,
+    tables::UPDATE_SEQUENCES,
+    tables::EXTENSIONS,
+    #[cfg(feature = "stats")]
+    CachedEmojiUse::TABLE,
+    #[cfg(feature = "stats")]
+    CachedStickerUse::TABLE,
+    #[cfg(feature = "journal")]
+    CachedJournalEntry::TABLE,
+    #[cfg(feature = "changelog")]
+    CachedChangelogEntry::TABLE,
+    #[cfg(feature = "embeds-json")]
+    tables::MESSAGE_EMBEDS,
+];
+
+/// Return the statements to create the tables and indexes of all the cached
+/// models in the given dialect
+///
+/// The tables are only created if they don't exist, so you can run these
+/// every time the bot starts
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::schema::{self, Dialect};
+///
+/// for statement in schema::create_statements(Dialect::Postgres) {
+///     sqlx::query(&statement).execute(&pool).await?;
+/// }
+/// ```
+#[must_use]
+pub fn create_statements(dialect: Dialect) -> Vec<String> {
+    TABLES
+        .iter()
+        .flat_map(|table| table.create_statements(dialect))
+        .collect()
+}
+
+/// Return the indexes the cache's queries need, as the name of the table, the
+/// names of the indexed columns and whether the combination of the columns is
+/// unique
+///
+/// These are derived from the columns the methods of [`crate::Backend`] filter
+/// and order by, the columns are in the order they should be indexed, so the
+/// leading columns of a composite index can serve queries that only filter by
+/// them, primary keys aren't included since they're indexed with the table
+///
+/// Backends that don't use [`create_statements`] should create these, for
+/// example in [`crate::Backend::prepare`]
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::schema;
+///
+/// for (table, columns, unique) in schema::recommended_indexes() {
+///     collection(table).create_index(columns, unique).await?;
+/// }
+/// ```
+#[must_use]
+pub fn recommended_indexes() -> Vec<(&'static str, &'static [&'static str], bool)> {
+    TABLES
+        .iter()
+        .flat_map(|table| {
+            table
+                .indexes
+                .iter()
+                .map(|index| (table.name, index.columns, index.unique))
+        })
+        .collect()
+}
+
+/// The changes made to the schema in a version
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Migration {
+    /// The schema version the changes were made in
+    pub version: u32,
+    /// What changed compared to the previous version, and how to migrate the
+    /// data
+    pub description: &'static str,
+}
+
+/// A model that's stored in an SQL table
+///
+/// The column names are the same as the model's field names, except for the
+/// `expires_at` column of the tables of the models that can expire, which
+/// stores when the row expires, refer to [`crate::config::CacheConfig::ttl`]
+pub trait CachedTable {
+    /// The table the model is stored in
+    const TABLE: Table;
+}
+
+/// The SQL dialect to generate the statements in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// MySQL or MariaDB
+    ///
+    /// MySQL doesn't support `IF NOT EXISTS` for indexes, so the statements
+    /// creating them return an error if they already exist
+    MySql,
+    /// PostgreSQL
+    Postgres,
+    /// SQLite
+    Sqlite,
+}
+
+impl Dialect {
+    /// Quote an identifier, so that column names that are keywords, such as
+    /// `user`, can be used
+    fn quote(self, identifier: &str) -> String {
+        match self {
+            Self::MySql => format!("`{identifier}`"),
+            Self::Postgres | Self::Sqlite => format!("\"{identifier}\""),
+        }
+    }
+}
+
+/// The kind of the values stored in a column
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// A Discord ID, stored as a 64-bit signed integer since IDs always fit
+    /// in it
+    Id,
+    /// An integer that fits in a 32-bit signed integer, such as the integer
+    /// values of enums
+    Integer,
+    /// An integer that doesn't always fit in a 32-bit signed integer, such as
+    /// bitflags, stored as a 64-bit signed integer
+    BigInteger,
+    /// A boolean
+    Boolean,
+    /// A string of any length
+    Text,
+    /// A string that's at most 255 characters, used for strings that are
+    /// indexed, since MySQL can't index `TEXT` columns
+    ShortText,
+    /// A timestamp, SQLite doesn't have a timestamp type, so it's stored as
+    /// the microseconds since the Unix epoch there
+    Timestamp,
+    /// Bytes of any length
+    Bytes,
+}
+
+impl ColumnKind {
+    /// Return the name of the type in the given dialect
+    #[must_use]
+    pub const fn sql_type(self, dialect: Dialect) -> &'static str {
+        match (self, dialect) {
+            (Self::Integer | Self::Id | Self::BigInteger | Self::Boolean, Dialect::Sqlite)
+            | (Self::Timestamp, Dialect::Sqlite)
+            | (Self::Integer, Dialect::MySql | Dialect::Postgres) => "INTEGER",
+            (Self::Id | Self::BigInteger, Dialect::MySql | Dialect::Postgres) => "BIGINT",
+            (Self::Boolean, Dialect::MySql | Dialect::Postgres) => "BOOLEAN",
+            (Self::Text, _) | (Self::ShortText, Dialect::Postgres | Dialect::Sqlite) => "TEXT",
+            (Self::ShortText, Dialect::MySql) => "VARCHAR(255)",
+            (Self::Timestamp, Dialect::MySql) => "DATETIME(6)",
+            (Self::Timestamp, Dialect::Postgres) => "TIMESTAMPTZ",
+            (Self::Bytes, Dialect::Sqlite) => "BLOB",
+            (Self::Bytes, Dialect::MySql) => "LONGBLOB",
+            (Self::Bytes, Dialect::Postgres) => "BYTEA",
+        }
+    }
+}
+
+/// A column of a table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Column {
+    /// The name of the column, the same as the model's field
+    pub name: &'static str,
+    /// The kind of the values stored in the column
+    pub kind: ColumnKind,
+    /// Whether the column can be `NULL`, which is when the field is an
+    /// `Option`
+    pub nullable: bool,
+}
+
+/// An index of a table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableIndex {
+    /// The names of the indexed columns
+    pub columns: &'static [&'static str],
+    /// Whether the combination of the columns is unique
+    pub unique: bool,
+}
+
+/// A table storing a model
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Table {
+    /// The name of the table
+    pub name: &'static str,
+    /// The columns of the table, in the same order as the model's fields
+    pub columns: &'static [Column],
+    /// The name of the primary key column, if the model has a unique ID
+    pub primary_key: Option<&'static str>,
+    /// The indexes of the table, other than the primary key
+    pub indexes: &'static [TableIndex],
+}
+
+impl Table {
+    /// Return the statement to create the table if it doesn't exist
+    #[must_use]
+    pub fn create_table_statement(&self, dialect: Dialect) -> String {
+        let mut definitions = self
+            .columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{} {}{}",
+                    dialect.quote(column.name),
+                    column.kind.sql_type(dialect),
+                    if column.nullable { "" } else { " NOT NULL" }
+                )
+            })
+            .collect::<Vec<_>>();
+        if let Some(primary_key) = self.primary_key {
+            definitions.push(format!("PRIMARY KEY ({})", dialect.quote(primary_key)));
+        }
+
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} ({})",
+            dialect.quote(self.name),
+            definitions.join(", ")
+        )
+    }
+
+    /// Return the statements to create the indexes of the table
+    ///
+    /// The indexes are named like `channels_guild_id_idx`
+    #[must_use]
+    pub fn create_index_statements(&self, dialect: Dialect) -> Vec<String> {
+        self.indexes
+            .iter()
+            .map(|index| {
+                format!(
+                    "CREATE {}INDEX {}{} ON {} ({})",
+                    if index.unique { "UNIQUE " } else { "" },
+                    if dialect == Dialect::MySql {
+                        ""
+                    } else {
+                        "IF NOT EXISTS "
+                    },
+                    dialect.quote(&format!("{}_{}_idx", self.name, index.columns.join("_"))),
+                    dialect.quote(self.name),
+                    index
+                        .columns
+                        .iter()
+                        .map(|column| dialect.quote(column))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect()
+    }
+
+    /// Return the statements to create the table and its indexes
+    #[must_use]
+    pub fn create_statements(&self, dialect: Dialect) -> Vec<String> {
+        let mut statements = vec![self.create_table_statement(dialect)];
+        statements.append(&mut self.create_index_statements(dialect));
+        statements
+    }
+}
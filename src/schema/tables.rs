@@ -0,0 +1,765 @@
+use twilight_model::{channel::StageInstance, oauth::PartialApplication, user::CurrentUser};
+
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+#[cfg(feature = "stats")]
+use crate::model::{CachedEmojiUse, CachedStickerUse};
+use crate::{
+    model::{
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker,
+    },
+    schema::{CachedTable, Column, ColumnKind, Table, TableIndex},
+};
+
+/// Return a column that can't be `NULL`
+const fn required(name: &'static str, kind: ColumnKind) -> Column {
+    Column {
+        name,
+        kind,
+        nullable: false,
+    }
+}
+
+/// Return a column that can be `NULL`
+const fn optional(name: &'static str, kind: ColumnKind) -> Column {
+    Column {
+        name,
+        kind,
+        nullable: true,
+    }
+}
+
+/// Return an index on the given columns
+const fn index(columns: &'static [&'static str]) -> TableIndex {
+    TableIndex {
+        columns,
+        unique: false,
+    }
+}
+
+/// Return a unique index on the given columns
+const fn unique_index(columns: &'static [&'static str]) -> TableIndex {
+    TableIndex {
+        columns,
+        unique: true,
+    }
+}
+
+impl CachedTable for CurrentUser {
+    const TABLE: Table = Table {
+        name: "current_users",
+        columns: &[
+            optional("accent_color", ColumnKind::BigInteger),
+            optional("avatar", ColumnKind::Text),
+            optional("banner", ColumnKind::Text),
+            required("bot", ColumnKind::Boolean),
+            required("discriminator", ColumnKind::Integer),
+            optional("email", ColumnKind::Text),
+            optional("flags", ColumnKind::BigInteger),
+            required("id", ColumnKind::Id),
+            optional("locale", ColumnKind::Text),
+            required("mfa_enabled", ColumnKind::Boolean),
+            required("name", ColumnKind::Text),
+            optional("premium_type", ColumnKind::Integer),
+            optional("public_flags", ColumnKind::BigInteger),
+            optional("verified", ColumnKind::Boolean),
+        ],
+        primary_key: Some("id"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for PartialApplication {
+    const TABLE: Table = Table {
+        name: "current_applications",
+        columns: &[
+            required("flags", ColumnKind::BigInteger),
+            required("id", ColumnKind::Id),
+        ],
+        primary_key: Some("id"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for CachedSession {
+    const TABLE: Table = Table {
+        name: "sessions",
+        columns: &[
+            required("session_id", ColumnKind::Text),
+            required("shard_id", ColumnKind::BigInteger),
+            required("shard_count", ColumnKind::BigInteger),
+            required("version", ColumnKind::BigInteger),
+        ],
+        primary_key: Some("shard_id"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for CachedShardState {
+    const TABLE: Table = Table {
+        name: "shard_states",
+        columns: &[
+            required("shard_id", ColumnKind::BigInteger),
+            required("sequence", ColumnKind::BigInteger),
+            required("events", ColumnKind::BigInteger),
+            required("missed_events", ColumnKind::BigInteger),
+        ],
+        primary_key: Some("shard_id"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for CachedInteraction {
+    const TABLE: Table = Table {
+        name: "interactions",
+        columns: &[
+            required("id", ColumnKind::Id),
+            required("application_id", ColumnKind::Id),
+            optional("channel_id", ColumnKind::Id),
+            optional("command_name", ColumnKind::ShortText),
+            optional("guild_id", ColumnKind::Id),
+            required("kind", ColumnKind::Integer),
+            required("token", ColumnKind::Text),
+            required("token_expires_at", ColumnKind::Timestamp),
+            optional("user_id", ColumnKind::Id),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["token_expires_at"])],
+    };
+}
+
+impl CachedTable for CachedChannel {
+    const TABLE: Table = Table {
+        name: "channels",
+        columns: &[
+            optional("application_id", ColumnKind::Id),
+            optional("bitrate", ColumnKind::BigInteger),
+            optional("default_auto_archive_duration", ColumnKind::Integer),
+            optional("guild_id", ColumnKind::Id),
+            optional("icon", ColumnKind::Text),
+            required("id", ColumnKind::Id),
+            optional("invitable", ColumnKind::Boolean),
+            required("kind", ColumnKind::Integer),
+            optional("name", ColumnKind::Text),
+            optional("nsfw", ColumnKind::Boolean),
+            optional("owner_id", ColumnKind::Id),
+            optional("parent_id", ColumnKind::Id),
+            optional("position", ColumnKind::Integer),
+            optional("rate_limit_per_user", ColumnKind::Integer),
+            optional("rtc_region", ColumnKind::Text),
+            optional("thread_archived", ColumnKind::Boolean),
+            optional("thread_auto_archive_duration", ColumnKind::Integer),
+            optional("thread_archive_timestamp", ColumnKind::Timestamp),
+            optional("thread_create_timestamp", ColumnKind::Timestamp),
+            optional("thread_invitable", ColumnKind::Boolean),
+            optional("thread_locked", ColumnKind::Boolean),
+            optional("topic", ColumnKind::Text),
+            optional("user_limit", ColumnKind::BigInteger),
+            optional("video_quality_mode", ColumnKind::Integer),
+            required("partial", ColumnKind::Boolean),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["guild_id"])],
+    };
+}
+
+impl CachedTable for CachedPermissionOverwrite {
+    const TABLE: Table = Table {
+        name: "permission_overwrites",
+        columns: &[
+            required("channel_id", ColumnKind::Id),
+            required("allow", ColumnKind::BigInteger),
+            required("deny", ColumnKind::BigInteger),
+            required("id", ColumnKind::Id),
+            required("kind", ColumnKind::Integer),
+        ],
+        primary_key: None,
+        indexes: &[unique_index(&["channel_id", "id"])],
+    };
+}
+
+impl CachedTable for CachedPrivateChannel {
+    const TABLE: Table = Table {
+        name: "private_channels",
+        columns: &[
+            required("channel_id", ColumnKind::Id),
+            required("user_id", ColumnKind::Id),
+        ],
+        primary_key: None,
+        indexes: &[
+            unique_index(&["channel_id", "user_id"]),
+            index(&["user_id"]),
+        ],
+    };
+}
+
+impl CachedTable for CachedMessage {
+    const TABLE: Table = Table {
+        name: "messages",
+        columns: &[
+            optional("activity_type", ColumnKind::Integer),
+            optional("activity_party_id", ColumnKind::Text),
+            optional("application_cover_image", ColumnKind::Text),
+            optional("application_description", ColumnKind::Text),
+            optional("application_icon", ColumnKind::Text),
+            optional("application_id", ColumnKind::Id),
+            optional("application_name", ColumnKind::Text),
+            optional("interaction_application_id", ColumnKind::Id),
+            required("author", ColumnKind::Id),
+            required("author_bot", ColumnKind::Boolean),
+            optional("author_name", ColumnKind::Text),
+            optional("author_discriminator", ColumnKind::Integer),
+            optional("author_avatar", ColumnKind::Text),
+            required("channel_id", ColumnKind::Id),
+            required("content", ColumnKind::Text),
+            optional("edited_timestamp", ColumnKind::Timestamp),
+            optional("flags", ColumnKind::BigInteger),
+            optional("guild_id", ColumnKind::Id),
+            required("id", ColumnKind::Id),
+            required("kind", ColumnKind::Integer),
+            required("mention_everyone", ColumnKind::Boolean),
+            required("pinned", ColumnKind::Boolean),
+            optional("reference_channel_id", ColumnKind::Id),
+            optional("reference_guild_id", ColumnKind::Id),
+            optional("reference_message_id", ColumnKind::Id),
+            optional("reference_fail_if_not_exists", ColumnKind::Boolean),
+            optional("referenced_message", ColumnKind::Id),
+            required("timestamp", ColumnKind::Timestamp),
+            optional("thread", ColumnKind::Id),
+            required("tts", ColumnKind::Boolean),
+            optional("webhook_id", ColumnKind::Id),
+            required("partial", ColumnKind::Boolean),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[
+            index(&["channel_id", "timestamp"]),
+            index(&["guild_id", "timestamp"]),
+            index(&["timestamp"]),
+            index(&["webhook_id"]),
+        ],
+    };
+}
+
+impl CachedTable for CachedEmbed {
+    const TABLE: Table = Table {
+        name: "embeds",
+        columns: &[
+            required("id", ColumnKind::Id),
+            required("message_id", ColumnKind::Id),
+            optional("author_icon_url", ColumnKind::Text),
+            optional("author_name", ColumnKind::Text),
+            optional("author_proxy_icon_url", ColumnKind::Text),
+            optional("author_url", ColumnKind::Text),
+            optional("color", ColumnKind::BigInteger),
+            optional("description", ColumnKind::Text),
+            optional("footer_icon_url", ColumnKind::Text),
+            optional("footer_proxy_icon_url", ColumnKind::Text),
+            optional("footer_text", ColumnKind::Text),
+            optional("image_height", ColumnKind::BigInteger),
+            optional("image_proxy_url", ColumnKind::Text),
+            optional("image_url", ColumnKind::Text),
+            optional("image_width", ColumnKind::BigInteger),
+            required("kind", ColumnKind::Text),
+            optional("provider_name", ColumnKind::Text),
+            optional("provider_url", ColumnKind::Text),
+            optional("thumbnail_height", ColumnKind::BigInteger),
+            optional("thumbnail_proxy_url", ColumnKind::Text),
+            optional("thumbnail_url", ColumnKind::Text),
+            optional("thumbnail_width", ColumnKind::BigInteger),
+            optional("timestamp", ColumnKind::Timestamp),
+            optional("title", ColumnKind::Text),
+            optional("url", ColumnKind::Text),
+            optional("video_height", ColumnKind::BigInteger),
+            optional("video_proxy_url", ColumnKind::Text),
+            optional("video_url", ColumnKind::Text),
+            optional("video_width", ColumnKind::BigInteger),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["message_id"])],
+    };
+}
+
+impl CachedTable for CachedEmbedField {
+    const TABLE: Table = Table {
+        name: "embed_fields",
+        columns: &[
+            required("embed_id", ColumnKind::Id),
+            required("inline", ColumnKind::Boolean),
+            required("name", ColumnKind::Text),
+            required("value", ColumnKind::Text),
+        ],
+        primary_key: None,
+        indexes: &[index(&["embed_id"])],
+    };
+}
+
+impl CachedTable for CachedMessageRevision {
+    const TABLE: Table = Table {
+        name: "message_revisions",
+        columns: &[
+            required("message_id", ColumnKind::Id),
+            required("revision_id", ColumnKind::Id),
+            required("edited_at", ColumnKind::Timestamp),
+            required("content", ColumnKind::Text),
+        ],
+        primary_key: Some("revision_id"),
+        indexes: &[index(&["message_id"])],
+    };
+}
+
+impl CachedTable for CachedAttachment {
+    const TABLE: Table = Table {
+        name: "attachments",
+        columns: &[
+            required("message_id", ColumnKind::Id),
+            optional("content_type", ColumnKind::Text),
+            optional("duration_millis", ColumnKind::BigInteger),
+            required("ephemeral", ColumnKind::Boolean),
+            required("filename", ColumnKind::Text),
+            optional("flags", ColumnKind::BigInteger),
+            optional("description", ColumnKind::Text),
+            optional("height", ColumnKind::BigInteger),
+            required("id", ColumnKind::Id),
+            required("proxy_url", ColumnKind::Text),
+            required("size", ColumnKind::BigInteger),
+            required("url", ColumnKind::Text),
+            optional("waveform", ColumnKind::Text),
+            optional("width", ColumnKind::BigInteger),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["message_id"])],
+    };
+}
+
+impl CachedTable for CachedAttachmentBlob {
+    const TABLE: Table = Table {
+        name: "attachment_blobs",
+        columns: &[
+            required("file_hash", ColumnKind::ShortText),
+            optional("content_type", ColumnKind::Text),
+            optional("duration_millis", ColumnKind::BigInteger),
+            required("filename", ColumnKind::Text),
+            optional("flags", ColumnKind::BigInteger),
+            optional("height", ColumnKind::BigInteger),
+            required("size", ColumnKind::BigInteger),
+            optional("waveform", ColumnKind::Text),
+            optional("width", ColumnKind::BigInteger),
+        ],
+        primary_key: Some("file_hash"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for CachedAttachmentPost {
+    const TABLE: Table = Table {
+        name: "attachment_posts",
+        columns: &[
+            required("file_hash", ColumnKind::ShortText),
+            required("message_id", ColumnKind::Id),
+            optional("description", ColumnKind::Text),
+            required("ephemeral", ColumnKind::Boolean),
+            required("id", ColumnKind::Id),
+            required("proxy_url", ColumnKind::Text),
+            required("url", ColumnKind::Text),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["message_id"]), index(&["file_hash"])],
+    };
+}
+
+impl CachedTable for CachedReaction {
+    const TABLE: Table = Table {
+        name: "reactions",
+        columns: &[
+            required("channel_id", ColumnKind::Id),
+            required("emoji", ColumnKind::ShortText),
+            optional("emoji_name", ColumnKind::Text),
+            optional("emoji_animated", ColumnKind::Boolean),
+            optional("guild_id", ColumnKind::Id),
+            required("message_id", ColumnKind::Id),
+            required("user_id", ColumnKind::Id),
+            required("burst", ColumnKind::Boolean),
+            optional("burst_colors", ColumnKind::ShortText),
+        ],
+        primary_key: None,
+        indexes: &[
+            unique_index(&["message_id", "user_id", "emoji"]),
+            index(&["emoji"]),
+        ],
+    };
+}
+
+impl CachedTable for CachedMember {
+    const TABLE: Table = Table {
+        name: "members",
+        columns: &[
+            optional("guild_avatar", ColumnKind::Text),
+            optional("communication_disabled_until", ColumnKind::Timestamp),
+            required("deaf", ColumnKind::Boolean),
+            required("guild_id", ColumnKind::Id),
+            required("joined_at", ColumnKind::Timestamp),
+            required("mute", ColumnKind::Boolean),
+            optional("nick", ColumnKind::Text),
+            required("pending", ColumnKind::Boolean),
+            optional("premium_since", ColumnKind::Timestamp),
+            optional("accent_color", ColumnKind::BigInteger),
+            optional("avatar", ColumnKind::Text),
+            optional("banner", ColumnKind::Text),
+            required("bot", ColumnKind::Boolean),
+            required("discriminator", ColumnKind::Integer),
+            optional("flags", ColumnKind::BigInteger),
+            required("id", ColumnKind::Id),
+            optional("locale", ColumnKind::Text),
+            optional("mfa_enabled", ColumnKind::Boolean),
+            required("name", ColumnKind::Text),
+            optional("premium_type", ColumnKind::Integer),
+            optional("public_flags", ColumnKind::BigInteger),
+            optional("system", ColumnKind::Boolean),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: None,
+        indexes: &[unique_index(&["guild_id", "id"]), index(&["id"])],
+    };
+}
+
+impl CachedTable for CachedPresence {
+    const TABLE: Table = Table {
+        name: "presences",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("status", ColumnKind::ShortText),
+            required("user", ColumnKind::Id),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: None,
+        indexes: &[unique_index(&["guild_id", "user"]), index(&["user"])],
+    };
+}
+
+impl CachedTable for CachedActivity {
+    const TABLE: Table = Table {
+        name: "activities",
+        columns: &[
+            required("user_id", ColumnKind::Id),
+            required("guild_id", ColumnKind::Id),
+            optional("application_id", ColumnKind::Id),
+            optional("asset_large_image", ColumnKind::Text),
+            optional("asset_large_text", ColumnKind::Text),
+            optional("asset_small_image", ColumnKind::Text),
+            optional("asset_small_text", ColumnKind::Text),
+            optional("button_1", ColumnKind::Text),
+            optional("button_2", ColumnKind::Text),
+            optional("created_at", ColumnKind::BigInteger),
+            optional("details", ColumnKind::Text),
+            optional("emoji_animated", ColumnKind::Boolean),
+            optional("emoji_name", ColumnKind::Text),
+            optional("emoji_id", ColumnKind::Text),
+            optional("flags", ColumnKind::BigInteger),
+            optional("id", ColumnKind::Text),
+            optional("instance", ColumnKind::Boolean),
+            required("kind", ColumnKind::Integer),
+            required("name", ColumnKind::Text),
+            optional("party_id", ColumnKind::Text),
+            optional("party_size_current", ColumnKind::BigInteger),
+            optional("party_size_max", ColumnKind::BigInteger),
+            optional("state", ColumnKind::Text),
+            optional("timestamp_end", ColumnKind::BigInteger),
+            optional("timestamp_start", ColumnKind::BigInteger),
+            optional("url", ColumnKind::Text),
+        ],
+        primary_key: None,
+        indexes: &[index(&["guild_id", "user_id"]), index(&["user_id"])],
+    };
+}
+
+impl CachedTable for CachedGuild {
+    const TABLE: Table = Table {
+        name: "guilds",
+        columns: &[
+            optional("afk_channel_id", ColumnKind::Id),
+            required("afk_timeout", ColumnKind::BigInteger),
+            optional("application_id", ColumnKind::Id),
+            optional("banner", ColumnKind::Text),
+            required("default_message_notifications", ColumnKind::Integer),
+            optional("description", ColumnKind::Text),
+            optional("discovery_splash", ColumnKind::Text),
+            required("explicit_content_filter", ColumnKind::Integer),
+            required("features", ColumnKind::Text),
+            optional("icon", ColumnKind::Text),
+            required("id", ColumnKind::Id),
+            optional("joined_at", ColumnKind::Timestamp),
+            required("large", ColumnKind::Boolean),
+            optional("max_members", ColumnKind::BigInteger),
+            optional("max_presences", ColumnKind::BigInteger),
+            optional("max_stage_video_channel_users", ColumnKind::BigInteger),
+            optional("max_video_channel_users", ColumnKind::BigInteger),
+            required("mfa_level", ColumnKind::Integer),
+            required("name", ColumnKind::Text),
+            required("nsfw_level", ColumnKind::Integer),
+            required("owner_id", ColumnKind::Id),
+            optional("owner", ColumnKind::Boolean),
+            optional("permissions", ColumnKind::BigInteger),
+            required("preferred_locale", ColumnKind::Text),
+            required("premium_progress_bar_enabled", ColumnKind::Boolean),
+            optional("premium_subscription_count", ColumnKind::BigInteger),
+            required("premium_tier", ColumnKind::Integer),
+            optional("public_updates_channel_id", ColumnKind::Id),
+            optional("rules_channel_id", ColumnKind::Id),
+            optional("safety_alerts_channel_id", ColumnKind::Id),
+            optional("splash", ColumnKind::Text),
+            required("system_channel_flags", ColumnKind::BigInteger),
+            optional("system_channel_id", ColumnKind::Id),
+            required("unavailable", ColumnKind::Boolean),
+            optional("vanity_url_code", ColumnKind::Text),
+            required("verification_level", ColumnKind::Integer),
+            optional("widget_channel_id", ColumnKind::Id),
+            optional("widget_enabled", ColumnKind::Boolean),
+            required("members_partial", ColumnKind::Boolean),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[],
+    };
+}
+
+impl CachedTable for CachedRole {
+    const TABLE: Table = Table {
+        name: "roles",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("color", ColumnKind::BigInteger),
+            required("hoist", ColumnKind::Boolean),
+            optional("icon", ColumnKind::Text),
+            required("id", ColumnKind::Id),
+            required("managed", ColumnKind::Boolean),
+            required("mentionable", ColumnKind::Boolean),
+            required("name", ColumnKind::Text),
+            required("permissions", ColumnKind::BigInteger),
+            required("position", ColumnKind::BigInteger),
+            optional("flags", ColumnKind::BigInteger),
+            optional("tags_available_for_purchase", ColumnKind::Boolean),
+            optional("tags_bot_id", ColumnKind::Id),
+            optional("tags_guild_connections", ColumnKind::Boolean),
+            optional("tags_integration_id", ColumnKind::Id),
+            optional("tags_premium_subscriber", ColumnKind::Boolean),
+            optional("tags_subscription_listing_id", ColumnKind::Id),
+            optional("unicode_emoji", ColumnKind::Text),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["guild_id"])],
+    };
+}
+
+impl CachedTable for CachedRoleAssignment {
+    const TABLE: Table = Table {
+        name: "role_assignments",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("user_id", ColumnKind::Id),
+            required("role_id", ColumnKind::Id),
+        ],
+        primary_key: None,
+        indexes: &[
+            unique_index(&["guild_id", "user_id", "role_id"]),
+            index(&["role_id"]),
+        ],
+    };
+}
+
+impl CachedTable for CachedEmoji {
+    const TABLE: Table = Table {
+        name: "emojis",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("animated", ColumnKind::Boolean),
+            required("available", ColumnKind::Boolean),
+            required("id", ColumnKind::Id),
+            required("managed", ColumnKind::Boolean),
+            required("name", ColumnKind::Text),
+            required("require_colons", ColumnKind::Boolean),
+            optional("user", ColumnKind::Id),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["guild_id"])],
+    };
+}
+
+impl CachedTable for CachedSticker {
+    const TABLE: Table = Table {
+        name: "stickers",
+        columns: &[
+            optional("message_id", ColumnKind::Id),
+            optional("available", ColumnKind::Boolean),
+            optional("description", ColumnKind::Text),
+            required("format_type", ColumnKind::Integer),
+            optional("guild_id", ColumnKind::Id),
+            required("id", ColumnKind::Id),
+            optional("kind", ColumnKind::Integer),
+            required("name", ColumnKind::Text),
+            optional("pack_id", ColumnKind::Id),
+            optional("sort_value", ColumnKind::BigInteger),
+            optional("tags", ColumnKind::Text),
+            optional("user_id", ColumnKind::Id),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: None,
+        indexes: &[index(&["id"]), index(&["message_id"]), index(&["guild_id"])],
+    };
+}
+
+impl CachedTable for StageInstance {
+    const TABLE: Table = Table {
+        name: "stage_instances",
+        columns: &[
+            required("channel_id", ColumnKind::Id),
+            required("guild_id", ColumnKind::Id),
+            optional("guild_scheduled_event_id", ColumnKind::Id),
+            required("id", ColumnKind::Id),
+            required("privacy_level", ColumnKind::Integer),
+            required("topic", ColumnKind::Text),
+            optional("expires_at", ColumnKind::Timestamp),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["guild_id"])],
+    };
+}
+
+#[cfg(feature = "stats")]
+impl CachedTable for CachedEmojiUse {
+    const TABLE: Table = Table {
+        name: "emoji_uses",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("emoji", ColumnKind::ShortText),
+            required("day", ColumnKind::BigInteger),
+            required("uses", ColumnKind::BigInteger),
+        ],
+        primary_key: None,
+        indexes: &[unique_index(&["guild_id", "emoji", "day"])],
+    };
+}
+
+#[cfg(feature = "stats")]
+impl CachedTable for CachedStickerUse {
+    const TABLE: Table = Table {
+        name: "sticker_uses",
+        columns: &[
+            required("guild_id", ColumnKind::Id),
+            required("sticker_id", ColumnKind::Id),
+            required("day", ColumnKind::BigInteger),
+            required("uses", ColumnKind::BigInteger),
+        ],
+        primary_key: None,
+        indexes: &[unique_index(&["guild_id", "sticker_id", "day"])],
+    };
+}
+
+/// The table the embeds of messages are stored in as one JSON document with
+/// [`crate::config::CacheConfig::embeds_json`], it has no model since the
+/// document is a [`serde_json::Value`]
+#[cfg(feature = "embeds-json")]
+pub(super) const MESSAGE_EMBEDS: Table = Table {
+    name: "message_embeds",
+    columns: &[
+        required("message_id", ColumnKind::Id),
+        required("embeds", ColumnKind::Text),
+    ],
+    primary_key: Some("message_id"),
+    indexes: &[],
+};
+
+/// The table the time of the latest message in each channel is stored in with
+/// [`crate::config::CacheConfig::channel_activity`], it has no model since
+/// it's only used to filter channels
+pub(super) const CHANNEL_ACTIVITY: Table = Table {
+    name: "channel_activity",
+    columns: &[
+        required("channel_id", ColumnKind::Id),
+        required("last_activity", ColumnKind::Timestamp),
+    ],
+    primary_key: Some("channel_id"),
+    indexes: &[],
+};
+
+/// The table the keys of applied events are stored in with
+/// [`crate::config::CacheConfig::event_dedup`], it has no model since the key
+/// is the only data
+pub(super) const SEEN_EVENTS: Table = Table {
+    name: "seen_events",
+    columns: &[
+        required("key", ColumnKind::ShortText),
+        required("seen_at", ColumnKind::Timestamp),
+    ],
+    primary_key: Some("key"),
+    indexes: &[index(&["seen_at"])],
+};
+
+/// The table the sequence numbers of the latest updates of entities are stored
+/// in with [`crate::config::CacheConfig::ordered_updates`], it has no model
+/// since it's only compared
+pub(super) const UPDATE_SEQUENCES: Table = Table {
+    name: "update_sequences",
+    columns: &[
+        required("key", ColumnKind::ShortText),
+        required("shard_id", ColumnKind::BigInteger),
+        required("sequence", ColumnKind::BigInteger),
+    ],
+    primary_key: Some("key"),
+    indexes: &[index(&["shard_id"])],
+};
+
+/// The table the values of extensions are stored in with
+/// [`crate::Backend::set_extension`], it has no model since the values are
+/// opaque to the crate
+pub(super) const EXTENSIONS: Table = Table {
+    name: "extensions",
+    columns: &[
+        required("namespace", ColumnKind::ShortText),
+        required("key", ColumnKind::ShortText),
+        required("value", ColumnKind::Bytes),
+    ],
+    primary_key: None,
+    indexes: &[unique_index(&["namespace", "key"])],
+};
+
+#[cfg(feature = "changelog")]
+impl CachedTable for CachedChangelogEntry {
+    const TABLE: Table = Table {
+        name: "changelog",
+        columns: &[
+            required("id", ColumnKind::Id),
+            required("recorded_at", ColumnKind::Timestamp),
+            required("kind", ColumnKind::ShortText),
+            required("entity_id", ColumnKind::Id),
+            optional("before", ColumnKind::Text),
+            optional("after", ColumnKind::Text),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["kind", "entity_id"])],
+    };
+}
+
+#[cfg(feature = "journal")]
+impl CachedTable for CachedJournalEntry {
+    const TABLE: Table = Table {
+        name: "journal",
+        columns: &[
+            required("id", ColumnKind::Id),
+            required("recorded_at", ColumnKind::Timestamp),
+            required("kind", ColumnKind::ShortText),
+            required("data", ColumnKind::Text),
+        ],
+        primary_key: Some("id"),
+        indexes: &[index(&["recorded_at"])],
+    };
+}
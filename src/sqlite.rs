@@ -0,0 +1,127 @@
+use sqlx::{sqlite::SqlitePool, Executor};
+use twilight_model::id::Id;
+
+use crate::{
+    schema::{self, Dialect, CURRENT_SCHEMA_VERSION},
+    Backend,
+};
+
+/// The implementation of [`crate::Backend`] for [`SqliteCache`]
+mod backend;
+/// The implementation of [`crate::Cache`] for [`SqliteCache`]
+mod cache;
+/// Functions to create cached structs from rows
+mod row;
+
+/// A cache backed by SQLite, implementing both [`crate::Backend`] and
+/// [`crate::Cache`]
+///
+/// Creating it creates the tables and indexes if they don't exist and clears
+/// them, as described in [`crate::Backend`]'s persistence section, if the
+/// tables were created by a version of this crate with a different
+/// [`CURRENT_SCHEMA_VERSION`], every table and view in the database is dropped,
+/// including the ones of older versions that aren't in [`crate::schema`]
+/// anymore, and the tables are created again, so the cached data isn't
+/// migrated, don't store your own tables in the cache's database
+///
+/// The tables are the ones in [`crate::schema`], enums are stored as their
+/// integer values, timestamps are stored as microseconds since the Unix epoch
+/// and image hashes are stored as their string representation
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::{sqlite::SqliteCache, Cache};
+///
+/// let cache = SqliteCache::connect("sqlite://cache.db?mode=rwc").await?;
+///
+/// while let Some((_, event)) = events.next().await {
+///     cache.update(&event).await?;
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SqliteCache {
+    /// The pool used to run the queries
+    pool: SqlitePool,
+}
+
+impl SqliteCache {
+    /// Create the cache by connecting to the given database URL
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when connecting to the database or creating the
+    /// schema fails
+    pub async fn connect(url: &str) -> Result<Self, sqlx::Error> {
+        Self::new(SqlitePool::connect(url).await?).await
+    }
+
+    /// Create the cache using the given pool
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when creating the schema fails
+    pub async fn new(pool: SqlitePool) -> Result<Self, sqlx::Error> {
+        let cache = Self { pool };
+
+        match cache.schema_version().await? {
+            Some(version) if version != CURRENT_SCHEMA_VERSION => {
+                cache.migrate(version, CURRENT_SCHEMA_VERSION).await?;
+            }
+            _ => cache.create_schema().await?,
+        }
+        for table in schema::TABLES {
+            cache
+                .pool
+                .execute(format!("DELETE FROM {}", table.name).as_str())
+                .await?;
+        }
+
+        Ok(cache)
+    }
+
+    /// Return the pool, so that you can run queries that aren't provided by
+    /// [`crate::Cache`]
+    #[must_use]
+    pub const fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// Create the tables and indexes if they don't exist and store
+    /// [`CURRENT_SCHEMA_VERSION`] as the database's `user_version`
+    async fn create_schema(&self) -> Result<(), sqlx::Error> {
+        for statement in schema::create_statements(Dialect::Sqlite) {
+            self.pool.execute(statement.as_str()).await?;
+        }
+        self.pool
+            .execute(format!("PRAGMA user_version = {CURRENT_SCHEMA_VERSION}").as_str())
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Convert an ID to store it in the database
+///
+/// IDs always fit in an `i64`, so the value is unchanged
+#[allow(clippy::as_conversions, clippy::cast_possible_wrap)]
+const fn id<T>(id: Id<T>) -> i64 {
+    id.get() as i64
+}
+
+/// Convert an integer to store it in the database, since SQLite doesn't
+/// support `u64`
+#[allow(clippy::as_conversions, clippy::cast_possible_wrap)]
+const fn int(value: u64) -> i64 {
+    value as i64
+}
+
+/// Convert a limit of the getters to use it in a `LIMIT` clause, where `-1`
+/// means no limit
+fn sql_limit(limit: u16) -> i64 {
+    if limit == 0 {
+        -1
+    } else {
+        limit.into()
+    }
+}
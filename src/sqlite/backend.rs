@@ -0,0 +1,2252 @@
+#![allow(clippy::std_instead_of_alloc)]
+
+use std::{borrow::Cow, collections::HashMap};
+
+use async_trait::async_trait;
+use sqlx::{sqlite::SqlitePool, Executor, QueryBuilder, Sqlite};
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwriteType, StageInstance},
+    gateway::presence::Status,
+    guild::GuildFeature,
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GenericMarker, GuildMarker, MessageMarker, RoleMarker,
+            StageMarker, StickerMarker, UserMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::{ImageHash, Timestamp},
+};
+
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::{
+    cache::EntityKind,
+    config::MessageKindFilter,
+    counts::EntityCount,
+    model::{
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker, GuildPatch, MemberPatch,
+    },
+    sqlite::{id, int, row, sql_limit, SqliteCache},
+    Backend,
+};
+
+/// The tables counted in [`Backend::entity_counts`], with the kind of the
+/// entities in them
+const COUNTED_TABLES: [(EntityKind, &str); 9] = [
+    (EntityKind::Channel, "channels"),
+    (EntityKind::Message, "messages"),
+    (EntityKind::Member, "members"),
+    (EntityKind::Guild, "guilds"),
+    (EntityKind::Role, "roles"),
+    (EntityKind::Emoji, "emojis"),
+    (EntityKind::Sticker, "stickers"),
+    (EntityKind::StageInstance, "stage_instances"),
+    (EntityKind::Presence, "presences"),
+];
+
+/// Return the string a status is stored as, the same as its JSON
+/// representation
+const fn status(status: Status) -> &'static str {
+    match status {
+        Status::DoNotDisturb => "dnd",
+        Status::Idle => "idle",
+        Status::Invisible => "invisible",
+        Status::Offline => "offline",
+        Status::Online => "online",
+    }
+}
+
+/// Return the `LIKE` pattern matching strings that start with the prefix, with
+/// the wildcards in the prefix escaped with `\`
+///
+/// `LIKE` ignores the case of ASCII characters, so the prefix matches
+/// case-insensitively
+fn like_prefix(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len().saturating_add(1));
+    for character in prefix.chars() {
+        if matches!(character, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(character);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Return the string guild features are stored as, their names separated by
+/// commas
+fn features(features: &[GuildFeature]) -> String {
+    features
+        .iter()
+        .map(|feature| Cow::from(feature.clone()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Return the placeholders to bind the given number of values in an `IN`
+/// clause, such as `?, ?, ?`
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+/// Return the string the kind of an entity is stored as in the changelog
+#[cfg(feature = "changelog")]
+const fn entity_kind(kind: EntityKind) -> &'static str {
+    match kind {
+        EntityKind::Channel => "channel",
+        EntityKind::Message => "message",
+        EntityKind::Member => "member",
+        EntityKind::Guild => "guild",
+        EntityKind::Role => "role",
+        EntityKind::Emoji => "emoji",
+        EntityKind::Sticker => "sticker",
+        EntityKind::StageInstance => "stage_instance",
+        EntityKind::Presence => "presence",
+    }
+}
+
+/// Return the string an image hash is stored as
+fn image_hash(hash: Option<ImageHash>) -> Option<String> {
+    hash.map(|hash| hash.to_string())
+}
+
+/// Run the statements one after the other
+async fn execute_all(pool: &SqlitePool, statements: &[&str]) -> Result<(), sqlx::Error> {
+    for statement in statements {
+        sqlx::query(statement).execute(pool).await?;
+    }
+
+    Ok(())
+}
+
+/// Run a query that selects IDs in a column named `id`
+async fn select_ids<T>(pool: &SqlitePool, query: &str) -> Result<Vec<Id<T>>, sqlx::Error> {
+    sqlx::query(query)
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(row::id)
+        .collect()
+}
+
+#[async_trait]
+impl Backend for SqliteCache {
+    type Error = sqlx::Error;
+
+    async fn schema_version(&self) -> Result<Option<u32>, Self::Error> {
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(u32::try_from(version).ok().filter(|version| *version != 0))
+    }
+
+    async fn migrate(&self, _from: u32, _to: u32) -> Result<(), Self::Error> {
+        let mut connection = self.pool.acquire().await?;
+        let objects: Vec<(String, String)> = sqlx::query_as(
+            "SELECT type, name FROM sqlite_master WHERE type IN ('table', 'view') AND name NOT \
+             LIKE 'sqlite_%'",
+        )
+        .fetch_all(&mut *connection)
+        .await?;
+
+        connection.execute("PRAGMA foreign_keys = OFF").await?;
+        for (kind, name) in objects {
+            connection
+                .execute(format!("DROP {} IF EXISTS \"{name}\"", kind.to_uppercase()).as_str())
+                .await?;
+        }
+        connection.execute("PRAGMA foreign_keys = ON").await?;
+        drop(connection);
+
+        self.create_schema().await
+    }
+
+    async fn prepare(&self) -> Result<(), Self::Error> {
+        self.pool.execute("PRAGMA optimize").await?;
+
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<(), Self::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn entity_counts(&self) -> Result<HashMap<EntityKind, EntityCount>, Self::Error> {
+        let mut counts = HashMap::new();
+        for (kind, table) in COUNTED_TABLES {
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                .fetch_one(&self.pool)
+                .await?;
+            counts.insert(
+                kind,
+                EntityCount {
+                    rows: u64::try_from(rows).unwrap_or_default(),
+                    bytes: None,
+                },
+            );
+        }
+
+        Ok(counts)
+    }
+
+    async fn set_current_user(&self, current_user: CurrentUser) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM current_users")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            "INSERT INTO current_users (accent_color, avatar, banner, bot, discriminator, email, \
+             flags, id, locale, mfa_enabled, name, premium_type, public_flags, verified) VALUES \
+             (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(current_user.accent_color)
+        .bind(image_hash(current_user.avatar))
+        .bind(image_hash(current_user.banner))
+        .bind(current_user.bot)
+        .bind(current_user.discriminator)
+        .bind(current_user.email)
+        .bind(current_user.flags.map(|flags| int(flags.bits())))
+        .bind(id(current_user.id))
+        .bind(current_user.locale)
+        .bind(current_user.mfa_enabled)
+        .bind(current_user.name)
+        .bind(current_user.premium_type.map(u8::from))
+        .bind(current_user.public_flags.map(|flags| int(flags.bits())))
+        .bind(current_user.verified)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_current_application(
+        &self,
+        application: PartialApplication,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM current_applications")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("INSERT INTO current_applications (flags, id) VALUES (?, ?)")
+            .bind(int(application.flags.bits()))
+            .bind(id(application.id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_session(&self, session: CachedSession) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO sessions (session_id, shard_id, shard_count, version) VALUES \
+             (?, ?, ?, ?)",
+        )
+        .bind(session.session_id)
+        .bind(int(session.shard_id))
+        .bind(int(session.shard_count))
+        .bind(int(session.version))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_shard_state(&self, state: CachedShardState) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO shard_states (shard_id, sequence, events, missed_events) \
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(int(state.shard_id))
+        .bind(int(state.sequence))
+        .bind(int(state.events))
+        .bind(int(state.missed_events))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_interaction(&self, interaction: CachedInteraction) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO interactions (id, application_id, channel_id, command_name, \
+             guild_id, kind, token, token_expires_at, user_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(interaction.id))
+        .bind(id(interaction.application_id))
+        .bind(interaction.channel_id.map(id))
+        .bind(interaction.command_name)
+        .bind(interaction.guild_id.map(id))
+        .bind(u8::from(interaction.kind))
+        .bind(interaction.token)
+        .bind(interaction.token_expires_at.as_micros())
+        .bind(interaction.user_id.map(id))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_expired_interactions(&self, now: Timestamp) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM interactions WHERE token_expires_at < ?")
+            .bind(now.as_micros())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn seen_event(&self, key: String, now: Timestamp) -> Result<bool, Self::Error> {
+        let result = sqlx::query("INSERT OR IGNORE INTO seen_events (key, seen_at) VALUES (?, ?)")
+            .bind(key)
+            .bind(now.as_micros())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 0)
+    }
+
+    async fn forget_event(&self, key: String) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM seen_events WHERE key = ?")
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_seen_events_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM seen_events WHERE seen_at < ?")
+            .bind(timestamp.as_micros())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_extension(
+        &self,
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("INSERT OR REPLACE INTO extensions (namespace, key, value) VALUES (?, ?, ?)")
+            .bind(namespace)
+            .bind(key)
+            .bind(value)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_extension(
+        &self,
+        namespace: String,
+        key: String,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(
+            sqlx::query_scalar("SELECT value FROM extensions WHERE namespace = ? AND key = ?")
+                .bind(namespace)
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await?,
+        )
+    }
+
+    async fn delete_extension(&self, namespace: String, key: String) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM extensions WHERE namespace = ? AND key = ?")
+            .bind(namespace)
+            .bind(key)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn advance_update_sequence(
+        &self,
+        key: String,
+        shard_id: u64,
+        sequence: u64,
+    ) -> Result<bool, Self::Error> {
+        let result = sqlx::query(
+            "INSERT INTO update_sequences (key, shard_id, sequence) VALUES (?, ?, ?) ON CONFLICT \
+             (key) DO UPDATE SET shard_id = excluded.shard_id, sequence = excluded.sequence \
+             WHERE excluded.sequence > update_sequences.sequence",
+        )
+        .bind(key)
+        .bind(int(shard_id))
+        .bind(int(sequence))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() != 0)
+    }
+
+    async fn delete_shard_update_sequences(&self, shard_id: u64) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM update_sequences WHERE shard_id = ?")
+            .bind(int(shard_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_channel_last_activity(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO channel_activity (channel_id, last_activity) VALUES (?, ?) ON CONFLICT \
+             (channel_id) DO UPDATE SET last_activity = MAX(last_activity, \
+             excluded.last_activity)",
+        )
+        .bind(id(channel_id))
+        .bind(timestamp.as_micros())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_channel(&self, channel: CachedChannel) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO channels (application_id, bitrate, \
+             default_auto_archive_duration, guild_id, icon, id, invitable, kind, name, nsfw, \
+             owner_id, parent_id, position, rate_limit_per_user, rtc_region, thread_archived, \
+             thread_auto_archive_duration, thread_archive_timestamp, thread_create_timestamp, \
+             thread_invitable, thread_locked, topic, user_limit, video_quality_mode, partial) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(channel.application_id.map(id))
+        .bind(channel.bitrate)
+        .bind(channel.default_auto_archive_duration.map(u16::from))
+        .bind(channel.guild_id.map(id))
+        .bind(image_hash(channel.icon))
+        .bind(id(channel.id))
+        .bind(channel.invitable)
+        .bind(u8::from(channel.kind))
+        .bind(channel.name)
+        .bind(channel.nsfw)
+        .bind(channel.owner_id.map(id))
+        .bind(channel.parent_id.map(id))
+        .bind(channel.position)
+        .bind(channel.rate_limit_per_user)
+        .bind(channel.rtc_region)
+        .bind(channel.thread_archived)
+        .bind(channel.thread_auto_archive_duration.map(u16::from))
+        .bind(channel.thread_archive_timestamp.map(Timestamp::as_micros))
+        .bind(channel.thread_create_timestamp.map(Timestamp::as_micros))
+        .bind(channel.thread_invitable)
+        .bind(channel.thread_locked)
+        .bind(channel.topic)
+        .bind(channel.user_limit)
+        .bind(channel.video_quality_mode.map(u8::from))
+        .bind(channel.partial)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_channel(&self, channel_id: Id<ChannelMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM channels WHERE id = ?")
+            .bind(id(channel_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_channels(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM channels WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_permission_overwrite(
+        &self,
+        permission_overwrite: CachedPermissionOverwrite,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO permission_overwrites (channel_id, allow, deny, id, kind) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(id(permission_overwrite.channel_id))
+        .bind(int(permission_overwrite.allow.bits()))
+        .bind(int(permission_overwrite.deny.bits()))
+        .bind(id(permission_overwrite.id))
+        .bind(u8::from(permission_overwrite.kind))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn select_overwrites_by_target(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        target_id: Id<GenericMarker>,
+        kind: PermissionOverwriteType,
+    ) -> Result<Option<CachedPermissionOverwrite>, Self::Error> {
+        sqlx::query(
+            "SELECT * FROM permission_overwrites WHERE channel_id = ? AND id = ? AND kind = ?",
+        )
+        .bind(id(channel_id))
+        .bind(id(target_id))
+        .bind(u8::from(kind))
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(row::permission_overwrite)
+        .transpose()
+    }
+
+    async fn delete_channel_permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM permission_overwrites WHERE channel_id = ?")
+            .bind(id(channel_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_private_channel(
+        &self,
+        private_channel: CachedPrivateChannel,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("INSERT OR REPLACE INTO private_channels (channel_id, user_id) VALUES (?, ?)")
+            .bind(id(private_channel.channel_id))
+            .bind(id(private_channel.user_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_private_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM private_channels WHERE channel_id = ?")
+            .bind(id(channel_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_message(&self, message: CachedMessage) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO messages (activity_type, activity_party_id, \
+             application_cover_image, application_description, application_icon, \
+             application_id, application_name, interaction_application_id, author, author_bot, \
+             author_name, author_discriminator, author_avatar, channel_id, content, \
+             edited_timestamp, flags, guild_id, id, kind, mention_everyone, pinned, \
+             reference_channel_id, reference_guild_id, reference_message_id, \
+             reference_fail_if_not_exists, referenced_message, timestamp, thread, tts, webhook_id, \
+             partial) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(message.activity_type.map(u8::from))
+        .bind(message.activity_party_id)
+        .bind(image_hash(message.application_cover_image))
+        .bind(message.application_description)
+        .bind(image_hash(message.application_icon))
+        .bind(message.application_id.map(id))
+        .bind(message.application_name)
+        .bind(message.interaction_application_id.map(id))
+        .bind(id(message.author))
+        .bind(message.author_bot)
+        .bind(message.author_name)
+        .bind(message.author_discriminator)
+        .bind(image_hash(message.author_avatar))
+        .bind(id(message.channel_id))
+        .bind(message.content)
+        .bind(message.edited_timestamp.map(Timestamp::as_micros))
+        .bind(message.flags.map(|flags| int(flags.bits())))
+        .bind(message.guild_id.map(id))
+        .bind(id(message.id))
+        .bind(u8::from(message.kind))
+        .bind(message.mention_everyone)
+        .bind(message.pinned)
+        .bind(message.reference_channel_id.map(id))
+        .bind(message.reference_guild_id.map(id))
+        .bind(message.reference_message_id.map(id))
+        .bind(message.reference_fail_if_not_exists)
+        .bind(message.referenced_message.map(id))
+        .bind(message.timestamp.as_micros())
+        .bind(message.thread.map(id))
+        .bind(message.tts)
+        .bind(message.webhook_id.map(id))
+        .bind(message.partial)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message(&self, message_id: Id<MessageMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM messages WHERE id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_messages(&self, message_ids: &[Id<MessageMarker>]) -> Result<(), Self::Error> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+
+        let statement = format!(
+            "DELETE FROM messages WHERE id IN ({})",
+            placeholders(message_ids.len())
+        );
+        let mut query = sqlx::query(&statement);
+        for &message_id in message_ids {
+            query = query.bind(id(message_id));
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn delete_messages_data(
+        &self,
+        message_ids: &[Id<MessageMarker>],
+    ) -> Result<(), Self::Error> {
+        if message_ids.is_empty() {
+            return Ok(());
+        }
+
+        let ids = placeholders(message_ids.len());
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            format!(
+                "DELETE FROM embed_fields WHERE embed_id IN (SELECT id FROM embeds WHERE \
+                 message_id IN ({ids}))"
+            ),
+            format!("DELETE FROM embeds WHERE message_id IN ({ids})"),
+            #[cfg(feature = "embeds-json")]
+            format!("DELETE FROM message_embeds WHERE message_id IN ({ids})"),
+            format!("DELETE FROM attachments WHERE message_id IN ({ids})"),
+            format!("DELETE FROM attachment_posts WHERE message_id IN ({ids})"),
+            format!("DELETE FROM reactions WHERE message_id IN ({ids})"),
+            format!("DELETE FROM stickers WHERE message_id IN ({ids})"),
+            format!("DELETE FROM message_revisions WHERE message_id IN ({ids})"),
+        ] {
+            let mut query = sqlx::query(&statement);
+            for &message_id in message_ids {
+                query = query.bind(id(message_id));
+            }
+            query.execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_messages_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM messages WHERE timestamp < ?")
+            .bind(timestamp.as_micros())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_messages_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM messages WHERE guild_id = ? AND timestamp < ?")
+            .bind(id(guild_id))
+            .bind(timestamp.as_micros())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn select_guild_message_authors(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        sqlx::query(
+            "SELECT DISTINCT author AS id FROM messages WHERE guild_id = ? AND timestamp >= ?",
+        )
+        .bind(id(guild_id))
+        .bind(since.as_micros())
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::id)
+        .collect()
+    }
+
+    async fn select_user_last_message(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedMessage>, Self::Error> {
+        sqlx::query(
+            "SELECT * FROM messages WHERE channel_id = ? AND author = ? ORDER BY timestamp DESC \
+             LIMIT 1",
+        )
+        .bind(id(channel_id))
+        .bind(id(user_id))
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(row::message)
+        .transpose()
+    }
+
+    async fn select_channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        let after = after.map(Timestamp::as_micros);
+        let before = before.map(Timestamp::as_micros);
+        sqlx::query(
+            "SELECT * FROM messages WHERE channel_id = ? AND (? IS NULL OR timestamp > ?) AND (? \
+             IS NULL OR timestamp < ?) ORDER BY timestamp DESC LIMIT ?",
+        )
+        .bind(id(channel_id))
+        .bind(after)
+        .bind(after)
+        .bind(before)
+        .bind(before)
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect()
+    }
+
+    async fn select_filtered_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        sqlx::query(
+            "SELECT * FROM messages WHERE channel_id = ? AND (? = 0 OR kind IN (0, 19, 20, 23)) \
+             AND (? = 0 OR author_bot = 0) AND (? = 0 OR webhook_id IS NULL) ORDER BY timestamp \
+             DESC LIMIT ?",
+        )
+        .bind(id(channel_id))
+        .bind(filter.excludes_system())
+        .bind(filter.excludes_bots())
+        .bind(filter.excludes_webhooks())
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect()
+    }
+
+    async fn delete_orphaned_message_data(&self) -> Result<(), Self::Error> {
+        execute_all(
+            &self.pool,
+            &[
+                "DELETE FROM embeds WHERE message_id NOT IN (SELECT id FROM messages)",
+                "DELETE FROM embed_fields WHERE embed_id NOT IN (SELECT id FROM embeds)",
+                "DELETE FROM attachments WHERE message_id NOT IN (SELECT id FROM messages)",
+                #[cfg(feature = "embeds-json")]
+                "DELETE FROM message_embeds WHERE message_id NOT IN (SELECT id FROM messages)",
+                "DELETE FROM attachment_posts WHERE message_id NOT IN (SELECT id FROM messages)",
+                "DELETE FROM attachment_blobs WHERE file_hash NOT IN (SELECT file_hash FROM \
+                 attachment_posts)",
+                "DELETE FROM reactions WHERE message_id NOT IN (SELECT id FROM messages)",
+                "DELETE FROM message_revisions WHERE message_id NOT IN (SELECT id FROM messages)",
+                "DELETE FROM stickers WHERE message_id IS NOT NULL AND message_id NOT IN (SELECT \
+                 id FROM messages)",
+            ],
+        )
+        .await
+    }
+
+    async fn insert_message_revision(
+        &self,
+        revision: CachedMessageRevision,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO message_revisions (message_id, revision_id, edited_at, content) VALUES \
+             (?, ?, ?, ?)",
+        )
+        .bind(id(revision.message_id))
+        .bind(id(revision.revision_id))
+        .bind(revision.edited_at.as_micros())
+        .bind(revision.content)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM message_revisions WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_embed(&self, embed: CachedEmbed) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO embeds (id, message_id, author_icon_url, author_name, \
+             author_proxy_icon_url, author_url, color, description, footer_icon_url, \
+             footer_proxy_icon_url, footer_text, image_height, image_proxy_url, image_url, \
+             image_width, kind, provider_name, provider_url, thumbnail_height, \
+             thumbnail_proxy_url, thumbnail_url, thumbnail_width, timestamp, title, url, \
+             video_height, video_proxy_url, video_url, video_width) VALUES (?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(embed.id))
+        .bind(id(embed.message_id))
+        .bind(embed.author_icon_url)
+        .bind(embed.author_name)
+        .bind(embed.author_proxy_icon_url)
+        .bind(embed.author_url)
+        .bind(embed.color)
+        .bind(embed.description)
+        .bind(embed.footer_icon_url)
+        .bind(embed.footer_proxy_icon_url)
+        .bind(embed.footer_text)
+        .bind(embed.image_height.map(int))
+        .bind(embed.image_proxy_url)
+        .bind(embed.image_url)
+        .bind(embed.image_width.map(int))
+        .bind(embed.kind)
+        .bind(embed.provider_name)
+        .bind(embed.provider_url)
+        .bind(embed.thumbnail_height.map(int))
+        .bind(embed.thumbnail_proxy_url)
+        .bind(embed.thumbnail_url)
+        .bind(embed.thumbnail_width.map(int))
+        .bind(embed.timestamp.map(Timestamp::as_micros))
+        .bind(embed.title)
+        .bind(embed.url)
+        .bind(embed.video_height.map(int))
+        .bind(embed.video_proxy_url)
+        .bind(embed.video_url)
+        .bind(embed.video_width.map(int))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_embed(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM embeds WHERE id = ?")
+            .bind(id(embed_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_embeds_bulk(&self, embed_ids: &[Id<GenericMarker>]) -> Result<(), Self::Error> {
+        if embed_ids.is_empty() {
+            return Ok(());
+        }
+
+        let statement = format!(
+            "DELETE FROM embeds WHERE id IN ({})",
+            placeholders(embed_ids.len())
+        );
+        let mut query = sqlx::query(&statement);
+        for &embed_id in embed_ids {
+            query = query.bind(id(embed_id));
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn upsert_embed_field(&self, embed_field: CachedEmbedField) -> Result<(), Self::Error> {
+        sqlx::query("INSERT INTO embed_fields (embed_id, inline, name, value) VALUES (?, ?, ?, ?)")
+            .bind(id(embed_field.embed_id))
+            .bind(embed_field.inline)
+            .bind(embed_field.name)
+            .bind(embed_field.value)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<(), Self::Error> {
+        if embed_ids.is_empty() {
+            return Ok(());
+        }
+
+        let statement = format!(
+            "DELETE FROM embed_fields WHERE embed_id IN ({})",
+            placeholders(embed_ids.len())
+        );
+        let mut query = sqlx::query(&statement);
+        for &embed_id in embed_ids {
+            query = query.bind(id(embed_id));
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn delete_embed_fields(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM embed_fields WHERE embed_id = ?")
+            .bind(id(embed_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn select_message_embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedEmbed>, Self::Error> {
+        sqlx::query("SELECT * FROM embeds WHERE message_id = ?")
+            .bind(id(message_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::embed)
+            .collect()
+    }
+
+    async fn select_embed_fields(
+        &self,
+        embed_id: Id<GenericMarker>,
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        sqlx::query("SELECT * FROM embed_fields WHERE embed_id = ?")
+            .bind(id(embed_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::embed_field)
+            .collect()
+    }
+
+    async fn select_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        if embed_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let statement = format!(
+            "SELECT * FROM embed_fields WHERE embed_id IN ({}) ORDER BY rowid",
+            placeholders(embed_ids.len())
+        );
+        let mut query = sqlx::query(&statement);
+        for &embed_id in embed_ids {
+            query = query.bind(id(embed_id));
+        }
+
+        query
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::embed_field)
+            .collect()
+    }
+
+    async fn select_orphaned_embed_fields(&self) -> Result<Vec<Id<GenericMarker>>, Self::Error> {
+        select_ids(
+            &self.pool,
+            "SELECT DISTINCT embed_id AS id FROM embed_fields WHERE embed_id NOT IN (SELECT id \
+             FROM embeds)",
+        )
+        .await
+    }
+
+    async fn select_orphaned_embeds(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        select_ids(
+            &self.pool,
+            "SELECT DISTINCT message_id AS id FROM embeds WHERE message_id NOT IN (SELECT id FROM \
+             messages)",
+        )
+        .await
+    }
+
+    async fn select_orphaned_attachments(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        select_ids(
+            &self.pool,
+            "SELECT DISTINCT message_id AS id FROM attachments WHERE message_id NOT IN (SELECT id \
+             FROM messages)",
+        )
+        .await
+    }
+
+    async fn select_orphaned_reactions(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        select_ids(
+            &self.pool,
+            "SELECT DISTINCT message_id AS id FROM reactions WHERE message_id NOT IN (SELECT id \
+             FROM messages)",
+        )
+        .await
+    }
+
+    async fn upsert_attachment(&self, attachment: CachedAttachment) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO attachments (message_id, content_type, duration_millis, \
+             ephemeral, filename, flags, description, height, id, proxy_url, size, url, waveform, \
+             width) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(attachment.message_id))
+        .bind(attachment.content_type)
+        .bind(attachment.duration_millis.map(int))
+        .bind(attachment.ephemeral)
+        .bind(attachment.filename)
+        .bind(attachment.flags.map(int))
+        .bind(attachment.description)
+        .bind(attachment.height.map(int))
+        .bind(id(attachment.id))
+        .bind(attachment.proxy_url)
+        .bind(int(attachment.size))
+        .bind(attachment.url)
+        .bind(attachment.waveform)
+        .bind(attachment.width.map(int))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_attachment_blob(&self, blob: CachedAttachmentBlob) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO attachment_blobs (file_hash, content_type, duration_millis, \
+             filename, flags, height, size, waveform, width) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(blob.file_hash)
+        .bind(blob.content_type)
+        .bind(blob.duration_millis.map(int))
+        .bind(blob.filename)
+        .bind(blob.flags.map(int))
+        .bind(blob.height.map(int))
+        .bind(int(blob.size))
+        .bind(blob.waveform)
+        .bind(blob.width.map(int))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_attachment_post(&self, post: CachedAttachmentPost) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO attachment_posts (file_hash, message_id, description, \
+             ephemeral, id, proxy_url, url) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(post.file_hash)
+        .bind(id(post.message_id))
+        .bind(post.description)
+        .bind(post.ephemeral)
+        .bind(id(post.id))
+        .bind(post.proxy_url)
+        .bind(post.url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message_attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM attachments WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM attachment_posts WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_reaction(&self, reaction: CachedReaction) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO reactions (channel_id, emoji, emoji_name, emoji_animated, \
+             guild_id, message_id, user_id, burst, burst_colors) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(reaction.channel_id))
+        .bind(reaction.emoji)
+        .bind(reaction.emoji_name)
+        .bind(reaction.emoji_animated)
+        .bind(reaction.guild_id.map(id))
+        .bind(id(reaction.message_id))
+        .bind(id(reaction.user_id))
+        .bind(reaction.burst)
+        .bind(reaction.burst_colors)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_reaction(
+        &self,
+        message_id: Id<MessageMarker>,
+        user_id: Id<UserMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM reactions WHERE message_id = ? AND user_id = ? AND emoji = ?")
+            .bind(id(message_id))
+            .bind(id(user_id))
+            .bind(emoji)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message_reactions_by_emoji(
+        &self,
+        message_id: Id<MessageMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM reactions WHERE message_id = ? AND emoji = ?")
+            .bind(id(message_id))
+            .bind(emoji)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message_reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM reactions WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_member(&self, member: CachedMember) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO members (guild_avatar, communication_disabled_until, deaf, \
+             guild_id, joined_at, mute, nick, pending, premium_since, accent_color, avatar, \
+             banner, bot, discriminator, flags, id, locale, mfa_enabled, name, premium_type, \
+             public_flags, system) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?)",
+        )
+        .bind(image_hash(member.guild_avatar))
+        .bind(
+            member
+                .communication_disabled_until
+                .map(Timestamp::as_micros),
+        )
+        .bind(member.deaf)
+        .bind(id(member.guild_id))
+        .bind(member.joined_at.as_micros())
+        .bind(member.mute)
+        .bind(member.nick)
+        .bind(member.pending)
+        .bind(member.premium_since.map(Timestamp::as_micros))
+        .bind(member.accent_color)
+        .bind(image_hash(member.avatar))
+        .bind(image_hash(member.banner))
+        .bind(member.bot)
+        .bind(member.discriminator)
+        .bind(member.flags.map(|flags| int(flags.bits())))
+        .bind(id(member.id))
+        .bind(member.locale)
+        .bind(member.mfa_enabled)
+        .bind(member.name)
+        .bind(member.premium_type.map(u8::from))
+        .bind(member.public_flags.map(|flags| int(flags.bits())))
+        .bind(member.system)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn patch_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        patch: MemberPatch,
+    ) -> Result<(), Self::Error> {
+        if patch.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new("UPDATE members SET ");
+        let mut set = query.separated(", ");
+        if let Some(guild_avatar) = patch.guild_avatar {
+            set.push("guild_avatar = ")
+                .push_bind_unseparated(image_hash(guild_avatar));
+        }
+        if let Some(communication_disabled_until) = patch.communication_disabled_until {
+            set.push("communication_disabled_until = ")
+                .push_bind_unseparated(communication_disabled_until.map(Timestamp::as_micros));
+        }
+        if let Some(deaf) = patch.deaf {
+            set.push("deaf = ").push_bind_unseparated(deaf);
+        }
+        if let Some(mute) = patch.mute {
+            set.push("mute = ").push_bind_unseparated(mute);
+        }
+        if let Some(nick) = patch.nick {
+            set.push("nick = ").push_bind_unseparated(nick);
+        }
+        if let Some(pending) = patch.pending {
+            set.push("pending = ").push_bind_unseparated(pending);
+        }
+        if let Some(premium_since) = patch.premium_since {
+            set.push("premium_since = ")
+                .push_bind_unseparated(premium_since.map(Timestamp::as_micros));
+        }
+        if let Some(accent_color) = patch.accent_color {
+            set.push("accent_color = ")
+                .push_bind_unseparated(accent_color);
+        }
+        if let Some(avatar) = patch.avatar {
+            set.push("avatar = ")
+                .push_bind_unseparated(image_hash(avatar));
+        }
+        if let Some(banner) = patch.banner {
+            set.push("banner = ")
+                .push_bind_unseparated(image_hash(banner));
+        }
+        if let Some(discriminator) = patch.discriminator {
+            set.push("discriminator = ")
+                .push_bind_unseparated(discriminator);
+        }
+        if let Some(flags) = patch.flags {
+            set.push("flags = ")
+                .push_bind_unseparated(flags.map(|flags| int(flags.bits())));
+        }
+        if let Some(locale) = patch.locale {
+            set.push("locale = ").push_bind_unseparated(locale);
+        }
+        if let Some(mfa_enabled) = patch.mfa_enabled {
+            set.push("mfa_enabled = ")
+                .push_bind_unseparated(mfa_enabled);
+        }
+        if let Some(name) = patch.name {
+            set.push("name = ").push_bind_unseparated(name);
+        }
+        if let Some(premium_type) = patch.premium_type {
+            set.push("premium_type = ")
+                .push_bind_unseparated(premium_type.map(u8::from));
+        }
+        if let Some(public_flags) = patch.public_flags {
+            set.push("public_flags = ")
+                .push_bind_unseparated(public_flags.map(|flags| int(flags.bits())));
+        }
+        if let Some(system) = patch.system {
+            set.push("system = ").push_bind_unseparated(system);
+        }
+        query
+            .push(" WHERE guild_id = ")
+            .push_bind(id(guild_id))
+            .push(" AND id = ")
+            .push_bind(id(user_id));
+        query.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn delete_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM members WHERE id = ? AND guild_id = ?")
+            .bind(id(user_id))
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_members(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM members WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn select_mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        sqlx::query(
+            "SELECT a.guild_id AS id FROM members AS a JOIN members AS b ON a.guild_id = \
+             b.guild_id WHERE a.id = ? AND b.id = ?",
+        )
+        .bind(id(user_a))
+        .bind(id(user_b))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::id)
+        .collect()
+    }
+
+    async fn select_members_by_prefix(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Self::Error> {
+        let pattern = like_prefix(prefix);
+        sqlx::query(
+            "SELECT * FROM members WHERE guild_id = ? AND (nick LIKE ? ESCAPE '\\' OR name LIKE \
+             ? ESCAPE '\\') ORDER BY COALESCE(nick, name) LIMIT ?",
+        )
+        .bind(id(guild_id))
+        .bind(&pattern)
+        .bind(&pattern)
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::member)
+        .collect()
+    }
+
+    async fn upsert_presence(&self, presence: CachedPresence) -> Result<(), Self::Error> {
+        sqlx::query("INSERT OR REPLACE INTO presences (guild_id, status, user) VALUES (?, ?, ?)")
+            .bind(id(presence.guild_id))
+            .bind(status(presence.status))
+            .bind(id(presence.user))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_presence(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM presences WHERE guild_id = ? AND user = ?")
+            .bind(id(guild_id))
+            .bind(id(user_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_presences(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM presences WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_memberless_presences(&self) -> Result<(), Self::Error> {
+        execute_all(
+            &self.pool,
+            &[
+                "DELETE FROM presences WHERE NOT EXISTS (SELECT 1 FROM members WHERE \
+                 members.guild_id = presences.guild_id AND members.id = presences.user)",
+                "DELETE FROM activities WHERE NOT EXISTS (SELECT 1 FROM members WHERE \
+                 members.guild_id = activities.guild_id AND members.id = activities.user_id)",
+            ],
+        )
+        .await
+    }
+
+    async fn set_expiry(
+        &self,
+        kind: EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+        expires_at: Timestamp,
+    ) -> Result<(), Self::Error> {
+        let query = match kind {
+            EntityKind::Channel => "UPDATE channels SET expires_at = ? WHERE id = ?",
+            EntityKind::Message => "UPDATE messages SET expires_at = ? WHERE id = ?",
+            EntityKind::Member => "UPDATE members SET expires_at = ? WHERE id = ? AND guild_id = ?",
+            EntityKind::Guild => "UPDATE guilds SET expires_at = ? WHERE id = ?",
+            EntityKind::Role => "UPDATE roles SET expires_at = ? WHERE id = ?",
+            EntityKind::Emoji => "UPDATE emojis SET expires_at = ? WHERE id = ?",
+            EntityKind::Sticker => {
+                "UPDATE stickers SET expires_at = ? WHERE id = ? AND message_id IS NULL"
+            }
+            EntityKind::StageInstance => "UPDATE stage_instances SET expires_at = ? WHERE id = ?",
+            EntityKind::Presence => {
+                "UPDATE presences SET expires_at = ? WHERE user = ? AND guild_id = ?"
+            }
+        };
+
+        let mut query = sqlx::query(query)
+            .bind(expires_at.as_micros())
+            .bind(int(entity_id));
+        if let (EntityKind::Member | EntityKind::Presence, Some(guild_id)) = (kind, guild_id) {
+            query = query.bind(id(guild_id));
+        }
+        query.execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn delete_expired(&self, now: Timestamp) -> Result<(), Self::Error> {
+        for table in [
+            "channels",
+            "messages",
+            "members",
+            "guilds",
+            "roles",
+            "emojis",
+            "stickers",
+            "stage_instances",
+            "presences",
+        ] {
+            sqlx::query(&format!("DELETE FROM {table} WHERE expires_at < ?"))
+                .bind(now.as_micros())
+                .execute(&self.pool)
+                .await?;
+        }
+        sqlx::query(
+            "DELETE FROM activities WHERE NOT EXISTS (SELECT 1 FROM presences WHERE \
+             presences.guild_id = activities.guild_id AND presences.user = activities.user_id)",
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_activity(&self, activity: CachedActivity) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO activities (user_id, guild_id, application_id, asset_large_image, \
+             asset_large_text, asset_small_image, asset_small_text, button_1, button_2, \
+             created_at, details, emoji_animated, emoji_name, emoji_id, flags, id, instance, kind, \
+             name, party_id, party_size_current, party_size_max, state, timestamp_end, \
+             timestamp_start, url) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(activity.user_id))
+        .bind(id(activity.guild_id))
+        .bind(activity.application_id.map(id))
+        .bind(activity.asset_large_image)
+        .bind(activity.asset_large_text)
+        .bind(activity.asset_small_image)
+        .bind(activity.asset_small_text)
+        .bind(activity.button_1)
+        .bind(activity.button_2)
+        .bind(activity.created_at.map(int))
+        .bind(activity.details)
+        .bind(activity.emoji_animated)
+        .bind(activity.emoji_name)
+        .bind(activity.emoji_id)
+        .bind(activity.flags.map(|flags| int(flags.bits())))
+        .bind(activity.id)
+        .bind(activity.instance)
+        .bind(u8::from(activity.kind))
+        .bind(activity.name)
+        .bind(activity.party_id)
+        .bind(activity.party_size_current.map(int))
+        .bind(activity.party_size_max.map(int))
+        .bind(activity.state)
+        .bind(activity.timestamp_end.map(int))
+        .bind(activity.timestamp_start.map(int))
+        .bind(activity.url)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_user_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM activities WHERE guild_id = ? AND user_id = ?")
+            .bind(id(guild_id))
+            .bind(id(user_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_guild(&self, guild: CachedGuild) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO guilds (afk_channel_id, afk_timeout, application_id, banner, \
+             default_message_notifications, description, discovery_splash, \
+             explicit_content_filter, features, icon, id, joined_at, large, max_members, \
+             max_presences, max_stage_video_channel_users, max_video_channel_users, mfa_level, \
+             name, nsfw_level, owner_id, owner, permissions, preferred_locale, \
+             premium_progress_bar_enabled, premium_subscription_count, premium_tier, \
+             public_updates_channel_id, rules_channel_id, safety_alerts_channel_id, splash, \
+             system_channel_flags, system_channel_id, unavailable, vanity_url_code, \
+             verification_level, widget_channel_id, widget_enabled, members_partial) VALUES (?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(guild.afk_channel_id.map(id))
+        .bind(int(guild.afk_timeout))
+        .bind(guild.application_id.map(id))
+        .bind(image_hash(guild.banner))
+        .bind(u8::from(guild.default_message_notifications))
+        .bind(guild.description)
+        .bind(image_hash(guild.discovery_splash))
+        .bind(u8::from(guild.explicit_content_filter))
+        .bind(features(&guild.features))
+        .bind(image_hash(guild.icon))
+        .bind(id(guild.id))
+        .bind(guild.joined_at.map(Timestamp::as_micros))
+        .bind(guild.large)
+        .bind(guild.max_members.map(int))
+        .bind(guild.max_presences.map(int))
+        .bind(guild.max_stage_video_channel_users.map(int))
+        .bind(guild.max_video_channel_users.map(int))
+        .bind(u8::from(guild.mfa_level))
+        .bind(guild.name)
+        .bind(u8::from(guild.nsfw_level))
+        .bind(id(guild.owner_id))
+        .bind(guild.owner)
+        .bind(guild.permissions.map(|permissions| int(permissions.bits())))
+        .bind(guild.preferred_locale)
+        .bind(guild.premium_progress_bar_enabled)
+        .bind(guild.premium_subscription_count.map(int))
+        .bind(u8::from(guild.premium_tier))
+        .bind(guild.public_updates_channel_id.map(id))
+        .bind(guild.rules_channel_id.map(id))
+        .bind(guild.safety_alerts_channel_id.map(id))
+        .bind(image_hash(guild.splash))
+        .bind(int(guild.system_channel_flags.bits()))
+        .bind(guild.system_channel_id.map(id))
+        .bind(guild.unavailable)
+        .bind(guild.vanity_url_code)
+        .bind(u8::from(guild.verification_level))
+        .bind(guild.widget_channel_id.map(id))
+        .bind(guild.widget_enabled)
+        .bind(guild.members_partial)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn patch_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+        patch: GuildPatch,
+    ) -> Result<(), Self::Error> {
+        if patch.is_empty() {
+            return Ok(());
+        }
+
+        let mut query = QueryBuilder::<Sqlite>::new("UPDATE guilds SET ");
+        let mut set = query.separated(", ");
+        if let Some(afk_channel_id) = patch.afk_channel_id {
+            set.push("afk_channel_id = ")
+                .push_bind_unseparated(afk_channel_id.map(id));
+        }
+        if let Some(afk_timeout) = patch.afk_timeout {
+            set.push("afk_timeout = ")
+                .push_bind_unseparated(int(afk_timeout));
+        }
+        if let Some(application_id) = patch.application_id {
+            set.push("application_id = ")
+                .push_bind_unseparated(application_id.map(id));
+        }
+        if let Some(banner) = patch.banner {
+            set.push("banner = ")
+                .push_bind_unseparated(image_hash(banner));
+        }
+        if let Some(default_message_notifications) = patch.default_message_notifications {
+            set.push("default_message_notifications = ")
+                .push_bind_unseparated(u8::from(default_message_notifications));
+        }
+        if let Some(description) = patch.description {
+            set.push("description = ")
+                .push_bind_unseparated(description);
+        }
+        if let Some(discovery_splash) = patch.discovery_splash {
+            set.push("discovery_splash = ")
+                .push_bind_unseparated(image_hash(discovery_splash));
+        }
+        if let Some(explicit_content_filter) = patch.explicit_content_filter {
+            set.push("explicit_content_filter = ")
+                .push_bind_unseparated(u8::from(explicit_content_filter));
+        }
+        if let Some(guild_features) = patch.features {
+            set.push("features = ")
+                .push_bind_unseparated(features(&guild_features));
+        }
+        if let Some(icon) = patch.icon {
+            set.push("icon = ").push_bind_unseparated(image_hash(icon));
+        }
+        if let Some(max_members) = patch.max_members {
+            set.push("max_members = ")
+                .push_bind_unseparated(max_members.map(int));
+        }
+        if let Some(max_presences) = patch.max_presences {
+            set.push("max_presences = ")
+                .push_bind_unseparated(max_presences.map(int));
+        }
+        if let Some(mfa_level) = patch.mfa_level {
+            set.push("mfa_level = ")
+                .push_bind_unseparated(u8::from(mfa_level));
+        }
+        if let Some(name) = patch.name {
+            set.push("name = ").push_bind_unseparated(name);
+        }
+        if let Some(nsfw_level) = patch.nsfw_level {
+            set.push("nsfw_level = ")
+                .push_bind_unseparated(u8::from(nsfw_level));
+        }
+        if let Some(owner_id) = patch.owner_id {
+            set.push("owner_id = ").push_bind_unseparated(id(owner_id));
+        }
+        if let Some(owner) = patch.owner {
+            set.push("owner = ").push_bind_unseparated(owner);
+        }
+        if let Some(permissions) = patch.permissions {
+            set.push("permissions = ")
+                .push_bind_unseparated(permissions.map(|permissions| int(permissions.bits())));
+        }
+        if let Some(preferred_locale) = patch.preferred_locale {
+            set.push("preferred_locale = ")
+                .push_bind_unseparated(preferred_locale);
+        }
+        if let Some(premium_progress_bar_enabled) = patch.premium_progress_bar_enabled {
+            set.push("premium_progress_bar_enabled = ")
+                .push_bind_unseparated(premium_progress_bar_enabled);
+        }
+        if let Some(premium_subscription_count) = patch.premium_subscription_count {
+            set.push("premium_subscription_count = ")
+                .push_bind_unseparated(premium_subscription_count.map(int));
+        }
+        if let Some(premium_tier) = patch.premium_tier {
+            set.push("premium_tier = ")
+                .push_bind_unseparated(u8::from(premium_tier));
+        }
+        if let Some(rules_channel_id) = patch.rules_channel_id {
+            set.push("rules_channel_id = ")
+                .push_bind_unseparated(rules_channel_id.map(id));
+        }
+        if let Some(splash) = patch.splash {
+            set.push("splash = ")
+                .push_bind_unseparated(image_hash(splash));
+        }
+        if let Some(system_channel_flags) = patch.system_channel_flags {
+            set.push("system_channel_flags = ")
+                .push_bind_unseparated(int(system_channel_flags.bits()));
+        }
+        if let Some(system_channel_id) = patch.system_channel_id {
+            set.push("system_channel_id = ")
+                .push_bind_unseparated(system_channel_id.map(id));
+        }
+        if let Some(verification_level) = patch.verification_level {
+            set.push("verification_level = ")
+                .push_bind_unseparated(u8::from(verification_level));
+        }
+        if let Some(vanity_url_code) = patch.vanity_url_code {
+            set.push("vanity_url_code = ")
+                .push_bind_unseparated(vanity_url_code);
+        }
+        if let Some(widget_channel_id) = patch.widget_channel_id {
+            set.push("widget_channel_id = ")
+                .push_bind_unseparated(widget_channel_id.map(id));
+        }
+        if let Some(widget_enabled) = patch.widget_enabled {
+            set.push("widget_enabled = ")
+                .push_bind_unseparated(widget_enabled);
+        }
+        query.push(" WHERE id = ").push_bind(id(guild_id));
+        query.build().execute(&self.pool).await?;
+
+        Ok(())
+    }
+
+    async fn select_guilds_by_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Self::Error> {
+        sqlx::query("SELECT * FROM guilds WHERE instr(',' || features || ',', ?) > 0")
+            .bind(format!(",{},", features(&[feature])))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::guild)
+            .collect()
+    }
+
+    async fn delete_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM guilds WHERE id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_cascade(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        let mut transaction = self.pool.begin().await?;
+        for statement in [
+            "DELETE FROM channels WHERE guild_id = ?",
+            "DELETE FROM emojis WHERE guild_id = ?",
+            "DELETE FROM stickers WHERE guild_id = ? AND message_id IS NULL",
+            "DELETE FROM members WHERE guild_id = ?",
+            "DELETE FROM presences WHERE guild_id = ?",
+            "DELETE FROM role_assignments WHERE guild_id = ?",
+            "DELETE FROM roles WHERE guild_id = ?",
+            "DELETE FROM stage_instances WHERE guild_id = ?",
+            "DELETE FROM guilds WHERE id = ?",
+        ] {
+            sqlx::query(statement)
+                .bind(id(guild_id))
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_remnants(&self) -> Result<(), Self::Error> {
+        execute_all(
+            &self.pool,
+            &[
+                "DELETE FROM channels WHERE guild_id IS NOT NULL AND guild_id NOT IN (SELECT id \
+                 FROM guilds)",
+                "DELETE FROM permission_overwrites WHERE channel_id NOT IN (SELECT id FROM \
+                 channels)",
+                "DELETE FROM channel_activity WHERE channel_id NOT IN (SELECT id FROM channels)",
+                "DELETE FROM members WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM presences WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM activities WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM roles WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM role_assignments WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM emojis WHERE guild_id NOT IN (SELECT id FROM guilds)",
+                "DELETE FROM stickers WHERE guild_id IS NOT NULL AND guild_id NOT IN (SELECT id \
+                 FROM guilds)",
+                "DELETE FROM stage_instances WHERE guild_id NOT IN (SELECT id FROM guilds)",
+            ],
+        )
+        .await
+    }
+
+    async fn upsert_role(&self, role: CachedRole) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO roles (guild_id, color, hoist, icon, id, managed, \
+             mentionable, name, permissions, position, flags, tags_available_for_purchase, \
+             tags_bot_id, tags_guild_connections, tags_integration_id, tags_premium_subscriber, \
+             tags_subscription_listing_id, unicode_emoji) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(role.guild_id))
+        .bind(role.color)
+        .bind(role.hoist)
+        .bind(image_hash(role.icon))
+        .bind(id(role.id))
+        .bind(role.managed)
+        .bind(role.mentionable)
+        .bind(role.name)
+        .bind(int(role.permissions.bits()))
+        .bind(role.position)
+        .bind(role.flags.map(int))
+        .bind(role.tags_available_for_purchase)
+        .bind(role.tags_bot_id.map(id))
+        .bind(role.tags_guild_connections)
+        .bind(role.tags_integration_id.map(id))
+        .bind(role.tags_premium_subscriber)
+        .bind(role.tags_subscription_listing_id.map(id))
+        .bind(role.unicode_emoji)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_role(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM roles WHERE id = ?")
+            .bind(id(role_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn select_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Self::Error> {
+        sqlx::query("SELECT * FROM roles WHERE guild_id = ? AND id = ?")
+            .bind(id(guild_id))
+            .bind(id(guild_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::role)
+            .transpose()
+    }
+
+    async fn select_orphaned_roles(&self) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        select_ids(
+            &self.pool,
+            "SELECT DISTINCT guild_id AS id FROM roles WHERE guild_id NOT IN (SELECT id FROM \
+             guilds)",
+        )
+        .await
+    }
+
+    async fn delete_guild_roles(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM roles WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_role_assignment(
+        &self,
+        assignment: CachedRoleAssignment,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO role_assignments (guild_id, user_id, role_id) VALUES (?, ?, ?)",
+        )
+        .bind(id(assignment.guild_id))
+        .bind(id(assignment.user_id))
+        .bind(id(assignment.role_id))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_role_assignments(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM role_assignments WHERE role_id = ?")
+            .bind(id(role_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM role_assignments WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_member_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM role_assignments WHERE guild_id = ? AND user_id = ?")
+            .bind(id(guild_id))
+            .bind(id(user_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn select_role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        sqlx::query("SELECT user_id AS id FROM role_assignments WHERE role_id = ?")
+            .bind(id(role_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::id)
+            .collect()
+    }
+
+    async fn select_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRoleAssignment>, Self::Error> {
+        sqlx::query("SELECT * FROM role_assignments WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::role_assignment)
+            .collect()
+    }
+
+    async fn upsert_emoji(&self, emoji: CachedEmoji) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO emojis (guild_id, animated, available, id, managed, name, \
+             require_colons, user) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(emoji.guild_id))
+        .bind(emoji.animated)
+        .bind(emoji.available)
+        .bind(id(emoji.id))
+        .bind(emoji.managed)
+        .bind(emoji.name)
+        .bind(emoji.require_colons)
+        .bind(emoji.user.map(id))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM emojis WHERE id = ?")
+            .bind(id(emoji_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_emojis(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM emojis WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn upsert_sticker(&self, sticker: CachedSticker) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stickers WHERE id = ? AND message_id IS ?")
+            .bind(id(sticker.id))
+            .bind(sticker.message_id.map(id))
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            "INSERT INTO stickers (message_id, available, description, format_type, guild_id, \
+             id, kind, name, pack_id, sort_value, tags, user_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?, ?)",
+        )
+        .bind(sticker.message_id.map(id))
+        .bind(sticker.available)
+        .bind(sticker.description)
+        .bind(u8::from(sticker.format_type))
+        .bind(sticker.guild_id.map(id))
+        .bind(id(sticker.id))
+        .bind(sticker.kind.map(u8::from))
+        .bind(sticker.name)
+        .bind(sticker.pack_id.map(id))
+        .bind(sticker.sort_value.map(int))
+        .bind(sticker.tags)
+        .bind(sticker.user_id.map(id))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_message_stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stickers WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_sticker(&self, sticker_id: Id<StickerMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stickers WHERE id = ? AND message_id IS NULL")
+            .bind(id(sticker_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_stickers(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stickers WHERE guild_id = ? AND message_id IS NULL")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[allow(clippy::as_conversions)]
+    async fn upsert_stage_instance(&self, stage: StageInstance) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO stage_instances (channel_id, guild_id, \
+             guild_scheduled_event_id, id, privacy_level, topic) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id(stage.channel_id))
+        .bind(id(stage.guild_id))
+        .bind(stage.guild_scheduled_event_id.map(id))
+        .bind(id(stage.id))
+        .bind(stage.privacy_level as u8)
+        .bind(stage.topic)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn delete_stage_instance(&self, stage_id: Id<StageMarker>) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stage_instances WHERE id = ?")
+            .bind(id(stage_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_guild_stage_instances(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM stage_instances WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+    #[cfg(feature = "stats")]
+    async fn increment_emoji_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        emoji: String,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO emoji_uses (guild_id, emoji, day, uses) VALUES (?, ?, ?, 1) ON CONFLICT \
+             (guild_id, emoji, day) DO UPDATE SET uses = uses + 1",
+        )
+        .bind(id(guild_id))
+        .bind(emoji)
+        .bind(int(day))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_sticker_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sticker_id: Id<StickerMarker>,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO sticker_uses (guild_id, sticker_id, day, uses) VALUES (?, ?, ?, 1) ON \
+             CONFLICT (guild_id, sticker_id, day) DO UPDATE SET uses = uses + 1",
+        )
+        .bind(id(guild_id))
+        .bind(id(sticker_id))
+        .bind(int(day))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(String, u64)>, Self::Error> {
+        sqlx::query(
+            "SELECT emoji, SUM(uses) AS uses FROM emoji_uses WHERE guild_id = ? AND day >= ? \
+             GROUP BY emoji ORDER BY SUM(uses) DESC",
+        )
+        .bind(id(guild_id))
+        .bind(int(since_day))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::emoji_usage)
+        .collect()
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Self::Error> {
+        sqlx::query(
+            "SELECT sticker_id, SUM(uses) AS uses FROM sticker_uses WHERE guild_id = ? AND day \
+             >= ? GROUP BY sticker_id ORDER BY SUM(uses) DESC",
+        )
+        .bind(id(guild_id))
+        .bind(int(since_day))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::sticker_usage)
+        .collect()
+    }
+
+    #[cfg(feature = "journal")]
+    async fn append_journal(&self, entry: CachedJournalEntry) -> Result<(), Self::Error> {
+        sqlx::query("INSERT INTO journal (id, recorded_at, kind, data) VALUES (?, ?, ?, ?)")
+            .bind(id(entry.id))
+            .bind(entry.recorded_at.as_micros())
+            .bind(entry.kind)
+            .bind(entry.data)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "journal")]
+    async fn select_journal(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<CachedJournalEntry>, Self::Error> {
+        sqlx::query("SELECT * FROM journal WHERE recorded_at >= ? AND recorded_at < ? ORDER BY id")
+            .bind(start.as_micros())
+            .bind(end.as_micros())
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::journal_entry)
+            .collect()
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn append_changelog(&self, entry: CachedChangelogEntry) -> Result<(), Self::Error> {
+        sqlx::query(
+            "INSERT INTO changelog (id, recorded_at, kind, entity_id, before, after) VALUES (?, \
+             ?, ?, ?, ?, ?)",
+        )
+        .bind(id(entry.id))
+        .bind(entry.recorded_at.as_micros())
+        .bind(entity_kind(entry.kind))
+        .bind(id(entry.entity_id))
+        .bind(entry.before)
+        .bind(entry.after)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn select_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Self::Error> {
+        sqlx::query(
+            "SELECT * FROM changelog WHERE kind = ? AND entity_id = ? ORDER BY id DESC LIMIT ?",
+        )
+        .bind(entity_kind(kind))
+        .bind(id(entity_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::changelog_entry)
+        .collect()
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn upsert_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: serde_json::Value,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("INSERT OR REPLACE INTO message_embeds (message_id, embeds) VALUES (?, ?)")
+            .bind(id(message_id))
+            .bind(embeds.to_string())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn select_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<serde_json::Value>, Self::Error> {
+        sqlx::query("SELECT embeds FROM message_embeds WHERE message_id = ?")
+            .bind(id(message_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::embeds_json)
+            .transpose()
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn delete_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        sqlx::query("DELETE FROM message_embeds WHERE message_id = ?")
+            .bind(id(message_id))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,621 @@
+use async_trait::async_trait;
+use twilight_model::{
+    channel::StageInstance,
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GuildMarker, InteractionMarker, MessageMarker, RoleMarker,
+            StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+use crate::{
+    cache::Error,
+    model::{
+        CachedActivity, CachedAttachment, CachedChannel, CachedEmoji, CachedGuild, CachedMember,
+        CachedMessage, CachedMessageRevision, CachedPermissionOverwrite, CachedPresence,
+        CachedReaction, CachedRole, CachedSession, CachedShardState, CachedSticker,
+    },
+    sqlite::{id, int, row, sql_limit, SqliteCache},
+    Cache,
+};
+
+#[async_trait]
+impl Cache for SqliteCache {
+    async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>> {
+        sqlx::query("SELECT * FROM current_users")
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::current_user)
+            .transpose()?
+            .ok_or(Error::CurrentUserMissing)
+    }
+
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>> {
+        sqlx::query("SELECT * FROM current_applications")
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::current_application)
+            .transpose()?
+            .ok_or(Error::CurrentApplicationMissing)
+    }
+
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM sessions ORDER BY shard_id")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::session)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM shard_states WHERE shard_id = ?")
+            .bind(int(shard_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::shard_state)
+            .transpose()?)
+    }
+
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM shard_states ORDER BY shard_id")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::shard_state)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM interactions WHERE id = ?")
+            .bind(id(interaction_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::interaction)
+            .transpose()?)
+    }
+
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM channels WHERE id = ?")
+            .bind(id(channel_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::channel)
+            .transpose()?)
+    }
+
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM permission_overwrites WHERE channel_id = ?")
+                .bind(id(channel_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::permission_overwrite)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT channels.* FROM channels JOIN private_channels ON channels.id = \
+             private_channels.channel_id WHERE private_channels.user_id = ?",
+        )
+        .bind(id(user_id))
+        .fetch_optional(&self.pool)
+        .await?
+        .as_ref()
+        .map(row::channel)
+        .transpose()?)
+    }
+
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT user_id AS id FROM private_channels WHERE channel_id = ?")
+                .bind(id(channel_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::id)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM channels WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::channel)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT channels.* FROM channels JOIN channel_activity ON \
+             channel_activity.channel_id = channels.id WHERE channels.guild_id = ? AND \
+             channel_activity.last_activity < ?",
+        )
+        .bind(id(guild_id))
+        .bind(since.as_micros())
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::channel)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM channels WHERE guild_id = ? AND thread_archived = TRUE")
+                .bind(id(guild_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::channel)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM channels WHERE parent_id = ? AND thread_archived = FALSE")
+                .bind(id(channel_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::channel)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM messages WHERE id = ?")
+            .bind(id(message_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::message)
+            .transpose()?)
+    }
+
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>> {
+        Ok(
+            sqlx::query(
+                "SELECT * FROM message_revisions WHERE message_id = ? ORDER BY revision_id",
+            )
+            .bind(id(message_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::message_revision)
+            .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT message_id, content_type, duration_millis, ephemeral, filename, flags, \
+             description, height, id, proxy_url, size, url, waveform, width FROM attachments \
+             WHERE message_id = ? UNION ALL SELECT attachment_posts.message_id, \
+             attachment_blobs.content_type, attachment_blobs.duration_millis, \
+             attachment_posts.ephemeral, attachment_blobs.filename, attachment_blobs.flags, \
+             attachment_posts.description, attachment_blobs.height, attachment_posts.id, \
+             attachment_posts.proxy_url, attachment_blobs.size, attachment_posts.url, \
+             attachment_blobs.waveform, attachment_blobs.width FROM attachment_posts JOIN \
+             attachment_blobs ON attachment_blobs.file_hash = attachment_posts.file_hash WHERE \
+             attachment_posts.message_id = ?",
+        )
+        .bind(id(message_id))
+        .bind(id(message_id))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::attachment)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT * FROM messages WHERE id IN (SELECT message_id FROM attachment_posts WHERE \
+             file_hash = ?) ORDER BY timestamp",
+        )
+        .bind(file_hash)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM reactions WHERE message_id = ?")
+            .bind(id(message_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::reaction)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM stickers WHERE message_id = ?")
+            .bind(id(message_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::sticker)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT * FROM messages WHERE channel_id = ? ORDER BY timestamp DESC LIMIT ?",
+        )
+        .bind(id(channel_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT * FROM messages WHERE channel_id = ? AND webhook_id IS NOT NULL ORDER BY \
+             timestamp DESC LIMIT ?",
+        )
+        .bind(id(channel_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT * FROM messages WHERE webhook_id = ? ORDER BY timestamp DESC LIMIT ?",
+        )
+        .bind(id(webhook_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT DISTINCT messages.* FROM messages JOIN stickers ON messages.id = \
+             stickers.message_id WHERE stickers.id = ? ORDER BY messages.timestamp DESC LIMIT ?",
+        )
+        .bind(id(sticker_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT DISTINCT messages.* FROM messages JOIN reactions ON messages.id = \
+             reactions.message_id WHERE reactions.emoji = ? AND messages.guild_id = ? ORDER BY \
+             messages.timestamp DESC LIMIT ?",
+        )
+        .bind(emoji)
+        .bind(id(guild_id))
+        .bind(sql_limit(limit))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::message)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM members WHERE id = ? AND guild_id = ?")
+                .bind(id(user_id))
+                .bind(id(guild_id))
+                .fetch_optional(&self.pool)
+                .await?
+                .as_ref()
+                .map(row::member)
+                .transpose()?,
+        )
+    }
+
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        Ok(sqlx::query(
+            "SELECT roles.* FROM roles JOIN role_assignments ON roles.id = \
+             role_assignments.role_id WHERE role_assignments.user_id = ? AND \
+             role_assignments.guild_id = ?",
+        )
+        .bind(id(user_id))
+        .bind(id(guild_id))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(row::role)
+        .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM presences WHERE user = ? LIMIT 1")
+                .bind(id(user_id))
+                .fetch_optional(&self.pool)
+                .await?
+                .as_ref()
+                .map(row::presence)
+                .transpose()?,
+        )
+    }
+
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM activities WHERE user_id = ?")
+            .bind(id(user_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::activity)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM activities WHERE guild_id = ? AND user_id = ?")
+                .bind(id(guild_id))
+                .bind(id(user_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::activity)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT id FROM guilds")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::id)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT id FROM guilds WHERE unavailable")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::id)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM members WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::member)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM guilds WHERE id = ?")
+            .bind(id(guild_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::guild)
+            .transpose()?)
+    }
+
+    async fn role(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedRole>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM roles WHERE id = ?")
+            .bind(id(role_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::role)
+            .transpose()?)
+    }
+
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM roles WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::role)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM emojis WHERE id = ?")
+            .bind(id(emoji_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::emoji)
+            .transpose()?)
+    }
+
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM emojis WHERE guild_id = ?")
+            .bind(id(guild_id))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(row::emoji)
+            .collect::<Result<_, sqlx::Error>>()?)
+    }
+
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM stickers WHERE id = ? AND message_id IS NULL")
+                .bind(id(sticker_id))
+                .fetch_optional(&self.pool)
+                .await?
+                .as_ref()
+                .map(row::sticker)
+                .transpose()?,
+        )
+    }
+
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        Ok(
+            sqlx::query("SELECT * FROM stickers WHERE guild_id = ? AND message_id IS NULL")
+                .bind(id(guild_id))
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(row::sticker)
+                .collect::<Result<_, sqlx::Error>>()?,
+        )
+    }
+
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>> {
+        Ok(sqlx::query("SELECT * FROM stage_instances WHERE id = ?")
+            .bind(id(stage_id))
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(row::stage_instance)
+            .transpose()?)
+    }
+}
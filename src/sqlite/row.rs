@@ -0,0 +1,653 @@
+use sqlx::{sqlite::SqliteRow, Row};
+use twilight_model::{
+    application::interaction::InteractionType,
+    channel::{
+        message::{
+            sticker::{StickerFormatType, StickerType},
+            MessageActivityType, MessageFlags, MessageType,
+        },
+        permission_overwrite::PermissionOverwriteType,
+        stage_instance::PrivacyLevel,
+        thread::AutoArchiveDuration,
+        ChannelType, StageInstance, VideoQualityMode,
+    },
+    gateway::presence::{ActivityFlags, ActivityType, Status},
+    guild::{
+        DefaultMessageNotificationLevel, ExplicitContentFilter, GuildFeature, MfaLevel, NSFWLevel,
+        Permissions, PremiumTier, SystemChannelFlags, VerificationLevel,
+    },
+    id::Id,
+    oauth::{ApplicationFlags, PartialApplication},
+    user::{CurrentUser, PremiumType, UserFlags},
+    util::{ImageHash, Timestamp},
+};
+
+#[cfg(feature = "stats")]
+use twilight_model::id::marker::StickerMarker;
+
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::model::{
+    CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji,
+    CachedGuild, CachedInteraction, CachedMember, CachedMessage, CachedMessageRevision,
+    CachedPermissionOverwrite, CachedPresence, CachedReaction, CachedRole, CachedRoleAssignment,
+    CachedSession, CachedShardState, CachedSticker,
+};
+#[cfg(feature = "changelog")]
+use crate::{cache::EntityKind, model::CachedChangelogEntry};
+
+/// Return the error for a column that has a value that can't be converted to
+/// the field's type
+fn decode_error(column: &str) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: column.to_owned(),
+        source: "the value is invalid for the field's type".into(),
+    }
+}
+
+/// Methods to get the values of columns that need conversions
+pub(super) trait RowExt {
+    /// Get the value of a column that stores an ID
+    fn try_get_id<T>(&self, column: &str) -> Result<Id<T>, sqlx::Error>;
+
+    /// Get the value of a column that stores an optional ID
+    fn try_get_optional_id<T>(&self, column: &str) -> Result<Option<Id<T>>, sqlx::Error>;
+
+    /// Get the value of a column that stores a `u64`
+    fn try_get_u64(&self, column: &str) -> Result<u64, sqlx::Error>;
+
+    /// Get the value of a column that stores an optional `u64`
+    fn try_get_optional_u64(&self, column: &str) -> Result<Option<u64>, sqlx::Error>;
+
+    /// Get the value of a column that stores a timestamp
+    fn try_get_timestamp(&self, column: &str) -> Result<Timestamp, sqlx::Error>;
+
+    /// Get the value of a column that stores an optional timestamp
+    fn try_get_optional_timestamp(&self, column: &str) -> Result<Option<Timestamp>, sqlx::Error>;
+
+    /// Get the value of a column that stores an optional image hash
+    fn try_get_optional_image_hash(&self, column: &str) -> Result<Option<ImageHash>, sqlx::Error>;
+}
+
+impl RowExt for SqliteRow {
+    fn try_get_id<T>(&self, column: &str) -> Result<Id<T>, sqlx::Error> {
+        self.try_get_optional_id(column)?
+            .ok_or_else(|| decode_error(column))
+    }
+
+    fn try_get_optional_id<T>(&self, column: &str) -> Result<Option<Id<T>>, sqlx::Error> {
+        self.try_get_optional_u64(column)?
+            .map(|value| Id::new_checked(value).ok_or_else(|| decode_error(column)))
+            .transpose()
+    }
+
+    fn try_get_u64(&self, column: &str) -> Result<u64, sqlx::Error> {
+        self.try_get_optional_u64(column)?
+            .ok_or_else(|| decode_error(column))
+    }
+
+    fn try_get_optional_u64(&self, column: &str) -> Result<Option<u64>, sqlx::Error> {
+        self.try_get::<Option<i64>, _>(column)?
+            .map(|value| u64::try_from(value).map_err(|_err| decode_error(column)))
+            .transpose()
+    }
+
+    fn try_get_timestamp(&self, column: &str) -> Result<Timestamp, sqlx::Error> {
+        self.try_get_optional_timestamp(column)?
+            .ok_or_else(|| decode_error(column))
+    }
+
+    fn try_get_optional_timestamp(&self, column: &str) -> Result<Option<Timestamp>, sqlx::Error> {
+        self.try_get::<Option<i64>, _>(column)?
+            .map(|value| Timestamp::from_micros(value).map_err(|_err| decode_error(column)))
+            .transpose()
+    }
+
+    fn try_get_optional_image_hash(&self, column: &str) -> Result<Option<ImageHash>, sqlx::Error> {
+        self.try_get::<Option<String>, _>(column)?
+            .map(|value| ImageHash::parse(value.as_bytes()).map_err(|_err| decode_error(column)))
+            .transpose()
+    }
+}
+
+/// Create a [`CurrentUser`] from a row of `current_users`
+pub(super) fn current_user(row: &SqliteRow) -> Result<CurrentUser, sqlx::Error> {
+    Ok(CurrentUser {
+        accent_color: row.try_get("accent_color")?,
+        avatar: row.try_get_optional_image_hash("avatar")?,
+        banner: row.try_get_optional_image_hash("banner")?,
+        bot: row.try_get("bot")?,
+        discriminator: row.try_get("discriminator")?,
+        email: row.try_get("email")?,
+        flags: row
+            .try_get_optional_u64("flags")?
+            .map(UserFlags::from_bits_truncate),
+        id: row.try_get_id("id")?,
+        locale: row.try_get("locale")?,
+        mfa_enabled: row.try_get("mfa_enabled")?,
+        name: row.try_get("name")?,
+        premium_type: row
+            .try_get::<Option<u8>, _>("premium_type")?
+            .map(PremiumType::from),
+        public_flags: row
+            .try_get_optional_u64("public_flags")?
+            .map(UserFlags::from_bits_truncate),
+        verified: row.try_get("verified")?,
+    })
+}
+
+/// Create a [`PartialApplication`] from a row of `current_applications`
+pub(super) fn current_application(row: &SqliteRow) -> Result<PartialApplication, sqlx::Error> {
+    Ok(PartialApplication {
+        flags: ApplicationFlags::from_bits_truncate(row.try_get_u64("flags")?),
+        id: row.try_get_id("id")?,
+    })
+}
+
+/// Create a [`CachedSession`] from a row of `sessions`
+pub(super) fn session(row: &SqliteRow) -> Result<CachedSession, sqlx::Error> {
+    Ok(CachedSession {
+        session_id: row.try_get("session_id")?,
+        shard_id: row.try_get_u64("shard_id")?,
+        shard_count: row.try_get_u64("shard_count")?,
+        version: row.try_get_u64("version")?,
+    })
+}
+
+/// Create a [`CachedShardState`] from a row of `shard_states`
+pub(super) fn shard_state(row: &SqliteRow) -> Result<CachedShardState, sqlx::Error> {
+    Ok(CachedShardState {
+        shard_id: row.try_get_u64("shard_id")?,
+        sequence: row.try_get_u64("sequence")?,
+        events: row.try_get_u64("events")?,
+        missed_events: row.try_get_u64("missed_events")?,
+    })
+}
+
+/// Create a [`CachedInteraction`] from a row of `interactions`
+pub(super) fn interaction(row: &SqliteRow) -> Result<CachedInteraction, sqlx::Error> {
+    Ok(CachedInteraction {
+        id: row.try_get_id("id")?,
+        application_id: row.try_get_id("application_id")?,
+        channel_id: row.try_get_optional_id("channel_id")?,
+        command_name: row.try_get("command_name")?,
+        guild_id: row.try_get_optional_id("guild_id")?,
+        kind: InteractionType::from(row.try_get::<u8, _>("kind")?),
+        token: row.try_get("token")?,
+        token_expires_at: row.try_get_timestamp("token_expires_at")?,
+        user_id: row.try_get_optional_id("user_id")?,
+    })
+}
+
+/// Create a [`CachedChannel`] from a row of `channels`
+pub(super) fn channel(row: &SqliteRow) -> Result<CachedChannel, sqlx::Error> {
+    Ok(CachedChannel {
+        application_id: row.try_get_optional_id("application_id")?,
+        bitrate: row.try_get("bitrate")?,
+        default_auto_archive_duration: row
+            .try_get::<Option<u16>, _>("default_auto_archive_duration")?
+            .map(AutoArchiveDuration::from),
+        guild_id: row.try_get_optional_id("guild_id")?,
+        icon: row.try_get_optional_image_hash("icon")?,
+        id: row.try_get_id("id")?,
+        invitable: row.try_get("invitable")?,
+        kind: ChannelType::from(row.try_get::<u8, _>("kind")?),
+        name: row.try_get("name")?,
+        nsfw: row.try_get("nsfw")?,
+        owner_id: row.try_get_optional_id("owner_id")?,
+        parent_id: row.try_get_optional_id("parent_id")?,
+        position: row.try_get("position")?,
+        rate_limit_per_user: row.try_get("rate_limit_per_user")?,
+        rtc_region: row.try_get("rtc_region")?,
+        thread_archived: row.try_get("thread_archived")?,
+        thread_auto_archive_duration: row
+            .try_get::<Option<u16>, _>("thread_auto_archive_duration")?
+            .map(AutoArchiveDuration::from),
+        thread_archive_timestamp: row.try_get_optional_timestamp("thread_archive_timestamp")?,
+        thread_create_timestamp: row.try_get_optional_timestamp("thread_create_timestamp")?,
+        thread_invitable: row.try_get("thread_invitable")?,
+        thread_locked: row.try_get("thread_locked")?,
+        topic: row.try_get("topic")?,
+        user_limit: row.try_get("user_limit")?,
+        video_quality_mode: row
+            .try_get::<Option<u8>, _>("video_quality_mode")?
+            .map(VideoQualityMode::from),
+        partial: row.try_get("partial")?,
+    })
+}
+
+/// Create a [`CachedPermissionOverwrite`] from a row of
+/// `permission_overwrites`
+pub(super) fn permission_overwrite(
+    row: &SqliteRow,
+) -> Result<CachedPermissionOverwrite, sqlx::Error> {
+    Ok(CachedPermissionOverwrite {
+        channel_id: row.try_get_id("channel_id")?,
+        allow: Permissions::from_bits_truncate(row.try_get_u64("allow")?),
+        deny: Permissions::from_bits_truncate(row.try_get_u64("deny")?),
+        id: row.try_get_id("id")?,
+        kind: PermissionOverwriteType::from(row.try_get::<u8, _>("kind")?),
+    })
+}
+
+/// Create a [`CachedMessage`] from a row of `messages`
+pub(super) fn message(row: &SqliteRow) -> Result<CachedMessage, sqlx::Error> {
+    Ok(CachedMessage {
+        activity_type: row
+            .try_get::<Option<u8>, _>("activity_type")?
+            .map(MessageActivityType::from),
+        activity_party_id: row.try_get("activity_party_id")?,
+        application_cover_image: row.try_get_optional_image_hash("application_cover_image")?,
+        application_description: row.try_get("application_description")?,
+        application_icon: row.try_get_optional_image_hash("application_icon")?,
+        application_id: row.try_get_optional_id("application_id")?,
+        application_name: row.try_get("application_name")?,
+        interaction_application_id: row.try_get_optional_id("interaction_application_id")?,
+        author: row.try_get_id("author")?,
+        author_bot: row.try_get("author_bot")?,
+        author_name: row.try_get("author_name")?,
+        author_discriminator: row.try_get("author_discriminator")?,
+        author_avatar: row.try_get_optional_image_hash("author_avatar")?,
+        channel_id: row.try_get_id("channel_id")?,
+        content: row.try_get("content")?,
+        edited_timestamp: row.try_get_optional_timestamp("edited_timestamp")?,
+        flags: row
+            .try_get_optional_u64("flags")?
+            .map(MessageFlags::from_bits_truncate),
+        guild_id: row.try_get_optional_id("guild_id")?,
+        id: row.try_get_id("id")?,
+        kind: MessageType::from(row.try_get::<u8, _>("kind")?),
+        mention_everyone: row.try_get("mention_everyone")?,
+        pinned: row.try_get("pinned")?,
+        reference_channel_id: row.try_get_optional_id("reference_channel_id")?,
+        reference_guild_id: row.try_get_optional_id("reference_guild_id")?,
+        reference_message_id: row.try_get_optional_id("reference_message_id")?,
+        reference_fail_if_not_exists: row.try_get("reference_fail_if_not_exists")?,
+        referenced_message: row.try_get_optional_id("referenced_message")?,
+        timestamp: row.try_get_timestamp("timestamp")?,
+        thread: row.try_get_optional_id("thread")?,
+        tts: row.try_get("tts")?,
+        webhook_id: row.try_get_optional_id("webhook_id")?,
+        partial: row.try_get("partial")?,
+    })
+}
+
+/// Create a [`CachedMessageRevision`] from a row of `message_revisions`
+pub(super) fn message_revision(row: &SqliteRow) -> Result<CachedMessageRevision, sqlx::Error> {
+    Ok(CachedMessageRevision {
+        message_id: row.try_get_id("message_id")?,
+        revision_id: row.try_get_id("revision_id")?,
+        edited_at: row.try_get_timestamp("edited_at")?,
+        content: row.try_get("content")?,
+    })
+}
+
+/// Create a [`CachedEmbed`] from a row of `embeds`
+pub(super) fn embed(row: &SqliteRow) -> Result<CachedEmbed, sqlx::Error> {
+    Ok(CachedEmbed {
+        id: row.try_get_id("id")?,
+        message_id: row.try_get_id("message_id")?,
+        author_icon_url: row.try_get("author_icon_url")?,
+        author_name: row.try_get("author_name")?,
+        author_proxy_icon_url: row.try_get("author_proxy_icon_url")?,
+        author_url: row.try_get("author_url")?,
+        color: row.try_get("color")?,
+        description: row.try_get("description")?,
+        footer_icon_url: row.try_get("footer_icon_url")?,
+        footer_proxy_icon_url: row.try_get("footer_proxy_icon_url")?,
+        footer_text: row.try_get("footer_text")?,
+        image_height: row.try_get_optional_u64("image_height")?,
+        image_proxy_url: row.try_get("image_proxy_url")?,
+        image_url: row.try_get("image_url")?,
+        image_width: row.try_get_optional_u64("image_width")?,
+        kind: row.try_get("kind")?,
+        provider_name: row.try_get("provider_name")?,
+        provider_url: row.try_get("provider_url")?,
+        thumbnail_height: row.try_get_optional_u64("thumbnail_height")?,
+        thumbnail_proxy_url: row.try_get("thumbnail_proxy_url")?,
+        thumbnail_url: row.try_get("thumbnail_url")?,
+        thumbnail_width: row.try_get_optional_u64("thumbnail_width")?,
+        timestamp: row.try_get_optional_timestamp("timestamp")?,
+        title: row.try_get("title")?,
+        url: row.try_get("url")?,
+        video_height: row.try_get_optional_u64("video_height")?,
+        video_proxy_url: row.try_get("video_proxy_url")?,
+        video_url: row.try_get("video_url")?,
+        video_width: row.try_get_optional_u64("video_width")?,
+    })
+}
+
+/// Create a [`CachedEmbedField`] from a row of `embed_fields`
+pub(super) fn embed_field(row: &SqliteRow) -> Result<CachedEmbedField, sqlx::Error> {
+    Ok(CachedEmbedField {
+        embed_id: row.try_get_id("embed_id")?,
+        inline: row.try_get("inline")?,
+        name: row.try_get("name")?,
+        value: row.try_get("value")?,
+    })
+}
+
+/// Create a [`CachedAttachment`] from a row of `attachments`
+pub(super) fn attachment(row: &SqliteRow) -> Result<CachedAttachment, sqlx::Error> {
+    Ok(CachedAttachment {
+        message_id: row.try_get_id("message_id")?,
+        content_type: row.try_get("content_type")?,
+        duration_millis: row.try_get_optional_u64("duration_millis")?,
+        ephemeral: row.try_get("ephemeral")?,
+        filename: row.try_get("filename")?,
+        flags: row.try_get_optional_u64("flags")?,
+        description: row.try_get("description")?,
+        height: row.try_get_optional_u64("height")?,
+        id: row.try_get_id("id")?,
+        proxy_url: row.try_get("proxy_url")?,
+        size: row.try_get_u64("size")?,
+        url: row.try_get("url")?,
+        waveform: row.try_get("waveform")?,
+        width: row.try_get_optional_u64("width")?,
+    })
+}
+
+/// Create a [`CachedReaction`] from a row of `reactions`
+pub(super) fn reaction(row: &SqliteRow) -> Result<CachedReaction, sqlx::Error> {
+    Ok(CachedReaction {
+        channel_id: row.try_get_id("channel_id")?,
+        emoji: row.try_get("emoji")?,
+        emoji_name: row.try_get("emoji_name")?,
+        emoji_animated: row.try_get("emoji_animated")?,
+        guild_id: row.try_get_optional_id("guild_id")?,
+        message_id: row.try_get_id("message_id")?,
+        user_id: row.try_get_id("user_id")?,
+        burst: row.try_get("burst")?,
+        burst_colors: row.try_get("burst_colors")?,
+    })
+}
+
+/// Create a [`CachedMember`] from a row of `members`
+pub(super) fn member(row: &SqliteRow) -> Result<CachedMember, sqlx::Error> {
+    Ok(CachedMember {
+        guild_avatar: row.try_get_optional_image_hash("guild_avatar")?,
+        communication_disabled_until: row
+            .try_get_optional_timestamp("communication_disabled_until")?,
+        deaf: row.try_get("deaf")?,
+        guild_id: row.try_get_id("guild_id")?,
+        joined_at: row.try_get_timestamp("joined_at")?,
+        mute: row.try_get("mute")?,
+        nick: row.try_get("nick")?,
+        pending: row.try_get("pending")?,
+        premium_since: row.try_get_optional_timestamp("premium_since")?,
+        accent_color: row.try_get("accent_color")?,
+        avatar: row.try_get_optional_image_hash("avatar")?,
+        banner: row.try_get_optional_image_hash("banner")?,
+        bot: row.try_get("bot")?,
+        discriminator: row.try_get("discriminator")?,
+        flags: row
+            .try_get_optional_u64("flags")?
+            .map(UserFlags::from_bits_truncate),
+        id: row.try_get_id("id")?,
+        locale: row.try_get("locale")?,
+        mfa_enabled: row.try_get("mfa_enabled")?,
+        name: row.try_get("name")?,
+        premium_type: row
+            .try_get::<Option<u8>, _>("premium_type")?
+            .map(PremiumType::from),
+        public_flags: row
+            .try_get_optional_u64("public_flags")?
+            .map(UserFlags::from_bits_truncate),
+        system: row.try_get("system")?,
+    })
+}
+
+/// Create a [`CachedPresence`] from a row of `presences`
+pub(super) fn presence(row: &SqliteRow) -> Result<CachedPresence, sqlx::Error> {
+    let status = match row.try_get::<&str, _>("status")? {
+        "dnd" => Status::DoNotDisturb,
+        "idle" => Status::Idle,
+        "invisible" => Status::Invisible,
+        "offline" => Status::Offline,
+        "online" => Status::Online,
+        _ => return Err(decode_error("status")),
+    };
+
+    Ok(CachedPresence {
+        guild_id: row.try_get_id("guild_id")?,
+        status,
+        user: row.try_get_id("user")?,
+    })
+}
+
+/// Create a [`CachedActivity`] from a row of `activities`
+pub(super) fn activity(row: &SqliteRow) -> Result<CachedActivity, sqlx::Error> {
+    Ok(CachedActivity {
+        user_id: row.try_get_id("user_id")?,
+        guild_id: row.try_get_id("guild_id")?,
+        application_id: row.try_get_optional_id("application_id")?,
+        asset_large_image: row.try_get("asset_large_image")?,
+        asset_large_text: row.try_get("asset_large_text")?,
+        asset_small_image: row.try_get("asset_small_image")?,
+        asset_small_text: row.try_get("asset_small_text")?,
+        button_1: row.try_get("button_1")?,
+        button_2: row.try_get("button_2")?,
+        created_at: row.try_get_optional_u64("created_at")?,
+        details: row.try_get("details")?,
+        emoji_animated: row.try_get("emoji_animated")?,
+        emoji_name: row.try_get("emoji_name")?,
+        emoji_id: row.try_get("emoji_id")?,
+        flags: row
+            .try_get_optional_u64("flags")?
+            .map(ActivityFlags::from_bits_truncate),
+        id: row.try_get("id")?,
+        instance: row.try_get("instance")?,
+        kind: ActivityType::from(row.try_get::<u8, _>("kind")?),
+        name: row.try_get("name")?,
+        party_id: row.try_get("party_id")?,
+        party_size_current: row.try_get_optional_u64("party_size_current")?,
+        party_size_max: row.try_get_optional_u64("party_size_max")?,
+        state: row.try_get("state")?,
+        timestamp_end: row.try_get_optional_u64("timestamp_end")?,
+        timestamp_start: row.try_get_optional_u64("timestamp_start")?,
+        url: row.try_get("url")?,
+    })
+}
+
+/// Create a [`CachedGuild`] from a row of `guilds`
+pub(super) fn guild(row: &SqliteRow) -> Result<CachedGuild, sqlx::Error> {
+    Ok(CachedGuild {
+        afk_channel_id: row.try_get_optional_id("afk_channel_id")?,
+        afk_timeout: row.try_get_u64("afk_timeout")?,
+        application_id: row.try_get_optional_id("application_id")?,
+        banner: row.try_get_optional_image_hash("banner")?,
+        default_message_notifications: DefaultMessageNotificationLevel::from(
+            row.try_get::<u8, _>("default_message_notifications")?,
+        ),
+        description: row.try_get("description")?,
+        discovery_splash: row.try_get_optional_image_hash("discovery_splash")?,
+        explicit_content_filter: ExplicitContentFilter::from(
+            row.try_get::<u8, _>("explicit_content_filter")?,
+        ),
+        features: row
+            .try_get::<&str, _>("features")?
+            .split(',')
+            .filter(|feature| !feature.is_empty())
+            .map(|feature| GuildFeature::from(feature.to_owned()))
+            .collect(),
+        icon: row.try_get_optional_image_hash("icon")?,
+        id: row.try_get_id("id")?,
+        joined_at: row.try_get_optional_timestamp("joined_at")?,
+        large: row.try_get("large")?,
+        max_members: row.try_get_optional_u64("max_members")?,
+        max_presences: row.try_get_optional_u64("max_presences")?,
+        max_stage_video_channel_users: row.try_get_optional_u64("max_stage_video_channel_users")?,
+        max_video_channel_users: row.try_get_optional_u64("max_video_channel_users")?,
+        mfa_level: MfaLevel::from(row.try_get::<u8, _>("mfa_level")?),
+        name: row.try_get("name")?,
+        nsfw_level: NSFWLevel::from(row.try_get::<u8, _>("nsfw_level")?),
+        owner_id: row.try_get_id("owner_id")?,
+        owner: row.try_get("owner")?,
+        permissions: row
+            .try_get_optional_u64("permissions")?
+            .map(Permissions::from_bits_truncate),
+        preferred_locale: row.try_get("preferred_locale")?,
+        premium_progress_bar_enabled: row.try_get("premium_progress_bar_enabled")?,
+        premium_subscription_count: row.try_get_optional_u64("premium_subscription_count")?,
+        premium_tier: PremiumTier::from(row.try_get::<u8, _>("premium_tier")?),
+        public_updates_channel_id: row.try_get_optional_id("public_updates_channel_id")?,
+        rules_channel_id: row.try_get_optional_id("rules_channel_id")?,
+        safety_alerts_channel_id: row.try_get_optional_id("safety_alerts_channel_id")?,
+        splash: row.try_get_optional_image_hash("splash")?,
+        system_channel_flags: SystemChannelFlags::from_bits_truncate(
+            row.try_get_u64("system_channel_flags")?,
+        ),
+        system_channel_id: row.try_get_optional_id("system_channel_id")?,
+        unavailable: row.try_get("unavailable")?,
+        vanity_url_code: row.try_get("vanity_url_code")?,
+        verification_level: VerificationLevel::from(row.try_get::<u8, _>("verification_level")?),
+        widget_channel_id: row.try_get_optional_id("widget_channel_id")?,
+        widget_enabled: row.try_get("widget_enabled")?,
+        members_partial: row.try_get("members_partial")?,
+    })
+}
+
+/// Create a [`CachedRole`] from a row of `roles`
+pub(super) fn role(row: &SqliteRow) -> Result<CachedRole, sqlx::Error> {
+    Ok(CachedRole {
+        guild_id: row.try_get_id("guild_id")?,
+        color: row.try_get("color")?,
+        hoist: row.try_get("hoist")?,
+        icon: row.try_get_optional_image_hash("icon")?,
+        id: row.try_get_id("id")?,
+        managed: row.try_get("managed")?,
+        mentionable: row.try_get("mentionable")?,
+        name: row.try_get("name")?,
+        permissions: Permissions::from_bits_truncate(row.try_get_u64("permissions")?),
+        position: row.try_get("position")?,
+        flags: row.try_get_optional_u64("flags")?,
+        tags_available_for_purchase: row.try_get("tags_available_for_purchase")?,
+        tags_bot_id: row.try_get_optional_id("tags_bot_id")?,
+        tags_guild_connections: row.try_get("tags_guild_connections")?,
+        tags_integration_id: row.try_get_optional_id("tags_integration_id")?,
+        tags_premium_subscriber: row.try_get("tags_premium_subscriber")?,
+        tags_subscription_listing_id: row.try_get_optional_id("tags_subscription_listing_id")?,
+        unicode_emoji: row.try_get("unicode_emoji")?,
+    })
+}
+
+/// Create a [`CachedRoleAssignment`] from a row of `role_assignments`
+pub(super) fn role_assignment(row: &SqliteRow) -> Result<CachedRoleAssignment, sqlx::Error> {
+    Ok(CachedRoleAssignment {
+        guild_id: row.try_get_id("guild_id")?,
+        user_id: row.try_get_id("user_id")?,
+        role_id: row.try_get_id("role_id")?,
+    })
+}
+
+/// Create a [`CachedEmoji`] from a row of `emojis`
+pub(super) fn emoji(row: &SqliteRow) -> Result<CachedEmoji, sqlx::Error> {
+    Ok(CachedEmoji {
+        guild_id: row.try_get_id("guild_id")?,
+        animated: row.try_get("animated")?,
+        available: row.try_get("available")?,
+        id: row.try_get_id("id")?,
+        managed: row.try_get("managed")?,
+        name: row.try_get("name")?,
+        require_colons: row.try_get("require_colons")?,
+        user: row.try_get_optional_id("user")?,
+    })
+}
+
+/// Create a [`CachedSticker`] from a row of `stickers`
+pub(super) fn sticker(row: &SqliteRow) -> Result<CachedSticker, sqlx::Error> {
+    Ok(CachedSticker {
+        message_id: row.try_get_optional_id("message_id")?,
+        available: row.try_get("available")?,
+        description: row.try_get("description")?,
+        format_type: StickerFormatType::from(row.try_get::<u8, _>("format_type")?),
+        guild_id: row.try_get_optional_id("guild_id")?,
+        id: row.try_get_id("id")?,
+        kind: row.try_get::<Option<u8>, _>("kind")?.map(StickerType::from),
+        name: row.try_get("name")?,
+        pack_id: row.try_get_optional_id("pack_id")?,
+        sort_value: row.try_get_optional_u64("sort_value")?,
+        tags: row.try_get("tags")?,
+        user_id: row.try_get_optional_id("user_id")?,
+    })
+}
+
+/// Create a [`StageInstance`] from a row of `stage_instances`
+pub(super) fn stage_instance(row: &SqliteRow) -> Result<StageInstance, sqlx::Error> {
+    let privacy_level = match row.try_get::<u8, _>("privacy_level")? {
+        2 => PrivacyLevel::GuildOnly,
+        _ => return Err(decode_error("privacy_level")),
+    };
+
+    Ok(StageInstance {
+        channel_id: row.try_get_id("channel_id")?,
+        guild_id: row.try_get_id("guild_id")?,
+        guild_scheduled_event_id: row.try_get_optional_id("guild_scheduled_event_id")?,
+        id: row.try_get_id("id")?,
+        privacy_level,
+        topic: row.try_get("topic")?,
+    })
+}
+
+/// Create an ID from a row that has only an ID column named `id`
+pub(super) fn id<T>(row: &SqliteRow) -> Result<Id<T>, sqlx::Error> {
+    row.try_get_id("id")
+}
+
+/// Create an emoji and its number of uses from a row that has the emoji in a
+/// column named `emoji` and the number of uses in a column named `uses`
+#[cfg(feature = "stats")]
+pub(super) fn emoji_usage(row: &SqliteRow) -> Result<(String, u64), sqlx::Error> {
+    Ok((row.try_get("emoji")?, row.try_get_u64("uses")?))
+}
+
+/// Create a sticker ID and its number of uses from a row that has the sticker
+/// ID in a column named `sticker_id` and the number of uses in a column named
+/// `uses`
+#[cfg(feature = "stats")]
+pub(super) fn sticker_usage(row: &SqliteRow) -> Result<(Id<StickerMarker>, u64), sqlx::Error> {
+    Ok((row.try_get_id("sticker_id")?, row.try_get_u64("uses")?))
+}
+
+/// Create a [`CachedJournalEntry`] from a row of `journal`
+#[cfg(feature = "journal")]
+pub(super) fn journal_entry(row: &SqliteRow) -> Result<CachedJournalEntry, sqlx::Error> {
+    Ok(CachedJournalEntry {
+        id: row.try_get_id("id")?,
+        recorded_at: row.try_get_timestamp("recorded_at")?,
+        kind: row.try_get("kind")?,
+        data: row.try_get("data")?,
+    })
+}
+
+/// Create a [`CachedChangelogEntry`] from a row of `changelog`
+#[cfg(feature = "changelog")]
+pub(super) fn changelog_entry(row: &SqliteRow) -> Result<CachedChangelogEntry, sqlx::Error> {
+    let kind = match row.try_get::<&str, _>("kind")? {
+        "channel" => EntityKind::Channel,
+        "message" => EntityKind::Message,
+        "member" => EntityKind::Member,
+        "guild" => EntityKind::Guild,
+        "role" => EntityKind::Role,
+        "emoji" => EntityKind::Emoji,
+        "sticker" => EntityKind::Sticker,
+        "stage_instance" => EntityKind::StageInstance,
+        "presence" => EntityKind::Presence,
+        _ => return Err(decode_error("kind")),
+    };
+
+    Ok(CachedChangelogEntry {
+        id: row.try_get_id("id")?,
+        recorded_at: row.try_get_timestamp("recorded_at")?,
+        kind,
+        entity_id: row.try_get_id("entity_id")?,
+        before: row.try_get("before")?,
+        after: row.try_get("after")?,
+    })
+}
+
+/// Get the embeds stored as JSON from a row of `message_embeds`
+#[cfg(feature = "embeds-json")]
+pub(super) fn embeds_json(row: &SqliteRow) -> Result<serde_json::Value, sqlx::Error> {
+    serde_json::from_str(row.try_get("embeds")?).map_err(|_| decode_error("embeds"))
+}
@@ -0,0 +1,10 @@
+/// The number of seconds in a day
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Returns the number of days since the Unix epoch in UTC of the given Unix
+/// timestamp in seconds, which is what the usage statistics are counted per
+///
+/// Timestamps before the Unix epoch return 0
+pub(crate) fn day(unix_secs: i64) -> u64 {
+    u64::try_from(unix_secs.div_euclid(SECS_PER_DAY)).unwrap_or_default()
+}
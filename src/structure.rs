@@ -0,0 +1,29 @@
+use crate::model::{CachedChannel, CachedGuild, CachedPermissionOverwrite, CachedRole};
+
+/// The layout of a guild made from the cached data, returned by
+/// [`crate::Cache::guild_structure_snapshot`]
+///
+/// This is useful to back up a guild's layout or to create it again with the
+/// HTTP API
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GuildStructure {
+    /// The guild, including its settings
+    pub guild: CachedGuild,
+    /// The guild's roles, sorted by their positions from the lowest to the
+    /// highest, the everyone role first
+    pub roles: Vec<CachedRole>,
+    /// The guild's channels and categories, in the order the client shows
+    /// them, each category followed by its channels
+    pub channels: Vec<ChannelStructure>,
+}
+
+/// A channel in a [`GuildStructure`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelStructure {
+    /// The channel
+    pub channel: CachedChannel,
+    /// The channel's permission overwrites
+    pub permission_overwrites: Vec<CachedPermissionOverwrite>,
+}
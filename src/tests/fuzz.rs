@@ -0,0 +1,438 @@
+use core::future::Future;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, ensure};
+use proptest::{
+    prelude::{prop, prop_oneof, Strategy},
+    strategy::ValueTree,
+    test_runner::{Config, TestRunner},
+};
+use serde_json::{json, Value};
+use twilight_model::id::Id;
+
+use crate::{tests::offline::events, Cache};
+
+/// The ID of the guild the generated events are in
+const GUILD_ID: u64 = 100_000_000_000_000_001;
+/// The ID of the channel the generated messages are in
+const CHANNEL_ID: u64 = 300_000_000_000_000_001;
+/// The ID of the author of the generated messages
+const AUTHOR_ID: u64 = 200_000_000_000_000_001;
+/// The timestamp used in the generated events
+const TIMESTAMP: &str = "2022-03-08T22:20:31.000000+00:00";
+
+/// Updates caches with randomized event sequences and asserts that updating
+/// never errors and that the cache ends consistent
+///
+/// Each case creates a new cache with `new_cache`, updates it with a guild
+/// create event, then with create, update and delete events of channels,
+/// messages, roles and members in a shuffled order, so that, for example, a
+/// message might be updated before it's created or deleted before it's
+/// updated
+///
+/// After each case the cached channels, messages, roles and members are
+/// compared to what the events describe, and [`Cache::verify_integrity`]
+/// shouldn't find any referential breaks
+///
+/// # Errors
+///
+/// Returns the first error the cache returns or the first inconsistency found,
+/// along with the operations of the case, so that it can be reproduced
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::tests::fuzz;
+///
+/// #[tokio::test]
+/// async fn fuzz() -> Result<(), anyhow::Error> {
+///     fuzz::fuzz(MyCache::new, 256).await
+/// }
+/// ```
+pub async fn fuzz<T, F, Fut>(mut new_cache: F, cases: u32) -> Result<(), anyhow::Error>
+where
+    T: Cache + Send + Sync,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = T>,
+{
+    let mut runner = TestRunner::new(Config::with_cases(cases));
+    let strategy = operations();
+
+    for _ in 0..cases {
+        let operations = strategy
+            .new_tree(&mut runner)
+            .map_err(|reason| anyhow!("couldn't generate the operations: {reason}"))?
+            .current();
+        let cache = new_cache().await;
+
+        run_case(&cache, &operations)
+            .await
+            .map_err(|err| err.context(format!("the operations were:\n{operations:#?}")))?;
+    }
+
+    Ok(())
+}
+
+/// A model the events are generated for, with the ID it's created with
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Entity {
+    /// A text channel in the guild
+    Channel(u64),
+    /// A message in the channel
+    Message(u64),
+    /// A role in the guild
+    Role(u64),
+    /// A member of the guild
+    Member(u64),
+}
+
+/// The kind of the event generated for an entity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// The create event, such as a channel create or member add event
+    Create,
+    /// The update event
+    Update,
+    /// The delete event, such as a channel delete or member remove event
+    Delete,
+}
+
+/// An event to update the cache with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Operation {
+    /// The model the event is about
+    pub entity: Entity,
+    /// The kind of the event
+    pub step: Step,
+}
+
+impl Entity {
+    /// Returns every entity that may be generated
+    fn all() -> impl Iterator<Item = Self> {
+        (1..=3).flat_map(|id| {
+            [
+                Self::Channel(id),
+                Self::Message(id),
+                Self::Role(id),
+                Self::Member(id),
+            ]
+        })
+    }
+
+    /// Returns the ID of the entity in Discord's format
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    const fn discord_id(self) -> u64 {
+        match self {
+            Self::Channel(id) => 300_000_000_000_000_001 + id,
+            Self::Message(id) => 600_000_000_000_000_000 + id,
+            Self::Role(id) => 400_000_000_000_000_000 + id,
+            Self::Member(id) => 200_000_000_000_000_001 + id,
+        }
+    }
+
+    /// Returns the label of the cached entity, which is the channel or role's
+    /// name, the message's content or the member's nick
+    async fn cached_label<T: Cache + Send + Sync>(
+        self,
+        cache: &T,
+    ) -> Result<Option<String>, anyhow::Error> {
+        let id = self.discord_id();
+
+        Ok(match self {
+            Self::Channel(_) => cache
+                .channel(Id::new(id))
+                .await?
+                .map(|channel| channel.name.unwrap_or_default()),
+            Self::Message(_) => cache
+                .message(Id::new(id))
+                .await?
+                .map(|message| message.content),
+            Self::Role(_) => cache.role(Id::new(id)).await?.map(|role| role.name),
+            Self::Member(_) => cache
+                .member(Id::new(id), Id::new(GUILD_ID))
+                .await?
+                .map(|member| member.nick.unwrap_or_default()),
+        })
+    }
+}
+
+impl Operation {
+    /// Returns the gateway payload of the event, using the label as the
+    /// channel or role's name, the message's content or the member's nick
+    fn payload(self, label: &str, sequence: usize) -> Value {
+        let id = self.entity.discord_id().to_string();
+        let (kind, data) = match (self.entity, self.step) {
+            (Entity::Channel(_), step) => (
+                match step {
+                    Step::Create => "CHANNEL_CREATE",
+                    Step::Update => "CHANNEL_UPDATE",
+                    Step::Delete => "CHANNEL_DELETE",
+                },
+                json!({
+                    "id": id,
+                    "type": 0,
+                    "guild_id": GUILD_ID.to_string(),
+                    "name": label,
+                    "position": 0,
+                    "permission_overwrites": [{
+                        "id": GUILD_ID.to_string(),
+                        "type": 0,
+                        "allow": "1024",
+                        "deny": "0"
+                    }]
+                }),
+            ),
+            (Entity::Message(_), Step::Create) => (
+                "MESSAGE_CREATE",
+                json!({
+                    "id": id,
+                    "channel_id": CHANNEL_ID.to_string(),
+                    "guild_id": GUILD_ID.to_string(),
+                    "author": user(AUTHOR_ID),
+                    "content": label,
+                    "timestamp": TIMESTAMP,
+                    "edited_timestamp": null,
+                    "tts": false,
+                    "mention_everyone": false,
+                    "mentions": [],
+                    "mention_roles": [],
+                    "attachments": [{
+                        "id": id,
+                        "filename": "fuzz.png",
+                        "size": 1024,
+                        "url": "https://cdn.discordapp.com/attachments/fuzz.png",
+                        "proxy_url": "https://media.discordapp.net/attachments/fuzz.png"
+                    }],
+                    "embeds": [{
+                        "type": "rich",
+                        "description": label,
+                        "fields": [{ "name": "field", "value": label, "inline": false }]
+                    }],
+                    "pinned": false,
+                    "type": 0
+                }),
+            ),
+            (Entity::Message(_), Step::Update) => (
+                "MESSAGE_UPDATE",
+                json!({
+                    "id": id,
+                    "channel_id": CHANNEL_ID.to_string(),
+                    "guild_id": GUILD_ID.to_string(),
+                    "content": label,
+                    "edited_timestamp": TIMESTAMP
+                }),
+            ),
+            (Entity::Message(_), Step::Delete) => (
+                "MESSAGE_DELETE",
+                json!({
+                    "id": id,
+                    "channel_id": CHANNEL_ID.to_string(),
+                    "guild_id": GUILD_ID.to_string()
+                }),
+            ),
+            (Entity::Role(_), Step::Create | Step::Update) => (
+                if self.step == Step::Create {
+                    "GUILD_ROLE_CREATE"
+                } else {
+                    "GUILD_ROLE_UPDATE"
+                },
+                json!({
+                    "guild_id": GUILD_ID.to_string(),
+                    "role": role(&id, label)
+                }),
+            ),
+            (Entity::Role(_), Step::Delete) => (
+                "GUILD_ROLE_DELETE",
+                json!({ "guild_id": GUILD_ID.to_string(), "role_id": id }),
+            ),
+            (Entity::Member(_), Step::Create | Step::Update) => (
+                if self.step == Step::Create {
+                    "GUILD_MEMBER_ADD"
+                } else {
+                    "GUILD_MEMBER_UPDATE"
+                },
+                json!({
+                    "guild_id": GUILD_ID.to_string(),
+                    "user": user(self.entity.discord_id()),
+                    "roles": [],
+                    "joined_at": TIMESTAMP,
+                    "deaf": false,
+                    "mute": false,
+                    "nick": label,
+                    "avatar": null,
+                    "premium_since": null,
+                    "pending": false,
+                    "communication_disabled_until": null
+                }),
+            ),
+            (Entity::Member(_), Step::Delete) => (
+                "GUILD_MEMBER_REMOVE",
+                json!({
+                    "guild_id": GUILD_ID.to_string(),
+                    "user": user(self.entity.discord_id())
+                }),
+            ),
+        };
+
+        json!({ "op": 0, "s": sequence, "t": kind, "d": data })
+    }
+
+    /// Applies the operation to the expected labels of the entities
+    ///
+    /// Update events of messages, roles and members are ignored by the cache
+    /// when they aren't cached, while channel update events cache the channel
+    fn apply(self, expected: &mut HashMap<Entity, String>, label: String) {
+        match (self.entity, self.step) {
+            (_, Step::Delete) => {
+                expected.remove(&self.entity);
+            }
+            (Entity::Channel(_), Step::Update) | (_, Step::Create) => {
+                expected.insert(self.entity, label);
+            }
+            (_, Step::Update) => {
+                if let Some(cached_label) = expected.get_mut(&self.entity) {
+                    *cached_label = label;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the strategy generating the operations of a case
+///
+/// Each generated entity has its create, update and delete events, or only
+/// the first one or two of them, then all the events are shuffled
+fn operations() -> impl Strategy<Value = Vec<Operation>> {
+    let entity = prop_oneof![
+        (1_u64..=3).prop_map(Entity::Channel),
+        (1_u64..=3).prop_map(Entity::Message),
+        (1_u64..=3).prop_map(Entity::Role),
+        (1_u64..=3).prop_map(Entity::Member),
+    ];
+
+    prop::collection::vec((entity, 1_usize..=3), 1..16)
+        .prop_map(|entities| {
+            entities
+                .into_iter()
+                .flat_map(|(entity, step_count)| {
+                    [Step::Create, Step::Update, Step::Delete]
+                        .into_iter()
+                        .take(step_count)
+                        .map(move |step| Operation { entity, step })
+                })
+                .collect::<Vec<_>>()
+        })
+        .prop_shuffle()
+}
+
+/// Updates the cache with the guild create event and the events of the
+/// operations, then asserts that the cache is consistent with them
+async fn run_case<T: Cache + Send + Sync>(
+    cache: &T,
+    operations: &[Operation],
+) -> Result<(), anyhow::Error> {
+    let mut payloads = vec![guild_create()];
+    let mut expected = HashMap::new();
+    for (index, operation) in operations.iter().enumerate() {
+        let label = format!("label {index}");
+        payloads.push(operation.payload(&label, payloads.len()));
+        operation.apply(&mut expected, label);
+    }
+
+    for event in events(&Value::Array(payloads).to_string())? {
+        cache.update(&event).await?;
+    }
+
+    for entity in Entity::all() {
+        let cached_label = entity.cached_label(cache).await?;
+        ensure!(
+            cached_label.as_ref() == expected.get(&entity),
+            "{entity:?} is cached as {cached_label:?} but should be {:?}",
+            expected.get(&entity)
+        );
+    }
+
+    let report = cache.verify_integrity(false).await?;
+    ensure!(
+        report.is_empty(),
+        "the cache has referential breaks:\n{report:#?}"
+    );
+
+    Ok(())
+}
+
+/// Returns the payload of the guild create event the cases start with
+fn guild_create() -> Value {
+    json!({
+        "op": 0,
+        "s": 0,
+        "t": "GUILD_CREATE",
+        "d": {
+            "id": GUILD_ID.to_string(),
+            "name": "fuzz",
+            "icon": null,
+            "splash": null,
+            "discovery_splash": null,
+            "banner": null,
+            "description": null,
+            "owner_id": AUTHOR_ID.to_string(),
+            "application_id": null,
+            "afk_channel_id": null,
+            "afk_timeout": 300,
+            "verification_level": 0,
+            "default_message_notifications": 0,
+            "explicit_content_filter": 0,
+            "mfa_level": 0,
+            "nsfw_level": 0,
+            "features": [],
+            "system_channel_id": null,
+            "system_channel_flags": 0,
+            "rules_channel_id": null,
+            "public_updates_channel_id": null,
+            "vanity_url_code": null,
+            "premium_tier": 0,
+            "premium_progress_bar_enabled": false,
+            "preferred_locale": "en-US",
+            "joined_at": TIMESTAMP,
+            "large": false,
+            "unavailable": false,
+            "member_count": 0,
+            "roles": [role(&GUILD_ID.to_string(), "@everyone")],
+            "emojis": [],
+            "channels": [],
+            "members": [],
+            "presences": [],
+            "voice_states": [],
+            "threads": [],
+            "stage_instances": [],
+            "stickers": [],
+            "guild_scheduled_events": []
+        }
+    })
+}
+
+/// Returns the JSON of a user with the given ID
+fn user(id: u64) -> Value {
+    json!({
+        "id": id.to_string(),
+        "username": "fuzz",
+        "discriminator": "0001",
+        "avatar": null
+    })
+}
+
+/// Returns the JSON of a role with the given ID and name
+fn role(id: &str, name: &str) -> Value {
+    json!({
+        "id": id,
+        "name": name,
+        "color": 0,
+        "hoist": false,
+        "icon": null,
+        "unicode_emoji": null,
+        "managed": false,
+        "mentionable": false,
+        "permissions": "0",
+        "position": 0
+    })
+}
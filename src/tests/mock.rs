@@ -0,0 +1,465 @@
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde_json::{json, Value};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::MessageCreate},
+    guild::Permissions,
+    id::Id,
+};
+
+use crate::{
+    batching::{BatchOptions, BatchingCache},
+    cache::EntityKind,
+    config::CacheConfig,
+    mock::MockBackend,
+    model::{
+        CachedChannel, CachedGuild, CachedMember, CachedRole, CachedRoleAssignment, MemberPatch,
+    },
+    tests::offline::{events, fixture_guild, GUILD_CREATE, GUILD_DELETE, MEMBERS, MESSAGES, ROLES},
+    tiered::TieredCache,
+    Backend, Cache,
+};
+
+/// Updates a [`MockBackend`] with the recorded guild create event and asserts
+/// that the guild, its channels, roles and members are returned by its getters
+///
+/// The roles of the members are only added if their roles are returned by
+/// [`Cache::role`], so this also asserts that updates depending on the cached
+/// data succeed
+pub async fn guild_create() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let guild = fixture_guild()?;
+
+    assert_eq!(
+        cache.guild(guild.id).await?,
+        Some(CachedGuild::from(&guild))
+    );
+    for channel in &guild.channels {
+        assert_eq!(
+            cache.channel(channel.id).await?,
+            Some(CachedChannel::from(channel))
+        );
+    }
+    for role in &guild.roles {
+        assert_eq!(
+            cache.role(role.id).await?,
+            Some(CachedRole::from_role(role.clone(), guild.id))
+        );
+    }
+    for member in &guild.members {
+        assert_eq!(
+            cache.member(member.user.id, guild.id).await?,
+            Some(CachedMember::from(member))
+        );
+    }
+    assert_eq!(
+        cache.upserts::<CachedRoleAssignment>().len(),
+        guild
+            .members
+            .iter()
+            .map(|member| member.roles.len())
+            .sum::<usize>()
+    );
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded guild create event marked as
+/// unavailable and asserts that the guild is cached as unavailable without its
+/// channels, so that it's known to be in an outage
+pub async fn unavailable_guild_create() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    let mut payloads: Vec<Value> = serde_json::from_str(GUILD_CREATE)?;
+    for payload in &mut payloads {
+        payload["d"]["unavailable"] = json!(true);
+    }
+    for event in events(&json!(payloads).to_string())? {
+        cache.update(&event).await?;
+    }
+    let guild = fixture_guild()?;
+
+    assert_eq!(
+        cache
+            .guild(guild.id)
+            .await?
+            .map(|cached_guild| cached_guild.unavailable),
+        Some(true)
+    );
+    for channel in &guild.channels {
+        assert!(cache.channel(channel.id).await?.is_none());
+    }
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded guild create and delete events
+/// and asserts that the guild is removed along with its contents, with the
+/// default [`Backend::delete_guild_cascade`] since the mock doesn't support
+/// cascading deletes
+pub async fn guild_delete() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    for event in events(GUILD_CREATE)?.iter().chain(&events(GUILD_DELETE)?) {
+        cache.update(event).await?;
+    }
+    let guild = fixture_guild()?;
+
+    assert!(cache.guild(guild.id).await?.is_none());
+    assert!(cache.guild_channels(guild.id).await?.is_empty());
+    assert!(cache.guild_roles(guild.id).await?.is_empty());
+    assert!(cache.guild_members(guild.id).await?.is_empty());
+    assert_eq!(cache.calls_to("delete_guild_channels").len(), 1);
+    assert_eq!(
+        cache.calls_to("delete_channel_permission_overwrites").len(),
+        guild.channels.len()
+    );
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded guild create event and asserts
+/// that the permissions of its owner and of a member without roles are
+/// calculated from the kept roles, role assignments and channels
+pub async fn permissions() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let guild = fixture_guild()?;
+    let (Some(owner), Some(member), Some(everyone_role)) = (
+        guild
+            .members
+            .iter()
+            .find(|member| member.user.id == guild.owner_id),
+        guild
+            .members
+            .iter()
+            .find(|member| member.user.id != guild.owner_id && member.roles.is_empty()),
+        guild
+            .roles
+            .iter()
+            .find(|role| role.id.get() == guild.id.get()),
+    ) else {
+        return Err(anyhow!(
+            "the guild create fixture doesn't have an owner, a member without roles or an \
+             everyone role"
+        ));
+    };
+
+    assert_eq!(
+        cache.member_roles(owner.user.id, guild.id).await?.len(),
+        owner.roles.len()
+    );
+    assert!(cache.is_admin(owner.user.id, guild.id).await?);
+    assert!(!cache.is_admin(member.user.id, guild.id).await?);
+    assert_eq!(
+        cache.guild_permissions(member.user.id, guild.id).await?,
+        everyone_role.permissions
+    );
+    assert_eq!(
+        cache
+            .visible_channels(member.user.id, guild.id)
+            .await?
+            .len(),
+        guild.channels.len()
+    );
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded guild create event, adds a
+/// role that grants [`Permissions::BAN_MEMBERS`] to a member and asserts that
+/// the members with the permission are the owner and that member, read with a
+/// single query of the guild's role assignments
+pub async fn members_with_permission() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let guild = fixture_guild()?;
+    let (Some(member), Some(role)) = (
+        guild
+            .members
+            .iter()
+            .find(|member| member.user.id != guild.owner_id),
+        guild.roles.first(),
+    ) else {
+        return Err(anyhow!(
+            "the guild create fixture doesn't have a member that isn't the owner or roles"
+        ));
+    };
+    let role_id = Id::new(1);
+    cache
+        .upsert_role(CachedRole {
+            id: role_id,
+            permissions: Permissions::BAN_MEMBERS,
+            ..CachedRole::from_role(role.clone(), guild.id)
+        })
+        .await?;
+    cache
+        .upsert_role_assignment(CachedRoleAssignment {
+            guild_id: guild.id,
+            user_id: member.user.id,
+            role_id,
+        })
+        .await?;
+
+    let mut user_ids = vec![guild.owner_id, member.user.id];
+    user_ids.sort_unstable();
+    assert_eq!(
+        cache
+            .members_with_permission(guild.id, Permissions::BAN_MEMBERS)
+            .await?,
+        user_ids
+    );
+    assert_eq!(cache.calls_to("select_guild_role_assignments").len(), 1);
+    assert!(cache.calls_to("select_role_members").is_empty());
+
+    Ok(())
+}
+
+/// Updates a [`BatchingCache`] wrapping a [`MockBackend`] with the recorded
+/// guild create event and asserts that its members are written in one batch,
+/// so that reading the roles of each member doesn't flush the buffer
+pub async fn batched_guild_create() -> Result<(), anyhow::Error> {
+    let cache = BatchingCache::new(MockBackend::new(), BatchOptions::new());
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    cache.flush().await?;
+    let guild = fixture_guild()?;
+
+    let backend = cache.inner();
+    assert_eq!(backend.calls_to("upsert_members").len(), 1);
+    assert!(backend.calls_to("upsert_member").is_empty());
+    assert_eq!(backend.upserts::<CachedMember>().len(), guild.members.len());
+
+    Ok(())
+}
+
+/// Updates a [`BatchingCache`] wrapping a [`MockBackend`] with
+/// [`CacheConfig::ttl`] set for messages with a message create event and
+/// asserts that the buffered message is written before its expiry is set
+pub async fn batched_expiry() -> Result<(), anyhow::Error> {
+    let cache = BatchingCache::new(
+        MockBackend::new()
+            .with_config(CacheConfig::new().ttl(EntityKind::Message, Duration::from_secs(60))),
+        BatchOptions::new(),
+    );
+    cache
+        .update(&Event::MessageCreate(message_create()?))
+        .await?;
+
+    let methods = cache
+        .inner()
+        .calls()
+        .into_iter()
+        .map(|call| call.method)
+        .filter(|method| matches!(*method, "upsert_messages" | "set_expiry"))
+        .collect::<Vec<_>>();
+    assert_eq!(methods, ["upsert_messages", "set_expiry"]);
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded member add and update events
+/// and asserts that the update is written as a patch of only the nick it
+/// changes, and that replaying it doesn't write anything
+pub async fn member_update_patch() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let member_events = events(MEMBERS)?;
+    let (Some(add), Some(update)) = (member_events.first(), member_events.get(1)) else {
+        return Err(anyhow!(
+            "the members fixture doesn't have an add and an update event"
+        ));
+    };
+
+    cache.update(add).await?;
+    cache.update(update).await?;
+    assert_eq!(
+        cache.upserts::<MemberPatch>(),
+        [MemberPatch {
+            nick: Some(Some("renamed tester".to_owned())),
+            ..MemberPatch::default()
+        }]
+    );
+
+    cache.update(update).await?;
+    assert_eq!(cache.calls_to("patch_member").len(), 1);
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with [`CacheConfig::event_dedup`] enabled with the
+/// same owned message create event twice and asserts that the message is
+/// cached the first time and the event is skipped the second time
+pub async fn update_owned_dedup() -> Result<(), anyhow::Error> {
+    let cache =
+        MockBackend::new().with_config(CacheConfig::new().event_dedup(Duration::from_secs(60)));
+    let message = message_create()?;
+    let message_id = message.id;
+    let event = Event::MessageCreate(message);
+
+    cache.update_owned(event.clone()).await?;
+    assert!(cache.message(message_id).await?.is_some());
+    assert_eq!(cache.calls_to("upsert_message").len(), 1);
+
+    cache.update_owned(event).await?;
+    assert_eq!(cache.calls_to("seen_event").len(), 2);
+    assert_eq!(cache.calls_to("upsert_message").len(), 1);
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with [`CacheConfig::event_dedup`] enabled with a
+/// message create event while writing the message fails, then with the same
+/// event again and asserts that the replayed event is applied
+pub async fn dedup_failed_update() -> Result<(), anyhow::Error> {
+    let cache =
+        MockBackend::new().with_config(CacheConfig::new().event_dedup(Duration::from_secs(60)));
+    let message = message_create()?;
+    let message_id = message.id;
+    let event = Event::MessageCreate(message);
+
+    cache.fail_times("upsert_message", 1);
+    assert!(cache.update(&event).await.is_err());
+    assert!(cache.message(message_id).await?.is_none());
+    assert_eq!(cache.calls_to("forget_event").len(), 1);
+
+    cache.update(&event).await?;
+    assert!(cache.message(message_id).await?.is_some());
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with the recorded role create and update events
+/// as owned events and asserts that the cached role is the same as when
+/// they're borrowed, even though its name is moved out of the events
+pub async fn update_owned_roles() -> Result<(), anyhow::Error> {
+    let (borrowed_cache, owned_cache) = (MockBackend::new(), MockBackend::new());
+    for event in events(ROLES)? {
+        if !matches!(event, Event::RoleCreate(_) | Event::RoleUpdate(_)) {
+            continue;
+        }
+        borrowed_cache.update(&event).await?;
+        owned_cache.update_owned(event).await?;
+
+        let (borrowed_roles, owned_roles) = (
+            borrowed_cache.upserts::<CachedRole>(),
+            owned_cache.upserts::<CachedRole>(),
+        );
+        assert_eq!(owned_roles, borrowed_roles);
+        assert!(owned_roles.iter().all(|role| !role.name.is_empty()));
+    }
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with an owned message create event and asserts
+/// that it's recorded in the journal once
+#[cfg(feature = "journal")]
+pub async fn update_owned_journal() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new();
+    cache
+        .update_owned(Event::MessageCreate(message_create()?))
+        .await?;
+    assert_eq!(cache.calls_to("append_journal").len(), 1);
+
+    Ok(())
+}
+
+/// Updates a [`MockBackend`] with [`CacheConfig::lazy_members`] enabled with
+/// the recorded guild create event, whose members aren't cached because of the
+/// threshold, then with an owned message create event and asserts that the
+/// message's author is cached
+pub async fn update_owned_lazy_members() -> Result<(), anyhow::Error> {
+    let cache = MockBackend::new().with_config(CacheConfig::new().lazy_members(1));
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let message = message_create()?;
+    let (Some(guild_id), author_id) = (message.guild_id, message.author.id) else {
+        return Err(anyhow!("the message create event isn't in a guild"));
+    };
+    assert!(cache.member(author_id, guild_id).await?.is_none());
+
+    cache.update_owned(Event::MessageCreate(message)).await?;
+    assert!(cache.member(author_id, guild_id).await?.is_some());
+
+    Ok(())
+}
+
+/// Updates a [`TieredCache`] of two [`MockBackend`]s with the recorded guild
+/// create event and asserts that the guild is read from the first tier, that
+/// it's returned from the second tier once it's deleted from the first tier,
+/// and
+/// that deleting it through the tiered cache deletes it from both tiers
+pub async fn tiered_reads() -> Result<(), anyhow::Error> {
+    let cache = TieredCache::new(MockBackend::new(), MockBackend::new());
+    for event in events(GUILD_CREATE)? {
+        cache.update(&event).await?;
+    }
+    let guild = fixture_guild()?;
+    let cached_guild = Some(CachedGuild::from(&guild));
+
+    cache.l2().clear();
+    assert_eq!(cache.guild(guild.id).await?, cached_guild);
+    assert!(cache.l2().calls_to("guild").is_empty());
+
+    cache.l1().delete_guild(guild.id).await?;
+    assert_eq!(cache.guild(guild.id).await?, cached_guild);
+    assert_eq!(cache.l2().calls_to("guild").len(), 1);
+
+    cache.delete_guild(guild.id).await?;
+    assert!(cache.guild(guild.id).await?.is_none());
+    assert!(cache.l1().guild(guild.id).await?.is_none());
+    assert!(cache.l2().guild(guild.id).await?.is_none());
+
+    Ok(())
+}
+
+/// Adds roles of guilds that aren't cached to each tier of a [`TieredCache`]
+/// of two [`MockBackend`]s and asserts that the guilds orphaned in either tier
+/// are returned, since the tiers might be out of sync
+pub async fn tiered_orphaned_roles() -> Result<(), anyhow::Error> {
+    let cache = TieredCache::new(MockBackend::new(), MockBackend::new());
+    let Some(role) = fixture_guild()?.roles.into_iter().next() else {
+        return Err(anyhow!("the guild create fixture doesn't have roles"));
+    };
+    let (l1_guild_id, l2_guild_id) = (Id::new(1), Id::new(2));
+
+    cache
+        .l1()
+        .upsert_role(CachedRole::from_role(role.clone(), l1_guild_id))
+        .await?;
+    cache
+        .l2()
+        .upsert_role(CachedRole::from_role(role, l2_guild_id))
+        .await?;
+    assert_eq!(
+        cache.select_orphaned_roles().await?,
+        [l1_guild_id, l2_guild_id]
+    );
+
+    cache.delete_guild_roles(l1_guild_id).await?;
+    assert_eq!(cache.select_orphaned_roles().await?, [l2_guild_id]);
+
+    Ok(())
+}
+
+/// Returns the first message create event in the recorded message events
+fn message_create() -> Result<Box<MessageCreate>, anyhow::Error> {
+    events(MESSAGES)?
+        .into_iter()
+        .find_map(|event| match event {
+            Event::MessageCreate(message) => Some(message),
+            _ => None,
+        })
+        .ok_or_else(|| anyhow!("the messages fixture doesn't have a message create event"))
+}
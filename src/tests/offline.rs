@@ -0,0 +1,1250 @@
+use anyhow::anyhow;
+use serde::de::DeserializeSeed;
+use std::sync::Arc;
+
+use serde_json::{json, Value};
+use twilight_model::{
+    channel::Channel,
+    gateway::event::{Event, GatewayEventDeserializer},
+    guild::{Guild, Member},
+    id::{
+        marker::{GuildMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::{
+    cache::{EntityKind, Error},
+    model::{
+        CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild,
+        CachedMember, CachedMessage, CachedPermissionOverwrite, CachedReaction,
+        CachedReactionEmoji, CachedRole, CachedSession, CachedShardState,
+    },
+    queue::Priority,
+    tests::assert_vecs_eq,
+    Cache,
+};
+
+/// The recorded ready event
+const READY: &str = include_str!("fixtures/ready.json");
+/// The recorded guild create event of the testing guild
+pub(super) const GUILD_CREATE: &str = include_str!("fixtures/guild_create.json");
+/// The recorded events of a channel being created, updated and deleted
+const CHANNELS: &str = include_str!("fixtures/channels.json");
+/// The recorded events of a message being created, updated, reacted to and
+/// deleted
+pub(super) const MESSAGES: &str = include_str!("fixtures/messages.json");
+/// The recorded events of a member being added, updated and removed
+pub(super) const MEMBERS: &str = include_str!("fixtures/members.json");
+/// The recorded events of a role being created, updated and deleted
+pub(super) const ROLES: &str = include_str!("fixtures/roles.json");
+/// The recorded events of the guild's emojis being updated
+const EMOJIS: &str = include_str!("fixtures/emojis.json");
+/// The recorded guild delete event of the testing guild
+pub(super) const GUILD_DELETE: &str = include_str!("fixtures/guild_delete.json");
+/// The ID of the guild the channels in [`OfflineTester::channel_order`] are
+/// in, so that the channels of the testing guild don't affect their order
+const ORDER_GUILD_ID: u64 = 100_000_000_000_000_002;
+/// The ID the IDs of the channels in [`OfflineTester::channel_order`] are
+/// added to
+const ORDER_CHANNEL_ID: u64 = 300_000_000_000_001_000;
+
+/// The ID the IDs of the messages in
+/// [`OfflineTester::assert_attachment_reposts`] are added to
+const REPOST_MESSAGE_ID: u64 = 600_000_000_000_002_000;
+/// The ID the IDs of the attachments in
+/// [`OfflineTester::assert_attachment_reposts`] are added to
+const REPOST_ATTACHMENT_ID: u64 = 700_000_000_000_002_000;
+
+/// The ID the IDs of the messages in the tests of the cache options, such as
+/// [`OfflineTester::event_dedup`], are added to
+const OPTION_MESSAGE_ID: u64 = 600_000_000_000_003_000;
+/// The ID of the channel in [`OfflineTester::arc_pool`], added to
+/// [`ORDER_CHANNEL_ID`]
+const POOL_CHANNEL_ID: u64 = 10;
+
+/// The ID of the message reacted to in
+/// [`OfflineTester::assert_emoji_normalization`]
+#[cfg(feature = "emoji-normalization")]
+const NORMALIZATION_MESSAGE_ID: u64 = 600_000_000_000_001_000;
+
+/// Struct that runs the tests without connecting to Discord
+///
+/// Instead of creating a guild and making requests, it updates the cache with
+/// gateway payloads recorded from the testing guild and asserts the cached
+/// data after each event, so it doesn't need a token and can be run in CI
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::tests::offline::OfflineTester;
+///
+/// #[tokio::test]
+/// async fn offline() -> Result<(), anyhow::Error> {
+///     let mut tester = OfflineTester::new(MyCache::new()).await?;
+///     tester.current_user().await?;
+///     tester.shards().await?;
+///     tester.guild().await?;
+///     tester.channels().await?;
+///     tester.channel_order().await?;
+///     tester.messages().await?;
+///     tester.members().await?;
+///     tester.roles().await?;
+///     tester.emojis().await?;
+///     tester.arc_pool().await?;
+///     tester.update_queue().await?;
+///     tester.event_dedup().await?;
+///     tester.ordered_updates().await?;
+///     tester.channel_activity().await?;
+///     tester.guild_delete().await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct OfflineTester<T: Cache + Send + Sync> {
+    /// The cache to test
+    cache: T,
+    /// The guild in the recorded guild create event
+    guild: Guild,
+}
+
+impl<T: Cache + Send + Sync> OfflineTester<T> {
+    /// Updates the cache with the recorded ready and guild create events to
+    /// return the tester
+    ///
+    /// # Warnings
+    /// The cache should be empty, since the tests assert the cached data of
+    /// the whole recorded guild
+    pub async fn new(cache: T) -> Result<Self, anyhow::Error> {
+        let mut tester = Self {
+            cache,
+            guild: fixture_guild()?,
+        };
+
+        tester.replay(READY).await?;
+        tester.replay(GUILD_CREATE).await?;
+
+        Ok(tester)
+    }
+
+    /// Updates the cache with the gateway payloads in the JSON array and
+    /// returns the events they were deserialized into
+    ///
+    /// This can be used to test the cache with your own recorded payloads
+    pub async fn replay(&mut self, payloads: &str) -> Result<Vec<Event>, anyhow::Error> {
+        let events = events(payloads)?;
+        for event in &events {
+            self.cache.update(event).await?;
+        }
+
+        Ok(events)
+    }
+
+    /// Does tests related to caching the current user
+    pub async fn current_user(&mut self) -> Result<(), anyhow::Error> {
+        let Some(Event::Ready(ready)) = events(READY)?.pop() else {
+            return Err(anyhow!("the ready fixture doesn't have a ready event"));
+        };
+        assert_eq!(self.cache.current_user().await?, ready.user);
+        assert_eq!(self.cache.current_application().await?, ready.application);
+        assert_eq!(
+            self.cache.session_info().await?,
+            vec![CachedSession::from(&*ready)]
+        );
+
+        Ok(())
+    }
+
+    /// Does tests related to tracking the sequence numbers of shards
+    pub async fn shards(&mut self) -> Result<(), anyhow::Error> {
+        let Some(ready) = events(READY)?.pop() else {
+            return Err(anyhow!("the ready fixture doesn't have a ready event"));
+        };
+        assert_eq!(self.cache.update_sharded(0, Some(1), &ready).await?, 0);
+        assert_eq!(
+            self.cache
+                .update_sharded(0, Some(2), &Event::GatewayHeartbeatAck)
+                .await?,
+            0
+        );
+        assert_eq!(
+            self.cache
+                .update_sharded(0, Some(5), &Event::GatewayHeartbeatAck)
+                .await?,
+            2
+        );
+        assert_eq!(
+            self.cache.shard_states().await?,
+            vec![CachedShardState {
+                shard_id: 0,
+                sequence: 5,
+                events: 3,
+                missed_events: 2,
+            }]
+        );
+
+        assert_eq!(self.cache.update_sharded(0, Some(1), &ready).await?, 0);
+        assert_eq!(
+            self.cache
+                .shard_state(0)
+                .await?
+                .map(|state| state.missed_events),
+            Some(0)
+        );
+
+        Ok(())
+    }
+
+    /// Does tests related to caching the guild create event, including the
+    /// guild's channels, roles, emojis and members
+    pub async fn guild(&mut self) -> Result<(), anyhow::Error> {
+        let guild_id = self.guild.id;
+
+        assert_eq!(
+            self.cache.guild(guild_id).await?,
+            Some(CachedGuild::from(&self.guild))
+        );
+
+        for channel in &self.guild.channels {
+            self.assert_channel_eq(channel).await?;
+        }
+        assert_vecs_eq(
+            &self
+                .guild
+                .channels
+                .iter()
+                .map(CachedChannel::from)
+                .collect(),
+            &self.cache.guild_channels(guild_id).await?,
+        );
+
+        let roles: Vec<_> = self
+            .guild
+            .roles
+            .iter()
+            .map(|role| CachedRole::from_role(role.clone(), guild_id))
+            .collect();
+        assert_vecs_eq(&roles, &self.cache.guild_roles(guild_id).await?);
+        for role in &roles {
+            assert_eq!(self.cache.role(role.id).await?.as_ref(), Some(role));
+        }
+
+        let emojis: Vec<_> = self
+            .guild
+            .emojis
+            .iter()
+            .map(|emoji| CachedEmoji::from_emoji(emoji, guild_id))
+            .collect();
+        assert_vecs_eq(&emojis, &self.cache.guild_emojis(guild_id).await?);
+
+        let members: Vec<_> = self.guild.members.iter().map(CachedMember::from).collect();
+        assert_vecs_eq(&members, &self.cache.guild_members(guild_id).await?);
+        for member in &self.guild.members {
+            self.assert_member_roles_eq(guild_id, member.user.id, &member.roles)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Does tests related to caching channels
+    pub async fn channels(&mut self) -> Result<(), anyhow::Error> {
+        for event in events(CHANNELS)? {
+            self.cache.update(&event).await?;
+
+            match &event {
+                Event::ChannelCreate(channel) => self.assert_channel_eq(&channel.0).await?,
+                Event::ChannelUpdate(channel) => self.assert_channel_eq(&channel.0).await?,
+                Event::ChannelDelete(channel) => {
+                    assert!(self.cache.channel(channel.id).await?.is_none());
+                    assert!(self
+                        .cache
+                        .permission_overwrites(channel.id)
+                        .await?
+                        .is_empty());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Does tests related to the order of channels returned by
+    /// [`Cache::guild_channels_ordered`]
+    ///
+    /// The channels are created in another guild with tied positions, then
+    /// reordered with channel update events sent one by one, like when a
+    /// channel is dragged in the client, and deleted at the end
+    ///
+    /// It also tests [`Cache::channel_synced_with_category`] with the same
+    /// channels
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn channel_order(&mut self) -> Result<(), anyhow::Error> {
+        // (ID, type, parent ID, position), 4 is a category and 2 is a voice
+        // channel
+        let channels = [
+            (1, 4, None, 1),
+            (2, 4, None, 0),
+            (3, 0, Some(1), 0),
+            (4, 2, Some(1), 0),
+            (5, 0, Some(1), 0),
+            (6, 0, None, 5),
+            (7, 2, None, 0),
+            (8, 0, Some(2), 0),
+        ];
+        let creates = channels.map(|(id, kind, parent_id, position)| {
+            channel_payload("CHANNEL_CREATE", id, kind, parent_id, position)
+        });
+        self.replay(&json!(creates).to_string()).await?;
+        self.assert_channel_order_eq(&[6, 7, 2, 8, 1, 3, 5, 4])
+            .await?;
+
+        let mut update = channel_payload("CHANNEL_UPDATE", 5, 0, Some(1), 0);
+        update["d"]["permission_overwrites"] = json!([{
+            "id": ORDER_GUILD_ID.to_string(),
+            "type": 0,
+            "allow": "0",
+            "deny": "1024"
+        }]);
+        self.replay(&json!([update]).to_string()).await?;
+        for (id, synced) in [(3, Some(true)), (5, Some(false)), (6, None)] {
+            assert_eq!(
+                self.cache
+                    .channel_synced_with_category(Id::new(ORDER_CHANNEL_ID + id))
+                    .await?,
+                synced
+            );
+        }
+
+        for (id, kind, parent_id, position) in
+            [(1, 4, None, 0), (2, 4, None, 1), (3, 0, Some(1), 1)]
+        {
+            let update = channel_payload("CHANNEL_UPDATE", id, kind, parent_id, position);
+            self.replay(&json!([update]).to_string()).await?;
+        }
+        self.assert_channel_order_eq(&[6, 7, 1, 5, 3, 4, 2, 8])
+            .await?;
+
+        let deletes = channels.map(|(id, kind, parent_id, position)| {
+            channel_payload("CHANNEL_DELETE", id, kind, parent_id, position)
+        });
+        self.replay(&json!(deletes).to_string()).await?;
+        self.assert_channel_order_eq(&[]).await?;
+
+        Ok(())
+    }
+
+    /// Does tests related to caching messages, including their embeds,
+    /// attachments and reactions
+    pub async fn messages(&mut self) -> Result<(), anyhow::Error> {
+        for event in events(MESSAGES)? {
+            self.cache.update(&event).await?;
+
+            match &event {
+                Event::MessageCreate(message) => {
+                    assert_eq!(
+                        self.cache.message(message.id).await?,
+                        Some(CachedMessage::from(&message.0))
+                    );
+
+                    let cached_embeds = self.cache.embeds(message.id).await?;
+                    let embeds: Vec<_> = message
+                        .embeds
+                        .iter()
+                        .zip(&cached_embeds)
+                        .map(|(embed, (cached_embed, _))| {
+                            let mut embed_into = CachedEmbed::from_embed(embed.clone(), message.id);
+                            embed_into.id = cached_embed.id;
+                            (
+                                embed_into,
+                                embed
+                                    .fields
+                                    .iter()
+                                    .map(|field| {
+                                        CachedEmbedField::from_embed_field(
+                                            field.clone(),
+                                            cached_embed.id,
+                                        )
+                                    })
+                                    .collect(),
+                            )
+                        })
+                        .collect();
+                    assert_eq!(embeds.len(), message.embeds.len());
+                    assert_eq!(embeds, cached_embeds);
+
+                    assert_vecs_eq(
+                        &message
+                            .attachments
+                            .iter()
+                            .map(|attachment| {
+                                CachedAttachment::from_attachment(attachment.clone(), message.id)
+                            })
+                            .collect(),
+                        &self.cache.attachments(message.id).await?,
+                    );
+
+                    if let Some(attachment) = message.attachments.first() {
+                        let mut voice_message =
+                            CachedAttachment::from_attachment(attachment.clone(), message.id);
+                        voice_message.duration_millis = Some(3_520);
+                        voice_message.flags = Some(8_192);
+                        voice_message.waveform = Some("AAAAAA==".to_owned());
+                        self.cache.upsert_attachment(voice_message.clone()).await?;
+                        assert!(self
+                            .cache
+                            .attachments(message.id)
+                            .await?
+                            .contains(&voice_message));
+                    }
+                }
+                Event::MessageUpdate(message) => {
+                    let cached_message = self.cache.message(message.id).await?.unwrap();
+                    if let Some(content) = &message.content {
+                        assert_eq!(&cached_message.content, content);
+                    }
+                    assert_eq!(cached_message.edited_timestamp, message.edited_timestamp);
+                    if self.cache.config().message_revisions {
+                        if let Some(revision) =
+                            self.cache.message_revisions(message.id).await?.last()
+                        {
+                            assert_ne!(revision.content, cached_message.content);
+                        }
+                    }
+                }
+                Event::ReactionAdd(reaction) => {
+                    assert!(self
+                        .cache
+                        .reactions(reaction.message_id)
+                        .await?
+                        .contains(&CachedReaction::from(&reaction.0)));
+                    let emoji = CachedReactionEmoji::from(&reaction.emoji).key();
+                    if let Some(guild_id) = reaction.guild_id {
+                        #[cfg(feature = "stats")]
+                        assert!(self
+                            .cache
+                            .emoji_usage(guild_id, Timestamp::from_secs(0)?)
+                            .await?
+                            .iter()
+                            .any(|(used_emoji, uses)| *used_emoji == emoji && *uses > 0));
+                        assert!(self
+                            .cache
+                            .messages_with_emoji_reaction(emoji, guild_id, 0)
+                            .await?
+                            .iter()
+                            .any(|message| message.id == reaction.message_id));
+                    }
+                }
+                Event::MessageDelete(message) => {
+                    assert!(self.cache.message(message.id).await?.is_none());
+                    assert!(self.cache.embeds(message.id).await?.is_empty());
+                    assert!(self.cache.attachments(message.id).await?.is_empty());
+                    assert!(self.cache.reactions(message.id).await?.is_empty());
+                }
+                _ => {}
+            }
+        }
+
+        self.assert_attachment_reposts().await?;
+        #[cfg(feature = "emoji-normalization")]
+        self.assert_emoji_normalization().await?;
+
+        Ok(())
+    }
+
+    /// Does tests related to caching members and their roles
+    pub async fn members(&mut self) -> Result<(), anyhow::Error> {
+        for event in events(MEMBERS)? {
+            self.cache.update(&event).await?;
+
+            match &event {
+                Event::MemberAdd(member) => {
+                    assert_eq!(
+                        self.cache.member(member.user.id, member.guild_id).await?,
+                        Some(CachedMember::from(&member.0))
+                    );
+                    self.assert_member_roles_eq(member.guild_id, member.user.id, &member.roles)
+                        .await?;
+                }
+                Event::MemberUpdate(member) => {
+                    let cached_member = self
+                        .cache
+                        .member(member.user.id, member.guild_id)
+                        .await?
+                        .unwrap();
+                    assert_eq!(cached_member.nick, member.nick);
+                    assert_eq!(cached_member.name, member.user.name);
+                    self.assert_member_roles_eq(member.guild_id, member.user.id, &member.roles)
+                        .await?;
+                }
+                Event::MemberRemove(member) => {
+                    assert!(self
+                        .cache
+                        .member(member.user.id, member.guild_id)
+                        .await?
+                        .is_none());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Does tests related to caching roles
+    pub async fn roles(&mut self) -> Result<(), anyhow::Error> {
+        for event in events(ROLES)? {
+            self.cache.update(&event).await?;
+
+            match &event {
+                Event::RoleCreate(role) => {
+                    let cached_role = CachedRole::from_role(role.role.clone(), role.guild_id);
+                    assert_eq!(
+                        self.cache.role(role.role.id).await?,
+                        Some(cached_role.clone())
+                    );
+                    assert!(self
+                        .cache
+                        .guild_roles(role.guild_id)
+                        .await?
+                        .contains(&cached_role));
+                    #[cfg(feature = "autocomplete")]
+                    self.assert_role_suggestion(role.guild_id, role.role.id, Some(&role.role.name));
+                }
+                Event::RoleUpdate(role) => {
+                    assert_eq!(
+                        self.cache.role(role.role.id).await?,
+                        Some(CachedRole::from_role(role.role.clone(), role.guild_id))
+                    );
+                    #[cfg(feature = "autocomplete")]
+                    self.assert_role_suggestion(role.guild_id, role.role.id, Some(&role.role.name));
+                }
+                Event::RoleDelete(role) => {
+                    assert!(self.cache.role(role.role_id).await?.is_none());
+                    assert!(!self
+                        .cache
+                        .guild_roles(role.guild_id)
+                        .await?
+                        .iter()
+                        .any(|cached_role| cached_role.id == role.role_id));
+                    assert!(self.cache.role_members(role.role_id).await?.is_empty());
+                    #[cfg(feature = "autocomplete")]
+                    self.assert_role_suggestion(role.guild_id, role.role_id, None);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Does tests related to caching emojis
+    pub async fn emojis(&mut self) -> Result<(), anyhow::Error> {
+        for event in events(EMOJIS)? {
+            self.cache.update(&event).await?;
+
+            if let Event::GuildEmojisUpdate(emojis) = &event {
+                let cached_emojis: Vec<_> = emojis
+                    .emojis
+                    .iter()
+                    .map(|emoji| CachedEmoji::from_emoji(emoji, emojis.guild_id))
+                    .collect();
+                assert_vecs_eq(
+                    &cached_emojis,
+                    &self.cache.guild_emojis(emojis.guild_id).await?,
+                );
+                for emoji in &cached_emojis {
+                    assert_eq!(self.cache.emoji(emoji.id).await?.as_ref(), Some(emoji));
+                    assert_eq!(
+                        emoji.to_reaction_string(),
+                        format!("{}:{}", emoji.name, emoji.id)
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Does tests related to removing the guild and everything in it when
+    /// the bot is removed from it
+    pub async fn guild_delete(&mut self) -> Result<(), anyhow::Error> {
+        self.replay(GUILD_DELETE).await?;
+
+        let guild_id = self.guild.id;
+        assert!(self.cache.guild(guild_id).await?.is_none());
+        assert!(self.cache.guild_channels(guild_id).await?.is_empty());
+        assert!(self.cache.guild_roles(guild_id).await?.is_empty());
+        assert!(self.cache.guild_emojis(guild_id).await?.is_empty());
+        assert!(self.cache.guild_members(guild_id).await?.is_empty());
+        assert!(matches!(
+            self.cache.try_guild_roles(guild_id).await,
+            Err(Error::NotCached(EntityKind::Guild, id)) if id == guild_id.get()
+        ));
+
+        Ok(())
+    }
+
+    /// Does tests related to invalidating the channels shared through the pool
+    /// returned by [`Cache::arc_pool`] when they're updated
+    ///
+    /// Does nothing if the cache doesn't have a pool
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn arc_pool(&mut self) -> Result<(), anyhow::Error> {
+        if self.cache.arc_pool().is_none() {
+            return Ok(());
+        }
+        let channel_id = Id::new(ORDER_CHANNEL_ID + POOL_CHANNEL_ID);
+
+        self.replay(
+            &json!([channel_payload(
+                "CHANNEL_CREATE",
+                POOL_CHANNEL_ID,
+                0,
+                None,
+                0
+            )])
+            .to_string(),
+        )
+        .await?;
+        let Some(channel) = self.cache.channel_ref(channel_id).await? else {
+            return Err(anyhow!("the created channel isn't cached"));
+        };
+        let Some(pooled_channel) = self.cache.channel_ref(channel_id).await? else {
+            return Err(anyhow!("the pooled channel isn't cached"));
+        };
+        assert!(Arc::ptr_eq(&channel, &pooled_channel));
+
+        let updates = self
+            .replay(
+                &json!([channel_payload(
+                    "CHANNEL_UPDATE",
+                    POOL_CHANNEL_ID,
+                    0,
+                    None,
+                    1
+                )])
+                .to_string(),
+            )
+            .await?;
+        let Some(Event::ChannelUpdate(update)) = updates.first() else {
+            return Err(anyhow!("the payload isn't a channel update event"));
+        };
+        let updated_channel = self.cache.channel_ref(channel_id).await?;
+        assert_eq!(
+            updated_channel.as_deref(),
+            Some(&CachedChannel::from(&update.0))
+        );
+        assert_ne!(updated_channel.as_deref(), Some(&*channel));
+
+        self.replay(
+            &json!([channel_payload(
+                "CHANNEL_DELETE",
+                POOL_CHANNEL_ID,
+                0,
+                None,
+                1
+            )])
+            .to_string(),
+        )
+        .await?;
+        assert!(self.cache.channel_ref(channel_id).await?.is_none());
+
+        Ok(())
+    }
+
+    /// Does tests related to dropping low-priority events in
+    /// [`Cache::update_with_priority`] when the queue returned by
+    /// [`Cache::update_queue`] is full
+    ///
+    /// The queue is filled by holding as many slots as its capacity, then a
+    /// low-priority message is created, which is cached only if the queue's
+    /// minimum priority is [`Priority::Low`]
+    ///
+    /// Does nothing if the cache doesn't have an update queue
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn update_queue(&mut self) -> Result<(), anyhow::Error> {
+        let Some(queue) = self.cache.update_queue() else {
+            return Ok(());
+        };
+        let message_id = Id::new(OPTION_MESSAGE_ID + 1);
+        let create = option_message_event("MESSAGE_CREATE", 1, "queued message")?;
+        let delete = option_message_event("MESSAGE_DELETE", 1, "")?;
+
+        let dropped = queue.dropped();
+        let slots: Vec<_> = (0..queue.capacity())
+            .filter_map(|_| queue.enter(Priority::High))
+            .collect();
+        self.cache
+            .update_with_priority(&create, Priority::Low)
+            .await?;
+        let was_dropped = queue.dropped() > dropped;
+        assert_eq!(
+            self.cache.message(message_id).await?.is_some(),
+            !was_dropped
+        );
+
+        self.cache
+            .update_with_priority(&create, Priority::High)
+            .await?;
+        assert!(self.cache.message(message_id).await?.is_some());
+        drop(slots);
+        assert_eq!(queue.depth(), 0);
+
+        self.cache
+            .update_with_priority(&delete, Priority::Low)
+            .await?;
+        assert!(self.cache.message(message_id).await?.is_none());
+
+        Ok(())
+    }
+
+    /// Does tests related to skipping events that were already applied with
+    /// [`crate::config::CacheConfig::event_dedup`]
+    ///
+    /// A message is created, edited and created again, the edit should be
+    /// kept since the second create is a duplicate
+    ///
+    /// Does nothing if event deduplication isn't enabled
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn event_dedup(&mut self) -> Result<(), anyhow::Error> {
+        if self.cache.config().event_dedup.is_none() {
+            return Ok(());
+        }
+        let message_id = Id::new(OPTION_MESSAGE_ID + 2);
+        let create = option_message_event("MESSAGE_CREATE", 2, "original message")?;
+        let update = option_message_event("MESSAGE_UPDATE", 2, "edited message")?;
+        let delete = option_message_event("MESSAGE_DELETE", 2, "")?;
+
+        for event in [&create, &update, &create] {
+            self.cache.update(event).await?;
+        }
+        assert_eq!(
+            self.cache
+                .message(message_id)
+                .await?
+                .map(|message| message.content),
+            Some("edited message".to_owned())
+        );
+
+        self.cache.update(&delete).await?;
+        self.cache.update(&create).await?;
+        assert!(self.cache.message(message_id).await?.is_none());
+
+        Ok(())
+    }
+
+    /// Does tests related to skipping outdated updates with
+    /// [`crate::config::CacheConfig::ordered_updates`]
+    ///
+    /// A member update with a lower sequence number than the previous one and
+    /// a message edit with an older edited timestamp than the previous one
+    /// should both be skipped
+    ///
+    /// The updates are received by shard 0, whose state is reset with the
+    /// recorded ready event at the end
+    ///
+    /// Does nothing if ordered updates aren't enabled
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn ordered_updates(&mut self) -> Result<(), anyhow::Error> {
+        if !self.cache.config().ordered_updates {
+            return Ok(());
+        }
+        let Some(member) = self.guild.members.first().cloned() else {
+            return Err(anyhow!("the guild create fixture doesn't have members"));
+        };
+        let guild_id = self.guild.id;
+
+        for (sequence, nick) in [(100, "newer nick"), (99, "older nick")] {
+            let update = member_update_event(&member, guild_id, Some(nick))?;
+            self.cache
+                .update_sharded(0, Some(sequence), &update)
+                .await?;
+        }
+        assert_eq!(
+            self.cache
+                .member(member.user.id, guild_id)
+                .await?
+                .and_then(|cached_member| cached_member.nick),
+            Some("newer nick".to_owned())
+        );
+
+        let restore = member_update_event(&member, guild_id, member.nick.as_deref())?;
+        self.cache.update_sharded(0, Some(101), &restore).await?;
+        assert_eq!(
+            self.cache
+                .member(member.user.id, guild_id)
+                .await?
+                .map(|cached_member| cached_member.nick),
+            Some(member.nick.clone())
+        );
+
+        let message_id = Id::new(OPTION_MESSAGE_ID + 3);
+        let mut newer_edit = option_message_payload("MESSAGE_UPDATE", 3, "newer edit");
+        newer_edit["d"]["edited_timestamp"] = json!("2022-03-08T23:20:00.000000+00:00");
+        let mut older_edit = option_message_payload("MESSAGE_UPDATE", 3, "older edit");
+        older_edit["d"]["edited_timestamp"] = json!("2022-03-08T23:10:00.000000+00:00");
+        self.replay(
+            &json!([
+                option_message_payload("MESSAGE_CREATE", 3, "ordered message"),
+                newer_edit,
+                older_edit
+            ])
+            .to_string(),
+        )
+        .await?;
+        assert_eq!(
+            self.cache
+                .message(message_id)
+                .await?
+                .map(|message| message.content),
+            Some("newer edit".to_owned())
+        );
+        self.replay(&json!([option_message_payload("MESSAGE_DELETE", 3, "")]).to_string())
+            .await?;
+
+        let Some(ready) = events(READY)?.pop() else {
+            return Err(anyhow!("the ready fixture doesn't have a ready event"));
+        };
+        self.cache.update_sharded(0, None, &ready).await?;
+
+        Ok(())
+    }
+
+    /// Does tests related to returning the channels without recent messages
+    /// with [`Cache::inactive_channels`]
+    ///
+    /// Does nothing if [`crate::config::CacheConfig::channel_activity`] isn't
+    /// enabled
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    pub async fn channel_activity(&mut self) -> Result<(), anyhow::Error> {
+        if !self.cache.config().channel_activity {
+            return Ok(());
+        }
+        let create = option_message_event("MESSAGE_CREATE", 4, "active message")?;
+        let Event::MessageCreate(message) = &create else {
+            return Err(anyhow!("the payload isn't a message create event"));
+        };
+        let guild_id = self.guild.id;
+        let channel_id = message.channel_id;
+        self.cache.update(&create).await?;
+
+        let contains_channel =
+            |channels: Vec<CachedChannel>| channels.iter().any(|channel| channel.id == channel_id);
+        assert!(!contains_channel(
+            self.cache
+                .inactive_channels(guild_id, message.timestamp)
+                .await?
+        ));
+        assert!(contains_channel(
+            self.cache
+                .inactive_channels(
+                    guild_id,
+                    Timestamp::from_micros(message.timestamp.as_micros() + 1)?
+                )
+                .await?
+        ));
+
+        self.cache
+            .update(&option_message_event("MESSAGE_DELETE", 4, "")?)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Asserts that the same file posted in two messages is stored in one blob
+    /// that both messages are returned as the posters of, while the
+    /// attachments of each message keep their own URLs
+    ///
+    /// Does nothing if [`crate::config::CacheConfig::attachment_dedup`] isn't
+    /// enabled
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    async fn assert_attachment_reposts(&mut self) -> Result<(), anyhow::Error> {
+        if !self.cache.config().attachment_dedup {
+            return Ok(());
+        }
+
+        let creates = [1, 2].map(|id| repost_payload("MESSAGE_CREATE", id));
+        let mut file_hashes = Vec::new();
+        for event in self.replay(&json!(creates).to_string()).await? {
+            let Event::MessageCreate(message) = event else {
+                continue;
+            };
+            let attachments: Vec<_> = message
+                .attachments
+                .iter()
+                .map(|attachment| CachedAttachment::from_attachment(attachment.clone(), message.id))
+                .collect();
+            assert_vecs_eq(&attachments, &self.cache.attachments(message.id).await?);
+            file_hashes.extend(
+                attachments
+                    .into_iter()
+                    .map(|attachment| attachment.split().0.file_hash),
+            );
+        }
+        assert_eq!(file_hashes.len(), 2);
+        assert_eq!(file_hashes.first(), file_hashes.last());
+
+        let file_hash = file_hashes.pop().unwrap_or_default();
+        let poster_ids: Vec<_> = self
+            .cache
+            .attachment_posters(&file_hash)
+            .await?
+            .iter()
+            .map(|message| message.id.get() - REPOST_MESSAGE_ID)
+            .collect();
+        assert_eq!(poster_ids, [1, 2]);
+
+        let deletes = [1, 2].map(|id| repost_payload("MESSAGE_DELETE", id));
+        self.replay(&json!(deletes).to_string()).await?;
+        assert!(self.cache.attachment_posters(&file_hash).await?.is_empty());
+
+        Ok(())
+    }
+
+    /// Asserts that an emoji reacted with and without its variation selector
+    /// is cached as one reaction, which is removed by either of them
+    #[cfg(feature = "emoji-normalization")]
+    async fn assert_emoji_normalization(&mut self) -> Result<(), anyhow::Error> {
+        let message_id = Id::new(NORMALIZATION_MESSAGE_ID);
+
+        self.replay(
+            &json!([
+                reaction_payload("MESSAGE_REACTION_ADD", "\u{2764}"),
+                reaction_payload("MESSAGE_REACTION_ADD", "\u{2764}\u{FE0F}"),
+                reaction_payload("MESSAGE_REACTION_ADD", "1\u{20E3}"),
+            ])
+            .to_string(),
+        )
+        .await?;
+        let mut emojis: Vec<_> = self
+            .cache
+            .reactions(message_id)
+            .await?
+            .into_iter()
+            .map(|reaction| reaction.emoji)
+            .collect();
+        emojis.sort();
+        assert_eq!(emojis, ["1\u{FE0F}\u{20E3}", "\u{2764}\u{FE0F}"]);
+
+        self.replay(
+            &json!([
+                reaction_payload("MESSAGE_REACTION_REMOVE", "\u{2764}"),
+                reaction_payload("MESSAGE_REACTION_REMOVE", "1\u{FE0F}\u{20E3}"),
+            ])
+            .to_string(),
+        )
+        .await?;
+        assert!(self.cache.reactions(message_id).await?.is_empty());
+
+        Ok(())
+    }
+
+    /// Asserts that the role is suggested when its name is searched for if a
+    /// name is given, or isn't suggested at all otherwise
+    ///
+    /// Does nothing if the cache doesn't have an autocomplete index
+    #[cfg(feature = "autocomplete")]
+    fn assert_role_suggestion(
+        &self,
+        guild_id: Id<GuildMarker>,
+        role_id: Id<RoleMarker>,
+        name: Option<&str>,
+    ) {
+        if self.cache.autocomplete_index().is_none() {
+            return;
+        }
+        let suggested = self
+            .cache
+            .suggest_roles(guild_id, &name.unwrap_or_default().to_ascii_uppercase(), 0)
+            .iter()
+            .any(|suggestion| suggestion.id == role_id);
+        assert_eq!(suggested, name.is_some());
+    }
+
+    /// Asserts that the cached channel and its permission overwrites are
+    /// equal to the given channel
+    async fn assert_channel_eq(&self, channel: &Channel) -> Result<(), anyhow::Error> {
+        assert_eq!(
+            self.cache.channel(channel.id).await?,
+            Some(CachedChannel::from(channel))
+        );
+        assert_vecs_eq(
+            &channel
+                .permission_overwrites
+                .iter()
+                .flatten()
+                .map(|overwrite| {
+                    CachedPermissionOverwrite::from_permission_overwrite(overwrite, channel.id)
+                })
+                .collect(),
+            &self.cache.permission_overwrites(channel.id).await?,
+        );
+
+        Ok(())
+    }
+
+    /// Asserts that the channels returned by
+    /// [`Cache::guild_channels_ordered`] are in the given order, the IDs are
+    /// the ones passed to [`channel_payload`]
+    #[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+    async fn assert_channel_order_eq(&self, ids: &[u64]) -> Result<(), anyhow::Error> {
+        let ordered_ids: Vec<_> = self
+            .cache
+            .guild_channels_ordered(Id::new(ORDER_GUILD_ID))
+            .await?
+            .iter()
+            .map(|channel| channel.id.get() - ORDER_CHANNEL_ID)
+            .collect();
+        assert_eq!(ordered_ids, ids);
+
+        Ok(())
+    }
+
+    /// Asserts that the IDs of the cached roles of the member are equal to the
+    /// given role IDs
+    async fn assert_member_roles_eq(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        role_ids: &[Id<RoleMarker>],
+    ) -> Result<(), anyhow::Error> {
+        assert_vecs_eq(
+            &role_ids.to_vec(),
+            &self
+                .cache
+                .member_roles(user_id, guild_id)
+                .await?
+                .iter()
+                .map(|role| role.id)
+                .collect(),
+        );
+        for role in self.cache.guild_roles(guild_id).await? {
+            assert_eq!(
+                self.cache.role_members(role.id).await?.contains(&user_id),
+                role_ids.contains(&role.id)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Deserializes the gateway payloads in the JSON array into events
+pub(super) fn events(payloads: &str) -> Result<Vec<Event>, anyhow::Error> {
+    serde_json::from_str::<Vec<Value>>(payloads)?
+        .iter()
+        .map(|payload| {
+            let json = payload.to_string();
+            let gateway_event = GatewayEventDeserializer::from_json(&json)
+                .ok_or_else(|| anyhow!("the payload isn't a gateway event:\n{json}"))?
+                .deserialize(&mut serde_json::Deserializer::from_str(&json))?;
+            Ok(Event::from(gateway_event))
+        })
+        .collect()
+}
+
+/// Returns the gateway payload of a channel event in the guild used in
+/// [`OfflineTester::channel_order`], the channel and parent IDs are added to
+/// [`ORDER_CHANNEL_ID`]
+#[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+fn channel_payload(
+    kind: &str,
+    id: u64,
+    channel_kind: u8,
+    parent_id: Option<u64>,
+    position: i32,
+) -> Value {
+    json!({
+        "op": 0,
+        "s": 0,
+        "t": kind,
+        "d": {
+            "id": (ORDER_CHANNEL_ID + id).to_string(),
+            "type": channel_kind,
+            "guild_id": ORDER_GUILD_ID.to_string(),
+            "name": format!("channel-{id}"),
+            "parent_id": parent_id.map(|parent_id| (ORDER_CHANNEL_ID + parent_id).to_string()),
+            "position": position,
+            "permission_overwrites": []
+        }
+    })
+}
+
+/// Returns the gateway payload of a message event of a message with the same
+/// file attached used in [`OfflineTester::assert_attachment_reposts`], the
+/// message and attachment IDs are added to [`REPOST_MESSAGE_ID`] and
+/// [`REPOST_ATTACHMENT_ID`]
+#[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+fn repost_payload(kind: &str, id: u64) -> Value {
+    let message_id = (REPOST_MESSAGE_ID + id).to_string();
+    let attachment_id = (REPOST_ATTACHMENT_ID + id).to_string();
+    let data = if kind == "MESSAGE_CREATE" {
+        json!({
+            "id": message_id,
+            "channel_id": "300000000000000001",
+            "guild_id": "100000000000000001",
+            "author": {
+                "id": "200000000000000002",
+                "username": "tester",
+                "discriminator": "0001",
+                "avatar": null,
+                "bot": false
+            },
+            "content": "reposted attachment",
+            "timestamp": format!("2022-03-08T22:3{id}:00.000000+00:00"),
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [{
+                "id": attachment_id,
+                "filename": "reposted_attachment.png",
+                "content_type": "image/png",
+                "size": 2048,
+                "url": format!(
+                    "https://cdn.discordapp.com/attachments/300000000000000001/{attachment_id}/reposted_attachment.png"
+                ),
+                "proxy_url": format!(
+                    "https://media.discordapp.net/attachments/300000000000000001/{attachment_id}/reposted_attachment.png"
+                ),
+                "height": 160,
+                "width": 160
+            }],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+            "flags": 0
+        })
+    } else {
+        json!({
+            "id": message_id,
+            "channel_id": "300000000000000001",
+            "guild_id": "100000000000000001"
+        })
+    };
+
+    json!({ "op": 0, "s": 0, "t": kind, "d": data })
+}
+
+/// Returns the gateway payload of a message event in the testing guild used
+/// in the tests of the cache options, the message ID is added to
+/// [`OPTION_MESSAGE_ID`]
+///
+/// Message updates are edited at a fixed timestamp, set the
+/// `edited_timestamp` field to change it
+#[allow(clippy::integer_arithmetic, clippy::arithmetic_side_effects)]
+fn option_message_payload(kind: &str, id: u64, content: &str) -> Value {
+    let message_id = (OPTION_MESSAGE_ID + id).to_string();
+    let data = match kind {
+        "MESSAGE_CREATE" => json!({
+            "id": message_id,
+            "channel_id": "300000000000000001",
+            "guild_id": "100000000000000001",
+            "author": {
+                "id": "200000000000000002",
+                "username": "tester",
+                "discriminator": "0001",
+                "avatar": null,
+                "bot": false
+            },
+            "content": content,
+            "timestamp": format!("2022-03-08T23:0{id}:00.000000+00:00"),
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+            "flags": 0
+        }),
+        "MESSAGE_UPDATE" => json!({
+            "id": message_id,
+            "channel_id": "300000000000000001",
+            "guild_id": "100000000000000001",
+            "content": content,
+            "edited_timestamp": format!("2022-03-08T23:3{id}:00.000000+00:00")
+        }),
+        _ => json!({
+            "id": message_id,
+            "channel_id": "300000000000000001",
+            "guild_id": "100000000000000001"
+        }),
+    };
+
+    json!({ "op": 0, "s": 0, "t": kind, "d": data })
+}
+
+/// Returns the event of the payload returned by [`option_message_payload`]
+fn option_message_event(kind: &str, id: u64, content: &str) -> Result<Event, anyhow::Error> {
+    events(&json!([option_message_payload(kind, id, content)]).to_string())?
+        .pop()
+        .ok_or_else(|| anyhow!("the {kind} payload isn't an event"))
+}
+
+/// Returns the member update event of the member with the given nick, the
+/// other fields are the member's
+fn member_update_event(
+    member: &Member,
+    guild_id: Id<GuildMarker>,
+    nick: Option<&str>,
+) -> Result<Event, anyhow::Error> {
+    let payload = json!({
+        "op": 0,
+        "s": 0,
+        "t": "GUILD_MEMBER_UPDATE",
+        "d": {
+            "user": member.user,
+            "roles": member.roles,
+            "joined_at": member.joined_at,
+            "deaf": member.deaf,
+            "mute": member.mute,
+            "nick": nick,
+            "avatar": member.avatar,
+            "premium_since": member.premium_since,
+            "pending": member.pending,
+            "communication_disabled_until": member.communication_disabled_until,
+            "guild_id": guild_id
+        }
+    });
+
+    events(&json!([payload]).to_string())?
+        .pop()
+        .ok_or_else(|| anyhow!("the member update payload isn't an event"))
+}
+
+/// Returns the gateway payload of a reaction event by the current user with the
+/// Unicode emoji on the message used in
+/// [`OfflineTester::assert_emoji_normalization`]
+#[cfg(feature = "emoji-normalization")]
+fn reaction_payload(kind: &str, emoji: &str) -> Value {
+    json!({
+        "op": 0,
+        "s": 0,
+        "t": kind,
+        "d": {
+            "user_id": "200000000000000001",
+            "channel_id": "300000000000000001",
+            "message_id": NORMALIZATION_MESSAGE_ID.to_string(),
+            "guild_id": "100000000000000001",
+            "emoji": { "id": null, "name": emoji }
+        }
+    })
+}
+
+/// Returns the guild in the recorded guild create event
+pub(super) fn fixture_guild() -> Result<Guild, anyhow::Error> {
+    match events(GUILD_CREATE)?.pop() {
+        Some(Event::GuildCreate(guild_create)) => Ok(guild_create.0),
+        _ => Err(anyhow!(
+            "the guild create fixture doesn't have a guild create event"
+        )),
+    }
+}
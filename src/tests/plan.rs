@@ -0,0 +1,188 @@
+use core::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+
+/// A group of tests of [`super::Tester`], each variant runs the method with
+/// the same name
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Suite {
+    /// Runs [`super::Tester::current_user`]
+    CurrentUser,
+    /// Runs [`super::Tester::channels`]
+    Channels,
+    /// Runs [`super::Tester::permission_overwrites`]
+    PermissionOverwrites,
+    /// Runs [`super::Tester::messages`]
+    Messages,
+    /// Runs [`super::Tester::members`]
+    Members,
+    /// Runs [`super::Tester::guilds`]
+    Guilds,
+    /// Runs [`super::Tester::roles`]
+    Roles,
+    /// Runs [`super::Tester::emojis`]
+    Emojis,
+    /// Runs [`super::Tester::stickers`]
+    Stickers,
+    /// Runs [`super::Tester::presences`]
+    Presences,
+    /// Runs [`super::Tester::stages`]
+    Stages,
+    /// Runs [`super::Tester::private_channels`]
+    PrivateChannels,
+    /// Runs [`super::Tester::ready`]
+    Ready,
+}
+
+impl Suite {
+    /// All the suites, in the order they're run in
+    pub const ALL: [Self; 13] = [
+        Self::CurrentUser,
+        Self::Channels,
+        Self::PermissionOverwrites,
+        Self::Messages,
+        Self::Members,
+        Self::Guilds,
+        Self::Roles,
+        Self::Emojis,
+        Self::Stickers,
+        Self::Presences,
+        Self::Stages,
+        Self::PrivateChannels,
+        Self::Ready,
+    ];
+}
+
+/// The suites to run with [`super::Tester::run`]
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::tests::plan::{Suite, TestPlan};
+///
+/// let report = tester
+///     .run(&TestPlan::new().suite(Suite::Channels).suite(Suite::Messages))
+///     .await;
+/// assert!(report.is_success(), "{report}");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestPlan {
+    /// The suites to run, in the order they're added in
+    suites: Vec<Suite>,
+}
+
+impl TestPlan {
+    /// Create a plan that doesn't run any suites
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { suites: Vec::new() }
+    }
+
+    /// Create a plan that runs all the suites
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            suites: Suite::ALL.to_vec(),
+        }
+    }
+
+    /// Add the suite to the plan, if it's not added already
+    #[must_use]
+    pub fn suite(mut self, suite: Suite) -> Self {
+        if !self.suites.contains(&suite) {
+            self.suites.push(suite);
+        }
+        self
+    }
+
+    /// Remove the suite from the plan
+    #[must_use]
+    pub fn without(mut self, suite: Suite) -> Self {
+        self.suites.retain(|planned| *planned != suite);
+        self
+    }
+
+    /// Return the suites to run, in the order they're run in
+    #[must_use]
+    pub fn suites(&self) -> &[Suite] {
+        &self.suites
+    }
+}
+
+/// The result of running a suite
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuiteResult {
+    /// The suite that was run
+    pub suite: Suite,
+    /// How long running the suite took
+    pub duration: Duration,
+    /// The error or the message of the failed assertion, `None` if the suite
+    /// passed
+    pub failure: Option<String>,
+}
+
+impl SuiteResult {
+    /// Return whether the suite passed
+    #[must_use]
+    pub const fn is_success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// The results of the suites run with [`super::Tester::run`]
+///
+/// Its [`Display`] implementation lists every suite with its result and
+/// duration, so that all failures can be seen at once
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestReport {
+    /// The results of the suites, in the order they were run in
+    pub results: Vec<SuiteResult>,
+}
+
+impl TestReport {
+    /// Return whether every suite passed
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(SuiteResult::is_success)
+    }
+
+    /// Return the results of the suites that passed
+    pub fn passed(&self) -> impl Iterator<Item = &SuiteResult> {
+        self.results.iter().filter(|result| result.is_success())
+    }
+
+    /// Return the results of the suites that failed
+    pub fn failed(&self) -> impl Iterator<Item = &SuiteResult> {
+        self.results.iter().filter(|result| !result.is_success())
+    }
+
+    /// Return the total time running the suites took
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.results.iter().map(|result| result.duration).sum()
+    }
+}
+
+impl Display for TestReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for result in &self.results {
+            match &result.failure {
+                None => writeln!(f, "{:?}: passed in {:?}", result.suite, result.duration)?,
+                Some(failure) => writeln!(
+                    f,
+                    "{:?}: failed in {:?}\n{failure}",
+                    result.suite, result.duration
+                )?,
+            }
+        }
+
+        write!(
+            f,
+            "{} passed, {} failed in {:?}",
+            self.passed().count(),
+            self.failed().count(),
+            self.duration()
+        )
+    }
+}
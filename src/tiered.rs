@@ -0,0 +1,1890 @@
+use core::future::Future;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use futures::{future, TryFutureExt};
+use twilight_model::{
+    channel::{permission_overwrite::PermissionOverwriteType, StageInstance},
+    guild::GuildFeature,
+    id::{
+        marker::{
+            ChannelMarker, EmojiMarker, GenericMarker, GuildMarker, InteractionMarker,
+            MessageMarker, RoleMarker, StageMarker, StickerMarker, UserMarker, WebhookMarker,
+        },
+        Id,
+    },
+    oauth::PartialApplication,
+    user::CurrentUser,
+    util::Timestamp,
+};
+
+pub use error::TieredError;
+
+#[cfg(feature = "autocomplete")]
+use crate::autocomplete::AutocompleteIndex;
+#[cfg(feature = "changelog")]
+use crate::model::CachedChangelogEntry;
+#[cfg(feature = "journal")]
+use crate::model::CachedJournalEntry;
+use crate::{
+    backend::Capabilities,
+    cache::{EntityKind, Error},
+    config::{CacheConfig, MessageKindFilter},
+    counts::EntityCount,
+    hooks::CacheHooks,
+    model::{
+        CachedActivity, CachedAttachment, CachedAttachmentBlob, CachedAttachmentPost,
+        CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji, CachedGuild, CachedInteraction,
+        CachedMember, CachedMessage, CachedMessageRevision, CachedPermissionOverwrite,
+        CachedPresence, CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment,
+        CachedSession, CachedShardState, CachedSticker, GuildPatch, MemberPatch,
+    },
+    pool::ArcPool,
+    queue::UpdateQueue,
+    Backend, Cache,
+};
+
+/// Put into a mod to allow lints
+#[allow(clippy::std_instead_of_core)]
+mod error {
+    use thiserror::Error;
+
+    /// The error returned by one of the tiers of a [`super::TieredCache`]
+    #[derive(Error, Debug)]
+    pub enum TieredError<E1, E2> {
+        /// An error was returned by the first tier
+        #[error("An error was returned by the first tier:\n{0}")]
+        L1(E1),
+        /// An error was returned by the second tier
+        #[error("An error was returned by the second tier:\n{0}")]
+        L2(E2),
+    }
+}
+
+/// A cache made of two caches, usually a fast in-memory one and a persistent
+/// one
+///
+/// Updating it writes to both tiers, getting data from it returns the data in
+/// the first tier if it's cached there, otherwise the data in the second tier,
+/// so the hot path is as fast as the first tier, while the data isn't lost
+/// when the first tier is cleared, for example on restarts
+///
+/// Both tiers are expected to cache the same data, a value is considered not
+/// cached in the first tier if it's `None` or empty, in which case it's
+/// returned from the second tier without being copied to the first tier,
+/// except for the orphaned data used in [`Cache::verify_integrity`], which is
+/// returned from both tiers
+///
+/// The configuration, hooks and update queue of the second tier are used, the
+/// tiers' own [`Cache::update`] methods aren't called, only their [`Backend`]
+/// methods
+///
+/// # Example
+///
+/// ```ignore
+/// use sparkle_cache::{sqlite::SqliteCache, tiered::TieredCache};
+///
+/// let cache = TieredCache::new(
+///     SqliteCache::connect("sqlite::memory:").await?,
+///     SqliteCache::connect("sqlite://cache.db?mode=rwc").await?,
+/// );
+/// cache.update(&event).await?;
+/// let channel = cache.channel(Id::new(123)).await?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct TieredCache<L1, L2> {
+    /// The cache that's read first
+    l1: L1,
+    /// The cache that's read when the data isn't in the first tier
+    l2: L2,
+}
+
+impl<L1, L2> TieredCache<L1, L2> {
+    /// Create a tiered cache from the given tiers
+    #[must_use]
+    pub const fn new(l1: L1, l2: L2) -> Self {
+        Self { l1, l2 }
+    }
+
+    /// Return the first tier, the one that's read first
+    #[must_use]
+    pub const fn l1(&self) -> &L1 {
+        &self.l1
+    }
+
+    /// Return the second tier, the one that's read when the data isn't in the
+    /// first tier
+    #[must_use]
+    pub const fn l2(&self) -> &L2 {
+        &self.l2
+    }
+}
+
+#[async_trait]
+impl<L1: Cache + Send + Sync, L2: Cache + Send + Sync> Backend for TieredCache<L1, L2> {
+    type Error = TieredError<L1::Error, L2::Error>;
+
+    async fn schema_version(&self) -> Result<Option<u32>, Self::Error> {
+        self.l2.schema_version().await.map_err(TieredError::L2)
+    }
+
+    async fn migrate(&self, from: u32, to: u32) -> Result<(), Self::Error> {
+        write_both(self.l1.migrate(from, to), self.l2.migrate(from, to)).await
+    }
+
+    async fn entity_counts(&self) -> Result<HashMap<EntityKind, EntityCount>, Self::Error> {
+        self.l2.entity_counts().await.map_err(TieredError::L2)
+    }
+
+    async fn prepare(&self) -> Result<(), Self::Error> {
+        write_both(self.l1.prepare(), self.l2.prepare()).await
+    }
+
+    async fn health_check(&self) -> Result<(), Self::Error> {
+        write_both(self.l1.health_check(), self.l2.health_check()).await
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        self.l1.capabilities().intersection(self.l2.capabilities())
+    }
+
+    async fn set_current_user(&self, current_user: CurrentUser) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.set_current_user(current_user.clone()),
+            self.l2.set_current_user(current_user),
+        )
+        .await
+    }
+
+    async fn set_current_application(
+        &self,
+        application: PartialApplication,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.set_current_application(application.clone()),
+            self.l2.set_current_application(application),
+        )
+        .await
+    }
+
+    async fn upsert_session(&self, session: CachedSession) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_session(session.clone()),
+            self.l2.upsert_session(session),
+        )
+        .await
+    }
+
+    async fn set_shard_state(&self, state: CachedShardState) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.set_shard_state(state),
+            self.l2.set_shard_state(state),
+        )
+        .await
+    }
+
+    async fn upsert_interaction(&self, interaction: CachedInteraction) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_interaction(interaction.clone()),
+            self.l2.upsert_interaction(interaction),
+        )
+        .await
+    }
+
+    async fn delete_expired_interactions(&self, now: Timestamp) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_expired_interactions(now),
+            self.l2.delete_expired_interactions(now),
+        )
+        .await
+    }
+
+    async fn seen_event(&self, key: String, now: Timestamp) -> Result<bool, Self::Error> {
+        self.l2.seen_event(key, now).await.map_err(TieredError::L2)
+    }
+
+    async fn forget_event(&self, key: String) -> Result<(), Self::Error> {
+        self.l2.forget_event(key).await.map_err(TieredError::L2)
+    }
+
+    async fn delete_seen_events_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        self.l2
+            .delete_seen_events_before(timestamp)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn set_extension(
+        &self,
+        namespace: String,
+        key: String,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error> {
+        self.l2
+            .set_extension(namespace, key, value)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn get_extension(
+        &self,
+        namespace: String,
+        key: String,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.l2
+            .get_extension(namespace, key)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn delete_extension(&self, namespace: String, key: String) -> Result<(), Self::Error> {
+        self.l2
+            .delete_extension(namespace, key)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn advance_update_sequence(
+        &self,
+        key: String,
+        shard_id: u64,
+        sequence: u64,
+    ) -> Result<bool, Self::Error> {
+        self.l2
+            .advance_update_sequence(key, shard_id, sequence)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn delete_shard_update_sequences(&self, shard_id: u64) -> Result<(), Self::Error> {
+        self.l2
+            .delete_shard_update_sequences(shard_id)
+            .await
+            .map_err(TieredError::L2)
+    }
+
+    async fn set_channel_last_activity(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.set_channel_last_activity(channel_id, timestamp),
+            self.l2.set_channel_last_activity(channel_id, timestamp),
+        )
+        .await
+    }
+
+    async fn upsert_channel(&self, channel: CachedChannel) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_channel(channel.clone()),
+            self.l2.upsert_channel(channel),
+        )
+        .await
+    }
+
+    async fn delete_channel(&self, channel_id: Id<ChannelMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_channel(channel_id),
+            self.l2.delete_channel(channel_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_channels(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_channels(guild_id),
+            self.l2.delete_guild_channels(guild_id),
+        )
+        .await
+    }
+
+    async fn upsert_permission_overwrite(
+        &self,
+        permission_overwrite: CachedPermissionOverwrite,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_permission_overwrite(permission_overwrite),
+            self.l2.upsert_permission_overwrite(permission_overwrite),
+        )
+        .await
+    }
+
+    async fn delete_channel_permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_channel_permission_overwrites(channel_id),
+            self.l2.delete_channel_permission_overwrites(channel_id),
+        )
+        .await
+    }
+
+    async fn select_overwrites_by_target(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        target_id: Id<GenericMarker>,
+        kind: PermissionOverwriteType,
+    ) -> Result<Option<CachedPermissionOverwrite>, Self::Error> {
+        first_some(
+            self.l1
+                .select_overwrites_by_target(channel_id, target_id, kind)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_overwrites_by_target(channel_id, target_id, kind)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn upsert_private_channel(
+        &self,
+        private_channel: CachedPrivateChannel,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_private_channel(private_channel),
+            self.l2.upsert_private_channel(private_channel),
+        )
+        .await
+    }
+
+    async fn delete_private_channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_private_channel(channel_id),
+            self.l2.delete_private_channel(channel_id),
+        )
+        .await
+    }
+
+    async fn upsert_message(&self, message: CachedMessage) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_message(message.clone()),
+            self.l2.upsert_message(message),
+        )
+        .await
+    }
+
+    async fn upsert_messages(&self, messages: Vec<CachedMessage>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_messages(messages.clone()),
+            self.l2.upsert_messages(messages),
+        )
+        .await
+    }
+
+    async fn delete_message(&self, message_id: Id<MessageMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message(message_id),
+            self.l2.delete_message(message_id),
+        )
+        .await
+    }
+
+    async fn delete_messages(&self, message_ids: &[Id<MessageMarker>]) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_messages(message_ids),
+            self.l2.delete_messages(message_ids),
+        )
+        .await
+    }
+
+    async fn delete_messages_data(
+        &self,
+        message_ids: &[Id<MessageMarker>],
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_messages_data(message_ids),
+            self.l2.delete_messages_data(message_ids),
+        )
+        .await
+    }
+
+    async fn delete_messages_before(&self, timestamp: Timestamp) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_messages_before(timestamp),
+            self.l2.delete_messages_before(timestamp),
+        )
+        .await
+    }
+
+    async fn delete_guild_messages_before(
+        &self,
+        guild_id: Id<GuildMarker>,
+        timestamp: Timestamp,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_messages_before(guild_id, timestamp),
+            self.l2.delete_guild_messages_before(guild_id, timestamp),
+        )
+        .await
+    }
+
+    async fn select_guild_message_authors(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_guild_message_authors(guild_id, since)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_guild_message_authors(guild_id, since)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_user_last_message(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedMessage>, Self::Error> {
+        first_some(
+            self.l1
+                .select_user_last_message(channel_id, user_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_user_last_message(channel_id, user_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_channel_messages_range(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        after: Option<Timestamp>,
+        before: Option<Timestamp>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_channel_messages_range(channel_id, after, before, limit)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_channel_messages_range(channel_id, after, before, limit)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_filtered_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        filter: MessageKindFilter,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_filtered_channel_messages(channel_id, filter, limit)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_filtered_channel_messages(channel_id, filter, limit)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn delete_orphaned_message_data(&self) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_orphaned_message_data(),
+            self.l2.delete_orphaned_message_data(),
+        )
+        .await
+    }
+
+    async fn insert_message_revision(
+        &self,
+        revision: CachedMessageRevision,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.insert_message_revision(revision.clone()),
+            self.l2.insert_message_revision(revision),
+        )
+        .await
+    }
+
+    async fn delete_message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message_revisions(message_id),
+            self.l2.delete_message_revisions(message_id),
+        )
+        .await
+    }
+
+    async fn upsert_embed(&self, embed: CachedEmbed) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_embed(embed.clone()),
+            self.l2.upsert_embed(embed),
+        )
+        .await
+    }
+
+    async fn delete_embed(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_embed(embed_id),
+            self.l2.delete_embed(embed_id),
+        )
+        .await
+    }
+
+    async fn delete_embeds_bulk(&self, embed_ids: &[Id<GenericMarker>]) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_embeds_bulk(embed_ids),
+            self.l2.delete_embeds_bulk(embed_ids),
+        )
+        .await
+    }
+
+    async fn upsert_embed_field(&self, embed_field: CachedEmbedField) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_embed_field(embed_field.clone()),
+            self.l2.upsert_embed_field(embed_field),
+        )
+        .await
+    }
+
+    async fn delete_embed_fields(&self, embed_id: Id<GenericMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_embed_fields(embed_id),
+            self.l2.delete_embed_fields(embed_id),
+        )
+        .await
+    }
+
+    async fn delete_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_embed_fields_bulk(embed_ids),
+            self.l2.delete_embed_fields_bulk(embed_ids),
+        )
+        .await
+    }
+
+    async fn select_message_embeds(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedEmbed>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_message_embeds(message_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_message_embeds(message_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_embed_fields(
+        &self,
+        embed_id: Id<GenericMarker>,
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_embed_fields(embed_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_embed_fields(embed_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_embed_fields_bulk(
+        &self,
+        embed_ids: &[Id<GenericMarker>],
+    ) -> Result<Vec<CachedEmbedField>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_embed_fields_bulk(embed_ids)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_embed_fields_bulk(embed_ids)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_orphaned_embed_fields(&self) -> Result<Vec<Id<GenericMarker>>, Self::Error> {
+        union(
+            self.l1
+                .select_orphaned_embed_fields()
+                .map_err(TieredError::L1),
+            self.l2
+                .select_orphaned_embed_fields()
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_orphaned_embeds(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        union(
+            self.l1.select_orphaned_embeds().map_err(TieredError::L1),
+            self.l2.select_orphaned_embeds().map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_orphaned_attachments(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        union(
+            self.l1
+                .select_orphaned_attachments()
+                .map_err(TieredError::L1),
+            self.l2
+                .select_orphaned_attachments()
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_orphaned_reactions(&self) -> Result<Vec<Id<MessageMarker>>, Self::Error> {
+        union(
+            self.l1.select_orphaned_reactions().map_err(TieredError::L1),
+            self.l2.select_orphaned_reactions().map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn upsert_attachment(&self, attachment: CachedAttachment) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_attachment(attachment.clone()),
+            self.l2.upsert_attachment(attachment),
+        )
+        .await
+    }
+
+    async fn upsert_attachment_blob(&self, blob: CachedAttachmentBlob) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_attachment_blob(blob.clone()),
+            self.l2.upsert_attachment_blob(blob),
+        )
+        .await
+    }
+
+    async fn upsert_attachment_post(&self, post: CachedAttachmentPost) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_attachment_post(post.clone()),
+            self.l2.upsert_attachment_post(post),
+        )
+        .await
+    }
+
+    async fn delete_message_attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message_attachments(message_id),
+            self.l2.delete_message_attachments(message_id),
+        )
+        .await
+    }
+
+    async fn upsert_reaction(&self, reaction: CachedReaction) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_reaction(reaction.clone()),
+            self.l2.upsert_reaction(reaction),
+        )
+        .await
+    }
+
+    async fn upsert_reactions(&self, reactions: Vec<CachedReaction>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_reactions(reactions.clone()),
+            self.l2.upsert_reactions(reactions),
+        )
+        .await
+    }
+
+    async fn delete_reaction(
+        &self,
+        message_id: Id<MessageMarker>,
+        user_id: Id<UserMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_reaction(message_id, user_id, emoji.clone()),
+            self.l2.delete_reaction(message_id, user_id, emoji),
+        )
+        .await
+    }
+
+    async fn delete_message_reactions_by_emoji(
+        &self,
+        message_id: Id<MessageMarker>,
+        emoji: String,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1
+                .delete_message_reactions_by_emoji(message_id, emoji.clone()),
+            self.l2.delete_message_reactions_by_emoji(message_id, emoji),
+        )
+        .await
+    }
+
+    async fn delete_message_reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message_reactions(message_id),
+            self.l2.delete_message_reactions(message_id),
+        )
+        .await
+    }
+
+    async fn upsert_member(&self, member: CachedMember) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_member(member.clone()),
+            self.l2.upsert_member(member),
+        )
+        .await
+    }
+
+    async fn upsert_members(&self, members: Vec<CachedMember>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_members(members.clone()),
+            self.l2.upsert_members(members),
+        )
+        .await
+    }
+
+    async fn patch_member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        patch: MemberPatch,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.patch_member(guild_id, user_id, patch.clone()),
+            self.l2.patch_member(guild_id, user_id, patch),
+        )
+        .await
+    }
+
+    async fn delete_member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_member(user_id, guild_id),
+            self.l2.delete_member(user_id, guild_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_members(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_members(guild_id),
+            self.l2.delete_guild_members(guild_id),
+        )
+        .await
+    }
+
+    async fn select_mutual_guilds(
+        &self,
+        user_a: Id<UserMarker>,
+        user_b: Id<UserMarker>,
+    ) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_mutual_guilds(user_a, user_b)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_mutual_guilds(user_a, user_b)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_members_by_prefix(
+        &self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+        limit: u16,
+    ) -> Result<Vec<CachedMember>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_members_by_prefix(guild_id, prefix, limit)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_members_by_prefix(guild_id, prefix, limit)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn upsert_presence(&self, presence: CachedPresence) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_presence(presence),
+            self.l2.upsert_presence(presence),
+        )
+        .await
+    }
+
+    async fn upsert_presences(&self, presences: Vec<CachedPresence>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_presences(presences.clone()),
+            self.l2.upsert_presences(presences),
+        )
+        .await
+    }
+
+    async fn delete_presence(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_presence(guild_id, user_id),
+            self.l2.delete_presence(guild_id, user_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_presences(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_presences(guild_id),
+            self.l2.delete_guild_presences(guild_id),
+        )
+        .await
+    }
+
+    async fn delete_memberless_presences(&self) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_memberless_presences(),
+            self.l2.delete_memberless_presences(),
+        )
+        .await
+    }
+
+    async fn set_expiry(
+        &self,
+        kind: EntityKind,
+        entity_id: u64,
+        guild_id: Option<Id<GuildMarker>>,
+        expires_at: Timestamp,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.set_expiry(kind, entity_id, guild_id, expires_at),
+            self.l2.set_expiry(kind, entity_id, guild_id, expires_at),
+        )
+        .await
+    }
+
+    async fn delete_expired(&self, now: Timestamp) -> Result<(), Self::Error> {
+        write_both(self.l1.delete_expired(now), self.l2.delete_expired(now)).await
+    }
+
+    async fn upsert_activity(&self, activity: CachedActivity) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_activity(activity.clone()),
+            self.l2.upsert_activity(activity),
+        )
+        .await
+    }
+
+    async fn upsert_activities(&self, activities: Vec<CachedActivity>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_activities(activities.clone()),
+            self.l2.upsert_activities(activities),
+        )
+        .await
+    }
+
+    async fn delete_user_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_user_activities(guild_id, user_id),
+            self.l2.delete_user_activities(guild_id, user_id),
+        )
+        .await
+    }
+
+    async fn upsert_guild(&self, guild: CachedGuild) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_guild(guild.clone()),
+            self.l2.upsert_guild(guild),
+        )
+        .await
+    }
+
+    async fn patch_guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+        patch: GuildPatch,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.patch_guild(guild_id, patch.clone()),
+            self.l2.patch_guild(guild_id, patch),
+        )
+        .await
+    }
+
+    async fn select_guilds_by_feature(
+        &self,
+        feature: GuildFeature,
+    ) -> Result<Vec<CachedGuild>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_guilds_by_feature(feature.clone())
+                .map_err(TieredError::L1),
+            self.l2
+                .select_guilds_by_feature(feature)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn delete_guild(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild(guild_id),
+            self.l2.delete_guild(guild_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_cascade(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_cascade(guild_id),
+            self.l2.delete_guild_cascade(guild_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_remnants(&self) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_remnants(),
+            self.l2.delete_guild_remnants(),
+        )
+        .await
+    }
+
+    async fn upsert_role(&self, role: CachedRole) -> Result<(), Self::Error> {
+        write_both(self.l1.upsert_role(role.clone()), self.l2.upsert_role(role)).await
+    }
+
+    async fn delete_role(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        write_both(self.l1.delete_role(role_id), self.l2.delete_role(role_id)).await
+    }
+
+    async fn select_everyone_role(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedRole>, Self::Error> {
+        first_some(
+            self.l1
+                .select_everyone_role(guild_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_everyone_role(guild_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_orphaned_roles(&self) -> Result<Vec<Id<GuildMarker>>, Self::Error> {
+        union(
+            self.l1.select_orphaned_roles().map_err(TieredError::L1),
+            self.l2.select_orphaned_roles().map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn delete_guild_roles(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_roles(guild_id),
+            self.l2.delete_guild_roles(guild_id),
+        )
+        .await
+    }
+
+    async fn upsert_role_assignment(
+        &self,
+        assignment: CachedRoleAssignment,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_role_assignment(assignment),
+            self.l2.upsert_role_assignment(assignment),
+        )
+        .await
+    }
+
+    async fn upsert_role_assignments(
+        &self,
+        assignments: Vec<CachedRoleAssignment>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_role_assignments(assignments.clone()),
+            self.l2.upsert_role_assignments(assignments),
+        )
+        .await
+    }
+
+    async fn delete_role_assignments(&self, role_id: Id<RoleMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_role_assignments(role_id),
+            self.l2.delete_role_assignments(role_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_role_assignments(guild_id),
+            self.l2.delete_guild_role_assignments(guild_id),
+        )
+        .await
+    }
+
+    async fn delete_member_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_member_role_assignments(guild_id, user_id),
+            self.l2.delete_member_role_assignments(guild_id, user_id),
+        )
+        .await
+    }
+
+    async fn select_role_members(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_role_members(role_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_role_members(role_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn select_guild_role_assignments(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRoleAssignment>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_guild_role_assignments(guild_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_guild_role_assignments(guild_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    async fn upsert_emoji(&self, emoji: CachedEmoji) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_emoji(emoji.clone()),
+            self.l2.upsert_emoji(emoji),
+        )
+        .await
+    }
+
+    async fn delete_emoji(&self, emoji_id: Id<EmojiMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_emoji(emoji_id),
+            self.l2.delete_emoji(emoji_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_emojis(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_emojis(guild_id),
+            self.l2.delete_guild_emojis(guild_id),
+        )
+        .await
+    }
+
+    async fn upsert_sticker(&self, sticker: CachedSticker) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_sticker(sticker.clone()),
+            self.l2.upsert_sticker(sticker),
+        )
+        .await
+    }
+
+    async fn delete_message_stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message_stickers(message_id),
+            self.l2.delete_message_stickers(message_id),
+        )
+        .await
+    }
+
+    async fn delete_sticker(&self, sticker_id: Id<StickerMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_sticker(sticker_id),
+            self.l2.delete_sticker(sticker_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_stickers(&self, guild_id: Id<GuildMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_stickers(guild_id),
+            self.l2.delete_guild_stickers(guild_id),
+        )
+        .await
+    }
+
+    async fn upsert_stage_instance(&self, stage: StageInstance) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.upsert_stage_instance(stage.clone()),
+            self.l2.upsert_stage_instance(stage),
+        )
+        .await
+    }
+
+    async fn delete_stage_instance(&self, stage_id: Id<StageMarker>) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_stage_instance(stage_id),
+            self.l2.delete_stage_instance(stage_id),
+        )
+        .await
+    }
+
+    async fn delete_guild_stage_instances(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_guild_stage_instances(guild_id),
+            self.l2.delete_guild_stage_instances(guild_id),
+        )
+        .await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_emoji_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        emoji: String,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.increment_emoji_use(guild_id, emoji.clone(), day),
+            self.l2.increment_emoji_use(guild_id, emoji, day),
+        )
+        .await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn increment_sticker_use(
+        &self,
+        guild_id: Id<GuildMarker>,
+        sticker_id: Id<StickerMarker>,
+        day: u64,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.increment_sticker_use(guild_id, sticker_id, day),
+            self.l2.increment_sticker_use(guild_id, sticker_id, day),
+        )
+        .await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_emoji_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(String, u64)>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_emoji_usage(guild_id, since_day)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_emoji_usage(guild_id, since_day)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    #[cfg(feature = "stats")]
+    async fn select_sticker_usage(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since_day: u64,
+    ) -> Result<Vec<(Id<StickerMarker>, u64)>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_sticker_usage(guild_id, since_day)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_sticker_usage(guild_id, since_day)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    #[cfg(feature = "journal")]
+    async fn append_journal(&self, entry: CachedJournalEntry) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.append_journal(entry.clone()),
+            self.l2.append_journal(entry),
+        )
+        .await
+    }
+
+    #[cfg(feature = "journal")]
+    async fn select_journal(
+        &self,
+        start: Timestamp,
+        end: Timestamp,
+    ) -> Result<Vec<CachedJournalEntry>, Self::Error> {
+        first_non_empty(
+            self.l1.select_journal(start, end).map_err(TieredError::L1),
+            self.l2.select_journal(start, end).map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn append_changelog(&self, entry: CachedChangelogEntry) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.append_changelog(entry.clone()),
+            self.l2.append_changelog(entry),
+        )
+        .await
+    }
+
+    #[cfg(feature = "changelog")]
+    async fn select_changelog(
+        &self,
+        kind: EntityKind,
+        entity_id: Id<GenericMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedChangelogEntry>, Self::Error> {
+        first_non_empty(
+            self.l1
+                .select_changelog(kind, entity_id, limit)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_changelog(kind, entity_id, limit)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn upsert_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+        embeds: serde_json::Value,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1
+                .upsert_message_embeds_json(message_id, embeds.clone()),
+            self.l2.upsert_message_embeds_json(message_id, embeds),
+        )
+        .await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn select_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<serde_json::Value>, Self::Error> {
+        first_some(
+            self.l1
+                .select_message_embeds_json(message_id)
+                .map_err(TieredError::L1),
+            self.l2
+                .select_message_embeds_json(message_id)
+                .map_err(TieredError::L2),
+        )
+        .await
+    }
+
+    #[cfg(feature = "embeds-json")]
+    async fn delete_message_embeds_json(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<(), Self::Error> {
+        write_both(
+            self.l1.delete_message_embeds_json(message_id),
+            self.l2.delete_message_embeds_json(message_id),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<L1: Cache + Send + Sync, L2: Cache + Send + Sync> Cache for TieredCache<L1, L2> {
+    fn config(&self) -> &CacheConfig {
+        self.l2.config()
+    }
+
+    fn hooks(&self) -> Option<&dyn CacheHooks> {
+        self.l2.hooks()
+    }
+
+    fn update_queue(&self) -> Option<&UpdateQueue> {
+        self.l2.update_queue()
+    }
+
+    fn arc_pool(&self) -> Option<&ArcPool> {
+        self.l2.arc_pool()
+    }
+
+    #[cfg(feature = "autocomplete")]
+    fn autocomplete_index(&self) -> Option<&AutocompleteIndex> {
+        self.l2.autocomplete_index()
+    }
+
+    async fn current_user(&self) -> Result<CurrentUser, Error<Self::Error>> {
+        match self.l1.current_user().await {
+            Err(Error::CurrentUserMissing) => self.l2.current_user().await.map_err(l2_error),
+            result => result.map_err(l1_error),
+        }
+    }
+
+    async fn current_application(&self) -> Result<PartialApplication, Error<Self::Error>> {
+        match self.l1.current_application().await {
+            Err(Error::CurrentApplicationMissing) => {
+                self.l2.current_application().await.map_err(l2_error)
+            }
+            result => result.map_err(l1_error),
+        }
+    }
+
+    async fn session_info(&self) -> Result<Vec<CachedSession>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.session_info().map_err(l1_error),
+            self.l2.session_info().map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn shard_state(
+        &self,
+        shard_id: u64,
+    ) -> Result<Option<CachedShardState>, Error<Self::Error>> {
+        first_some(
+            self.l1.shard_state(shard_id).map_err(l1_error),
+            self.l2.shard_state(shard_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn shard_states(&self) -> Result<Vec<CachedShardState>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.shard_states().map_err(l1_error),
+            self.l2.shard_states().map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn interaction(
+        &self,
+        interaction_id: Id<InteractionMarker>,
+    ) -> Result<Option<CachedInteraction>, Error<Self::Error>> {
+        first_some(
+            self.l1.interaction(interaction_id).map_err(l1_error),
+            self.l2.interaction(interaction_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        first_some(
+            self.l1.channel(channel_id).map_err(l1_error),
+            self.l2.channel(channel_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn permission_overwrites(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedPermissionOverwrite>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.permission_overwrites(channel_id).map_err(l1_error),
+            self.l2.permission_overwrites(channel_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn private_channel(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedChannel>, Error<Self::Error>> {
+        first_some(
+            self.l1.private_channel(user_id).map_err(l1_error),
+            self.l2.private_channel(user_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn private_channel_users(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<Id<UserMarker>>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.private_channel_users(channel_id).map_err(l1_error),
+            self.l2.private_channel_users(channel_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_channels(guild_id).map_err(l1_error),
+            self.l2.guild_channels(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn inactive_channels(
+        &self,
+        guild_id: Id<GuildMarker>,
+        since: Timestamp,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.inactive_channels(guild_id, since).map_err(l1_error),
+            self.l2.inactive_channels(guild_id, since).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn archived_threads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.archived_threads(guild_id).map_err(l1_error),
+            self.l2.archived_threads(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn active_threads(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> Result<Vec<CachedChannel>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.active_threads(channel_id).map_err(l1_error),
+            self.l2.active_threads(channel_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn message(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Option<CachedMessage>, Error<Self::Error>> {
+        first_some(
+            self.l1.message(message_id).map_err(l1_error),
+            self.l2.message(message_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn message_revisions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedMessageRevision>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.message_revisions(message_id).map_err(l1_error),
+            self.l2.message_revisions(message_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn attachments(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedAttachment>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.attachments(message_id).map_err(l1_error),
+            self.l2.attachments(message_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn reactions(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedReaction>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.reactions(message_id).map_err(l1_error),
+            self.l2.reactions(message_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn stickers(
+        &self,
+        message_id: Id<MessageMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.stickers(message_id).map_err(l1_error),
+            self.l2.stickers(message_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .channel_messages(channel_id, limit)
+                .map_err(l1_error),
+            self.l2
+                .channel_messages(channel_id, limit)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn channel_webhook_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .channel_webhook_messages(channel_id, limit)
+                .map_err(l1_error),
+            self.l2
+                .channel_webhook_messages(channel_id, limit)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn attachment_posters(
+        &self,
+        file_hash: &str,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.attachment_posters(file_hash).map_err(l1_error),
+            self.l2.attachment_posters(file_hash).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn messages_by_webhook(
+        &self,
+        webhook_id: Id<WebhookMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .messages_by_webhook(webhook_id, limit)
+                .map_err(l1_error),
+            self.l2
+                .messages_by_webhook(webhook_id, limit)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn messages_with_sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .messages_with_sticker(sticker_id, limit)
+                .map_err(l1_error),
+            self.l2
+                .messages_with_sticker(sticker_id, limit)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn messages_with_emoji_reaction(
+        &self,
+        emoji: String,
+        guild_id: Id<GuildMarker>,
+        limit: u16,
+    ) -> Result<Vec<CachedMessage>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .messages_with_emoji_reaction(emoji.clone(), guild_id, limit)
+                .map_err(l1_error),
+            self.l2
+                .messages_with_emoji_reaction(emoji, guild_id, limit)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn member(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedMember>, Error<Self::Error>> {
+        first_some(
+            self.l1.member(user_id, guild_id).map_err(l1_error),
+            self.l2.member(user_id, guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn member_roles(
+        &self,
+        user_id: Id<UserMarker>,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.member_roles(user_id, guild_id).map_err(l1_error),
+            self.l2.member_roles(user_id, guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn presence(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Option<CachedPresence>, Error<Self::Error>> {
+        first_some(
+            self.l1.presence(user_id).map_err(l1_error),
+            self.l2.presence(user_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn member_activities(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.member_activities(user_id).map_err(l1_error),
+            self.l2.member_activities(user_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_member_activities(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<CachedActivity>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1
+                .guild_member_activities(guild_id, user_id)
+                .map_err(l1_error),
+            self.l2
+                .guild_member_activities(guild_id, user_id)
+                .map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_ids(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_ids().map_err(l1_error),
+            self.l2.guild_ids().map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn unavailable_guilds(&self) -> Result<Vec<Id<GuildMarker>>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.unavailable_guilds().map_err(l1_error),
+            self.l2.unavailable_guilds().map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_members(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedMember>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_members(guild_id).map_err(l1_error),
+            self.l2.guild_members(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Option<CachedGuild>, Error<Self::Error>> {
+        first_some(
+            self.l1.guild(guild_id).map_err(l1_error),
+            self.l2.guild(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn role(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> Result<Option<CachedRole>, Error<Self::Error>> {
+        first_some(
+            self.l1.role(role_id).map_err(l1_error),
+            self.l2.role(role_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_roles(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedRole>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_roles(guild_id).map_err(l1_error),
+            self.l2.guild_roles(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> Result<Option<CachedEmoji>, Error<Self::Error>> {
+        first_some(
+            self.l1.emoji(emoji_id).map_err(l1_error),
+            self.l2.emoji(emoji_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_emojis(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedEmoji>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_emojis(guild_id).map_err(l1_error),
+            self.l2.guild_emojis(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> Result<Option<CachedSticker>, Error<Self::Error>> {
+        first_some(
+            self.l1.sticker(sticker_id).map_err(l1_error),
+            self.l2.sticker(sticker_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn guild_stickers(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> Result<Vec<CachedSticker>, Error<Self::Error>> {
+        first_non_empty(
+            self.l1.guild_stickers(guild_id).map_err(l1_error),
+            self.l2.guild_stickers(guild_id).map_err(l2_error),
+        )
+        .await
+    }
+
+    async fn stage_instance(
+        &self,
+        stage_id: Id<StageMarker>,
+    ) -> Result<Option<StageInstance>, Error<Self::Error>> {
+        first_some(
+            self.l1.stage_instance(stage_id).map_err(l1_error),
+            self.l2.stage_instance(stage_id).map_err(l2_error),
+        )
+        .await
+    }
+}
+
+/// Runs the writes to both tiers concurrently, returning the first error
+async fn write_both<E1, E2>(
+    l1: impl Future<Output = Result<(), E1>> + Send,
+    l2: impl Future<Output = Result<(), E2>> + Send,
+) -> Result<(), TieredError<E1, E2>> {
+    future::try_join(l1.map_err(TieredError::L1), l2.map_err(TieredError::L2)).await?;
+
+    Ok(())
+}
+
+/// Returns the value of the first tier if it's `Some`, otherwise the value of
+/// the second tier, which is only awaited in that case
+async fn first_some<T, E>(
+    l1: impl Future<Output = Result<Option<T>, E>> + Send,
+    l2: impl Future<Output = Result<Option<T>, E>> + Send,
+) -> Result<Option<T>, E> {
+    match l1.await? {
+        Some(value) => Ok(Some(value)),
+        None => l2.await,
+    }
+}
+
+/// Returns the values of the first tier if there are any, otherwise the values
+/// of the second tier, which is only awaited in that case
+async fn first_non_empty<T, E>(
+    l1: impl Future<Output = Result<Vec<T>, E>> + Send,
+    l2: impl Future<Output = Result<Vec<T>, E>> + Send,
+) -> Result<Vec<T>, E> {
+    let values = l1.await?;
+    if values.is_empty() {
+        l2.await
+    } else {
+        Ok(values)
+    }
+}
+
+/// Returns the values of both tiers without duplicates, for the queries whose
+/// results might be in either tier, such as the orphaned data, which is
+/// orphaned in only one tier if the tiers went out of sync
+async fn union<T: Ord, E1, E2>(
+    l1: impl Future<Output = Result<Vec<T>, TieredError<E1, E2>>> + Send,
+    l2: impl Future<Output = Result<Vec<T>, TieredError<E1, E2>>> + Send,
+) -> Result<Vec<T>, TieredError<E1, E2>> {
+    let (mut values, l2_values) = future::try_join(l1, l2).await?;
+    values.extend(l2_values);
+    values.sort_unstable();
+    values.dedup();
+
+    Ok(values)
+}
+
+/// Wraps the backend error of the first tier in [`TieredError::L1`]
+fn l1_error<E1: Send, E2: Send>(err: Error<E1>) -> Error<TieredError<E1, E2>> {
+    err.map_backend(TieredError::L1)
+}
+
+/// Wraps the backend error of the second tier in [`TieredError::L2`]
+fn l2_error<E1: Send, E2: Send>(err: Error<E2>) -> Error<TieredError<E1, E2>> {
+    err.map_backend(TieredError::L2)
+}
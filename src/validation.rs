@@ -0,0 +1,452 @@
+use core::fmt::{self, Debug, Display, Formatter};
+
+use twilight_model::{
+    channel::{
+        message::{sticker::StickerFormatType, MessageType},
+        permission_overwrite::PermissionOverwriteType,
+        stage_instance::PrivacyLevel,
+        ChannelType, StageInstance,
+    },
+    gateway::presence::{ActivityType, Status},
+    guild::{
+        DefaultMessageNotificationLevel, ExplicitContentFilter, GuildFeature, MfaLevel, NSFWLevel,
+        Permissions, PremiumTier, SystemChannelFlags, VerificationLevel,
+    },
+    id::Id,
+    util::Timestamp,
+};
+
+use crate::model::{
+    CachedActivity, CachedAttachment, CachedChannel, CachedEmbed, CachedEmbedField, CachedEmoji,
+    CachedGuild, CachedMember, CachedMessage, CachedPermissionOverwrite, CachedPresence,
+    CachedPrivateChannel, CachedReaction, CachedRole, CachedRoleAssignment, CachedSticker,
+};
+
+/// The ID of every synthetic entity, Discord never uses it, so it doesn't
+/// collide with the cached data
+const SYNTHETIC_ID: u64 = 1;
+
+/// The timestamp of the synthetic entities, in whole seconds so that backends
+/// storing timestamps in seconds don't report a mismatch
+const SYNTHETIC_TIMESTAMP_SECS: i64 = 1_420_070_400;
+
+/// A method of the backend that didn't work as expected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationFailure {
+    /// The name of the method, such as `upsert_channel` or `channel`
+    pub method: &'static str,
+    /// The error the method returned or how the data it returned was
+    /// different from the written data
+    pub error: String,
+}
+
+/// The methods of the backend that didn't work as expected, returned by
+/// [`crate::Cache::validate_backend`]
+///
+/// Its [`Display`] implementation lists every failure, so that it can be
+/// logged at startup
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The failures, in the order the methods were called in
+    pub failures: Vec<ValidationFailure>,
+}
+
+impl ValidationReport {
+    /// Return whether every method worked as expected
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Add a failure if the method returned an error
+    pub(crate) fn record<E: Display>(&mut self, method: &'static str, result: Result<(), E>) {
+        if let Err(err) = result {
+            self.failures.push(ValidationFailure {
+                method,
+                error: err.to_string(),
+            });
+        }
+    }
+
+    /// Add a failure if the method returned an error or data different from
+    /// the expected data
+    pub(crate) fn check<T: Debug + PartialEq, E: Display>(
+        &mut self,
+        method: &'static str,
+        result: Result<T, E>,
+        expected: &T,
+    ) {
+        match result {
+            Ok(returned) if returned == *expected => {}
+            Ok(returned) => self.failures.push(ValidationFailure {
+                method,
+                error: format!("returned {returned:?} instead of {expected:?}"),
+            }),
+            Err(err) => self.failures.push(ValidationFailure {
+                method,
+                error: err.to_string(),
+            }),
+        }
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for failure in &self.failures {
+            writeln!(f, "{}: {}", failure.method, failure.error)?;
+        }
+
+        write!(f, "{} methods failed", self.failures.len())
+    }
+}
+
+/// An entity of every cached type, written to the backend and read back in
+/// [`crate::Cache::validate_backend`]
+///
+/// Optional fields are set where possible, so that a field mapped to the wrong
+/// column is caught
+#[derive(Clone, Debug)]
+pub(crate) struct Synthetic {
+    /// The synthetic guild, the other entities are in it
+    pub(crate) guild: CachedGuild,
+    /// The synthetic text channel
+    pub(crate) channel: CachedChannel,
+    /// The synthetic permission overwrite in the channel
+    pub(crate) permission_overwrite: CachedPermissionOverwrite,
+    /// The synthetic private channel with the member
+    pub(crate) private_channel: CachedPrivateChannel,
+    /// The synthetic message in the channel
+    pub(crate) message: CachedMessage,
+    /// The synthetic embed in the message
+    pub(crate) embed: CachedEmbed,
+    /// The synthetic field in the embed
+    pub(crate) embed_field: CachedEmbedField,
+    /// The synthetic attachment in the message
+    pub(crate) attachment: CachedAttachment,
+    /// The synthetic reaction to the message
+    pub(crate) reaction: CachedReaction,
+    /// The synthetic member of the guild
+    pub(crate) member: CachedMember,
+    /// The synthetic presence of the member
+    pub(crate) presence: CachedPresence,
+    /// The synthetic activity of the member
+    pub(crate) activity: CachedActivity,
+    /// The synthetic role in the guild
+    pub(crate) role: CachedRole,
+    /// The assignment of the synthetic role to the member
+    pub(crate) role_assignment: CachedRoleAssignment,
+    /// The synthetic emoji in the guild
+    pub(crate) emoji: CachedEmoji,
+    /// The synthetic sticker in the guild
+    pub(crate) sticker: CachedSticker,
+    /// The synthetic stage instance in the guild
+    pub(crate) stage_instance: StageInstance,
+}
+
+impl Synthetic {
+    /// Create the synthetic entities, returns `None` if the timestamp couldn't
+    /// be created
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn new() -> Option<Self> {
+        let timestamp = Timestamp::from_secs(SYNTHETIC_TIMESTAMP_SECS).ok()?;
+        let guild_id = Id::new(SYNTHETIC_ID);
+        let channel_id = Id::new(SYNTHETIC_ID);
+        let message_id = Id::new(SYNTHETIC_ID);
+        let user_id = Id::new(SYNTHETIC_ID);
+        let role_id = Id::new(SYNTHETIC_ID);
+
+        Some(Self {
+            guild: CachedGuild {
+                afk_channel_id: Some(channel_id),
+                afk_timeout: 300,
+                application_id: None,
+                banner: None,
+                default_message_notifications: DefaultMessageNotificationLevel::All,
+                description: Some("synthetic description".to_owned()),
+                discovery_splash: None,
+                explicit_content_filter: ExplicitContentFilter::None,
+                features: vec![GuildFeature::Community],
+                icon: None,
+                id: guild_id,
+                joined_at: Some(timestamp),
+                large: false,
+                max_members: Some(500_000),
+                max_presences: None,
+                max_stage_video_channel_users: Some(50),
+                max_video_channel_users: Some(25),
+                mfa_level: MfaLevel::None,
+                name: "synthetic guild".to_owned(),
+                nsfw_level: NSFWLevel::Default,
+                owner_id: user_id,
+                owner: Some(true),
+                permissions: Some(Permissions::ADMINISTRATOR),
+                preferred_locale: "en-US".to_owned(),
+                premium_progress_bar_enabled: true,
+                premium_subscription_count: Some(2),
+                premium_tier: PremiumTier::None,
+                public_updates_channel_id: Some(channel_id),
+                rules_channel_id: Some(channel_id),
+                safety_alerts_channel_id: Some(channel_id),
+                splash: None,
+                system_channel_flags: SystemChannelFlags::empty(),
+                system_channel_id: Some(channel_id),
+                unavailable: false,
+                vanity_url_code: Some("synthetic".to_owned()),
+                verification_level: VerificationLevel::None,
+                widget_channel_id: Some(channel_id),
+                widget_enabled: Some(false),
+                members_partial: true,
+            },
+            channel: CachedChannel {
+                application_id: None,
+                bitrate: None,
+                default_auto_archive_duration: None,
+                guild_id: Some(guild_id),
+                icon: None,
+                id: channel_id,
+                invitable: None,
+                kind: ChannelType::GuildText,
+                name: Some("synthetic-channel".to_owned()),
+                nsfw: Some(false),
+                owner_id: None,
+                parent_id: None,
+                position: Some(3),
+                rate_limit_per_user: Some(5),
+                rtc_region: None,
+                thread_archived: None,
+                thread_auto_archive_duration: None,
+                thread_archive_timestamp: None,
+                thread_create_timestamp: None,
+                thread_invitable: None,
+                thread_locked: None,
+                topic: Some("synthetic topic".to_owned()),
+                user_limit: None,
+                video_quality_mode: None,
+                partial: false,
+            },
+            permission_overwrite: CachedPermissionOverwrite {
+                channel_id,
+                allow: Permissions::SEND_MESSAGES,
+                deny: Permissions::ADD_REACTIONS,
+                id: user_id.cast(),
+                kind: PermissionOverwriteType::Member,
+            },
+            private_channel: CachedPrivateChannel {
+                channel_id,
+                user_id,
+            },
+            message: CachedMessage {
+                activity_type: None,
+                activity_party_id: None,
+                application_cover_image: None,
+                application_description: None,
+                application_icon: None,
+                application_id: None,
+                application_name: None,
+                interaction_application_id: None,
+                author: user_id,
+                author_bot: false,
+                author_name: Some("synthetic user".to_owned()),
+                author_discriminator: Some(1),
+                author_avatar: None,
+                channel_id,
+                content: "synthetic content".to_owned(),
+                edited_timestamp: Some(timestamp),
+                flags: None,
+                guild_id: Some(guild_id),
+                id: message_id,
+                kind: MessageType::Regular,
+                mention_everyone: false,
+                pinned: true,
+                reference_channel_id: None,
+                reference_guild_id: None,
+                reference_message_id: None,
+                reference_fail_if_not_exists: None,
+                referenced_message: None,
+                timestamp,
+                thread: None,
+                tts: false,
+                webhook_id: None,
+                partial: false,
+            },
+            embed: CachedEmbed {
+                id: Id::new(SYNTHETIC_ID),
+                message_id,
+                author_icon_url: None,
+                author_name: Some("synthetic author".to_owned()),
+                author_proxy_icon_url: None,
+                author_url: None,
+                color: Some(0x00ff_00ff),
+                description: Some("synthetic description".to_owned()),
+                footer_icon_url: None,
+                footer_proxy_icon_url: None,
+                footer_text: Some("synthetic footer".to_owned()),
+                image_height: None,
+                image_proxy_url: None,
+                image_url: None,
+                image_width: None,
+                kind: "rich".to_owned(),
+                provider_name: None,
+                provider_url: None,
+                thumbnail_height: None,
+                thumbnail_proxy_url: None,
+                thumbnail_url: None,
+                thumbnail_width: None,
+                timestamp: Some(timestamp),
+                title: Some("synthetic title".to_owned()),
+                url: None,
+                video_height: None,
+                video_proxy_url: None,
+                video_url: None,
+                video_width: None,
+            },
+            embed_field: CachedEmbedField {
+                embed_id: Id::new(SYNTHETIC_ID),
+                inline: true,
+                name: "synthetic name".to_owned(),
+                value: "synthetic value".to_owned(),
+            },
+            attachment: CachedAttachment {
+                message_id,
+                content_type: Some("image/png".to_owned()),
+                duration_millis: None,
+                ephemeral: false,
+                filename: "synthetic.png".to_owned(),
+                flags: None,
+                description: Some("synthetic description".to_owned()),
+                height: Some(64),
+                id: Id::new(SYNTHETIC_ID),
+                proxy_url: "https://media.discordapp.net/synthetic.png".to_owned(),
+                size: 1024,
+                url: "https://cdn.discordapp.com/synthetic.png".to_owned(),
+                waveform: None,
+                width: Some(128),
+            },
+            reaction: CachedReaction {
+                channel_id,
+                emoji: "👍".to_owned(),
+                emoji_name: None,
+                emoji_animated: None,
+                guild_id: Some(guild_id),
+                message_id,
+                user_id,
+                burst: false,
+                burst_colors: None,
+            },
+            member: CachedMember {
+                guild_avatar: None,
+                communication_disabled_until: None,
+                deaf: false,
+                guild_id,
+                joined_at: timestamp,
+                mute: true,
+                nick: Some("synthetic nick".to_owned()),
+                pending: false,
+                premium_since: Some(timestamp),
+                accent_color: Some(0x00ff_0000),
+                avatar: None,
+                banner: None,
+                bot: true,
+                discriminator: 1,
+                flags: None,
+                id: user_id,
+                locale: Some("en-US".to_owned()),
+                mfa_enabled: Some(true),
+                name: "synthetic user".to_owned(),
+                premium_type: None,
+                public_flags: None,
+                system: Some(false),
+            },
+            presence: CachedPresence {
+                guild_id,
+                status: Status::Idle,
+                user: user_id,
+            },
+            activity: CachedActivity {
+                user_id,
+                guild_id,
+                application_id: None,
+                asset_large_image: None,
+                asset_large_text: None,
+                asset_small_image: None,
+                asset_small_text: None,
+                button_1: Some("synthetic button".to_owned()),
+                button_2: None,
+                created_at: Some(1_420_070_400_000),
+                details: Some("synthetic details".to_owned()),
+                emoji_animated: None,
+                emoji_name: None,
+                emoji_id: None,
+                flags: None,
+                id: None,
+                instance: Some(false),
+                kind: ActivityType::Playing,
+                name: "synthetic activity".to_owned(),
+                party_id: None,
+                party_size_current: Some(1),
+                party_size_max: Some(4),
+                state: Some("synthetic state".to_owned()),
+                timestamp_end: None,
+                timestamp_start: Some(1_420_070_400_000),
+                url: None,
+            },
+            role: CachedRole {
+                guild_id,
+                color: 0x0000_ff00,
+                hoist: true,
+                icon: None,
+                id: role_id,
+                managed: false,
+                mentionable: true,
+                name: "synthetic role".to_owned(),
+                permissions: Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+                position: 2,
+                flags: None,
+                tags_available_for_purchase: None,
+                tags_bot_id: None,
+                tags_guild_connections: None,
+                tags_integration_id: None,
+                tags_premium_subscriber: Some(false),
+                tags_subscription_listing_id: None,
+                unicode_emoji: None,
+            },
+            role_assignment: CachedRoleAssignment {
+                guild_id,
+                user_id,
+                role_id,
+            },
+            emoji: CachedEmoji {
+                guild_id,
+                animated: true,
+                available: true,
+                id: Id::new(SYNTHETIC_ID),
+                managed: false,
+                name: "synthetic_emoji".to_owned(),
+                require_colons: true,
+                user: Some(user_id),
+            },
+            sticker: CachedSticker {
+                message_id: None,
+                available: Some(true),
+                description: Some("synthetic description".to_owned()),
+                format_type: StickerFormatType::Png,
+                guild_id: Some(guild_id),
+                id: Id::new(SYNTHETIC_ID),
+                kind: None,
+                name: "synthetic sticker".to_owned(),
+                pack_id: None,
+                sort_value: None,
+                tags: Some("synthetic".to_owned()),
+                user_id: Some(user_id),
+            },
+            stage_instance: StageInstance {
+                channel_id,
+                guild_id,
+                guild_scheduled_event_id: None,
+                id: Id::new(SYNTHETIC_ID),
+                privacy_level: PrivacyLevel::GuildOnly,
+                topic: "synthetic topic".to_owned(),
+            },
+        })
+    }
+}